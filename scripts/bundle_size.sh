@@ -0,0 +1,13 @@
+#!/bin/sh
+# Bundle size report, run by trunk after each build (see trunk.toml). Sizes
+# are in bytes, gzipped as most servers send them.
+dir="${TRUNK_STAGING_DIR:-dist}"
+total=0
+for file in "$dir"/*.wasm "$dir"/*.js; do
+    [ -f "$file" ] || continue
+    size=$(wc -c < "$file")
+    gzipped=$(gzip -9 -c "$file" | wc -c)
+    total=$((total + gzipped))
+    printf '%-50s %10d %10d gzipped\n' "$(basename "$file")" "$size" "$gzipped"
+done
+printf '%-50s %10s %10d gzipped\n' "total" "" "$total"
@@ -1,26 +1,98 @@
 use yew::prelude::*;
 use wasm_bindgen::prelude::*;
-use crate::components::{top_menu::TopMenu, left_bar::LeftBar, right_bar::RightBar, main_canvas::MainCanvas};
-use crate::models::roster::Roster;
+use crate::components::{top_menu::TopMenu, left_bar::LeftBar, right_bar::RightBar, main_canvas::MainCanvas, settings_panel::SettingsPanel};
+use crate::components::campaign_editor::CampaignEditor;
+#[cfg(feature = "editors")]
+use crate::components::campaign_dashboard::CampaignDashboard;
+#[cfg(feature = "editors")]
+use crate::components::scenario_editor::ScenarioEditor;
+#[cfg(feature = "print")]
+use crate::components::print_dialog::PrintDialog;
+use crate::components::swap_dialog::SwapDialog;
+use crate::components::history_panel::HistoryPanel;
+use crate::components::overlay::Overlay;
+use crate::components::presentation_dialog::PresentationDialog;
+#[cfg(feature = "qr")]
+use crate::components::qr_dialog::QrDialog;
+use crate::components::play_mode::PlayMode;
+
+// Files offered for download
+use crate::export::{audio, clipboard, download, image, report, speech};
+#[cfg(feature = "print")]
+use crate::export::print;
+use crate::render::{card_svg, text};
+
+// Campaign play, linking the roster to the games played
+use crate::core::campaign::{BattleRecord, Campaign, CampaignExport};
+use crate::core::roster::Roster;
+use crate::core::roster_names;
+use crate::core::swap;
+use crate::core::battlescribe;
+use crate::core::event_pack::EventPack;
+use crate::models::presentation::OverlayOptions;
+use crate::core::history::History;
+use crate::core::commands::RosterCommand;
 
 // Importing the quasi-static Armmylist
-use crate::models::armylist;
+use crate::core::armylist;
 
-// Handling the roster as a pointer
+// Handling the file reader as a pointer
 use std::rc::Rc;
-use std::cell::RefCell;
+use gloo::events::EventListener;
+use gloo::timers::callback::{Interval, Timeout};
 
 // For the file selection
 use wasm_bindgen::JsCast;
 use wasm_bindgen::closure::Closure;
 
-// For browser debugging
-use web_sys::console;
-
 // A common definition for all messages:
-use crate::shared_messages::SharedMessage;
+use crate::shared_messages::{MetadataMessage, SharedMessage};
+
+use crate::core::roster::RosterElement;
 
-use crate::models::roster::RosterElement;
+// Images are fetched ahead of time, see assets::preloader
+use crate::assets::{paths, preloader};
+use crate::core::armylist::Faction;
+
+// User settings, persisted in the browser
+use crate::models::settings::{Settings, MAX_LOGO_BYTES, STORED_SETTINGS_KEY};
+use crate::models::profiler;
+use crate::models::features::{Feature, FeatureFlags};
+use crate::components::profiler_overlay::ProfilerOverlay;
+use crate::components::report_dialog::ReportDialog;
+use crate::components::repair_wizard::RepairWizard;
+use crate::components::recovery_prompt::RecoveryPrompt;
+use crate::components::roster_manager::RosterManager;
+use crate::core::roster_slots::RosterSlots;
+use crate::core::ids;
+use crate::core::snapshots::{Snapshot, Snapshots};
+use crate::models::session;
+use crate::models::integrity::{self, IntegritySource, NeedsAttention};
+use crate::core::repair;
+use crate::models::tabs::{self, TabCoordinator, TabEvent};
+use crate::models::share;
+use crate::models::deadline;
+use crate::models::sync::{self, SyncClient};
+use crate::core::sync::{self as roster_sync, PullOutcome};
+use crate::core::provenance;
+use crate::models::catalog_updates;
+use crate::models::clock;
+#[cfg(debug_assertions)]
+use crate::models::clock::ShiftedClock;
+use crate::models::bus;
+use crate::models::selection::{Selection, SelectionChange, SelectionContext};
+use crate::models::storage::{CAMPAIGN_KEY, ROSTER_KEY, SCENARIOS_KEY, SLOTS_KEY, SNAPSHOTS_KEY};
+use crate::models::storage_backend::{self, StorageBackend, StorageKind};
+use crate::models::roster_store::{RosterAspect, RosterStore, Subscription};
+
+// How long a toast stays on screen
+const TOAST_MS: u32 = 2500;
+const DEADLINE_REFRESH_MS: u32 = 60 * 1000;
+
+// Loaded from the storage backend at startup, and again when another tab
+// saved.
+const SAVED_STATE_KEYS: [&str; 5] = [SLOTS_KEY, SNAPSHOTS_KEY, CAMPAIGN_KEY, ROSTER_KEY, STORED_SETTINGS_KEY];
+const RELOADED_STATE_KEYS: [&str; 3] = [SLOTS_KEY, CAMPAIGN_KEY, ROSTER_KEY];
 
 #[wasm_bindgen]
 extern "C" {
@@ -31,19 +103,108 @@ extern "C" {
 pub struct App{
 
     // Roster Logic
-    roster: Rc<RefCell<Roster>>,
+    roster: RosterStore,
+    _roster_subscription: Subscription,
 
     // Right Bar Model:
     right_bar_model: Vec<RosterElement>,
 
     // input file
     file_input_ref: NodeRef,
+    // Why the last file couldn't be loaded, until dismissed
+    load_error: Option<String>,
+    // What a BattleScribe import couldn't carry over, and its file reader
+    import_notes: Vec<String>,
+    import_reader: Option<gloo::file::callbacks::FileReader>,
+    // Readers of the logo picked for the print header and of an event pack
+    logo_reader: Option<gloo::file::callbacks::FileReader>,
+    event_pack_reader: Option<gloo::file::callbacks::FileReader>,
+
+    // Elements changed since the catalog the roster was built with, shown
+    is_catalog_changes_open: bool,
 
     // Dark/light mode
     is_dark_mode: bool,
 
-    // Currently selected element
-    selected_index: Option<usize>,
+    // Selected entries, handed down as a context with its callback
+    selection: Selection,
+    on_selection_change: Callback<SelectionChange>,
+
+    // Faction currently shown in the right bar, and the preloading of its images
+    current_faction: Option<Faction>,
+    preload_progress: Option<(usize, usize)>,
+
+    // Settings and their modal
+    settings: Settings,
+    features: FeatureFlags,
+    is_settings_open: bool,
+
+    // Problem report being shown, built when opened
+    problem_report: Option<String>,
+
+    // Stored data that failed the checks on load, kept aside until repaired
+    needs_attention: Vec<NeedsAttention>,
+    is_repair_wizard_open: bool,
+
+    // Rosters saved side by side, the active one is the roster above
+    roster_slots: RosterSlots,
+    is_roster_manager_open: bool,
+    // Where the slots and the snapshots are kept, see models::storage_backend
+    storage_backend: Rc<dyn StorageBackend>,
+    // Server the roster is pushed to and pulled from, if one is set
+    sync_client: Option<Rc<dyn SyncClient>>,
+
+    // Latest states of the roster, and the ones offered after a crash
+    snapshots: Snapshots,
+    recovery_snapshots: Vec<Snapshot>,
+    // Clears the running mark when the page closes, see models::session
+    _session_listener: Option<EventListener>,
+    // Ctrl+Z and Ctrl+Shift+Z
+    _undo_listener: EventListener,
+    _metadata_subscription: bus::Subscription,
+
+    // Other tabs of the app, only one of them saves. None for an overlay.
+    tabs: Option<TabCoordinator>,
+
+    // Entry whose campaign record is being edited
+    campaign_editor_id: Option<String>,
+
+    // Running campaign, if any, and its dashboard
+    campaign: Option<Campaign>,
+    is_campaign_dashboard_open: bool,
+
+    // Custom missions editor
+    is_scenario_editor_open: bool,
+
+    // Print options modal, the options themselves live in the roster
+    is_print_dialog_open: bool,
+
+    // Swapping every copy of an element for another one
+    is_swap_dialog_open: bool,
+
+    // Edits of the roster, for undo and checkpoints
+    history: History,
+    // As of the last change, the cue only plays when going over it
+    was_over_limit: bool,
+    is_history_panel_open: bool,
+
+    // Tools for the table during a game
+    is_play_mode_open: bool,
+
+    // Presentation mode, set from the url when the app is opened as an overlay
+    overlay: Option<OverlayOptions>,
+    is_presentation_dialog_open: bool,
+    is_qr_dialog_open: bool,
+    // Short confirmation at the bottom of the screen, and its timer
+    toast: Option<String>,
+    _toast_timeout: Option<Timeout>,
+    // Refreshes the countdown to the submission deadline
+    _deadline_interval: Interval,
+
+    // Saved state and catalog are only loaded after the first paint, until
+    // then the canvas shows a placeholder.
+    is_restoring: bool,
+    army_lists: Vec<(Faction, armylist::ArmyList)>,
 }
 
 
@@ -68,18 +229,214 @@ impl Component for App {
             closure.forget(); // Don't forget to forget the closure to prevent it from being dropped
         }
         
+        let settings = Settings::load();
+        paths::set_runtime_asset_base(settings.asset_base.clone());
+        // Before anything reads the catalog
+        catalog_updates::apply_saved_delta();
+        card_svg::set_points_hidden(settings.hide_points);
+        deadline::set_deadline(settings.get_submission_deadline());
+        audio::set_cue_volume(settings.get_cue_volume());
+        profiler::set_enabled(settings.show_profiler || App::is_profiler_requested());
+        ids::seed(App::get_random_seed());
+        #[cfg(debug_assertions)]
+        if let Some(shifted) = App::get_requested_clock() {
+            clock::set_clock(Rc::new(shifted));
+        }
+
+        // An overlay brings its own roster, if it has one.
+        let overlay = gloo::utils::window().location().hash().ok()
+            .and_then(|fragment| OverlayOptions::from_fragment(&fragment));
+        let mut roster = Roster::new();
+        let overlay = overlay.map(|(options, overlay_roster)| {
+            if let Some(overlay_roster) = overlay_roster {
+                roster = overlay_roster;
+            }
+            options
+        });
+        let history = History::new("Opened roster");
+        let was_over_limit = roster.is_over_points_limit(roster.get_points_limit());
+        let roster = RosterStore::new(roster);
+        let tabs = overlay.is_none().then(|| TabCoordinator::new(ctx.link().callback(SharedMessage::TabEvent)));
+
         App {
-            roster: Rc::new(RefCell::new(Roster::new().into())),
+            history,
+            was_over_limit,
+            is_history_panel_open: false,
+            is_play_mode_open: false,
+            overlay,
+            is_presentation_dialog_open: false,
+            is_qr_dialog_open: false,
+            toast: None,
+            _toast_timeout: None,
+            _deadline_interval: {
+                let link = ctx.link().clone();
+                Interval::new(DEADLINE_REFRESH_MS, move || link.send_message(SharedMessage::DeadlineTick))
+            },
+            is_restoring: true,
+            army_lists: Vec::new(),
+            _roster_subscription: roster.subscribe(RosterAspect::Any, ctx.link().callback(|_| SharedMessage::NotifyRosterUpdated)),
+            roster,
             right_bar_model: Vec::<RosterElement>::new(),
             file_input_ref: NodeRef::default(),
+            load_error: None,
+            import_notes: Vec::new(),
+            import_reader: None,
+            logo_reader: None,
+            event_pack_reader: None,
+            is_catalog_changes_open: false,
             is_dark_mode: false,
-            selected_index: None,
+            selection: Selection::default(),
+            on_selection_change: ctx.link().callback(SharedMessage::ChangeSelection),
+            current_faction: None,
+            preload_progress: None,
+            features: App::get_feature_flags(&settings),
+            sync_client: sync::get_sync_client(&settings.sync),
+            storage_backend: storage_backend::get_storage_backend(settings.storage, &settings.sync),
+            settings,
+            is_settings_open: false,
+            problem_report: None,
+            needs_attention: Vec::new(),
+            is_repair_wizard_open: false,
+            roster_slots: RosterSlots::default(),
+            is_roster_manager_open: false,
+            snapshots: Snapshots::default(),
+            recovery_snapshots: Vec::new(),
+            _session_listener: None,
+            _undo_listener: App::listen_for_undo(ctx),
+            _metadata_subscription: bus::subscribe(ctx.link().callback(SharedMessage::Metadata)),
+            tabs,
+            campaign_editor_id: None,
+            campaign: None,
+            is_campaign_dashboard_open: false,
+            is_scenario_editor_open: false,
+            is_print_dialog_open: false,
+            is_swap_dialog_open: false,
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if first_render {
+            // Giving the browser the time to paint the empty app first.
+            let link = ctx.link().clone();
+            Timeout::new(0, move || link.send_message(SharedMessage::RestoreSavedState)).forget();
         }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg : Self::Message) -> bool {
+        let _timer = profiler::time_update("App");
         match msg {
 
+            // What the backend holds may take a while, the rest waits for it.
+            SharedMessage::RestoreSavedState => {
+                storage_backend::load_texts(self.storage_backend.clone(), &SAVED_STATE_KEYS, ctx.link().callback(SharedMessage::FinishRestoringState));
+                if self.overlay.is_none() {
+                    catalog_updates::check_for_update(ctx.link().callback(SharedMessage::CatalogUpdated));
+                }
+                false
+            }
+
+            SharedMessage::CatalogUpdated(result) => {
+                match result {
+                    Ok(Some(edition)) => {
+                        // The lists are built again from the updated catalog,
+                        // the elements shown are picked again.
+                        self.army_lists.clear();
+                        self.right_bar_model.clear();
+                        self.show_toast(ctx, format!("Catalog updated to {}", edition));
+                        true
+                    }
+                    Ok(None) => false,
+                    Err(e) => {
+                        log::warn!("Error checking for a catalog update: {}", e);
+                        false
+                    }
+                }
+            }
+
+            SharedMessage::FinishRestoringState(texts) => {
+                let [slots_json, snapshots_json, campaign_json, autosave_json, settings_json] = texts.try_into().unwrap_or_default();
+                let roster_slots: RosterSlots = storage_backend::parse_value(SLOTS_KEY, slots_json);
+                let snapshots: Snapshots = storage_backend::parse_value(SNAPSHOTS_KEY, snapshots_json);
+                if let Some(settings) = storage_backend::parse_value::<Option<Settings>>(STORED_SETTINGS_KEY, settings_json) {
+                    self.apply_settings(settings.with_backend_of(&self.settings));
+                }
+
+                // Checked before this session marks itself as running.
+                let did_crash = self.overlay.is_none() && self.is_tab_writer() && session::did_last_session_crash();
+                if self.overlay.is_none() && self.is_tab_writer() {
+                    self._session_listener = Some(session::mark_running());
+                }
+
+                // A running campaign brings its roster along if it's the
+                // one of the open slot, otherwise the roster of the last
+                // session comes back. Unless an overlay brought one already,
+                // or they need repairs first.
+                (self.campaign, self.needs_attention) = integrity::load_campaign(campaign_json);
+                let (autosaved_roster, autosave_items) = integrity::load_autosave(autosave_json);
+                self.needs_attention.extend(autosave_items);
+                self.roster_slots = roster_slots;
+                let has_slots = !self.roster_slots.slots.is_empty();
+                let mut roster = self.roster.borrow().clone();
+                if self.overlay.is_none() {
+                    let campaign_roster = self.campaign.as_ref()
+                        .filter(|_| !self.is_campaign_roster_quarantined())
+                        .filter(|_| !has_slots || self.roster_slots.is_campaign_slot_active())
+                        .map(|campaign| campaign.roster.clone());
+                    let slot_roster = self.roster_slots.find(&self.roster_slots.active_id).map(|slot| slot.roster.clone());
+                    if let Some(stored_roster) = campaign_roster.or(autosaved_roster).or(slot_roster) {
+                        roster = stored_roster;
+                    }
+                }
+                self.is_repair_wizard_open = !self.needs_attention.is_empty();
+                if roster.metadata.name.is_empty() {
+                    roster.metadata.name = App::generate_roster_name(&roster);
+                }
+                self.open_roster("Opened roster", roster);
+
+                // Rosters from before the slots become the first one.
+                if self.overlay.is_none() && !has_slots {
+                    let id = self.roster_slots.add(self.roster.borrow().clone(), &clock::get_timestamp());
+                    self.roster_slots.campaign_slot_id = self.campaign.is_some().then(|| id.clone());
+                    self.roster_slots.active_id = id;
+                    self.save_roster_slots();
+                }
+
+                // A shared link opens its roster in a slot of its own, or in
+                // the one already holding it, e.g. when the page is reloaded.
+                match share::get_page_roster().filter(|_| self.overlay.is_none()) {
+                    Some(Ok(shared_roster)) if !shared_roster.is_same_list(&self.roster.borrow()) => {
+                        let id = match self.roster_slots.slots.iter().find(|slot| slot.roster.is_same_list(&shared_roster)) {
+                            Some(slot) => slot.id.clone(),
+                            None => self.roster_slots.add(shared_roster.clone(), &clock::get_timestamp()),
+                        };
+                        self.roster_slots.active_id = id;
+                        self.save_roster_slots();
+                        self.open_roster("Opened shared roster", shared_roster);
+                    }
+                    Some(Err(e)) => self.load_error = Some(format!("The shared roster couldn't be opened: {}", e)),
+                    _ => {}
+                }
+
+                // Whatever is already in the roster should be ready before it's drawn.
+                let roster_images = self.roster.borrow().elements.iter()
+                    .map(|entry| paths::image_url(&entry.element.get_image()))
+                    .collect();
+                preloader::preload_images(roster_images, ctx.link().callback(|(done, total)| SharedMessage::PreloadProgress(done, total)));
+
+                for faction in Faction::ALL {
+                    self.get_army_list(faction);
+                }
+                if self.overlay.is_none() {
+                    self.snapshots = snapshots;
+                }
+                if did_crash {
+                    self.recovery_snapshots = self.snapshots.get_alternatives(&self.roster.borrow());
+                }
+                self.is_restoring = false;
+                self.roster.notify();
+                true
+            }
+
             SharedMessage::LoadRoster => {
                 if let Some(input) = self.file_input_ref.cast::<web_sys::HtmlInputElement>() {
                     
@@ -91,174 +448,943 @@ impl Component for App {
             }
 
             SharedMessage::SaveRoster => {
-                match self.roster.borrow().to_json() {
-                    Ok(json_string) => {
-                        let document = web_sys::window().unwrap().document().unwrap();
-                        let a = document.create_element("a")
-                            .unwrap()
-                            .dyn_into::<web_sys::HtmlAnchorElement>()
-                            .unwrap();
-                        
-                        // Convert the JSON string to a Blob
-                        let mut blob_parts: web_sys::BlobPropertyBag = web_sys::BlobPropertyBag::new();
-                        blob_parts.type_("application/json");
-                        let blob = web_sys::Blob::new_with_str_sequence_and_options(&js_sys::Array::of1(&json_string.into()), &blob_parts).unwrap();
-                        
-                        // Create an Object URL from the Blob
-                        let url = web_sys::Url::create_object_url_with_blob(&blob).unwrap();
-                        
-                        a.set_href(&url);
-                        a.set_download("roster.json");
-                        a.set_attribute("style", "display: none").unwrap();
-                        document.body().unwrap().append_child(&a).unwrap();
-                        a.click();
-                        a.remove();
-                        
-                        // Clean up the Object URL to free resources
-                        web_sys::Url::revoke_object_url(&url).unwrap();
-                    },
+                let roster = self.roster.borrow();
+                match roster.to_json() {
+                    Ok(json_string) => download::download_text(&json_string, &download::get_file_name(&roster.metadata.name, "json"), "application/json"),
                     Err(e) => {
-                        console::log_1(&format!("Error serializing roster: {:?}", e).into());
+                        log::warn!("Error serializing roster: {:?}", e);
+                    }
+                }
+                false
+            }
+
+            SharedMessage::CopyShareLink => {
+                if !deadline::confirm_export() {
+                    return false;
+                }
+                match share::get_share_url(&self.roster.borrow()) {
+                    Some(url) => clipboard::copy_text(&url),
+                    None => log::warn!("Error building the share link"),
+                }
+                false
+            }
+
+            SharedMessage::CopyRoster => {
+                if !deadline::confirm_export() {
+                    return false;
+                }
+                let roster = self.roster.borrow();
+                match roster.to_json() {
+                    Ok(json) => clipboard::copy_text_and_json(&text::roster_paste(&roster), &json, ctx.link().callback(SharedMessage::RosterCopied)),
+                    Err(e) => log::warn!("Error copying the roster: {:?}", e),
+                }
+                false
+            }
+
+            SharedMessage::RosterCopied(result) => {
+                self.show_toast(ctx, match result {
+                    Ok(true) => "Roster copied, as text and data".to_string(),
+                    Ok(false) => "Roster copied as text".to_string(),
+                    Err(e) => format!("The roster couldn't be copied: {}", e),
+                });
+                true
+            }
+
+            SharedMessage::PushRoster => {
+                let Some(client) = self.sync_client.clone() else {
+                    return false;
+                };
+                let roster = self.roster.borrow().clone();
+                if !roster_sync::has_local_changes(&roster) {
+                    self.show_toast(ctx, "The roster is up to date on the server".to_string());
+                    return true;
+                }
+                // The content as pushed, the roster may be edited before the answer.
+                let id = roster.metadata.sync.as_ref().map_or_else(ids::new_id, |record| record.id.clone());
+                let content_hash = roster_sync::get_synced_hash(&roster);
+                let body = roster_sync::get_push_body(&roster);
+                let record_id = id.clone();
+                client.push(&id, body, ctx.link().callback(move |result: Result<roster_sync::PushReceipt, sync::SyncError>| {
+                    SharedMessage::RosterPushed(result.map(|receipt| {
+                        roster_sync::SyncRecord {id: record_id.clone(), revision: receipt.revision, content_hash: content_hash.clone()}
+                    }))
+                }));
+                false
+            }
+
+            SharedMessage::RosterPushed(result) => {
+                match result {
+                    Ok(record) => {
+                        self.show_toast(ctx, format!("Roster pushed, revision {}", record.revision));
+                        self.roster.execute(RosterCommand::SetSyncRecord(Some(record)));
+                    }
+                    Err(e) => self.show_toast(ctx, e.get_message()),
+                }
+                true
+            }
+
+            SharedMessage::PullRoster => {
+                let Some(client) = self.sync_client.clone() else {
+                    return false;
+                };
+                let id = self.roster.borrow().metadata.sync.as_ref().map(|record| record.id.clone());
+                match id {
+                    Some(id) => client.pull(&id, ctx.link().callback(SharedMessage::RosterPulled)),
+                    None => {
+                        self.show_toast(ctx, "The roster was never pushed, there's nothing to pull".to_string());
+                        return true;
                     }
                 }
                 false
             }
 
+            SharedMessage::RosterPulled(result) => {
+                let remote = match result {
+                    Ok(remote) => remote,
+                    Err(e) => {
+                        self.show_toast(ctx, e.get_message());
+                        return true;
+                    }
+                };
+                let outcome = roster_sync::get_pull_outcome(&self.roster.borrow(), remote);
+                match outcome {
+                    PullOutcome::UpToDate => self.show_toast(ctx, "The roster is already up to date".to_string()),
+                    PullOutcome::Updated(roster) => {
+                        self.show_toast(ctx, "Roster pulled from the server".to_string());
+                        self.roster.batch(|current| *current = roster);
+                    }
+                    PullOutcome::Conflict(roster) => {
+                        let message = "The roster was changed here and on the server since the last sync. Replace it with the one of the server?\n\nCancel keeps this one, to be pushed over the server's.";
+                        let roster = if gloo::utils::window().confirm_with_message(message).unwrap_or(false) {
+                            roster
+                        } else {
+                            let revision = roster.metadata.sync.as_ref().map_or(0, |record| record.revision);
+                            let mut local = self.roster.borrow().clone();
+                            roster_sync::keep_local(&mut local, revision);
+                            local
+                        };
+                        self.roster.batch(|current| *current = roster);
+                    }
+                }
+                true
+            }
+
+            SharedMessage::ExportImage => {
+                if !deadline::confirm_export() {
+                    return false;
+                }
+                let theme = card_svg::CardTheme::for_mode(self.is_dark_mode);
+                image::download_roster_image(&self.roster.borrow(), &theme, ctx.link().callback(SharedMessage::ImageExported));
+                false
+            }
+
+            SharedMessage::ImageExported(result) => {
+                match result {
+                    Ok(()) => self.show_toast(ctx, "Roster image saved".to_string()),
+                    Err(e) => self.show_toast(ctx, format!("The roster image couldn't be made: {}", e)),
+                }
+                true
+            }
+
+            SharedMessage::DeadlineTick => deadline::get_countdown_label().is_some(),
+
+            SharedMessage::HideToast => {
+                self.toast = None;
+                self._toast_timeout = None;
+                true
+            }
+
+            SharedMessage::ToggleQrDialog => {
+                self.is_qr_dialog_open = !self.is_qr_dialog_open;
+                true
+            }
+
             SharedMessage::ClearRoster => {
-                console::log_1(&"Called CLEAR for the roster".into());
-                self.roster.borrow_mut().clear();
-                ctx.link().callback(|_| SharedMessage::NotifyRosterUpdated).emit(());
+                log::debug!("Called CLEAR for the roster");
+                // A cleared roster is a new one, and gets a new name.
+                self.roster.batch(|roster| {
+                    roster.clear();
+                    roster.metadata.name = App::generate_roster_name(roster);
+                });
                 true            
             }
 
             SharedMessage::FileContentReceived(text) => {
                 match Roster::from_json(&text) {
-                    Ok(roster) => {
-                        *self.roster.borrow_mut() = roster;
+                    Ok(mut roster) => {
+                        if roster.metadata.name.is_empty() {
+                            roster.metadata.name = App::generate_roster_name(&roster);
+                        }
+                        self.open_roster("Loaded roster", roster);
+                        self.load_error = None;
+                        self.import_notes.clear();
+                    }
+
+                    Err(e) => {
+                        self.load_error = Some(format!("The file isn't a valid roster: {}", e));
+                    }
+                }
+
+                true
+            }
+
+            SharedMessage::BattleScribeFileSelected(file) => {
+                let link = ctx.link().clone();
+                self.import_reader = Some(gloo::file::callbacks::read_as_bytes(&gloo::file::File::from(file), move |result| {
+                    link.send_message(SharedMessage::BattleScribeFileLoaded(result.map_err(|e| e.to_string())));
+                }));
+                false
+            }
+
+            SharedMessage::BattleScribeFileLoaded(result) => {
+                self.import_reader = None;
+                match result.and_then(|bytes| battlescribe::import_roster(&bytes)) {
+                    Ok(mut import) => {
+                        if import.roster.metadata.name.is_empty() {
+                            import.roster.metadata.name = App::generate_roster_name(&import.roster);
+                        }
+                        self.open_roster("Imported BattleScribe roster", import.roster);
+                        self.import_notes = import.notes;
+                        self.load_error = None;
                     }
 
-                    Err(_e) => {
-                        // Do something TODO
+                    Err(e) => {
+                        self.load_error = Some(format!("The BattleScribe roster couldn't be imported: {}", e));
                     }
                 }
+                true
+            }
+
+            SharedMessage::ToggleCatalogChanges => {
+                self.is_catalog_changes_open = !self.is_catalog_changes_open;
+                true
+            }
+
+            SharedMessage::PinCatalog => {
+                self.is_catalog_changes_open = false;
+                self.roster.execute(RosterCommand::SetCatalogHash(armylist::get_catalog_hash()));
+                true
+            }
+
+            SharedMessage::DismissImportNotes => {
+                self.import_notes.clear();
+                true
+            }
+
+            SharedMessage::DismissLoadError => {
+                self.load_error = None;
+                true
+            }
 
+            SharedMessage::Metadata(message) => {
+                let command = match message {
+                    MetadataMessage::Rename(name) => RosterCommand::SetName(name.trim().to_string()),
+                    MetadataMessage::RerollName => RosterCommand::SetName(App::generate_roster_name(&self.roster.borrow())),
+                    MetadataMessage::SetPointsLimit(points_limit) => RosterCommand::SetPointsLimit(points_limit),
+                    MetadataMessage::SetEscalationMilestones(milestones) => RosterCommand::SetEscalationMilestones(milestones),
+                };
+                self.roster.execute(command);
                 true
             }
 
             SharedMessage::ShowUnits(faction) => {
-                self.right_bar_model = armylist::ArmyList::new(faction).get_units().
+                self.set_current_faction(ctx, faction);
+                self.right_bar_model = self.get_army_list(faction).get_units().
                     into_iter().map(|elem| {elem.into()}).collect();
                 true
             }
 
             SharedMessage::ShowCharacters(faction) => {
-                self.right_bar_model = armylist::ArmyList::new(faction).get_characters().
+                self.set_current_faction(ctx, faction);
+                self.right_bar_model = self.get_army_list(faction).get_characters().
                     into_iter().map(|elem| {elem.into()}).collect();
                 true
             }
 
             SharedMessage::ShowSupports(faction) => {
-                self.right_bar_model = armylist::ArmyList::new(faction).get_supports().
+                self.set_current_faction(ctx, faction);
+                self.right_bar_model = self.get_army_list(faction).get_supports().
                     into_iter().map(|elem| {elem.into()}).collect();
                 true
             }
     
             SharedMessage::AddToRoster(element) => {
-                self.roster.borrow_mut().add_element(element); // Implement the add_element method
-                ctx.link().callback(|_| SharedMessage::NotifyRosterUpdated).emit(());
+                let index = self.roster.borrow().elements.len();
+                self.roster.execute(RosterCommand::InsertEntry(index, element.into()));
                 true
             }
 
-            SharedMessage::AddToElement(target_index, element_to_attach) => {
+            SharedMessage::AddToElement(target_id, element_to_attach) => {
 
-                let mut roster_ref = self.roster.borrow_mut();
-                console::log_1(&format!("AddToElement Called. Target index is {:?}.", target_index).into());
-                console::log_1(&format!("Elem to add is {:?}.", element_to_attach).into());
+                let Some(target_index) = self.roster.borrow().find_entry(&target_id) else {
+                    return false;
+                };
+                let target_entry = self.roster.borrow().elements.get(target_index).cloned();
+                log::debug!("AddToElement Called. Target index is {:?}.", target_index);
+                log::debug!("Elem to add is {:?}.", element_to_attach);
 
-                if let Some(target_element) = roster_ref.elements.get_mut(target_index) {
-                    console::log_1(&format!("index found. Target elem is {:?}.", target_element).into());
+                if let Some(mut target_entry) = target_entry {
+                    log::debug!("index found. Target elem is {:?}.", target_entry.element);
 
-                    if let RosterElement::ElemUnit(unit) = target_element {
+                    if let RosterElement::ElemUnit(unit) = &mut target_entry.element {
                         if let RosterElement::ElemCharacter(character) = element_to_attach{
                             unit.attached_elements.push(RosterElement::ElemCharacter(character)); 
-                            console::log_1(&format!("Added Character.").into());
+                            log::debug!("Added Character.");
                         }
                     }else {
-                        console::log_1(&format!("Can't add elements to non-units.").into());
+                        log::debug!("Can't add elements to non-units.");
                         // Handle non-unit target elements if necessary
                     }
+                    self.roster.execute(RosterCommand::ReplaceEntry(target_index, target_entry));
                 }
-
-                ctx.link().callback(|_| SharedMessage::NotifyRosterUpdated).emit(());
                 true
             }
             
-            SharedMessage::RemoveCharacterFromElement(index) => {
-                let mut roster_ref = self.roster.borrow_mut();
-                if let Some(target_element) = roster_ref.elements.get_mut(index) {
-                    if let RosterElement::ElemUnit(unit) = target_element {
+            SharedMessage::RemoveCharacterFromElement(id) => {
+                let Some(index) = self.roster.borrow().find_entry(&id) else {
+                    return false;
+                };
+                let entry = self.roster.borrow().elements.get(index).cloned();
+                if let Some(mut entry) = entry {
+                    if let RosterElement::ElemUnit(unit) = &mut entry.element {
                         unit.attached_elements.clear();
                         // TODO implement it as follows, after setting the attached_elements as actual RosterElements
                         //unit.attached_elements.retain(|elem| !matches!(elem, RosterElement::ElemCharacter(_)));
                     }
+                    self.roster.execute(RosterCommand::ReplaceEntry(index, entry));
+                }
+                true
+            }
+
+            SharedMessage::CopyElementInfo(id) => {
+                let roster = self.roster.borrow();
+                if let Some(entry) = roster.find_entry(&id).map(|index| &roster.elements[index]) {
+                    clipboard::copy_text(&text::entry_summary(entry));
+                }
+                false
+            }
+
+            SharedMessage::OpenCampaignEditor(id) => {
+                self.campaign_editor_id = Some(id);
+                true
+            }
+
+            SharedMessage::UpdateCampaignRecord(id, record) => {
+                let found = self.roster.borrow().find_entry(&id).map(|index| (index, self.roster.borrow().elements[index].clone()));
+                if let Some((index, mut entry)) = found {
+                    entry.campaign = record;
+                    self.roster.execute(RosterCommand::ReplaceEntry(index, entry));
+                }
+                true
+            }
+
+            SharedMessage::CloseCampaignEditor => {
+                self.campaign_editor_id = None;
+                true
+            }
+
+            SharedMessage::ToggleCampaignDashboard => {
+                self.is_campaign_dashboard_open = !self.is_campaign_dashboard_open;
+                true
+            }
+
+            SharedMessage::StartCampaign(name) => {
+                self.campaign = Some(Campaign::new(name, self.roster.borrow().clone()));
+                self.save_campaign();
+                self.roster_slots.campaign_slot_id = Some(self.roster_slots.active_id.clone());
+                self.save_roster_slots();
+                true
+            }
+
+            SharedMessage::RecordBattle(opponent, result, resource_points, notes) => {
+                if let Some(campaign) = &mut self.campaign {
+                    campaign.record_battle(BattleRecord {
+                        date: clock::get_date(),
+                        opponent,
+                        result,
+                        resource_points,
+                        notes,
+                        scenario: self.roster.borrow().scenario.as_ref().map(|scenario| scenario.name.clone()),
+                        roster: self.roster.borrow().clone(),
+                    });
+                }
+                self.save_campaign();
+                true
+            }
+
+            SharedMessage::SetCampaignChangeAllowance(allowed_changes) => {
+                if let Some(campaign) = &mut self.campaign {
+                    campaign.allowed_changes_between_games = allowed_changes;
+                }
+                self.save_campaign();
+                true
+            }
+
+            SharedMessage::SetCampaignPlayer(player) => {
+                if let Some(campaign) = &mut self.campaign {
+                    campaign.player = player;
+                }
+                self.save_campaign();
+                true
+            }
+
+            SharedMessage::SetCampaignTerritories(territories) => {
+                if let Some(campaign) = &mut self.campaign {
+                    campaign.territories = territories;
+                }
+                self.save_campaign();
+                true
+            }
+
+            SharedMessage::ExportCampaign => {
+                if let Some(campaign) = &self.campaign {
+                    match CampaignExport::to_json(campaign) {
+                        Ok(json_string) => download::download_text(&json_string, &download::get_file_name(&campaign.name, "campaign.json"), "application/json"),
+                        Err(e) => log::warn!("Error serializing campaign: {:?}", e),
+                    }
                 }
-                ctx.link().callback(|_| SharedMessage::NotifyRosterUpdated).emit(());
+                false
+            }
+
+            SharedMessage::EndCampaign => {
+                self.campaign = None;
+                self.save_campaign();
+                true
+            }
+
+            SharedMessage::NotifyRosterUpdated => {
+                // Only going over the limit is signaled, not staying over it.
+                let roster = self.roster.borrow().clone();
+                let is_over_limit = roster.is_over_points_limit(roster.get_points_limit());
+                if is_over_limit && !self.was_over_limit {
+                    audio::play_cue(audio::Cue::OverPointsLimit);
+                }
+                self.was_over_limit = is_over_limit;
+                for executed in self.roster.take_executed() {
+                    self.history.record(executed);
+                }
+                self.sync_campaign_roster();
+                self.autosave_roster();
+                self.sync_active_slot();
+                self.update_share_link();
+                self.take_snapshot();
+                self.check_roster_invariants();
+                // The ones gone can't stay selected.
+                self.selection.retain_entries(&roster)
+            }
+
+            SharedMessage::ToggleHistoryPanel => {
+                self.is_history_panel_open = !self.is_history_panel_open;
+                true
+            }
+
+            SharedMessage::Undo => {
+                let command = self.history.undo();
+                self.replay(command);
+                true
+            }
+
+            SharedMessage::Redo => {
+                let command = self.history.redo();
+                self.replay(command);
+                true
+            }
+
+            SharedMessage::JumpToHistory(index) => {
+                let command = self.history.jump_to(index);
+                self.replay(command);
+                true
+            }
+
+            SharedMessage::CreateCheckpoint(name) => {
+                self.history.add_checkpoint(name);
+                true
+            }
+
+            SharedMessage::SaveRevision(name) => {
+                let taken = clock::get_timestamp();
+                if let Some(revisions) = self.roster_slots.get_active_revisions_mut() {
+                    revisions.add(&name, &self.roster.borrow(), &taken);
+                    self.save_roster_slots();
+                }
+                true
+            }
+
+            SharedMessage::RestoreRevision(index) => {
+                let revision = self.roster_slots.get_active_revisions()
+                    .and_then(|revisions| revisions.revisions.get(index))
+                    .map(|revision| revision.roster.clone());
+                // An edit like any other, it can be undone.
+                if let Some(roster) = revision {
+                    self.roster.batch(|current| *current = roster);
+                }
+                true
+            }
+
+            SharedMessage::DeleteRevision(index) => {
+                if let Some(revisions) = self.roster_slots.get_active_revisions_mut() {
+                    revisions.remove(index);
+                    self.save_roster_slots();
+                }
+                true
+            }
+
+            SharedMessage::ToggleScenarioEditor => {
+                self.is_scenario_editor_open = !self.is_scenario_editor_open;
+                true
+            }
+
+            SharedMessage::AttachScenario(scenario) => {
+                self.roster.execute(RosterCommand::SetScenario(scenario));
                 true
             }
 
             SharedMessage::ToggleTheme => {
                 self.is_dark_mode = !self.is_dark_mode;
-                console::log_1(&"calling Update".into());
+                log::debug!("calling Update");
                 true // Return true to re-render the component
             }
 
-            SharedMessage::SelectElement(index) => {
-                console::log_1(&format!("Selecting element {:?}.", index).into());
+            SharedMessage::ToggleHidePoints => {
+                let mut settings = self.settings.clone();
+                settings.hide_points = !settings.hide_points;
+                ctx.link().send_message(SharedMessage::UpdateSettings(settings));
+                false
+            }
+
+            SharedMessage::ToggleSettings => {
+                self.is_settings_open = !self.is_settings_open;
+                true
+            }
+
+            SharedMessage::ToggleProblemReport => {
+                self.problem_report = match self.problem_report {
+                    Some(_) => None,
+                    None => {
+                        self.is_settings_open = false;
+                        Some(report::build_report(&self.roster.borrow(), &self.settings, &self.features))
+                    }
+                };
+                true
+            }
+
+            SharedMessage::ToggleRosterManager => {
+                self.is_roster_manager_open = !self.is_roster_manager_open;
+                true
+            }
+
+            SharedMessage::NewRosterSlot => {
+                let mut roster = Roster::new();
+                roster.metadata.name = App::generate_roster_name(&roster);
+                let id = self.roster_slots.add(roster, &clock::get_timestamp());
+                self.save_roster_slots();
+                Component::update(self, ctx, SharedMessage::SwitchRosterSlot(id))
+            }
+
+            SharedMessage::SwitchRosterSlot(id) => {
+                let Some(roster) = self.roster_slots.find(&id).map(|slot| slot.roster.clone()) else {
+                    return false;
+                };
+                // The roster being left is already saved in its slot.
+                self.roster_slots.active_id = id;
+                self.save_roster_slots();
+                self.open_roster("Switched roster", roster);
+                true
+            }
+
+            SharedMessage::RenameRosterSlot(id, name) => {
+                if self.roster_slots.is_active(&id) {
+                    return Component::update(self, ctx, SharedMessage::Metadata(MetadataMessage::Rename(name)));
+                }
+                if let Some(slot) = self.roster_slots.find_mut(&id) {
+                    slot.roster.metadata.name = name.trim().to_string();
+                    self.save_roster_slots();
+                }
+                true
+            }
+
+            SharedMessage::ToggleEventEntrySlot(id) => {
+                self.roster_slots.toggle_event_entry(&id);
+                self.save_roster_slots();
+                true
+            }
+
+            SharedMessage::DeleteRosterSlot(id) => {
+                if self.roster_slots.remove(&id).is_none() {
+                    return false;
+                }
+                self.save_roster_slots();
+                if self.roster_slots.active_id != id {
+                    return true;
+                }
+                // The open roster is replaced by another one, or a new one.
+                match self.roster_slots.slots.first().map(|slot| slot.id.clone()) {
+                    Some(other_id) => Component::update(self, ctx, SharedMessage::SwitchRosterSlot(other_id)),
+                    None => Component::update(self, ctx, SharedMessage::NewRosterSlot),
+                }
+            }
+
+            SharedMessage::TabEvent(event) => {
+                // A tab that doesn't save follows the one that does.
+                let should_reload = match event {
+                    TabEvent::CampaignSaved | TabEvent::RosterSaved => !self.is_tab_writer(),
+                    TabEvent::WriterChanged(is_writer) => !is_writer,
+                };
+                if should_reload {
+                    storage_backend::load_texts(self.storage_backend.clone(), &RELOADED_STATE_KEYS, ctx.link().callback(SharedMessage::ReloadSavedState));
+                }
+                true
+            }
+
+            SharedMessage::ReloadSavedState(texts) => {
+                self.reload_saved_state(texts);
+                true
+            }
+
+            SharedMessage::RestoreSnapshot(id) => {
+                if let Some(snapshot) = self.recovery_snapshots.iter().find(|snapshot| snapshot.id == id).cloned() {
+                    self.recovery_snapshots.clear();
+                    self.roster.batch(|current| *current = snapshot.roster);
+                }
+                true
+            }
+
+            SharedMessage::DismissRecovery => {
+                self.recovery_snapshots.clear();
+                true
+            }
+
+            SharedMessage::TakeOverWriting => {
+                if let Some(tabs) = &self.tabs {
+                    tabs.take_over();
+                }
+                self._session_listener = Some(session::mark_running());
+                self.sync_campaign_roster();
+                self.autosave_roster();
+                true
+            }
+
+            SharedMessage::ToggleRepairWizard => {
+                self.is_repair_wizard_open = !self.is_repair_wizard_open;
+                true
+            }
+
+            SharedMessage::RepairDropBadElements(index) => {
+                if let Some(item) = self.needs_attention.get_mut(index) {
+                    let dropped = repair::drop_bad_elements(&mut item.roster);
+                    log::info!("Dropped {} bad elements", dropped);
+                    item.recheck();
+                }
+                true
+            }
+
+            SharedMessage::RepairResetPoints(index) => {
+                if let Some(item) = self.needs_attention.get_mut(index) {
+                    let changed = repair::reset_points(&mut item.roster);
+                    log::info!("Reset the points of {} elements", changed);
+                    item.recheck();
+                }
+                true
+            }
 
-                if self.selected_index == Some(index) {
-                    self.selected_index = None;
+            SharedMessage::UseRepairedRoster(index) => {
+                if index < self.needs_attention.len() {
+                    let item = self.needs_attention.remove(index);
+                    self.open_roster("Repaired roster", item.roster);
+                    self.is_repair_wizard_open = !self.needs_attention.is_empty();
                 }
-                else {
-                    self.selected_index = Some(index); 
+                true
+            }
+
+            SharedMessage::DiscardNeedsAttention(index) => {
+                if index < self.needs_attention.len() {
+                    // What couldn't be loaded is gone for good, the rest is
+                    // only replaced by the current roster.
+                    if self.needs_attention.remove(index).source == IntegritySource::UnreadableCampaign {
+                        self.save_campaign();
+                    }
+                    self.sync_campaign_roster();
+                    self.autosave_roster();
+                    self.is_repair_wizard_open = !self.needs_attention.is_empty();
                 }
                 true
             }
 
-            SharedMessage::DeselectElements => {
-                self.selected_index = None;
+            SharedMessage::CopyProblemReport => {
+                if let Some(report) = &self.problem_report {
+                    clipboard::copy_text(report);
+                }
+                false
+            }
+
+            SharedMessage::UpdateSettings(settings) => {
+                self.apply_settings(settings);
+                self.settings.save(&*self.storage_backend);
                 true
             }
 
+            SharedMessage::LogoFileSelected(file) => {
+                let file = gloo::file::File::from(file);
+                if file.size() > MAX_LOGO_BYTES {
+                    self.load_error = Some(format!("The logo is too large, it must be under {} KB.", MAX_LOGO_BYTES / 1024));
+                    return true;
+                }
+                let link = ctx.link().clone();
+                self.logo_reader = Some(gloo::file::callbacks::read_as_data_url(&file, move |result| {
+                    link.send_message(SharedMessage::LogoFileLoaded(result.map_err(|e| e.to_string())));
+                }));
+                false
+            }
+
+            SharedMessage::LogoFileLoaded(result) => {
+                self.logo_reader = None;
+                match result {
+                    Ok(data_url) => {
+                        let mut settings = self.settings.clone();
+                        settings.print_branding.logo = data_url;
+                        Component::update(self, ctx, SharedMessage::UpdateSettings(settings))
+                    }
+
+                    Err(e) => {
+                        self.load_error = Some(format!("The logo couldn't be loaded: {}", e));
+                        true
+                    }
+                }
+            }
+
+            SharedMessage::EventPackFileSelected(file) => {
+                let link = ctx.link().clone();
+                self.event_pack_reader = Some(gloo::file::callbacks::read_as_text(&gloo::file::File::from(file), move |result| {
+                    link.send_message(SharedMessage::EventPackFileLoaded(result.map_err(|e| e.to_string())));
+                }));
+                false
+            }
+
+            SharedMessage::EventPackFileLoaded(result) => {
+                self.event_pack_reader = None;
+                match result.and_then(|json| EventPack::from_json(&json)) {
+                    Ok(pack) => {
+                        self.import_notes = pack.get_import_notes();
+                        if !deadline::set_deadline(pack.submission_deadline.as_deref()) {
+                            self.import_notes.push("The submission deadline of the event couldn't be read, it's left out".to_string());
+                        }
+                        self.load_error = None;
+                        let mut settings = self.settings.clone();
+                        settings.join_event(pack);
+                        Component::update(self, ctx, SharedMessage::UpdateSettings(settings))
+                    }
+
+                    Err(e) => {
+                        self.load_error = Some(format!("The event pack couldn't be imported: {}", e));
+                        true
+                    }
+                }
+            }
+
+            SharedMessage::LeaveEvent => {
+                let mut settings = self.settings.clone();
+                settings.event_pack = None;
+                Component::update(self, ctx, SharedMessage::UpdateSettings(settings))
+            }
+
+            SharedMessage::SetCanvasZoom(percent) => {
+                let mut settings = self.settings.clone();
+                settings.canvas_zoom_percent = percent;
+                Component::update(self, ctx, SharedMessage::UpdateSettings(settings))
+            }
+
+            SharedMessage::ToggleDeploymentBoard => {
+                let mut settings = self.settings.clone();
+                settings.show_deployment_board = !settings.show_deployment_board;
+                Component::update(self, ctx, SharedMessage::UpdateSettings(settings))
+            }
+
+            SharedMessage::ToggleSwapDialog => {
+                self.is_swap_dialog_open = !self.is_swap_dialog_open;
+                true
+            }
+
+            SharedMessage::SwapElements(name, replacement) => {
+                self.roster.batch(|roster| {
+                    swap::swap_elements(roster, &name, &replacement);
+                });
+                self.is_swap_dialog_open = false;
+                true
+            }
+
+            SharedMessage::TogglePrintDialog => {
+                self.is_print_dialog_open = !self.is_print_dialog_open;
+                true
+            }
+
+            SharedMessage::UpdatePrintOptions(options) => {
+                self.roster.execute(RosterCommand::SetPrintOptions(options));
+                true
+            }
+
+            #[cfg(feature = "print")]
+            SharedMessage::PrintRoster => {
+                if !deadline::confirm_export() {
+                    return false;
+                }
+                let roster = self.roster.borrow();
+                print::print_document(&print::build_print_document(&roster, &roster.metadata.print_options, &self.settings.print_branding));
+                false
+            }
+
+            // A second click stops the reading.
+            SharedMessage::ReadRosterAloud => {
+                if speech::is_speaking() {
+                    speech::stop();
+                } else {
+                    speech::speak(&speech::build_readout(&self.roster.borrow()));
+                }
+                false
+            }
+
+            SharedMessage::TogglePlayMode => {
+                self.is_play_mode_open = !self.is_play_mode_open;
+                true
+            }
+
+            SharedMessage::TogglePresentationDialog => {
+                self.is_presentation_dialog_open = !self.is_presentation_dialog_open;
+                true
+            }
+
+            SharedMessage::OpenOverlay(options) => {
+                if let Some(url) = self.get_overlay_url(&options) {
+                    if let Err(e) = gloo::utils::window().open_with_url_and_target(&url, "_blank") {
+                        log::warn!("Error opening the overlay: {:?}", e);
+                    }
+                }
+                false
+            }
+
+            SharedMessage::CopyOverlayLink(options) => {
+                if let Some(url) = self.get_overlay_url(&options) {
+                    clipboard::copy_text(&url);
+                }
+                false
+            }
+
+            SharedMessage::PreloadProgress(done, total) => {
+                self.preload_progress = if done < total { Some((done, total)) } else { None };
+                true
+            }
+
+            SharedMessage::ChangeSelection(change) => {
+                self.selection.apply(change)
+            }
+
             _ => false // Passing to the child objects to be handled.
         }    
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        profiler::record_render("App");
+        if let Some(options) = &self.overlay {
+            return html! { <Overlay roster={self.roster.clone()} options={options.clone()} /> };
+        }
+
 
         // Checking the information on the selected unit to pass to the right_bar
-        let (selected_element_is_unit, selected_unit_has_character) = if let Some(index) = self.selected_index {
-            if let Some(element) = self.roster.borrow().elements.get(index) {
-                match element {
-                    RosterElement::ElemUnit(unit) => (true, !unit.attached_elements.is_empty()),
-                    _ => (false, false),
-                }
-            } else {
-                (false, false)
+        let (selected_element_is_unit, selected_unit_has_character) = if let Some(index) = self.selection.get_focused_index(&self.roster.borrow()) {
+            match self.roster.borrow().elements.get(index).map(|entry| &entry.element) {
+                Some(RosterElement::ElemUnit(unit)) => (true, !unit.attached_elements.is_empty()),
+                _ => (false, false),
             }
         } else {
             (false, false)
         };
 
+        // Only looked for when asked, it goes through the whole catalog.
+        let catalog_changes = (self.is_catalog_changes_open && self.roster.borrow().has_catalog_changed())
+            .then(|| provenance::get_catalog_changes(&self.roster.borrow()));
+
+        let selection_context = SelectionContext {selection: self.selection.clone(), on_change: self.on_selection_change.clone()};
+
         html! {
-            <div class={if self.is_dark_mode { "app dark-mode" } else { "app" }}>
+        <ContextProvider<SelectionContext> context={selection_context}>
+            <div class={classes!("app", self.is_dark_mode.then_some("dark-mode"), self.settings.colorblind_safe.then_some("colorblind-safe"))}>
                 <div class="top-menu">
                     <TopMenu 
                         on_load_roster = {ctx.link().callback(|_| SharedMessage::LoadRoster)} 
                         on_clear_roster = {ctx.link().callback(|_| SharedMessage::ClearRoster)} 
                         on_save_roster = {ctx.link().callback(|_| SharedMessage::SaveRoster)} 
                         on_toggle_theme = {ctx.link().callback(|_| SharedMessage::ToggleTheme)}
+                        on_toggle_settings = {ctx.link().callback(|_| SharedMessage::ToggleSettings)}
+                        on_toggle_campaign = {ctx.link().callback(|_| SharedMessage::ToggleCampaignDashboard)}
+                        on_toggle_scenarios = {ctx.link().callback(|_| SharedMessage::ToggleScenarioEditor)}
+                        on_toggle_print = {ctx.link().callback(|_| SharedMessage::TogglePrintDialog)}
+                        on_read_aloud = {ctx.link().callback(|_| SharedMessage::ReadRosterAloud)}
+                        on_toggle_history = {ctx.link().callback(|_| SharedMessage::ToggleHistoryPanel)}
+                        on_toggle_hide_points = {ctx.link().callback(|_| SharedMessage::ToggleHidePoints)}
+                        on_toggle_presentation = {ctx.link().callback(|_| SharedMessage::TogglePresentationDialog)}
+                        on_toggle_play_mode = {ctx.link().callback(|_| SharedMessage::TogglePlayMode)}
+                        on_toggle_repair_wizard = {ctx.link().callback(|_| SharedMessage::ToggleRepairWizard)}
+                        on_toggle_roster_manager = {ctx.link().callback(|_| SharedMessage::ToggleRosterManager)}
+                        on_copy_share_link = {ctx.link().callback(|_| SharedMessage::CopyShareLink)}
+                        on_toggle_qr = {ctx.link().callback(|_| SharedMessage::ToggleQrDialog)}
+                        on_copy_roster = {ctx.link().callback(|_| SharedMessage::CopyRoster)}
+                        on_export_image = {ctx.link().callback(|_| SharedMessage::ExportImage)}
+                        on_push_roster = {ctx.link().callback(|_| SharedMessage::PushRoster)}
+                        on_pull_roster = {ctx.link().callback(|_| SharedMessage::PullRoster)}
+                        is_sync_enabled = {self.sync_client.is_some()}
+                        on_toggle_swap = {ctx.link().callback(|_| SharedMessage::ToggleSwapDialog)}
+                        needs_attention_count = {self.needs_attention.len()}
+                        are_points_hidden = {self.settings.hide_points}
+                        features = {self.features.clone()}
                         is_dark_mode = {self.is_dark_mode}
                     />
+                    if let Some((done, total)) = self.preload_progress {
+                        <div class="preload-progress">
+                            <progress max={total.to_string()} value={done.to_string()} />
+                            { format!("Loading images {}/{}", done, total) }
+                        </div>
+                    }
+                    if let Some(countdown) = deadline::get_countdown_label() {
+                        <div class={classes!("deadline-banner", deadline::is_past_deadline().then_some("over-limit"))}>{ countdown }</div>
+                    }
+                    if let Some(error) = &self.load_error {
+                        <div class="load-error over-limit">
+                            { error }
+                            <button onclick={ctx.link().callback(|_| SharedMessage::DismissLoadError)}>{"Dismiss"}</button>
+                        </div>
+                    }
+                    if !self.import_notes.is_empty() {
+                        <div class="import-notes">
+                            {"Imported, with some notes:"}
+                            <ul>
+                                { for self.import_notes.iter().map(|note| html! { <li>{ note }</li> }) }
+                            </ul>
+                            <button onclick={ctx.link().callback(|_| SharedMessage::DismissImportNotes)}>{"Dismiss"}</button>
+                        </div>
+                    }
+                    if self.roster.borrow().has_catalog_changed() {
+                        <div class="catalog-changes">
+                            { format!("This roster was built with another version of the catalog than this one ({}), some points may have changed.", armylist::get_catalog_edition()) }
+                            <button onclick={ctx.link().callback(|_| SharedMessage::ToggleCatalogChanges)}>
+                                { if catalog_changes.is_some() { "Hide Changes" } else { "Show Changes" } }
+                            </button>
+                            <button title="Stop warning, the roster is checked against this catalog from now on" onclick={ctx.link().callback(|_| SharedMessage::PinCatalog)}>{"Use This Catalog"}</button>
+                            if let Some(changes) = &catalog_changes {
+                                if changes.is_empty() {
+                                    <div>{"The points of the elements in the roster are the same."}</div>
+                                } else {
+                                    <ul>
+                                        { for changes.iter().map(|change| html! { <li>{ change }</li> }) }
+                                    </ul>
+                                }
+                            }
+                        </div>
+                    }
+                    if !self.is_tab_writer() {
+                        <div class="tab-warning over-limit">
+                            {"The app is open in another tab, changes made here aren't saved."}
+                            <button onclick={ctx.link().callback(|_| SharedMessage::TakeOverWriting)}>{"Edit Here"}</button>
+                        </div>
+                    }
                 </div>
                 <div class="left-bar">
                     <LeftBar
@@ -268,28 +1394,131 @@ impl Component for App {
                     />
                 </div>
                 <div class="main-canvas">
-                    <MainCanvas 
-                        roster = {self.roster.clone()} 
-                        on_roster_updated = {ctx.link().callback(|_| SharedMessage::NotifyRosterUpdated)}
-                        is_dark_mode = {self.is_dark_mode}
-                        on_reorder = {ctx.link().callback(|_| SharedMessage::ReorderElements)} 
-                        selected_index={self.selected_index} 
-                        on_select_element={ctx.link().callback(|index| SharedMessage::SelectElement(index))} 
-                        />
+                    if self.is_restoring {
+                        <div class="canvas-skeleton">
+                            { for (0..3).map(|_| html! { <div class="skeleton-card" /> }) }
+                        </div>
+                    } else {
+                        <MainCanvas 
+                            roster = {self.roster.clone()} 
+                            is_dark_mode = {self.is_dark_mode}
+                            on_edit_element={ctx.link().callback(SharedMessage::OpenCampaignEditor)}
+                            double_click_action={self.settings.double_click_action}
+                            is_color_coded={self.settings.color_code_types}
+                            zoom_percent={self.settings.get_canvas_zoom_percent()}
+                            on_zoom={ctx.link().callback(SharedMessage::SetCanvasZoom)}
+                            is_deployment_board={self.settings.show_deployment_board}
+                            group_cap_percent={self.settings.group_cap_percent}
+                            house_rules={self.settings.get_house_rules().to_vec()}
+                            on_toggle_deployment_board={ctx.link().callback(|_| SharedMessage::ToggleDeploymentBoard)}
+                            />
+                    }
                 </div>
                 <div class="right-bar">
                     <RightBar 
                         model = {self.right_bar_model.clone()}
                         on_element_action={ctx.link().callback(|msg| msg)}
-                        selected_element_index={self.selected_index} 
                         selected_element_is_unit={selected_element_is_unit}
                         selected_unit_has_character={selected_unit_has_character}
-                        on_deselect_elements={ctx.link().callback(|_| SharedMessage::DeselectElements)}  
                     />                    
                 </div>
 
+            if profiler::is_enabled() {
+                <ProfilerOverlay />
+            }
+
+            if let Some(report) = &self.problem_report {
+                <ReportDialog
+                    report = {report.clone()}
+                    on_report_action = {ctx.link().callback(|msg| msg)}
+                />
+            }
+
+            if self.is_roster_manager_open {
+                <RosterManager
+                    slots = {self.roster_slots.clone()}
+                    are_points_hidden = {self.settings.hide_points}
+                    storage_name = {self.storage_backend.get_name()}
+                    lists_per_entry = {self.settings.event_pack.as_ref().and_then(|pack| pack.lists_per_entry).filter(|lists| *lists > 1)}
+                    house_rules = {self.settings.get_house_rules().to_vec()}
+                    on_slot_action = {ctx.link().callback(|msg| msg)}
+                />
+            }
+
+            if self.is_repair_wizard_open {
+                <RepairWizard
+                    items = {self.needs_attention.clone()}
+                    on_repair_action = {ctx.link().callback(|msg| msg)}
+                />
+            }
+
+            if !self.recovery_snapshots.is_empty() && !self.is_repair_wizard_open {
+                <RecoveryPrompt
+                    snapshots = {self.recovery_snapshots.clone()}
+                    are_points_hidden = {self.settings.hide_points}
+                    on_recovery_action = {ctx.link().callback(|msg| msg)}
+                />
+            }
+
+            if self.is_settings_open {
+                <SettingsPanel
+                    settings = {self.settings.clone()}
+                    features = {self.features.clone()}
+                    points_limit = {self.roster.borrow().get_points_limit()}
+                    on_update_settings = {ctx.link().callback(|msg| msg)}
+                    on_close = {ctx.link().callback(|msg| msg)}
+                />
+            }
+
+            if let Some(id) = &self.campaign_editor_id {
+                if let Some(entry) = self.roster.borrow().find_entry(id).map(|index| self.roster.borrow().elements[index].clone()) {
+                    <CampaignEditor
+                        id = {id.clone()}
+                        name = {entry.element.get_name()}
+                        record = {entry.campaign.clone()}
+                        on_update = {ctx.link().callback(|msg| msg)}
+                        on_close = {ctx.link().callback(|msg| msg)}
+                    />
+                }
+            }
+
+            { self.view_editors(ctx) }
+
+            if self.is_swap_dialog_open {
+                <SwapDialog
+                    roster = {self.roster.clone()}
+                    on_swap_action = {ctx.link().callback(|msg| msg)}
+                />
+            }
+
+            { self.view_print_dialog(ctx) }
+
+            if self.is_play_mode_open && self.features.is_enabled(Feature::PlayMode) {
+                <PlayMode
+                    roster = {self.roster.clone()}
+                    on_play_action = {ctx.link().callback(|msg| msg)}
+                    sync = {self.settings.sync.clone()}
+                />
+            }
+
+            if let Some(toast) = &self.toast {
+                <div class="toast" role="status">{ toast }</div>
+            }
+            { self.view_qr_dialog(ctx) }
+            if self.is_presentation_dialog_open && self.features.is_enabled(Feature::Presentation) {
+                <PresentationDialog on_presentation_action = {ctx.link().callback(|msg| msg)} />
+            }
+
+            if self.is_history_panel_open {
+                <HistoryPanel
+                    history = {self.history.clone()}
+                    revisions = {self.roster_slots.get_active_revisions().cloned()}
+                    on_history_action = {ctx.link().callback(|msg| msg)}
+                />
+            }
+
             // File Selection Popup
-            <input type="file" ref={self.file_input_ref.clone()} style="display: none" onchange={
+            <input type="file" accept=".json,application/json,.ros,.rosz" ref={self.file_input_ref.clone()} style="display: none" onchange={
                 let link_clone_outer = ctx.link().clone(); // Clone the link outside of the callback
                 let link_clone_inner = link_clone_outer.clone(); // Clone the link for the inner closure
                 link_clone_outer.callback(move |event: web_sys::Event| {
@@ -300,7 +1529,13 @@ impl Component for App {
                                     
                                     // Get the name of the file
                                     let file_name = file.name();
-                                    console::log_1(&format!("Selected file name: {}", file_name).into());
+                                    log::debug!("Selected file name: {}", file_name);
+
+                                    // BattleScribe rosters are read as bytes, they can be zipped.
+                                    if battlescribe::is_battlescribe_file(&file_name) {
+                                        input.set_value("");
+                                        return SharedMessage::BattleScribeFileSelected(file);
+                                    }
                         
                                     // Read the content of the file
                                     let file_reader = web_sys::FileReader::new().unwrap();
@@ -323,6 +1558,8 @@ impl Component for App {
                         
                                     file_reader_rc.read_as_text(&file).unwrap();
                                 }
+                                // Picking the same file again is a change too.
+                                input.set_value("");
                             }
                         }
                     }
@@ -330,6 +1567,318 @@ impl Component for App {
                 })
             }/>
         </div>
+        </ContextProvider<SelectionContext>>
+        }
+    }
+}
+
+// The panels behind cargo features (see Cargo.toml), drawn by the builds
+// that have them.
+impl App {
+    #[cfg(feature = "editors")]
+    fn view_editors(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <>
+            if self.is_campaign_dashboard_open {
+                <CampaignDashboard
+                    campaign = {self.campaign.clone()}
+                    on_campaign_action = {ctx.link().callback(|msg| msg)}
+                />
+            }
+
+            if self.is_scenario_editor_open {
+                <ScenarioEditor
+                    storage_backend = {self.storage_backend.clone()}
+                    attached_scenario = {self.roster.borrow().scenario.clone()}
+                    on_scenario_action = {ctx.link().callback(|msg| msg)}
+                />
+            }
+            </>
         }
     }
+
+    #[cfg(not(feature = "editors"))]
+    fn view_editors(&self, _: &Context<Self>) -> Html {
+        Html::default()
+    }
+
+    #[cfg(feature = "print")]
+    fn view_print_dialog(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            if self.is_print_dialog_open {
+                <PrintDialog
+                    options = {self.roster.borrow().metadata.print_options.clone()}
+                    preview = {print::build_print_document(&self.roster.borrow(), &self.roster.borrow().metadata.print_options, &self.settings.print_branding)}
+                    on_print_action = {ctx.link().callback(|msg| msg)}
+                />
+            }
+        }
+    }
+
+    #[cfg(not(feature = "print"))]
+    fn view_print_dialog(&self, _: &Context<Self>) -> Html {
+        Html::default()
+    }
+
+    #[cfg(feature = "qr")]
+    fn view_qr_dialog(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            if self.is_qr_dialog_open {
+                <QrDialog url={share::get_share_url(&self.roster.borrow())} on_qr_action={ctx.link().callback(|msg| msg)} />
+            }
+        }
+    }
+
+    #[cfg(not(feature = "qr"))]
+    fn view_qr_dialog(&self, _: &Context<Self>) -> Html {
+        Html::default()
+    }
+}
+
+impl App {
+    // The linked campaign follows every change of the roster, as long as
+    // it's the roster of the campaign slot that's open.
+    fn sync_campaign_roster(&mut self) {
+        if self.is_campaign_roster_quarantined() || !self.roster_slots.is_campaign_slot_active() {
+            return;
+        }
+        if let Some(campaign) = &mut self.campaign {
+            campaign.register_roster_change(&self.roster.borrow());
+        }
+        self.save_campaign();
+    }
+
+    // Only the writer tab saves, the others would overwrite its changes. A
+    // campaign that couldn't be loaded stays until it's discarded.
+    fn save_campaign(&self) {
+        if !self.is_tab_writer() || self.needs_attention.iter().any(|item| item.source == IntegritySource::UnreadableCampaign) {
+            return;
+        }
+        storage_backend::save_value(&*self.storage_backend, CAMPAIGN_KEY, &self.campaign);
+        tabs::announce_saved(CAMPAIGN_KEY);
+    }
+
+    // The settings take effect, without being saved. What was saved so far
+    // goes along to a new backend.
+    fn apply_settings(&mut self, settings: Settings) {
+        deadline::set_deadline(settings.get_submission_deadline());
+        paths::set_runtime_asset_base(settings.asset_base.clone());
+        card_svg::set_points_hidden(settings.hide_points);
+        audio::set_cue_volume(settings.get_cue_volume());
+        profiler::set_enabled(settings.show_profiler || App::is_profiler_requested());
+        self.features = App::get_feature_flags(&settings);
+        if settings.sync != self.settings.sync {
+            self.sync_client = sync::get_sync_client(&settings.sync);
+        }
+        if settings.storage != self.settings.storage || (settings.storage == StorageKind::Server && settings.sync != self.settings.sync) {
+            let previous = std::mem::replace(&mut self.storage_backend, storage_backend::get_storage_backend(settings.storage, &settings.sync));
+            if self.is_tab_writer() {
+                storage_backend::copy_value(&*previous, self.storage_backend.clone(), SCENARIOS_KEY);
+                storage_backend::save_value(&*self.storage_backend, SNAPSHOTS_KEY, &self.snapshots);
+            }
+            self.save_roster_slots();
+            self.save_campaign();
+            self.autosave_roster();
+        }
+        self.settings = settings;
+    }
+
+    fn is_tab_writer(&self) -> bool {
+        self.tabs.as_ref().is_none_or(TabCoordinator::is_writer)
+    }
+
+    // Saved after every change, by the writer tab and once the saved state
+    // is restored, not to replace it with the empty roster of the start.
+    fn autosave_roster(&self) {
+        if self.overlay.is_some() || self.is_restoring || !self.is_tab_writer() || self.is_autosave_quarantined() {
+            return;
+        }
+        storage_backend::save_value(&*self.storage_backend, ROSTER_KEY, &*self.roster.borrow());
+        tabs::announce_saved(ROSTER_KEY);
+    }
+
+    // The address of the page follows the roster, see models::share.
+    fn update_share_link(&self) {
+        if self.overlay.is_none() && !self.is_restoring {
+            share::update_page_fragment(&self.roster.borrow());
+        }
+    }
+
+    fn take_snapshot(&mut self) {
+        if self.overlay.is_some() || self.is_restoring || !self.is_tab_writer() {
+            return;
+        }
+        if self.snapshots.push(&self.roster.borrow(), &clock::get_timestamp()) {
+            storage_backend::save_value(&*self.storage_backend, SNAPSHOTS_KEY, &self.snapshots);
+        }
+    }
+
+    fn save_roster_slots(&self) {
+        if self.is_tab_writer() {
+            storage_backend::save_value(&*self.storage_backend, SLOTS_KEY, &self.roster_slots);
+            tabs::announce_saved(SLOTS_KEY);
+        }
+    }
+
+    fn sync_active_slot(&mut self) {
+        if self.overlay.is_some() || self.is_restoring || !self.is_tab_writer() {
+            return;
+        }
+        if self.roster_slots.update_active(&self.roster.borrow(), &clock::get_timestamp()) {
+            self.save_roster_slots();
+        }
+    }
+
+    fn reload_saved_state(&mut self, texts: Vec<Option<String>>) {
+        let [slots_json, campaign_json, autosave_json] = texts.try_into().unwrap_or_default();
+        self.campaign = integrity::load_campaign(campaign_json).0;
+        self.roster_slots = storage_backend::parse_value(SLOTS_KEY, slots_json);
+        let stored_roster = match &self.campaign {
+            Some(campaign) if self.roster_slots.is_campaign_slot_active() && !self.is_campaign_roster_quarantined() => Some(campaign.roster.clone()),
+            _ if !self.is_autosave_quarantined() => integrity::load_autosave(autosave_json).0,
+            _ => None,
+        };
+        if let Some(roster) = stored_roster {
+            if roster != *self.roster.borrow() {
+                self.open_roster("Changed in another tab", roster);
+            }
+        }
+    }
+
+    // The stored campaign roster stays as it is until it's repaired or discarded.
+    fn is_campaign_roster_quarantined(&self) -> bool {
+        self.needs_attention.iter().any(|item| item.source == IntegritySource::CampaignRoster)
+    }
+
+    fn is_autosave_quarantined(&self) -> bool {
+        self.needs_attention.iter().any(|item| item.source.is_autosave())
+    }
+
+    // Thematic default name, from the faction of the roster and today's date.
+    fn generate_roster_name(roster: &Roster) -> String {
+        let roll = (js_sys::Math::random() * u32::MAX as f64) as u32;
+        roster_names::generate_roster_name(roster, roster.get_points_limit(), &clock::get_date(), roll)
+    }
+
+    // Address of this very page, opened as an overlay of the current roster.
+    fn get_overlay_url(&self, options: &OverlayOptions) -> Option<String> {
+        let fragment = match options.to_fragment(&self.roster.borrow()) {
+            Ok(fragment) => fragment,
+            Err(e) => {
+                log::warn!("Error serializing roster: {:?}", e);
+                return None;
+            }
+        };
+        let location = gloo::utils::window().location();
+        let page = format!("{}{}{}",
+            location.origin().ok()?, location.pathname().ok()?, location.search().unwrap_or_default());
+        Some(page + &fragment)
+    }
+
+    // Ctrl+Z and Ctrl+Shift+Z, text fields keep their own undo.
+    fn listen_for_undo(ctx: &Context<Self>) -> EventListener {
+        let link = ctx.link().clone();
+        EventListener::new(&gloo::utils::window(), "keydown", move |event| {
+            let event: &KeyboardEvent = event.unchecked_ref();
+            let is_typing = event.target()
+                .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+                .is_some_and(|element| matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT") || element.closest("[contenteditable]").ok().flatten().is_some());
+            if !(event.ctrl_key() || event.meta_key()) || !event.key().eq_ignore_ascii_case("z") || is_typing {
+                return;
+            }
+            event.prevent_default();
+            link.send_message(if event.shift_key() { SharedMessage::Redo } else { SharedMessage::Undo });
+        })
+    }
+
+    // Puts another roster in place of this one, e.g. a file loaded or
+    // another slot. It starts a history of its own, the edits of the roster
+    // left behind don't apply to it.
+    fn open_roster(&mut self, label: &str, roster: Roster) {
+        self.selection.apply(SelectionChange::Clear);
+        self.roster.batch(|current| *current = roster.clone());
+        self.roster.take_executed();
+        self.history = History::new(label);
+        self.was_over_limit = roster.is_over_points_limit(roster.get_points_limit());
+    }
+
+    // Undoing and redoing apply the commands of the history, which already
+    // has them.
+    fn replay(&mut self, command: Option<RosterCommand>) {
+        if let Some(command) = command {
+            self.roster.replay(command);
+        }
+    }
+
+    fn show_toast(&mut self, ctx: &Context<Self>, toast: String) {
+        self.toast = Some(toast);
+        let link = ctx.link().clone();
+        self._toast_timeout = Some(Timeout::new(TOAST_MS, move || link.send_message(SharedMessage::HideToast)));
+    }
+
+    // Debug builds only: a broken roster means some edit is buggy.
+    fn check_roster_invariants(&self) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        if let Err(errors) = self.roster.borrow().check_invariants() {
+            for error in errors {
+                log::error!("Roster invariant broken: {}", error);
+            }
+        }
+    }
+
+
+    fn get_feature_flags(settings: &Settings) -> FeatureFlags {
+        let search = gloo::utils::window().location().search().unwrap_or_default();
+        FeatureFlags::new(&settings.feature_flags, &search)
+    }
+
+    // The profiler can also be turned on from the url, e.g. ?profiler=1, to
+    // look into a slow roster without touching the settings.
+    fn is_profiler_requested() -> bool {
+        gloo::utils::window().location().search().ok()
+            .is_some_and(|search| search.trim_start_matches('?').split('&').any(|pair| pair == "profiler=1"))
+    }
+
+    // The ids of the app are always random, see core::ids: two people
+    // opening the same link mustn't make the same ones.
+    fn get_random_seed() -> u64 {
+        let random = || (js_sys::Math::random() * u32::MAX as f64) as u64;
+        (random() << 32) | random()
+    }
+
+    // Another time than now from the url, e.g. ?clock=2026-05-01T17:55:00Z,
+    // see models::clock. Debug builds only.
+    #[cfg(debug_assertions)]
+    fn get_requested_clock() -> Option<ShiftedClock> {
+        let search = gloo::utils::window().location().search().unwrap_or_default();
+        let time = search.trim_start_matches('?').split('&').find_map(|pair| pair.strip_prefix("clock="))?;
+        ShiftedClock::starting_at(&js_sys::decode_uri_component(time).ok()?.as_string()?)
+    }
+
+    // Army lists are built once, on first use.
+    fn get_army_list(&mut self, faction: Faction) -> &armylist::ArmyList {
+        let index = match self.army_lists.iter().position(|(cached, _)| *cached == faction) {
+            Some(index) => index,
+            None => {
+                self.army_lists.push((faction, armylist::ArmyList::new(faction)));
+                self.army_lists.len() - 1
+            }
+        };
+        &self.army_lists[index].1
+    }
+
+    // Switching faction starts preloading all its images in the background.
+    fn set_current_faction(&mut self, ctx: &Context<Self>, faction: Faction) {
+        if self.current_faction == Some(faction) {
+            return;
+        }
+        self.current_faction = Some(faction);
+
+        let images = self.get_army_list(faction).get_images().iter()
+            .map(|image| paths::image_url(image))
+            .collect();
+        preloader::preload_images(images, ctx.link().callback(|(done, total)| SharedMessage::PreloadProgress(done, total)));
+    }
 }
@@ -0,0 +1,3 @@
+// Handling of the static assets (images for now).
+pub mod preloader;
+pub mod paths;
@@ -0,0 +1,35 @@
+// Resolution of the static assets urls.
+// The base defaults to ./static/ but can be changed at build time with the
+// FSD_ASSET_BASE environment variable (e.g. for a CDN), and at runtime from
+// the settings, which take precedence over both.
+
+use std::cell::RefCell;
+
+const DEFAULT_ASSET_BASE: &str = "./static/";
+
+thread_local! {
+    static RUNTIME_ASSET_BASE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+pub fn set_runtime_asset_base(base: Option<String>) {
+    let base = base.filter(|base| !base.trim().is_empty());
+    RUNTIME_ASSET_BASE.with(|runtime_base| *runtime_base.borrow_mut() = base);
+}
+
+pub fn asset_base() -> String {
+    let base = RUNTIME_ASSET_BASE.with(|runtime_base| runtime_base.borrow().clone())
+        .or_else(|| option_env!("FSD_ASSET_BASE").map(|base| base.to_string()))
+        .unwrap_or_else(|| DEFAULT_ASSET_BASE.to_string());
+
+    // Always ending with a slash, so the paths can simply be appended.
+    if base.ends_with('/') { base } else { format!("{}/", base) }
+}
+
+pub fn asset_url(path: &str) -> String {
+    format!("{}{}", asset_base(), path.trim_start_matches('/'))
+}
+
+// Url of a catalog image, given its path relative to the images folder.
+pub fn image_url(image: &str) -> String {
+    asset_url(&format!("images/{}", image))
+}
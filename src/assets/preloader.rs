@@ -0,0 +1,77 @@
+// Preloading of the catalog images.
+// The images are fetched through detached <img> elements which are kept
+// alive in a cache, so the browser never drops them and the cards show up
+// immediately once an element is hovered or added.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlImageElement;
+use yew::Callback;
+
+thread_local! {
+    static IMAGE_CACHE: RefCell<HashMap<String, HtmlImageElement>> = RefCell::new(HashMap::new());
+    // Urls whose image couldn't be loaded, see core::lint
+    static FAILED_IMAGES: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+pub fn is_cached(url: &str) -> bool {
+    IMAGE_CACHE.with(|cache| cache.borrow().contains_key(url))
+}
+
+// The image once loaded, None while it's loading or if it failed.
+pub fn get_loaded_image(url: &str) -> Option<HtmlImageElement> {
+    IMAGE_CACHE.with(|cache| cache.borrow().get(url).cloned())
+        .filter(|image| image.complete() && image.natural_width() > 0)
+}
+
+pub fn has_failed(url: &str) -> bool {
+    FAILED_IMAGES.with(|failed| failed.borrow().contains(url))
+}
+
+// Starts loading all the given urls. on_progress receives (done, total)
+// every time an image is loaded or fails, and once right away.
+pub fn preload_images(urls: Vec<String>, on_progress: Callback<(usize, usize)>) {
+    let mut pending = Vec::<String>::new();
+    for url in urls {
+        if !is_cached(&url) && !pending.contains(&url) {
+            pending.push(url);
+        }
+    }
+
+    let total = pending.len();
+    let done = Rc::new(Cell::new(0));
+    on_progress.emit((0, total));
+
+    for url in pending {
+        let image = match HtmlImageElement::new() {
+            Ok(image) => image,
+            Err(_) => continue,
+        };
+
+        // Errors count as done too, the progress must reach the end anyway.
+        let on_finished = |failed_url: Option<String>| {
+            let done = done.clone();
+            let on_progress = on_progress.clone();
+            Closure::<dyn FnMut()>::new(move || {
+                if let Some(url) = &failed_url {
+                    FAILED_IMAGES.with(|failed| failed.borrow_mut().insert(url.clone()));
+                }
+                done.set(done.get() + 1);
+                on_progress.emit((done.get(), total));
+            })
+        };
+        let on_loaded = on_finished(None);
+        let on_failed = on_finished(Some(url.clone()));
+        image.set_onload(Some(on_loaded.as_ref().unchecked_ref()));
+        image.set_onerror(Some(on_failed.as_ref().unchecked_ref()));
+        on_loaded.forget();
+        on_failed.forget();
+
+        image.set_src(&url);
+        IMAGE_CACHE.with(|cache| cache.borrow_mut().insert(url, image));
+    }
+}
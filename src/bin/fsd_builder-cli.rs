@@ -0,0 +1,193 @@
+// Command line companion of the web app, for organizers and the CI of
+// community events: checks roster files and converts them, using the same
+// core as the builder. Only built with the "cli" feature:
+//
+//   cargo run --features cli --bin fsd_builder-cli -- validate --limit 60 roster.json
+//   cargo run --features cli --bin fsd_builder-cli -- convert --to text roster.json
+//   cargo run --features cli --bin fsd_builder-cli -- convert --to json roster.rosz
+//   cargo run --features cli --bin fsd_builder-cli -- catalog-hash delta.json
+//   cargo run --features cli --bin fsd_builder-cli -- fixture skirmish-30
+use std::io::Write;
+use std::process::ExitCode;
+
+use fsd_builder::core::armylist;
+use fsd_builder::core::battlescribe;
+use fsd_builder::core::catalog_delta::CatalogDelta;
+use fsd_builder::core::composed::ComposedElement;
+use fsd_builder::core::fixtures::FIXTURES;
+use fsd_builder::core::ids;
+use fsd_builder::core::provenance;
+use fsd_builder::core::roster::Roster;
+use fsd_builder::core::validation::Validator;
+
+const USAGE: &str = "Usage:
+  fsd_builder-cli validate [--limit POINTS] FILE...
+  fsd_builder-cli convert [--to json|canonical|binary|text] FILE
+  fsd_builder-cli catalog-hash [DELTA]
+  fsd_builder-cli fixture [--id-seed SEED] NAME";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.split_first() {
+        Some((command, rest)) if command == "validate" => validate(rest),
+        Some((command, rest)) if command == "convert" => convert(rest),
+        Some((command, rest)) if command == "catalog-hash" => catalog_hash(rest),
+        Some((command, rest)) if command == "fixture" => fixture(rest),
+        _ => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("{}", error);
+            ExitCode::from(2)
+        }
+    }
+}
+
+// Splits the arguments into the value of the given option and the files.
+fn parse_args<'a>(args: &'a [String], option: &str) -> Result<(Option<&'a str>, Vec<&'a str>), String> {
+    let mut value = None;
+    let mut files = Vec::<&str>::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == option {
+            value = Some(args.next().ok_or(format!("Missing value for {}", option))?.as_str());
+        } else if arg.starts_with("--") {
+            return Err(format!("Unknown option {}\n{}", arg, USAGE));
+        } else {
+            files.push(arg);
+        }
+    }
+    if files.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    Ok((value, files))
+}
+
+// Binary or JSON roster, a JSON file never starts like a binary one.
+// BattleScribe rosters are told apart by their extension.
+fn read_roster(path: &str) -> Result<Roster, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+    if battlescribe::is_battlescribe_file(path) {
+        let import = battlescribe::import_roster(&bytes).map_err(|e| format!("{}: {}", path, e))?;
+        for note in import.notes {
+            eprintln!("{}: {}", path, note);
+        }
+        return Ok(import.roster);
+    }
+    if let Ok(roster) = Roster::from_binary(&bytes) {
+        return Ok(roster);
+    }
+    let json = String::from_utf8(bytes).map_err(|_| format!("{}: not a valid roster", path))?;
+    Roster::from_json(&json).map_err(|e| format!("{}: not a valid roster ({})", path, e))
+}
+
+// One line per file, true if all of them are valid.
+fn validate(args: &[String]) -> Result<bool, String> {
+    let (limit, files) = parse_args(args, "--limit")?;
+    // The limit of each roster unless one is given.
+    let points_limit = limit.map(|limit| limit.parse::<u32>().map_err(|_| format!("Invalid points limit {}", limit))).transpose()?;
+
+    let mut all_valid = true;
+    for path in files {
+        let roster = match read_roster(path) {
+            Ok(roster) => roster,
+            Err(error) => {
+                println!("{}", error);
+                all_valid = false;
+                continue;
+            }
+        };
+        if roster.has_catalog_changed() {
+            eprintln!("{}: built with another version of the catalog", path);
+            for change in provenance::get_catalog_changes(&roster) {
+                eprintln!("{}: {}", path, change);
+            }
+        }
+        // The same rules as the app, against the limit given if there's one.
+        let mut checked = roster.clone();
+        checked.metadata.points_limit = points_limit.unwrap_or(roster.get_points_limit());
+        let errors = Validator::for_roster(None, &[]).get_errors(&checked);
+        if !errors.is_empty() {
+            println!("{}: INVALID, {}", path, errors.join(", "));
+            all_valid = false;
+        } else {
+            let hash = roster.get_content_hash().map_err(|e| e.to_string())?;
+            println!("{}: OK, {} points, hash {}", path, roster.get_total_points(), hash);
+        }
+    }
+    Ok(all_valid)
+}
+
+fn convert(args: &[String]) -> Result<bool, String> {
+    let (format, files) = parse_args(args, "--to")?;
+    let [path] = files[..] else {
+        return Err(USAGE.to_string());
+    };
+    let roster = read_roster(path)?;
+    match format.unwrap_or("json") {
+        "json" => println!("{}", serde_json::to_string_pretty(&roster).map_err(|e| e.to_string())?),
+        "canonical" => println!("{}", roster.to_canonical_json().map_err(|e| e.to_string())?),
+        "binary" => {
+            let bytes = roster.to_binary().map_err(|e| e.to_string())?;
+            std::io::stdout().write_all(&bytes).map_err(|e| e.to_string())?;
+        }
+        "text" => print!("{}", roster_text(&roster)),
+        format => return Err(format!("Unknown format {}\n{}", format, USAGE)),
+    }
+    Ok(true)
+}
+
+// Hash of the built-in catalog or, for publishing a catalog update (see
+// core::catalog_delta), the hashes to write in it.
+fn catalog_hash(args: &[String]) -> Result<bool, String> {
+    match args {
+        [] => println!("{}", armylist::get_catalog_hash()),
+        [path] => {
+            let json = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+            let delta = CatalogDelta::from_json(&json).map_err(|e| format!("{}: {}", path, e))?;
+            let (base_hash, result_hash) = armylist::get_delta_hashes(&delta);
+            println!("\"base_hash\": \"{}\",\n\"result_hash\": \"{}\"", base_hash, result_hash);
+        }
+        _ => return Err(USAGE.to_string()),
+    }
+    Ok(true)
+}
+
+// One of the sample rosters of core::fixtures, as JSON. The ids of its
+// entries follow from the seed (see core::ids), the same every time unless
+// another seed is given.
+fn fixture(args: &[String]) -> Result<bool, String> {
+    let names: Vec<&str> = FIXTURES.iter().map(|(name, _)| *name).collect();
+    let (seed, args) = match args {
+        [option, seed, rest @ ..] if option == "--id-seed" => (seed.parse::<u64>().map_err(|_| format!("Invalid id seed {}", seed))?, rest),
+        _ => (0, args),
+    };
+    ids::seed(seed);
+    let [name] = args else {
+        return Err(format!("{}\nFixtures: {}", USAGE, names.join(", ")));
+    };
+    let (_, build) = FIXTURES.iter().find(|(fixture_name, _)| fixture_name == name)
+        .ok_or(format!("Unknown fixture {}, one of: {}", name, names.join(", ")))?;
+    println!("{}", serde_json::to_string_pretty(&build()).map_err(|e| e.to_string())?);
+    Ok(true)
+}
+
+// Plain list of the roster, one entry per line.
+fn roster_text(roster: &Roster) -> String {
+    let mut text = String::new();
+    if !roster.metadata.name.is_empty() {
+        text += &format!("{}\n", roster.metadata.name);
+    }
+    for entry in &roster.elements {
+        let composed = ComposedElement::from(&entry.element);
+        text += &format!("{} ({} pts)", entry.get_counted_name(), entry.get_total_points());
+        if !composed.abilities.is_empty() {
+            text += &format!(" - {}", composed.abilities.join(", "));
+        }
+        text += "\n";
+    }
+    text += &format!("Total: {} pts\n", roster.get_total_points());
+    text
+}
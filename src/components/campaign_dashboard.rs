@@ -0,0 +1,183 @@
+use yew::prelude::*;
+
+// A common definition for all messages:
+use crate::shared_messages::SharedMessage;
+
+use crate::core::campaign::{BattleResult, Campaign};
+use crate::components::campaign_standings::CampaignStandings;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub campaign: Option<Campaign>,
+    pub on_campaign_action: Callback<SharedMessage>,
+}
+
+// Overview of the running campaign: results, resource points, the games
+// played and how many roster changes are still allowed before the next one.
+pub struct CampaignDashboard {
+    name_ref: NodeRef,
+    opponent_ref: NodeRef,
+    result_ref: NodeRef,
+    resource_points_ref: NodeRef,
+    notes_ref: NodeRef,
+}
+
+impl Component for CampaignDashboard {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        CampaignDashboard {
+            name_ref: NodeRef::default(),
+            opponent_ref: NodeRef::default(),
+            result_ref: NodeRef::default(),
+            resource_points_ref: NodeRef::default(),
+            notes_ref: NodeRef::default(),
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_action = ctx.props().on_campaign_action.clone();
+        html! {
+            <div class="modal-backdrop">
+                <div class="modal campaign-dashboard">
+                    {
+                        match &ctx.props().campaign {
+                            Some(campaign) => self.render_campaign(ctx, campaign),
+                            None => self.render_new_campaign(ctx),
+                        }
+                    }
+                    <CampaignStandings />
+                    <button onclick={on_action.reform(|_| SharedMessage::ToggleCampaignDashboard)}>{"Close"}</button>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl CampaignDashboard {
+    fn render_new_campaign(&self, ctx: &Context<Self>) -> Html {
+        let name_ref = self.name_ref.clone();
+        let on_start = ctx.props().on_campaign_action.reform(move |_| {
+            let name = name_ref.cast::<web_sys::HtmlInputElement>()
+                .map(|input| input.value())
+                .filter(|name| !name.trim().is_empty())
+                .unwrap_or_else(|| "Campaign".to_string());
+            SharedMessage::StartCampaign(name)
+        });
+
+        html! {
+            <>
+                <div class="modal-title">{"New Campaign"}</div>
+                <p>{"The current roster will be linked to the campaign."}</p>
+                <label class="settings-row">
+                    <span>{"Name"}</span>
+                    <input type="text" ref={self.name_ref.clone()} />
+                </label>
+                <button onclick={on_start}>{"Start Campaign"}</button>
+            </>
+        }
+    }
+
+    fn render_campaign(&self, ctx: &Context<Self>, campaign: &Campaign) -> Html {
+        let on_action = ctx.props().on_campaign_action.clone();
+        let (wins, draws, losses) = campaign.get_results();
+
+        let on_allowance_change = on_action.reform(|event: Event| {
+            let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+            SharedMessage::SetCampaignChangeAllowance(input.value().parse().unwrap_or(0))
+        });
+        let on_player_change = on_action.reform(|event: Event| {
+            let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+            SharedMessage::SetCampaignPlayer(input.value())
+        });
+        let on_territories_change = on_action.reform(|event: Event| {
+            let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+            SharedMessage::SetCampaignTerritories(input.value().parse().unwrap_or(0))
+        });
+
+        html! {
+            <>
+                <div class="modal-title">{ format!("Campaign - {}", campaign.name) }</div>
+                <div class="campaign-summary">
+                    <div>{ format!("Games: {} (W {} / D {} / L {})", campaign.battles.len(), wins, draws, losses) }</div>
+                    <div>{ format!("Resource Points: {}", campaign.get_resource_points()) }</div>
+                    <div class={if campaign.is_over_change_limit() { "over-limit" } else { "" }}>
+                        { format!("Roster changes since last game: {} of {}", campaign.changes_since_last_battle, campaign.allowed_changes_between_games) }
+                    </div>
+                </div>
+                <label class="settings-row">
+                    <span>{"Player"}</span>
+                    <input type="text" value={campaign.player.clone()} onchange={on_player_change} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Territories held"}</span>
+                    <input type="number" min="0" value={campaign.territories.to_string()} onchange={on_territories_change} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Changes allowed between games"}</span>
+                    <input type="number" min="0" value={campaign.allowed_changes_between_games.to_string()} onchange={on_allowance_change} />
+                </label>
+
+                <table class="campaign-battles">
+                    <tr><th>{"Date"}</th><th>{"Opponent"}</th><th>{"Result"}</th><th>{"RP"}</th><th>{"Points"}</th><th>{"Notes"}</th></tr>
+                    { for campaign.battles.iter().map(|battle| html! {
+                        <tr>
+                            <td>{ &battle.date }</td>
+                            <td>{ &battle.opponent }</td>
+                            <td>{ battle.result.get_label() }</td>
+                            <td>{ battle.resource_points }</td>
+                            <td>{ battle.roster.elements.iter().map(|entry| entry.get_total_points()).sum::<u32>() }</td>
+                            <td>{ &battle.notes }</td>
+                        </tr>
+                    }) }
+                </table>
+
+                { self.render_battle_form(ctx) }
+                <button onclick={on_action.reform(|_| SharedMessage::ExportCampaign)}>{"Export Campaign"}</button>
+                <button onclick={on_action.reform(|_| SharedMessage::EndCampaign)}>{"End Campaign"}</button>
+            </>
+        }
+    }
+
+    fn render_battle_form(&self, ctx: &Context<Self>) -> Html {
+        let (opponent_ref, result_ref, resource_points_ref, notes_ref) = (
+            self.opponent_ref.clone(), self.result_ref.clone(), self.resource_points_ref.clone(), self.notes_ref.clone());
+        let on_record = ctx.props().on_campaign_action.reform(move |_| {
+            let value = |node: &NodeRef| node.cast::<web_sys::HtmlInputElement>().map(|input| input.value()).unwrap_or_default();
+            let result_index = result_ref.cast::<web_sys::HtmlSelectElement>()
+                .map(|select| select.selected_index().max(0) as usize)
+                .unwrap_or(0);
+            SharedMessage::RecordBattle(
+                value(&opponent_ref),
+                BattleResult::ALL.get(result_index).copied().unwrap_or_default(),
+                value(&resource_points_ref).parse().unwrap_or(0),
+                value(&notes_ref))
+        });
+
+        html! {
+            <div class="campaign-battle-form">
+                <div class="modal-subtitle">{"Record a game"}</div>
+                <label class="settings-row">
+                    <span>{"Opponent"}</span>
+                    <input type="text" ref={self.opponent_ref.clone()} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Result"}</span>
+                    <select ref={self.result_ref.clone()}>
+                        { for BattleResult::ALL.iter().map(|result| html! { <option>{ result.get_label() }</option> }) }
+                    </select>
+                </label>
+                <label class="settings-row">
+                    <span>{"Resource Points earned"}</span>
+                    <input type="number" value="0" ref={self.resource_points_ref.clone()} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Notes"}</span>
+                    <input type="text" ref={self.notes_ref.clone()} />
+                </label>
+                <button onclick={on_record}>{"Record Game"}</button>
+            </div>
+        }
+    }
+}
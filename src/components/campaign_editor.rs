@@ -0,0 +1,123 @@
+use yew::prelude::*;
+
+// A common definition for all messages:
+use crate::shared_messages::SharedMessage;
+
+use crate::core::campaign::{CampaignRecord, Rank};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    // Id of the entry, wherever it's moved meanwhile
+    pub id: String,
+    pub name: String,
+    pub record: Option<CampaignRecord>,
+    pub on_update: Callback<SharedMessage>,
+    pub on_close: Callback<SharedMessage>,
+}
+
+// Modal to edit the campaign record of a roster entry between games.
+// Every change is sent up as a complete record.
+pub struct CampaignEditor {}
+
+impl Component for CampaignEditor {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        CampaignEditor {}
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let id = props.id.clone();
+
+        let tracking_toggle = {
+            let record = props.record.clone();
+            props.on_update.reform(move |_| {
+                let new_record = if record.is_some() { None } else { Some(CampaignRecord::default()) };
+                SharedMessage::UpdateCampaignRecord(id.clone(), new_record)
+            })
+        };
+
+        html! {
+            <div class="modal-backdrop">
+                <div class="modal campaign-editor">
+                    <div class="modal-title">{ format!("Campaign - {}", props.name) }</div>
+                    <label class="settings-row">
+                        <span>{"Track in campaign"}</span>
+                        <input type="checkbox" checked={props.record.is_some()} onchange={tracking_toggle} />
+                    </label>
+                    if let Some(record) = &props.record {
+                        { self.render_fields(ctx, record) }
+                    }
+                    <button onclick={props.on_close.reform(|_| SharedMessage::CloseCampaignEditor)}>{"Close"}</button>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl CampaignEditor {
+    fn render_fields(&self, ctx: &Context<Self>, record: &CampaignRecord) -> Html {
+        let id = &ctx.props().id;
+
+        // Each field updates its own part of a copy of the record.
+        let update = |apply: fn(&mut CampaignRecord, String)| {
+            let (id, record) = (id.clone(), record.clone());
+            ctx.props().on_update.reform(move |event: Event| {
+                let value = match event.target_dyn_into::<web_sys::HtmlInputElement>() {
+                    Some(input) => input.value(),
+                    None => event.target_unchecked_into::<web_sys::HtmlTextAreaElement>().value(),
+                };
+                let mut new_record = record.clone();
+                apply(&mut new_record, value);
+                SharedMessage::UpdateCampaignRecord(id.clone(), Some(new_record))
+            })
+        };
+
+        let on_rank_change = {
+            let (id, record) = (id.clone(), record.clone());
+            ctx.props().on_update.reform(move |event: Event| {
+                let select: web_sys::HtmlSelectElement = event.target_unchecked_into();
+                let mut new_record = record.clone();
+                new_record.rank = Rank::ALL.get(select.selected_index().max(0) as usize).copied().unwrap_or_default();
+                SharedMessage::UpdateCampaignRecord(id.clone(), Some(new_record))
+            })
+        };
+
+        html! {
+            <>
+                <label class="settings-row">
+                    <span>{"XP"}</span>
+                    <input type="number" min="0" value={record.xp.to_string()}
+                        onchange={update(|record, value| record.xp = value.parse().unwrap_or(record.xp))} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Rank"}</span>
+                    <select onchange={on_rank_change}>
+                        { for Rank::ALL.iter().map(|rank| html! {
+                            <option selected={*rank == record.rank}>{ rank.get_label() }</option>
+                        }) }
+                    </select>
+                </label>
+                <label class="settings-row">
+                    <span>{"Injuries (one per line)"}</span>
+                    <textarea value={record.injuries.join("\n")}
+                        onchange={update(|record, value| record.injuries = split_lines(&value))} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Honors (one per line)"}</span>
+                    <textarea value={record.honors.join("\n")}
+                        onchange={update(|record, value| record.honors = split_lines(&value))} />
+                </label>
+            </>
+        }
+    }
+}
+
+fn split_lines(value: &str) -> Vec<String> {
+    value.lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
@@ -0,0 +1,104 @@
+use yew::prelude::*;
+
+use gloo::file::callbacks::FileReader;
+use gloo::file::File;
+
+use crate::core::campaign::{compute_standings, Campaign, CampaignExport};
+
+pub enum Msg {
+    FilesSelected(Vec<web_sys::File>),
+    FileLoaded(String /* File name */, Result<String, String>),
+    Clear,
+}
+
+// Organizer view: imports the campaign files exported by the participants
+// and ranks them. Everything happens locally, nothing is uploaded.
+pub struct CampaignStandings {
+    campaigns: Vec<Campaign>,
+    errors: Vec<String>,
+    readers: Vec<FileReader>,
+}
+
+impl Component for CampaignStandings {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: &Context<Self>) -> Self {
+        CampaignStandings {
+            campaigns: Vec::<Campaign>::new(),
+            errors: Vec::<String>::new(),
+            readers: Vec::<FileReader>::new(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::FilesSelected(files) => {
+                for file in files {
+                    let file = File::from(file);
+                    let file_name = file.name();
+                    let link = ctx.link().clone();
+                    self.readers.push(gloo::file::callbacks::read_as_text(&file, move |result| {
+                        link.send_message(Msg::FileLoaded(file_name, result.map_err(|e| e.to_string())));
+                    }));
+                }
+                false
+            }
+
+            Msg::FileLoaded(file_name, result) => {
+                match result.and_then(|text| CampaignExport::from_json(&text).map_err(|e| e.to_string())) {
+                    Ok(campaign) => self.campaigns.push(campaign),
+                    Err(e) => self.errors.push(format!("{}: {}", file_name, e)),
+                }
+                true
+            }
+
+            Msg::Clear => {
+                self.campaigns.clear();
+                self.errors.clear();
+                self.readers.clear();
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_files = ctx.link().callback(|event: Event| {
+            let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+            let files = input.files()
+                .map(|files| (0..files.length()).filter_map(|i| files.get(i)).collect())
+                .unwrap_or_default();
+            input.set_value("");
+            Msg::FilesSelected(files)
+        });
+
+        html! {
+            <div class="campaign-standings">
+                <div class="modal-subtitle">{"Organizer Standings"}</div>
+                <input type="file" accept=".json" multiple=true onchange={on_files} />
+                if !self.campaigns.is_empty() {
+                    <table class="campaign-battles">
+                        <tr>
+                            <th>{"#"}</th><th>{"Player"}</th><th>{"Campaign"}</th><th>{"Games"}</th>
+                            <th>{"W / D / L"}</th><th>{"Territory"}</th><th>{"RP"}</th><th>{"XP"}</th>
+                        </tr>
+                        { for compute_standings(&self.campaigns).iter().enumerate().map(|(position, row)| html! {
+                            <tr>
+                                <td>{ position + 1 }</td>
+                                <td>{ &row.player }</td>
+                                <td>{ &row.campaign }</td>
+                                <td>{ row.games }</td>
+                                <td>{ format!("{} / {} / {}", row.wins, row.draws, row.losses) }</td>
+                                <td>{ row.territories }</td>
+                                <td>{ row.resource_points }</td>
+                                <td>{ row.xp }</td>
+                            </tr>
+                        }) }
+                    </table>
+                    <button onclick={ctx.link().callback(|_| Msg::Clear)}>{"Clear Standings"}</button>
+                }
+                { for self.errors.iter().map(|error| html! { <div class="over-limit">{ error }</div> }) }
+            </div>
+        }
+    }
+}
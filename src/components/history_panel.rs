@@ -0,0 +1,148 @@
+use yew::prelude::*;
+
+// A common definition for all messages:
+use crate::shared_messages::SharedMessage;
+
+use crate::core::history::History;
+use crate::core::revisions::Revisions;
+use crate::render::text;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub history: History,
+    // Revisions of the roster slot being edited, None without one
+    pub revisions: Option<Revisions>,
+    pub on_history_action: Callback<SharedMessage>,
+}
+
+pub enum Msg {
+    TogglePreview(usize),
+}
+
+// List of the recent edits of the roster, newest first. Clicking one brings
+// the roster back to that point, checkpoints are named by the player. Below
+// it, the revisions kept across sessions (core::revisions), each with a
+// preview before restoring it.
+pub struct HistoryPanel {
+    checkpoint_name_ref: NodeRef,
+    revision_name_ref: NodeRef,
+    previewed_revision: Option<usize>,
+}
+
+// The name typed in, or the default one, and the input emptied.
+fn take_name(input_ref: &NodeRef, default_name: &str) -> String {
+    let input = input_ref.cast::<web_sys::HtmlInputElement>();
+    let name = input.as_ref()
+        .map(|input| input.value())
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| default_name.to_string());
+    if let Some(input) = input {
+        input.set_value("");
+    }
+    name
+}
+
+impl Component for HistoryPanel {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        HistoryPanel {checkpoint_name_ref: NodeRef::default(), revision_name_ref: NodeRef::default(), previewed_revision: None}
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::TogglePreview(index) => {
+                self.previewed_revision = if self.previewed_revision == Some(index) { None } else { Some(index) };
+                true
+            }
+        }
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, old_props: &Self::Properties) -> bool {
+        // Indexes move when a revision is deleted.
+        if ctx.props().revisions != old_props.revisions {
+            self.previewed_revision = None;
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_action = ctx.props().on_history_action.clone();
+        let history = &ctx.props().history;
+
+        let checkpoint_name_ref = self.checkpoint_name_ref.clone();
+        let on_checkpoint = on_action.reform(move |_| SharedMessage::CreateCheckpoint(take_name(&checkpoint_name_ref, "Checkpoint")));
+
+        html! {
+            <div class="modal-backdrop">
+                <div class="modal history-panel">
+                    <div class="modal-title">{"History"}</div>
+                    <div class="settings-row">
+                        <input type="text" placeholder="Checkpoint name" ref={self.checkpoint_name_ref.clone()} />
+                        <button onclick={on_checkpoint}>{"Save Checkpoint"}</button>
+                    </div>
+                    <div class="history-list">
+                        { for history.entries.iter().enumerate().rev().map(|(index, entry)| {
+                            let class = classes!(
+                                "history-entry",
+                                (index == history.position).then_some("current"),
+                                (index > history.position).then_some("undone"),
+                                entry.is_checkpoint.then_some("checkpoint"));
+                            html! {
+                                <button class={class} onclick={on_action.reform(move |_| SharedMessage::JumpToHistory(index))}>
+                                    { if entry.is_checkpoint { format!("★ {}", entry.label) } else { entry.label.clone() } }
+                                </button>
+                            }
+                        }) }
+                    </div>
+                    if let Some(revisions) = &ctx.props().revisions {
+                        { self.view_revisions(ctx, revisions) }
+                    }
+                    <button disabled={!history.can_undo()} title="Ctrl+Z" onclick={on_action.reform(|_| SharedMessage::Undo)}>{"Undo"}</button>
+                    <button disabled={!history.can_redo()} title="Ctrl+Shift+Z" onclick={on_action.reform(|_| SharedMessage::Redo)}>{"Redo"}</button>
+                    <button onclick={on_action.reform(|_| SharedMessage::ToggleHistoryPanel)}>{"Close"}</button>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl HistoryPanel {
+    fn view_revisions(&self, ctx: &Context<Self>, revisions: &Revisions) -> Html {
+        let on_action = ctx.props().on_history_action.clone();
+        let revision_name_ref = self.revision_name_ref.clone();
+        let on_save = on_action.reform(move |_| SharedMessage::SaveRevision(take_name(&revision_name_ref, "Revision")));
+
+        html! {
+            <>
+                <div class="modal-subtitle">{"Saved Revisions"}</div>
+                <div class="settings-row">
+                    <input type="text" placeholder="Revision name" ref={self.revision_name_ref.clone()} />
+                    <button onclick={on_save}>{"Save Revision"}</button>
+                </div>
+                if revisions.is_empty() {
+                    <div class="storage-note">{"Revisions are kept with the roster, one is also saved for each day it's edited."}</div>
+                }
+                <div class="history-list">
+                    { for revisions.revisions.iter().enumerate().rev().map(|(index, revision)| {
+                        let is_previewed = self.previewed_revision == Some(index);
+                        html! {
+                            <div class={classes!("revision", revision.is_daily.then_some("daily"))}>
+                                <span>{ format!("{} ({})", revision.name, revision.taken) }</span>
+                                <button onclick={ctx.link().callback(move |_| Msg::TogglePreview(index))}>
+                                    { if is_previewed { "Hide" } else { "Preview" } }
+                                </button>
+                                <button onclick={on_action.reform(move |_| SharedMessage::RestoreRevision(index))}>{"Restore"}</button>
+                                <button onclick={on_action.reform(move |_| SharedMessage::DeleteRevision(index))}>{"Delete"}</button>
+                                if is_previewed {
+                                    <pre class="revision-preview">{ text::roster_paste(&revision.roster) }</pre>
+                                }
+                            </div>
+                        }
+                    }) }
+                </div>
+            </>
+        }
+    }
+}
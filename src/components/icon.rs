@@ -0,0 +1,30 @@
+use yew::prelude::*;
+
+use crate::render::icons::{self, IconName};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub name: IconName,
+    #[prop_or(AttrValue::Static("currentColor"))]
+    pub color: AttrValue,
+    #[prop_or(24)]
+    pub size: u32,
+}
+
+// Small inline icon. Uses the current text color unless told otherwise,
+// so it follows the light/dark theme without any CSS filter.
+pub struct Icon {}
+
+impl Component for Icon {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        Icon {}
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        Html::from_html_unchecked(icons::standalone_icon_svg(props.name, &props.color, props.size).into())
+    }
+}
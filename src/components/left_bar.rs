@@ -1,7 +1,6 @@
 use yew::prelude::*;
 use crate::shared_messages::SharedMessage;
-use crate::models::armylist::Faction;
-use web_sys::console;
+use crate::core::armylist::Faction;
 
 pub struct LeftBar {
     expanded_menu: Option<Faction>,
@@ -14,8 +13,12 @@ pub struct Props {
     pub on_show_supports: Callback<SharedMessage>,
 }
 
+pub enum Msg {
+    ToggleMenu(Faction),
+}
+
 impl Component for LeftBar {
-    type Message = SharedMessage;
+    type Message = Msg;
     type Properties = Props;
 
     fn create(_: &Context<Self>) -> Self {
@@ -24,7 +27,7 @@ impl Component for LeftBar {
 
     fn update(&mut self, _: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
-            SharedMessage::ToggleMenu(faction) => {
+            Msg::ToggleMenu(faction) => {
                 if self.expanded_menu.as_ref() == Some(&faction) {
                     self.expanded_menu = None;
                 } else {
@@ -32,8 +35,6 @@ impl Component for LeftBar {
                 }
                 true
             }
-
-            _ => false
         }
     }
 
@@ -53,12 +54,12 @@ impl LeftBar {
     fn render_menu(&self, ctx: &Context<Self>, faction: Faction) -> Html {
         let is_expanded = self.expanded_menu.as_ref() == Some(&faction);
         let button_text = format!("{:?}", faction);
-        let out_faction = faction.clone();
+        let out_faction = faction;
 
 
         html! {
             <div class={if is_expanded { "left-menu expanded" } else { "left-menu" }}>
-                <button onclick={ctx.link().callback(move |_| SharedMessage::ToggleMenu(faction.clone()))}>
+                <button onclick={ctx.link().callback(move |_| Msg::ToggleMenu(faction))}>
                     { button_text }
                 </button>
                 <div class="left-menu-content">
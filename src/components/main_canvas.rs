@@ -1,27 +1,70 @@
 use yew::prelude::*;
 //use yew::html::ComponentLink;
 
-// Pointer to roster, which is only one for the app.
+// The roster is only one for the app, shared through the store.
 use std::rc::Rc;
 use std::cell::RefCell;
+use crate::models::roster_store::{RosterAspect, RosterStore, Subscription};
 
 // A common definition for all messages:
-use crate::shared_messages::SharedMessage;
-
-// For browser debugging
-use web_sys::console;
+use crate::shared_messages::{CanvasMessage, MetadataMessage};
+use crate::models::bus;
+use crate::models::selection::{SelectionChange, SelectionContext};
 
 // Using the Roster as a model for the canvas
-use crate::models::roster::{Roster, RosterElement};
+use crate::core::roster::{Roster, RosterElement, RosterEntry, Wave, MAX_QUANTITY};
+
+// Cards are drawn as SVG, shared with the exports.
+use crate::render::card_svg::{self, CardTheme};
+use crate::assets::paths;
+
+use crate::components::tooltip::Tooltip;
+use crate::components::roster_totals::RosterTotals;
+
+// Ctrl+C on a hovered card copies its info, Ctrl+D duplicates it.
+use crate::export::{battlescribe, clipboard, download};
+use crate::render::text;
+
+// Scrolling anywhere in the page moves the cards under the pointer.
+use gloo::events::{EventListener, EventListenerOptions};
+use wasm_bindgen::JsCast;
+
+// Pointer tracking is throttled to one update per animation frame.
+use gloo::render::{request_animation_frame, AnimationFrame};
+
+// Render counts and timings for the dev overlay
+use crate::models::profiler;
+use crate::models::deadline;
+
+// Cards are keyed so they can be animated when added, deleted or moved.
+use std::collections::HashMap;
+use gloo::timers::callback::Timeout;
+
+use crate::core::escalation;
+use crate::core::breakdown;
+use crate::core::lint;
+use crate::core::quick_fix::QuickFix;
+use crate::core::commands::RosterCommand;
+use crate::core::rules::{Severity, Suppression, Violation};
+use crate::core::validation::Validator;
+use crate::core::event_pack::RuleOverride;
+use crate::assets::preloader;
+use crate::models::settings::{DoubleClickAction, MAX_CANVAS_ZOOM_PERCENT, MIN_CANVAS_ZOOM_PERCENT};
 
 #[derive(Properties, Clone, PartialEq)]
 pub struct Props {
-    pub roster: Rc<RefCell<Roster>>,
-    pub on_roster_updated: Callback<()>,
+    pub roster: RosterStore,
     pub is_dark_mode: bool,
-    pub on_reorder: Callback<SharedMessage>,
-    pub selected_index: Option<usize>,    
-    pub on_select_element: Callback<usize>, 
+    pub on_edit_element: Callback<String /* Id of the entry */>,
+    pub double_click_action: DoubleClickAction,
+    pub is_color_coded: bool,
+    pub zoom_percent: u32,
+    pub on_zoom: Callback<u32>,
+    pub is_deployment_board: bool,
+    pub on_toggle_deployment_board: Callback<()>,
+    pub group_cap_percent: Option<u32>,
+    // House rules of the event, see core::event_pack
+    pub house_rules: Vec<RuleOverride>,
 }
 
 pub struct MainCanvas {
@@ -30,271 +73,1237 @@ pub struct MainCanvas {
     tooltip_content: Option<Html>,
     tooltip_x: i32,
     tooltip_y: i32,    
+    tooltip_index: Option<usize>,
+    // Selected entries, from App. The details modal shows the focused one.
+    selection: SelectionContext,
+    _selection_handle: ContextHandle<SelectionContext>,
+    // Whether the details modal is open, and the context menu with its position
+    is_details_open: bool,
+    context_menu: Option<(usize, i32, i32)>,
+    is_legend_open: bool,
+    is_breakdown_open: bool,
+    is_suggestions_open: bool,
+    // What the roster breaks, checked again after every change, and the
+    // violation being set aside, waiting for its reason
+    violations: Vec<Violation>,
+    suppressing_key: Option<String>,
+    // Card dragged to another place or wave, and the card it would be put at
+    dragged_index: Option<usize>,
+    drop_index: Option<usize>,
+    // Card edited in place, its inputs, and whether the name still has to
+    // take the focus
+    editing_index: Option<usize>,
+    edit_name_ref: NodeRef,
+    edit_points_ref: NodeRef,
+    edit_note_ref: NodeRef,
+    is_edit_focus_pending: bool,
+    // Entry whose options were just changed, with copies left as they were
+    copies_offer: Option<(usize, RosterEntry)>,
+    // Cards are keyed by the ids of their entries: the ones fading out before
+    // being deleted, and where each card was drawn last
+    leaving_ids: Vec<String>,
+    card_positions: HashMap<String, (f64, f64)>,
+    // The cards moved since the last layout animation
+    is_layout_dirty: bool,
+    area_ref: NodeRef,
+    pointer_tracker: Rc<RefCell<PointerTracker>>,
+    // The hovered entry, to keep the tooltip up to date
+    tooltip_subscription: Option<Subscription>,
+    _roster_subscriptions: Vec<Subscription>,
+    _scroll_listener: EventListener,
+    _copy_listener: EventListener,
 }
 
+// Latest pointer position, waiting for the next animation frame to be sent
+// to the component as a MoveTooltip.
+#[derive(Default)]
+struct PointerTracker {
+    latest: (i32, i32),
+    is_frame_pending: bool,
+    frame: Option<AnimationFrame>,
+}
+
+// Length of the fade out of a deleted card, as in the card-leave animation.
+const LEAVE_ANIMATION_MS: u32 = 200;
+
+// Length of the bar of a group taking the whole list, in the breakdown.
+const SHARE_BAR_WIDTH: f64 = 60.0;
+
+// Distance between the pointer and the tooltip, so it doesn't cover the cursor.
+const TOOLTIP_OFFSET: i32 = 12;
+
 impl Component for MainCanvas {
-    type Message = SharedMessage;
+    type Message = CanvasMessage;
     type Properties = Props;
 
     fn create(ctx: &Context<Self>) -> Self {
+        // Scroll events don't bubble, listening in the capture phase catches
+        // the scrolling of any container, not only the window.
+        let link = ctx.link().clone();
+        let scroll_listener = EventListener::new_with_options(
+            &gloo::utils::window(),
+            "scroll",
+            EventListenerOptions::run_in_capture_phase(),
+            move |_| link.send_message(CanvasMessage::CanvasScrolled));
+
+        // Copying is left alone if some text is selected.
+        let link = ctx.link().clone();
+        let copy_listener = EventListener::new(&gloo::utils::window(), "keydown", move |event| {
+            let event: &KeyboardEvent = event.unchecked_ref();
+            let has_selection = gloo::utils::window().get_selection().ok().flatten()
+                .map(|selection| !selection.is_collapsed())
+                .unwrap_or(false);
+            if (event.ctrl_key() || event.meta_key()) && event.key() == "c" && !has_selection {
+                link.send_message(CanvasMessage::CopyHoveredElement);
+            }
+            // Bookmarking is left alone away from the cards.
+            let is_card_hovered = gloo::utils::document().query_selector(".hoverable-area:hover").ok().flatten().is_some();
+            if (event.ctrl_key() || event.meta_key()) && event.key() == "d" && is_card_hovered {
+                event.prevent_default();
+                link.send_message(CanvasMessage::DuplicateHoveredElement);
+            }
+        });
+
+        // The totals have their own component.
+        let roster_subscriptions = [RosterAspect::Elements, RosterAspect::Metadata].into_iter()
+            .map(|aspect| ctx.props().roster.subscribe(aspect, ctx.link().callback(|_| CanvasMessage::NotifyRosterUpdated)))
+            .collect();
+
+        let (selection, selection_handle) = ctx.link()
+            .context::<SelectionContext>(ctx.link().callback(CanvasMessage::SelectionChanged))
+            .expect("The selection is provided by App");
+
         MainCanvas {
             props: ctx.props().clone(),
             tooltip_visible: false,
             tooltip_content: None,
             tooltip_x: 0,
             tooltip_y: 0,
+            tooltip_index: None,
+            selection,
+            _selection_handle: selection_handle,
+            is_details_open: false,
+            context_menu: None,
+            is_legend_open: false,
+            is_breakdown_open: false,
+            is_suggestions_open: false,
+            violations: MainCanvas::validate(ctx.props()),
+            suppressing_key: None,
+            dragged_index: None,
+            drop_index: None,
+            editing_index: None,
+            edit_name_ref: NodeRef::default(),
+            edit_points_ref: NodeRef::default(),
+            edit_note_ref: NodeRef::default(),
+            is_edit_focus_pending: false,
+            copies_offer: None,
+            leaving_ids: Vec::new(),
+            card_positions: HashMap::new(),
+            is_layout_dirty: false,
+            area_ref: NodeRef::default(),
+            pointer_tracker: Rc::new(RefCell::new(PointerTracker::default())),
+            tooltip_subscription: None,
+            _roster_subscriptions: roster_subscriptions,
+            _scroll_listener: scroll_listener,
+            _copy_listener: copy_listener,
         }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let _timer = profiler::time_update("MainCanvas");
         match msg {
 
-            SharedMessage::NotifyRosterUpdated => {
-                console::log_1(&"Roster updated notification received in MAIN CANVAS".into());
+            // The index being edited may now point at another entry.
+            CanvasMessage::NotifyRosterUpdated => {
+                self.editing_index = None;
+                self.is_layout_dirty = true;
+                self.violations = MainCanvas::validate(&self.props);
                 true
             }
 
-            SharedMessage::DeleteElement(index) => {
-                let mut roster = self.props.roster.borrow_mut();
-                if index < roster.elements.len() {
-                    roster.elements.remove(index);
-                    self.props.on_roster_updated.emit(());
+            // The card fades out first, it's removed once that's over.
+            CanvasMessage::DeleteElement(index) => {
+                let id = self.props.roster.borrow().elements.get(index).map(|entry| entry.id.clone());
+                if let Some(id) = id.filter(|id| !self.leaving_ids.contains(id)) {
+                    self.leaving_ids.push(id.clone());
+                    let link = ctx.link().clone();
+                    Timeout::new(LEAVE_ANIMATION_MS, move || link.send_message(CanvasMessage::FinishDeleteElement(id))).forget();
                 }
                 self.tooltip_visible = false; 
+                self.tooltip_subscription = None;
+                self.is_details_open = false;
+                self.context_menu = None;
                 true
             }
 
-            SharedMessage::ReorderElements => {
-
-                let mut roster = self.props.roster.borrow_mut();
-
-                let mut new_roster_characters = Roster::new();
-                let mut new_roster_units = Roster::new();
-                let mut new_roster_supports = Roster::new();
-                let mut new_roster_others = Roster::new();
-
-                console::log_1(&format!("Called reorder element with {:?} elems.", roster.elements.len()).into());
-
-                // Searching for Characters:
-                roster.elements.iter().for_each(|element| {
-                    match element {
-
-                        // TODO the whole elems types are going to be removed eventually and even now everything is marked as 
-                        // ElemOther. Therefore, I need a different way to organize based on the element type. This is not fun, but hey,
-                        RosterElement::ElemCharacter(character) => new_roster_characters.add_element(RosterElement::ElemCharacter(character.clone())),
-                        RosterElement::ElemUnit(unit) => new_roster_units.add_element(RosterElement::ElemUnit(unit.clone())),
-                        RosterElement::ElemSupport(support) => new_roster_supports.add_element(RosterElement::ElemSupport(support.clone())),
-                        RosterElement::ElemOther((name, points, attached, image)) => {
-                            if image.contains("character.png") {
-                                // Handle character case
-                                new_roster_characters.add_element(RosterElement::ElemOther((name.clone(), *points, Vec::<String>::new(), image.clone())));
-                            } else if image.contains("support.png") {
-                                // Handle support case
-                                new_roster_supports.add_element(RosterElement::ElemOther((name.clone(), *points, Vec::<String>::new(), image.clone())));
-                            } else {
-                                // Handle other cases
-                                new_roster_units.add_element(RosterElement::ElemOther((name.clone(), *points, attached.clone(), image.clone())));
-                            }
-                        },
-                            
-                    };
-                });
-
-                // Now sorting within one category
-                new_roster_characters.elements.sort_by(|a, b| self.get_element_name(a).cmp(&self.get_element_name(b))); // Sort them
-                new_roster_units.elements.sort_by(|a, b| self.get_element_name(a).cmp(&self.get_element_name(b))); // Sort them
-                new_roster_supports.elements.sort_by(|a, b| self.get_element_name(a).cmp(&self.get_element_name(b))); // Sort them
-                new_roster_others.elements.sort_by(|a, b| self.get_element_name(a).cmp(&self.get_element_name(b))); // Sort them
-
-                // Inserting in the "good" roster the elements of the various kinds.
-                roster.elements.clear();
-                roster.elements.append(&mut new_roster_characters.elements);
-                roster.elements.append(&mut new_roster_units.elements);
-                roster.elements.append(&mut new_roster_supports.elements);
-                roster.elements.append(&mut new_roster_others.elements);
-                
-                self.tooltip_visible = false; 
+            // Other entries may have moved meanwhile, the id finds it.
+            CanvasMessage::FinishDeleteElement(id) => {
+                self.leaving_ids.retain(|leaving_id| *leaving_id != id);
+                let index = self.props.roster.borrow().find_entry(&id);
+                if let Some(index) = index {
+                    self.props.roster.execute(RosterCommand::RemoveEntry(index));
+                }
+                true
+            }
+
+            // Right after the original, with the same options. The campaign
+            // record stays with the original, the copy hasn't fought yet.
+            CanvasMessage::DuplicateElement(index) => {
+                let entry = self.props.roster.borrow().elements.get(index).map(RosterEntry::renewed);
+                if let Some(mut entry) = entry {
+                    entry.campaign = None;
+                    self.props.roster.execute(RosterCommand::InsertEntry(index + 1, entry));
+                }
+                self.tooltip_visible = false;
+                self.context_menu = None;
+                true
+            }
+
+            CanvasMessage::DuplicateHoveredElement => {
+                match self.tooltip_index {
+                    Some(index) => Component::update(self, ctx, CanvasMessage::DuplicateElement(index)),
+                    None => false,
+                }
+            }
+
+            CanvasMessage::MoveElement(index, offset) => {
+                // Next to each other, moving is swapping.
+                if let Some(target) = index.checked_add_signed(offset) {
+                    self.props.roster.execute(RosterCommand::MoveEntry(index, target));
+                }
+                self.tooltip_visible = false;
+                true
+            }
+
+            // Down to a single copy, deleting is for the last one.
+            CanvasMessage::ChangeQuantity(index, offset) => {
+                let quantity = self.props.roster.borrow().elements.get(index).and_then(|entry| entry.quantity.checked_add_signed(offset));
+                if let Some(quantity) = quantity {
+                    self.props.roster.execute(RosterCommand::SetQuantity(index, quantity));
+                }
+                true
+            }
+
+            CanvasMessage::StartEditingElement(index) => {
+                self.editing_index = Some(index);
+                self.is_edit_focus_pending = true;
+                self.context_menu = None;
+                self.is_details_open = false;
+                self.tooltip_visible = false;
+                true
+            }
+
+            // An empty name or unreadable points leave the element as it
+            // was, the note is saved anyway. One edit for both.
+            CanvasMessage::SaveElementEdit(index) => {
+                let value = |node: &NodeRef| node.cast::<web_sys::HtmlInputElement>().map(|input| input.value()).unwrap_or_default();
+                let name = value(&self.edit_name_ref).trim().to_string();
+                let points = value(&self.edit_points_ref).trim().parse::<u32>().ok();
+                let note = self.edit_note_ref.cast::<web_sys::HtmlTextAreaElement>().map(|input| input.value()).unwrap_or_default();
+                self.editing_index = None;
+                let Some(entry) = self.props.roster.borrow().elements.get(index).cloned() else {
+                    return true;
+                };
+                let mut commands = Vec::new();
+                if let (false, Some(points)) = (name.is_empty(), points) {
+                    if entry.element.get_name_and_points() != (name.clone(), points) {
+                        commands.push(RosterCommand::SetNameAndPoints(index, name, points));
+                    }
+                }
+                if entry.note != note.trim() {
+                    commands.push(RosterCommand::SetNote(index, note.trim().to_string()));
+                }
+                match commands.len() {
+                    0 => {}
+                    1 => { self.props.roster.execute(commands.remove(0)); }
+                    _ => { self.props.roster.execute(RosterCommand::Batch(commands)); }
+                }
+                true
+            }
+
+            CanvasMessage::CancelElementEdit => {
+                self.editing_index = None;
+                true
+            }
+
+            CanvasMessage::OpenCampaignEditor(index) => {
+                if let Some(entry) = self.props.roster.borrow().elements.get(index) {
+                    ctx.props().on_edit_element.emit(entry.id.clone());
+                }
+                false
+            }
+
+            CanvasMessage::ActivateElement(index) => {
+                match ctx.props().double_click_action {
+                    DoubleClickAction::Details => ctx.link().send_message(CanvasMessage::ShowElementDetails(index)),
+                    DoubleClickAction::Edit => ctx.link().send_message(CanvasMessage::OpenCampaignEditor(index)),
+                    DoubleClickAction::Delete => ctx.link().send_message(CanvasMessage::DeleteElement(index)),
+                    DoubleClickAction::Rename => ctx.link().send_message(CanvasMessage::StartEditingElement(index)),
+                    DoubleClickAction::Nothing => {}
+                }
+                false
+            }
+
+            // The details follow the focus, wherever it's changed from.
+            CanvasMessage::ShowElementDetails(index) => {
+                let Some(id) = self.props.roster.borrow().elements.get(index).map(|entry| entry.id.clone()) else {
+                    return false;
+                };
+                self.selection.change(SelectionChange::Focus(id));
+                self.is_details_open = true;
+                self.context_menu = None;
+                self.tooltip_visible = false;
                 true
             }
 
-            SharedMessage::ShowTooltip(index) => {
+            CanvasMessage::HideElementDetails => {
+                self.is_details_open = false;
+                true
+            }
+
+            CanvasMessage::SelectionChanged(selection) => {
+                self.selection = selection;
+                true
+            }
+
+            CanvasMessage::SelectElement(index, is_extending) => {
+                let Some(id) = self.props.roster.borrow().elements.get(index).map(|entry| entry.id.clone()) else {
+                    return false;
+                };
+                self.selection.change(if is_extending { SelectionChange::Extend(id) } else { SelectionChange::Select(id) });
+                false
+            }
+
+            CanvasMessage::OpenContextMenu(index, x, y) => {
+                self.context_menu = Some((index, x, y));
+                self.tooltip_visible = false;
+                true
+            }
+
+            CanvasMessage::CloseContextMenu => {
+                self.context_menu = None;
+                true
+            }
+
+            CanvasMessage::ToggleTypeLegend => {
+                self.is_legend_open = !self.is_legend_open;
+                true
+            }
+
+            CanvasMessage::ToggleBreakdown => {
+                self.is_breakdown_open = !self.is_breakdown_open;
+                true
+            }
+
+            CanvasMessage::ToggleSuggestions => {
+                self.is_suggestions_open = !self.is_suggestions_open;
+                true
+            }
+
+            // Removals go through the usual delete, the rest is edited here.
+            CanvasMessage::ApplyQuickFix(QuickFix::RemoveEntry(index)) => {
+                Component::update(self, ctx, CanvasMessage::DeleteElement(index))
+            }
+
+            CanvasMessage::ApplyQuickFix(fix) => {
+                let command = fix.get_command(&self.props.roster.borrow());
+                if let Some(command) = command {
+                    self.props.roster.execute(command);
+                }
+                true
+            }
+
+            CanvasMessage::StartSuppressing(key) => {
+                self.suppressing_key = Some(key);
+                true
+            }
+
+            CanvasMessage::SuppressViolation(key, reason) => {
+                self.suppressing_key = None;
+                let reason = reason.trim().to_string();
+                if reason.is_empty() {
+                    return true;
+                }
+                let mut suppressions = self.props.roster.borrow().metadata.suppressions.clone();
+                suppressions.retain(|suppression| suppression.key != key);
+                suppressions.push(Suppression {key, reason});
+                self.props.roster.execute(RosterCommand::SetSuppressions(suppressions));
+                true
+            }
+
+            CanvasMessage::UnsuppressViolation(key) => {
+                let mut suppressions = self.props.roster.borrow().metadata.suppressions.clone();
+                suppressions.retain(|suppression| suppression.key != key);
+                self.props.roster.execute(RosterCommand::SetSuppressions(suppressions));
+                true
+            }
+
+            CanvasMessage::DragElement(index) => {
+                self.dragged_index = Some(index);
+                self.tooltip_visible = false;
+                false
+            }
+
+            CanvasMessage::DragOverElement(index) => {
+                let is_new_target = self.dragged_index.is_some_and(|dragged| dragged != index) && self.drop_index != Some(index);
+                if is_new_target {
+                    self.drop_index = Some(index);
+                }
+                is_new_target
+            }
+
+            // The card takes the place of the one it's dropped on, and its
+            // wave on the deployment board.
+            CanvasMessage::DropElement(index) => {
+                self.drop_index = None;
+                let Some(dragged) = self.dragged_index.take() else {
+                    return true;
+                };
+                let roster = self.props.roster.borrow();
+                let (Some(target), Some(entry)) = (roster.elements.get(index), roster.elements.get(dragged)) else {
+                    return true;
+                };
+                let mut commands = vec![RosterCommand::MoveEntry(dragged, index)];
+                if ctx.props().is_deployment_board && entry.wave != target.wave {
+                    commands.push(RosterCommand::SetWave(index, target.wave));
+                }
+                drop(roster);
+                // Onto itself, only the wave may change.
+                if dragged == index {
+                    commands.remove(0);
+                }
+                self.props.roster.execute(RosterCommand::Batch(commands));
+                true
+            }
+
+            CanvasMessage::EndDrag => {
+                self.dragged_index = None;
+                self.drop_index.take().is_some()
+            }
+
+            CanvasMessage::DropElementOnWave(wave) => {
+                self.drop_index = None;
                 let roster = self.props.roster.borrow();
-                if let Some(elem) = roster.elements.get(index) {
-                    self.tooltip_content = Some(self.get_tooltip_content(ctx, elem, index));
+                let Some(index) = self.dragged_index.take().filter(|index| roster.elements.get(*index).is_some_and(|entry| entry.wave != wave)) else {
+                    return true;
+                };
+                let mut entry = roster.elements[index].clone();
+                entry.wave = wave;
+                let has_other_copies = roster.get_copies(index).into_iter().any(|copy| roster.elements[copy].wave != wave);
+                self.copies_offer = has_other_copies.then(|| (index, entry.clone()));
+                drop(roster);
+                self.props.roster.execute(RosterCommand::SetWave(index, wave));
+                true
+            }
+
+            // One roster change for all the copies.
+            CanvasMessage::ApplyToCopies(index) => {
+                if let Some((offer_index, entry)) = self.copies_offer.take().filter(|(offer_index, _)| *offer_index == index) {
+                    let command = Some(self.props.roster.borrow())
+                        .filter(|roster| roster.elements.get(offer_index) == Some(&entry))
+                        .and_then(|roster| RosterCommand::wave_to_copies(&roster, offer_index));
+                    if let Some(command) = command {
+                        self.props.roster.execute(command);
+                    }
+                }
+                true
+            }
+
+            CanvasMessage::DismissCopiesOffer => {
+                self.copies_offer = None;
+                true
+            }
+
+            CanvasMessage::ReorderElements => {
+                // Characters first, then units, then supports, each by name.
+                // Elements off the catalog go by their image.
+                let rank = |element: &RosterElement| match element {
+                    RosterElement::ElemCharacter(_) => 0,
+                    RosterElement::ElemOther((_, _, _, image)) if image.contains("character.png") => 0,
+                    RosterElement::ElemSupport(_) => 2,
+                    RosterElement::ElemOther((_, _, _, image)) if image.contains("support.png") => 2,
+                    RosterElement::ElemUnit(_) | RosterElement::ElemOther(_) => 1,
+                };
+                let mut roster = self.props.roster.borrow().clone();
+                roster.elements.sort_by_key(|entry| (rank(&entry.element), entry.element.get_name()));
+                self.props.roster.batch(|current| *current = roster);
+
+                self.tooltip_visible = false;
+                true
+            }
+
+            CanvasMessage::ShowTooltip(index) => {
+                let roster = self.props.roster.borrow();
+                if let Some(entry) = roster.elements.get(index) {
+                    // Grouped entries preview their whole composition.
+                    self.tooltip_content = Some(self.get_tooltip_content(ctx, entry, index));
                     self.tooltip_visible = true;
+                    if self.tooltip_index != Some(index) {
+                        self.tooltip_index = Some(index);
+                        // Followed by its id, wherever it's moved to.
+                        let (store, id) = (self.props.roster.clone(), entry.id.clone());
+                        self.tooltip_subscription = Some(self.props.roster.subscribe(
+                            RosterAspect::Entry(entry.id.clone()),
+                            ctx.link().callback(move |_| match store.borrow().find_entry(&id) {
+                                Some(index) => CanvasMessage::ShowTooltip(index),
+                                None => CanvasMessage::HideTooltip,
+                            })));
+                    }
                 }
                 true
             }
 
-            SharedMessage::MoveTooltip(x, y) => {
+            // Coordinates are relative to the viewport, like the tooltip itself.
+            CanvasMessage::MoveTooltip(x, y) => {
                 self.tooltip_x = x;
                 self.tooltip_y = y;
                 true
             }
 
-            SharedMessage::HideTooltip => {
+            CanvasMessage::HideTooltip => {
                 self.tooltip_visible = false;
+                self.tooltip_index = None;
+                self.tooltip_subscription = None;
                 true
             }
-            
-            _ => panic!("Wrong message received!")
+
+            CanvasMessage::CopyHoveredElement => {
+                if let Some(entry) = self.tooltip_index.and_then(|index| self.props.roster.borrow().elements.get(index).cloned()) {
+                    clipboard::copy_text(&text::entry_summary(&entry));
+                }
+                false
+            }
+
+            CanvasMessage::CopyRosterForChat => {
+                if !deadline::confirm_export() {
+                    return false;
+                }
+                clipboard::copy_text(&text::roster_paste(&self.props.roster.borrow()));
+                false
+            }
+
+            CanvasMessage::ExportMarkdown => {
+                if !deadline::confirm_export() {
+                    return false;
+                }
+                let roster = self.props.roster.borrow();
+                download::download_text(&text::roster_markdown(&roster), &download::get_file_name(&roster.metadata.name, "md"), "text/markdown");
+                false
+            }
+
+            CanvasMessage::ExportBattleScribe => {
+                if !deadline::confirm_export() {
+                    return false;
+                }
+                let roster = self.props.roster.borrow();
+                let xml = battlescribe::build_roster_xml(&roster);
+                download::download_text(&xml, &download::get_file_name(&roster.metadata.name, "ros"), "application/xml");
+                false
+            }
+
+            // The pointer didn't move, but the content under it might have.
+            CanvasMessage::CanvasScrolled => {
+                if !self.tooltip_visible {
+                    return false;
+                }
+                match self.get_card_index_at(self.tooltip_x, self.tooltip_y) {
+                    Some(index) if Some(index) == self.tooltip_index => false,
+                    Some(index) => {
+                        ctx.link().send_message(CanvasMessage::ShowTooltip(index));
+                        false
+                    }
+                    None => {
+                        self.tooltip_visible = false;
+                        self.tooltip_index = None;
+                        self.tooltip_subscription = None;
+                        true
+                    }
+                }
+            }
         }
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        profiler::record_render("MainCanvas");
         let roster = self.props.roster.borrow();
-        let total_points: u32 = roster.elements.iter()
-            .map(|elem| self.get_element_points(elem)).sum();
+        let on_pointer_move = self.get_pointer_move_callback(ctx);
 
         html! {
-            <div class="central-area">
-                <div class={if total_points > 60 { "total-points over-limit" } else { "total-points" }}>
-                    { format!("Total Points: {}", total_points) }
+            <div class={classes!("central-area", ctx.props().is_color_coded.then_some("type-colors"))}
+                style={format!("--card-size: {}px;", card_svg::ROSTER_CARD_WIDTH * ctx.props().zoom_percent / 100)}
+                ref={self.area_ref.clone()}>
+                <div class="roster-name">
+                    <input type="text" value={roster.metadata.name.clone()}
+                        onchange={Callback::from(|event: Event| {
+                            let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                            bus::publish(MetadataMessage::Rename(input.value()));
+                        })} />
+                    <button title="New random name" onclick={Callback::from(|_| bus::publish(MetadataMessage::RerollName))}>{"Reroll"}</button>
+                    <input type="text" class="milestones-input" placeholder="Escalation milestones, e.g. 30, 45, 60"
+                        title="Escalation milestones"
+                        value={escalation::format_milestones(&roster.metadata.escalation_milestones)}
+                        onchange={Callback::from(|event: Event| {
+                            let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                            bus::publish(MetadataMessage::SetEscalationMilestones(escalation::parse_milestones(&input.value())));
+                        })} />
                 </div>
-                {
-                    for roster.elements.iter().enumerate().map(|(i, elem)| {
-
-                        // Checking for selected elements, with a different css look.
-                        let is_selected = ctx.props().selected_index == Some(i);
-                        let element_class = if is_selected {console::log_1(&"Selected".into()); "hoverable-area selected" } else { "hoverable-area" };
-
-                        
-                        // Preparing a couple of variables for the conditional below.
-                        let image_path = self.get_image(elem);
-                        let image_class = if {ctx.props().is_dark_mode} && {image_path == "character.png" || image_path == "support.png"} {
-                            "inverted-roster-image"
-                        } else {
-                            "roster-image"
-                        };
-
-                        html!{
-                            <div class={element_class}
-                                //onclick={ctx.link().callback(move |_| SharedMessage::SelectElement(i))}
-                                onclick={ctx.props().on_select_element.reform(move |_| i)}
-
-                                onmouseover={ctx.link().callback(move |_| SharedMessage::ShowTooltip(i))}
-                                onmousemove={ctx.link().callback(move |e: MouseEvent| SharedMessage::MoveTooltip(e.client_x(), e.client_y()))}
-                                onmouseout={ctx.link().callback(|_| SharedMessage::HideTooltip)}
-                                ondblclick={ctx.link().callback(move |_| SharedMessage::DeleteElement(i))}>
-                                <div class="content-container">
-                                    { self.get_element_name(elem) }
-                                    <img src={format!("./static/images/{}", image_path)} class={image_class} />
-                                    <div class="points-label">{ if self.get_element_points(elem) > 1 {
-                                            format!("{} Points", self.get_element_points(elem))
-                                        }
-                                        else {
-                                            "1 Point".to_string()
-                                        }}
-                                    </div>
-                                    { self.render_attached_elements(elem) }
-                                </div>
-                            </div>
-                        }
-                    })
+                <RosterTotals roster={self.props.roster.clone()} are_points_hidden={card_svg::are_points_hidden()}
+                    group_cap_percent={ctx.props().group_cap_percent} house_rules={ctx.props().house_rules.clone()} />
+                { self.view_toolbar(ctx) }
+                if !card_svg::are_points_hidden() {
+                    { self.view_violations(ctx, &roster) }
+                }
+                if let Some((index, entry)) = self.copies_offer.as_ref().filter(|(index, entry)| roster.elements.get(*index) == Some(entry)) {
+                    { self.view_copies_offer(ctx, &roster, *index, entry) }
+                }
+                if self.is_breakdown_open && !card_svg::are_points_hidden() {
+                    { self.view_breakdown(ctx, &roster) }
+                }
+                if self.is_suggestions_open {
+                    { self.view_suggestions(ctx, &roster) }
+                }
+                if let Some(scenario) = &roster.scenario {
+                    <div class="scenario-banner">{ format!("Scenario: {}", scenario.name) }</div>
+                }
+                if ctx.props().is_deployment_board {
+                    { self.view_deployment_board(ctx, &roster.elements, &on_pointer_move) }
+                } else {
+                    { for roster.elements.iter().enumerate().map(|(i, entry)| {
+                        self.view_card(ctx, entry, i, roster.elements.len(), &on_pointer_move)
+                    }) }
                 }
                 <div class="reorder-button-area">
-                    <button onclick = {ctx.link().callback(move |_|  SharedMessage::ReorderElements)}>{"REORDER"}</button>
+                    <button onclick = {ctx.link().callback(move |_|  CanvasMessage::ReorderElements)}>{"REORDER"}</button>
                 </div>
-                {
-                    if self.tooltip_visible {
-                        html! {
-                            <div class="tooltip" style={format!("left: {}px; top: {}px;", self.tooltip_x, self.tooltip_y)}>
-                                { self.tooltip_content.clone().unwrap_or_default() }
-                            </div>
-                        }
-                    } else {
-                        html! {}
-                    }
+                if let Some((index, x, y)) = self.context_menu {
+                    { self.view_context_menu(ctx, index, x, y) }
+                }
+                if let Some((index, entry)) = self.selection.selection.get_focused_index(&roster).filter(|_| self.is_details_open).map(|index| (index, &roster.elements[index])) {
+                    { self.view_details(ctx, entry, index) }
+                }
+                if self.tooltip_visible {
+                    <Tooltip x={self.tooltip_x + TOOLTIP_OFFSET} y={self.tooltip_y + TOOLTIP_OFFSET} is_dark_mode={ctx.props().is_dark_mode}>
+                        { self.tooltip_content.clone().unwrap_or_default() }
+                    </Tooltip>
                 }
             </div>
         }
     }
 
+    fn rendered(&mut self, _ctx: &Context<Self>, _first_render: bool) {
+        if std::mem::take(&mut self.is_layout_dirty) {
+            self.animate_layout();
+        }
+        if std::mem::take(&mut self.is_edit_focus_pending) {
+            if let Some(input) = self.edit_name_ref.cast::<web_sys::HtmlInputElement>() {
+                let _ = input.focus();
+                input.select();
+            }
+        }
+    }
+
     fn changed(&mut self, _: &Context<Self>, new_props: &Self::Properties) -> bool {
-        let old_elements = &self.props.roster.borrow().elements.clone();
-        let new_elements = &new_props.roster.borrow().elements.clone();
-        
         self.props = new_props.clone();
+        self.violations = MainCanvas::validate(&self.props);
         true
     }
 }
 
 impl MainCanvas {
-    // Simple rendering of the various elements of the roster.
-    fn get_element_name(&self, elem: &RosterElement) -> String {
-        match elem {
-            RosterElement::ElemCharacter(character) => character.name.clone(),
-            RosterElement::ElemUnit(unit) => unit.name.clone(),
-            RosterElement::ElemSupport(support) => support.name.clone(),
-            RosterElement::ElemOther((name, _ , _, _)) => format!("{}", name),
+    fn validate(props: &Props) -> Vec<Violation> {
+        Validator::for_roster(props.group_cap_percent, &props.house_rules).validate(&props.roster.borrow())
+    }
+
+    // Cards that moved are drawn back where they were, then let slide to
+    // their new place by the transform transition of the cards.
+    fn animate_layout(&mut self) {
+        let Some(area) = self.area_ref.cast::<web_sys::Element>() else {
+            return;
+        };
+        let Ok(cards) = area.query_selector_all("[data-key]") else {
+            return;
+        };
+        let origin = area.get_bounding_client_rect();
+        let mut positions = HashMap::new();
+        for index in 0..cards.length() {
+            let Some(card) = cards.item(index).and_then(|node| node.dyn_into::<web_sys::HtmlElement>().ok()) else {
+                continue;
+            };
+            let Some(key) = card.get_attribute("data-key") else {
+                continue;
+            };
+            let rect = card.get_bounding_client_rect();
+            let position = (rect.left() - origin.left(), rect.top() - origin.top());
+            if let Some((x, y)) = self.card_positions.get(&key) {
+                let (dx, dy) = (x - position.0, y - position.1);
+                if dx != 0.0 || dy != 0.0 {
+                    let style = card.style();
+                    let _ = style.set_property("transition", "none");
+                    let _ = style.set_property("transform", &format!("translate({}px, {}px)", dx, dy));
+                    // Reading the layout applies the transform before it's removed.
+                    card.offset_width();
+                    let _ = style.remove_property("transition");
+                    let _ = style.remove_property("transform");
+                }
+            }
+            positions.insert(key, position);
         }
+        self.card_positions = positions;
     }
 
-    fn get_tooltip_content(&self, _ctx: &Context<Self>, elem: &RosterElement, _index: usize) -> Html {
+    // Mousemove fires far more often than the screen refreshes: only the
+    // latest position is kept and sent once per animation frame.
+    fn get_pointer_move_callback(&self, ctx: &Context<Self>) -> Callback<MouseEvent> {
+        let tracker = self.pointer_tracker.clone();
+        let link = ctx.link().clone();
+        Callback::from(move |e: MouseEvent| {
+            let mut tracker_ref = tracker.borrow_mut();
+            tracker_ref.latest = (e.client_x(), e.client_y());
+            if tracker_ref.is_frame_pending {
+                return;
+            }
+            tracker_ref.is_frame_pending = true;
+
+            let tracker_clone = tracker.clone();
+            let link_clone = link.clone();
+            tracker_ref.frame = Some(request_animation_frame(move |_| {
+                let (x, y) = {
+                    let mut tracker_ref = tracker_clone.borrow_mut();
+                    tracker_ref.is_frame_pending = false;
+                    tracker_ref.latest
+                };
+                link_clone.send_message(CanvasMessage::MoveTooltip(x, y));
+            }));
+        })
+    }
+
+    // Index of the card found at the given viewport coordinates, if any.
+    fn get_card_index_at(&self, x: i32, y: i32) -> Option<usize> {
+        gloo::utils::document()
+            .element_from_point(x as f32, y as f32)?
+            .closest(".hoverable-area").ok()??
+            .get_attribute("data-index")?
+            .parse().ok()
+    }
+
+    fn get_tooltip_hint(&self, ctx: &Context<Self>) -> String {
+        let double_click = match ctx.props().double_click_action {
+            DoubleClickAction::Details => "Double click for details, ",
+            DoubleClickAction::Edit => "Double click to edit, ",
+            DoubleClickAction::Delete => "Double click to delete, ",
+            DoubleClickAction::Rename => "Double click to rename, ",
+            DoubleClickAction::Nothing => "",
+        };
+        format!("{}right click for more, Ctrl+C to copy, Ctrl+D to duplicate", double_click)
+    }
+
+    // Layout and zoom of the cards, and the legend of their colors.
+    fn view_toolbar(&self, ctx: &Context<Self>) -> Html {
+        let on_zoom = ctx.props().on_zoom.reform(|event: InputEvent| {
+            let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+            input.value().parse().unwrap_or(100)
+        });
         html! {
-            <>
-                //{ format!("Details about: {}", self.get_element_name(elem)) }
-                <div>{ "Double click to delete" }</div>
-            </>
+            <div class="canvas-toolbar">
+                <button onclick={ctx.props().on_toggle_deployment_board.reform(|_| ())}>
+                    { if ctx.props().is_deployment_board { "Grid" } else { "Deployment Board" } }
+                </button>
+                <label class="zoom-slider">
+                    <span>{ format!("Zoom {}%", ctx.props().zoom_percent) }</span>
+                    <input type="range" step="10"
+                        min={MIN_CANVAS_ZOOM_PERCENT.to_string()} max={MAX_CANVAS_ZOOM_PERCENT.to_string()}
+                        value={ctx.props().zoom_percent.to_string()} oninput={on_zoom} />
+                </label>
+                if !card_svg::are_points_hidden() {
+                    <button onclick={ctx.link().callback(|_| CanvasMessage::ToggleBreakdown)}>
+                        { if self.is_breakdown_open { "Hide Breakdown" } else { "Breakdown" } }
+                    </button>
+                }
+                <button title="Copy the roster as short text, to paste into a chat"
+                    onclick={ctx.link().callback(|_| CanvasMessage::CopyRosterForChat)}>{"Copy for Chat"}</button>
+                <button title="Download the roster as a Markdown document"
+                    onclick={ctx.link().callback(|_| CanvasMessage::ExportMarkdown)}>{"Markdown"}</button>
+                <button title="Download the roster as a BattleScribe roster file"
+                    onclick={ctx.link().callback(|_| CanvasMessage::ExportBattleScribe)}>{"BattleScribe"}</button>
+                <button title="Likely mistakes in the roster" onclick={ctx.link().callback(|_| CanvasMessage::ToggleSuggestions)}>
+                    { if self.is_suggestions_open { "Hide Suggestions" } else { "Suggestions" } }
+                </button>
+                if ctx.props().is_color_coded {
+                    <button onclick={ctx.link().callback(|_| CanvasMessage::ToggleTypeLegend)}>
+                        { if self.is_legend_open { "Hide Legend" } else { "Legend" } }
+                    </button>
+                    if self.is_legend_open {
+                        { for ["Character", "Unit", "Support", "Other"].iter().map(|type_name| html! {
+                            <span class={classes!("type-swatch", format!("type-{}", type_name.to_lowercase()))}>{ type_name }</span>
+                        }) }
+                    }
+                }
+            </div>
         }
     }
 
-    fn get_element_points(&self, elem: &RosterElement) -> u32 {
-        match elem {
-            RosterElement::ElemCharacter(character) => character.points,
-            RosterElement::ElemUnit(unit) => {
-                unit.attached_elements.iter().map(|elem| elem.get_name_and_points().1).sum::<u32>() + unit.points},
-            RosterElement::ElemSupport(support) => support.points,
-            RosterElement::ElemOther((_, value, _, _)) => *value,
+    fn view_card(&self, ctx: &Context<Self>, entry: &RosterEntry, i: usize, count: usize, on_pointer_move: &Callback<MouseEvent>) -> Html {
+        let theme = CardTheme::for_mode(ctx.props().is_dark_mode);
+
+        // Checking for selected elements, with a different css look.
+        let is_selected = self.selection.selection.is_selected(&entry.id);
+        // The worst rule the entry breaks, if not set aside
+        let severity = self.violations.iter()
+            .filter(|violation| violation.suppression.is_none() && violation.entries.contains(&i))
+            .map(|violation| violation.rule.severity)
+            .min();
+        let element_class = classes!(
+            "hoverable-area",
+            format!("type-{}", entry.element.get_type_name().to_lowercase()),
+            is_selected.then_some("selected"),
+            severity.map(|severity| format!("violation-{}", severity.get_label().to_lowercase())),
+            (self.drop_index == Some(i)).then_some("drop-target"),
+            self.leaving_ids.contains(&entry.id).then_some("leaving"));
+
+        let card = card_svg::roster_card(entry, &theme, &paths::image_url(&entry.element.get_image()));
+        let is_editing = self.editing_index == Some(i);
+
+        html!{
+            <div class={element_class}
+                key={entry.id.clone()}
+                data-key={entry.id.clone()}
+                data-index={i.to_string()}
+                tabindex="0"
+                draggable={(!is_editing).to_string()}
+                // Ctrl+click adds to the selection
+                onclick={ctx.link().callback(move |event: MouseEvent| CanvasMessage::SelectElement(i, event.ctrl_key() || event.meta_key()))}
+
+                onmouseover={ctx.link().callback(move |_| CanvasMessage::ShowTooltip(i))}
+                onmousemove={on_pointer_move.clone()}
+                onmouseout={ctx.link().callback(|_| CanvasMessage::HideTooltip)}
+                ondblclick={ctx.link().callback(move |_| CanvasMessage::ActivateElement(i))}
+                oncontextmenu={ctx.link().callback(move |event: MouseEvent| {
+                    event.prevent_default();
+                    CanvasMessage::OpenContextMenu(i, event.client_x(), event.client_y())
+                })}
+                ondragstart={ctx.link().callback(move |event: DragEvent| {
+                    // Firefox doesn't start a drag without data.
+                    if let Some(data) = event.data_transfer() {
+                        let _ = data.set_data("text/plain", &i.to_string());
+                    }
+                    CanvasMessage::DragElement(i)
+                })}
+                ondragover={ctx.link().callback(move |event: DragEvent| {
+                    event.prevent_default();
+                    CanvasMessage::DragOverElement(i)
+                })}
+                ondrop={ctx.link().callback(move |event: DragEvent| {
+                    // Not dropped on the wave column as well.
+                    event.prevent_default();
+                    event.stop_propagation();
+                    CanvasMessage::DropElement(i)
+                })}
+                ondragend={ctx.link().callback(|_| CanvasMessage::EndDrag)}>
+                { Html::from_html_unchecked(card.into()) }
+                if is_editing {
+                    { self.view_element_editor(ctx, entry, i) }
+                } else {
+                    { self.view_card_actions(ctx, i, count, entry.quantity) }
+                }
+            </div>
         }
     }
-    
-    fn get_image(&self, elem: &RosterElement) -> String {
-        match elem {
-            RosterElement::ElemCharacter(_) => "character.png".to_string(), // TODO TBR Unused
-            RosterElement::ElemUnit(unit) => unit.image.clone(), // TODO TBR Unused
-            RosterElement::ElemSupport(_) => "support.png".to_string(),// TODO TBR Unused
-            RosterElement::ElemOther((_, _, _, image)) => image.clone(),
+
+    // Over the card, for its name and its own points, attachments aside, and
+    // its note. Enter saves, Escape gives up. Clicks stay in the form.
+    fn view_element_editor(&self, ctx: &Context<Self>, entry: &RosterEntry, index: usize) -> Html {
+        let (name, points) = entry.element.get_name_and_points();
+        let stop = Callback::from(|event: MouseEvent| event.stop_propagation());
+        html! {
+            <form class="element-editor"
+                onclick={stop.clone()}
+                ondblclick={stop}
+                onsubmit={ctx.link().callback(move |event: SubmitEvent| {
+                    event.prevent_default();
+                    CanvasMessage::SaveElementEdit(index)
+                })}
+                onkeydown={ctx.link().batch_callback(|event: KeyboardEvent| {
+                    (event.key() == "Escape").then_some(CanvasMessage::CancelElementEdit)
+                })}>
+                <label>{"Name"}<input type="text" ref={self.edit_name_ref.clone()} value={name} /></label>
+                <label>{"Points"}<input type="number" min="0" ref={self.edit_points_ref.clone()} value={points.to_string()} /></label>
+                <label>{"Note"}<textarea rows="2" placeholder="Loadout, stand-in models..." ref={self.edit_note_ref.clone()} value={entry.note.clone()} /></label>
+                <div class="element-editor-buttons">
+                    <button type="submit">{"Save"}</button>
+                    <button type="button" onclick={ctx.link().callback(|_| CanvasMessage::CancelElementEdit)}>{"Cancel"}</button>
+                </div>
+            </form>
         }
     }
 
-    fn render_attached_elements(&self, elem: &RosterElement) -> Html {
-        match elem {
-            RosterElement::ElemUnit(unit) => {
-                if !unit.attached_elements.is_empty() {
+    // The roster split in its two waves, cards are dragged from one column
+    // to the other. Dropped on a card, they take its place in the roster too.
+    fn view_deployment_board(&self, ctx: &Context<Self>, entries: &[RosterEntry], on_pointer_move: &Callback<MouseEvent>) -> Html {
+        html! {
+            <div class="deployment-board">
+                { for Wave::ALL.iter().map(|wave| {
+                    let wave = *wave;
+                    let wave_entries: Vec<(usize, &RosterEntry)> = entries.iter().enumerate()
+                        .filter(|(_, entry)| entry.wave == wave)
+                        .collect();
+                    let points: u32 = wave_entries.iter().map(|(_, entry)| entry.get_total_points()).sum();
                     html! {
-                        <div class="attached-elements">
-                            { for unit.attached_elements.iter().map(|element| html!{
-                                <div class="attached-element-name">{ element.get_name_and_points().0 }</div>
-                            }) }
+                        <div class="wave-column"
+                            ondragover={Callback::from(|event: DragEvent| event.prevent_default())}
+                            ondrop={ctx.link().callback(move |event: DragEvent| {
+                                event.prevent_default();
+                                CanvasMessage::DropElementOnWave(wave)
+                            })}>
+                            <div class="wave-title">
+                                { wave.get_label() }
+                                <span class="wave-summary">
+                                    { if card_svg::are_points_hidden() {
+                                        format!("{} elements", wave_entries.len())
+                                    } else {
+                                        format!("{} elements, {} points", wave_entries.len(), points)
+                                    } }
+                                </span>
+                            </div>
+                            <div class="wave-cards">
+                                { for wave_entries.iter().map(|(i, entry)| {
+                                    self.view_card(ctx, entry, *i, entries.len(), on_pointer_move)
+                                }) }
+                            </div>
                         </div>
                     }
-                } else {
-                    html! {}
+                }) }
+            </div>
+        }
+    }
+
+    fn view_copies_offer(&self, ctx: &Context<Self>, roster: &Roster, index: usize, entry: &RosterEntry) -> Html {
+        let count = roster.get_copies(index).into_iter().filter(|copy| roster.elements[*copy].wave != entry.wave).count();
+        html! {
+            <div class="copies-offer">
+                { format!("{} other {} of {} not in the {}.", count, if count == 1 { "copy" } else { "copies" }, entry.element.get_name(), entry.wave.get_label()) }
+                <button onclick={ctx.link().callback(move |_| CanvasMessage::ApplyToCopies(index))}>{"Apply to All Copies"}</button>
+                <button onclick={ctx.link().callback(|_| CanvasMessage::DismissCopiesOffer)}>{"Dismiss"}</button>
+            </div>
+        }
+    }
+
+    fn view_quick_fix(&self, ctx: &Context<Self>, roster: &Roster, fix: &QuickFix) -> Html {
+        let label = fix.get_label(roster);
+        let fix = fix.clone();
+        html! {
+            <button class="quick-fix" onclick={ctx.link().callback(move |_| CanvasMessage::ApplyQuickFix(fix.clone()))}>{ label }</button>
+        }
+    }
+
+    // Rules the roster breaks, with their quick fix when there is one. The
+    // ones set aside are faded, with their reason and a way to take them back.
+    fn view_violations(&self, ctx: &Context<Self>, roster: &Roster) -> Html {
+        html! {
+            if !self.violations.is_empty() {
+                <div class="violations">
+                    { for self.violations.iter().map(|violation| {
+                        let severity = violation.rule.severity;
+                        let is_error = severity == Severity::Error && violation.suppression.is_none();
+                        let key = violation.key.clone();
+                        html! {
+                            <div class={classes!("violation", format!("severity-{}", severity.get_label().to_lowercase()),
+                                violation.suppression.is_some().then_some("suppressed"), is_error.then_some("over-limit"))}>
+                                <span class="severity">{ severity.get_label() }</span>
+                                <span>{ &violation.message }</span>
+                                if let Some(suppression) = &violation.suppression {
+                                    <span class="suppression-reason">{ format!("Set aside: {}", suppression.reason) }</span>
+                                    <button onclick={ctx.link().callback(move |_| CanvasMessage::UnsuppressViolation(key.clone()))}>{"Undo Set Aside"}</button>
+                                } else if self.suppressing_key.as_ref() == Some(&violation.key) {
+                                    <input type="text" placeholder="Reason, e.g. allowed by the organizer"
+                                        onchange={ctx.link().callback(move |event: Event| {
+                                            let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                                            CanvasMessage::SuppressViolation(key.clone(), input.value())
+                                        })} />
+                                } else {
+                                    if let Some(fix) = &violation.fix {
+                                        { self.view_quick_fix(ctx, roster, fix) }
+                                    }
+                                    if severity != Severity::Info {
+                                        <button onclick={ctx.link().callback(move |_| CanvasMessage::StartSuppressing(key.clone()))}>{"Set Aside"}</button>
+                                    }
+                                }
+                            </div>
+                        }
+                    }) }
+                </div>
+            }
+        }
+    }
+
+    // Likely mistakes in the roster, each with its fix if it has one.
+    fn view_suggestions(&self, ctx: &Context<Self>, roster: &Roster) -> Html {
+        let suggestions = lint::lint_roster(roster, |image| preloader::has_failed(&paths::image_url(image)));
+        html! {
+            <div class="lint-suggestions">
+                if suggestions.is_empty() {
+                    <div>{"Nothing to suggest, the roster looks fine."}</div>
                 }
-            },
-            _ => html! {}
+                { for suggestions.iter().map(|suggestion| html! {
+                    <div class="lint-suggestion">
+                        <span>{ format!("#{}: {}", suggestion.index + 1, suggestion.message) }</span>
+                        if let Some(fix) = &suggestion.fix {
+                            { self.view_quick_fix(ctx, roster, fix) }
+                        }
+                    </div>
+                }) }
+            </div>
         }
     }
 
-    // Deciding the style of the image based on the image type.
-    fn get_image_class(&self, ctx: &Context<Self>, elem: &RosterElement) -> &str {
-        let image_path = self.get_image(elem);
-        if ctx.props().is_dark_mode && (image_path == "character.png" || image_path == "support.png") {
-            "inverted-roster-image"
-        } else {
-            "roster-image"
+    // Subtotal and share of the points of each group, flagged past the cap.
+    fn view_breakdown(&self, ctx: &Context<Self>, roster: &Roster) -> Html {
+        let cap = ctx.props().group_cap_percent;
+        html! {
+            <div class="cost-breakdown">
+                { for breakdown::get_groupings(roster).into_iter().map(|grouping| html! {
+                    <table>
+                        <tr>
+                            <th>{ grouping.get_label() }</th><th>{"Entries"}</th><th>{"Points"}</th>
+                            <th>{ cap.map(|cap| format!("Share (cap {}%)", cap)).unwrap_or_else(|| "Share".to_string()) }</th>
+                        </tr>
+                        { for breakdown::get_breakdown(roster, grouping, cap).into_iter().map(|group| html! {
+                            <tr class={classes!(group.is_over_cap.then_some("over-limit"))}>
+                                <td>{ group.label }</td>
+                                <td>{ group.entries }</td>
+                                <td>{ group.points }</td>
+                                <td>
+                                    <span class="share-bar" style={format!("width: {:.0}px;", group.share_percent * SHARE_BAR_WIDTH / 100.0)} />
+                                    { format!("{:.0}%", group.share_percent) }
+                                </td>
+                            </tr>
+                        }) }
+                    </table>
+                }) }
+            </div>
+        }
+    }
+
+    // Shown over the card on hover or focus. Clicks stay in the bar, not
+    // selecting nor double clicking the card below.
+    fn view_card_actions(&self, ctx: &Context<Self>, index: usize, count: usize, quantity: u32) -> Html {
+        let action = |message: fn(usize) -> CanvasMessage| ctx.link().callback(move |event: MouseEvent| {
+            event.stop_propagation();
+            message(index)
+        });
+        html! {
+            <div class="card-actions" ondblclick={Callback::from(|event: MouseEvent| event.stop_propagation())}>
+                <button title="Move back" disabled={index == 0} onclick={action(|index| CanvasMessage::MoveElement(index, -1))}>{"\u{2190}"}</button>
+                <button title="Move forward" disabled={index + 1 >= count} onclick={action(|index| CanvasMessage::MoveElement(index, 1))}>{"\u{2192}"}</button>
+                <button title="One copy less" disabled={quantity <= 1} onclick={action(|index| CanvasMessage::ChangeQuantity(index, -1))}>{"\u{2212}"}</button>
+                <button title="One copy more" disabled={quantity >= MAX_QUANTITY} onclick={action(|index| CanvasMessage::ChangeQuantity(index, 1))}>{"+"}</button>
+                <button title="Duplicate" onclick={action(CanvasMessage::DuplicateElement)}>{"\u{29C9}"}</button>
+                <button title="Edit name, points and note" onclick={action(CanvasMessage::StartEditingElement)}>{"\u{270E}"}</button>
+                <button title="Edit campaign record" onclick={action(CanvasMessage::OpenCampaignEditor)}>{"\u{2605}"}</button>
+                <button title="Delete" onclick={action(CanvasMessage::DeleteElement)}>{"\u{2715}"}</button>
+            </div>
+        }
+    }
+
+    // Closed by clicking anywhere else.
+    fn view_context_menu(&self, ctx: &Context<Self>, index: usize, x: i32, y: i32) -> Html {
+        html! {
+            <div class="context-menu-backdrop"
+                onclick={ctx.link().callback(|_| CanvasMessage::CloseContextMenu)}
+                oncontextmenu={ctx.link().callback(|event: MouseEvent| {
+                    event.prevent_default();
+                    CanvasMessage::CloseContextMenu
+                })}>
+                <div class="context-menu" style={format!("left: {}px; top: {}px;", x, y)}>
+                    <button onclick={ctx.link().callback(move |_| CanvasMessage::ShowElementDetails(index))}>{"Details"}</button>
+                    <button onclick={ctx.link().callback(move |_| CanvasMessage::DuplicateElement(index))}>{"Duplicate"}</button>
+                    <button onclick={ctx.link().callback(move |_| CanvasMessage::StartEditingElement(index))}>{"Edit Name, Points and Note"}</button>
+                    <button onclick={ctx.link().callback(move |_| CanvasMessage::OpenCampaignEditor(index))}>{"Edit Campaign Record"}</button>
+                    <button onclick={ctx.link().callback(move |_| CanvasMessage::DeleteElement(index))}>{"Delete"}</button>
+                </div>
+            </div>
         }
     }
 
-    // Simple logic to correctly format the point label.
-    fn get_points_label(&self, elem: &RosterElement) -> String {
-        let points = self.get_element_points(elem);
-        if points > 1 {
-            format!("{} Points", points)
-        } else {
-            "1 Point".to_string()
+    fn view_details(&self, ctx: &Context<Self>, entry: &RosterEntry, index: usize) -> Html {
+        let theme = CardTheme::for_mode(ctx.props().is_dark_mode);
+        let card = card_svg::stat_card(entry, &theme, &paths::image_url(&entry.element.get_image()));
+        let on_edit = {
+            let (on_edit, link, id) = (ctx.props().on_edit_element.clone(), ctx.link().clone(), entry.id.clone());
+            Callback::from(move |_| {
+                link.send_message(CanvasMessage::HideElementDetails);
+                on_edit.emit(id.clone());
+            })
+        };
+        html! {
+            <div class="modal-backdrop">
+                <div class="modal element-details">
+                    <div class="modal-title">{ entry.element.get_name() }</div>
+                    { Html::from_html_unchecked(card.into()) }
+                    <button onclick={ctx.link().callback(move |_| CanvasMessage::StartEditingElement(index))}>{"Edit Name, Points and Note"}</button>
+                    <button onclick={on_edit}>{"Edit Campaign Record"}</button>
+                    <button onclick={ctx.link().batch_callback(move |_| vec![CanvasMessage::HideElementDetails, CanvasMessage::DuplicateElement(index)])}>{"Duplicate"}</button>
+                    <button onclick={ctx.link().callback(move |_| CanvasMessage::DeleteElement(index))}>{"Delete"}</button>
+                    <button onclick={ctx.link().callback(|_| CanvasMessage::HideElementDetails)}>{"Close"}</button>
+                </div>
+            </div>
         }
     }
 
+    // The tooltip shows the full stat card of the hovered element, with the
+    // rules it breaks.
+    fn get_tooltip_content(&self, ctx: &Context<Self>, entry: &RosterEntry, index: usize) -> Html {
+        let theme = CardTheme::for_mode(ctx.props().is_dark_mode);
+        let card = card_svg::stat_card(entry, &theme, &paths::image_url(&entry.element.get_image()));
+        html! {
+            <>
+                { Html::from_html_unchecked(card.into()) }
+                if !entry.note.is_empty() {
+                    <div class="tooltip-note">{ &entry.note }</div>
+                }
+                { for self.violations.iter().filter(|violation| violation.suppression.is_none() && violation.entries.contains(&index)).map(|violation| html! {
+                    <div class={classes!("tooltip-violation", format!("severity-{}", violation.rule.severity.get_label().to_lowercase()))}>{ &violation.message }</div>
+                }) }
+                <div>{ self.get_tooltip_hint(ctx) }</div>
+            </>
+        }
+    }
+}
+
+// In a browser, see wasm-pack test: the canvas is drawn from a fixture and
+// driven through its cards, as the player would.
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use wasm_bindgen_test::*;
+
+    use super::*;
+    use crate::core::fixtures::RosterFixture;
+    use crate::models::selection::Selection;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Properties, PartialEq)]
+    struct HarnessProps {
+        roster: RosterStore,
+    }
+
+    // What App provides around the canvas.
+    #[function_component]
+    fn Harness(props: &HarnessProps) -> Html {
+        let selection = SelectionContext {selection: Selection::default(), on_change: Callback::noop()};
+        html! {
+            <ContextProvider<SelectionContext> context={selection}>
+                <MainCanvas
+                    roster={props.roster.clone()}
+                    is_dark_mode={false}
+                    on_edit_element={Callback::noop()}
+                    double_click_action={DoubleClickAction::Details}
+                    is_color_coded={true}
+                    zoom_percent={100}
+                    on_zoom={Callback::noop()}
+                    is_deployment_board={false}
+                    on_toggle_deployment_board={Callback::noop()}
+                    group_cap_percent={None}
+                    house_rules={Vec::new()}
+                />
+            </ContextProvider<SelectionContext>>
+        }
+    }
+
+    // The canvas in an element of its own, and the roster it edits.
+    async fn render(roster: Roster) -> (web_sys::Element, RosterStore) {
+        let store = RosterStore::new(roster);
+        let root = gloo::utils::document().create_element("div").unwrap();
+        gloo::utils::body().append_child(&root).unwrap();
+        yew::Renderer::<Harness>::with_root_and_props(root.clone(), HarnessProps {roster: store.clone()}).render();
+        wait(0).await;
+        (root, store)
+    }
+
+    async fn wait(ms: i32) {
+        let promise = js_sys::Promise::new(&mut |resolve, _| {
+            gloo::utils::window().set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, ms).unwrap();
+        });
+        wasm_bindgen_futures::JsFuture::from(promise).await.unwrap();
+    }
+
+    fn get_cards(root: &web_sys::Element) -> Vec<web_sys::HtmlElement> {
+        let cards = root.query_selector_all(".hoverable-area").unwrap();
+        (0..cards.length()).filter_map(|index| cards.get(index)?.dyn_into().ok()).collect()
+    }
+
+    fn dispatch(target: &web_sys::Element, event_type: &str) {
+        let init = web_sys::MouseEventInit::new();
+        init.set_bubbles(true);
+        let event = web_sys::MouseEvent::new_with_mouse_event_init_dict(event_type, &init).unwrap();
+        target.dispatch_event(&event).unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    async fn delete_fades_the_card_out_then_removes_its_entry() {
+        let (root, store) = render(RosterFixture::skirmish_30()).await;
+        let count = store.borrow().elements.len();
+        let deleted_id = store.borrow().elements[0].id.clone();
+
+        let cards = get_cards(&root);
+        assert_eq!(cards.len(), count);
+        cards[0].query_selector("button[title=Delete]").unwrap().unwrap().unchecked_into::<web_sys::HtmlElement>().click();
+        wait(0).await;
+        assert!(get_cards(&root)[0].class_list().contains("leaving"));
+        assert_eq!(store.borrow().elements.len(), count);
+
+        wait(LEAVE_ANIMATION_MS as i32 + 100).await;
+        assert_eq!(store.borrow().elements.len(), count - 1);
+        assert_eq!(store.borrow().find_entry(&deleted_id), None);
+        assert_eq!(get_cards(&root).len(), count - 1);
+        assert_eq!(store.take_executed().len(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    async fn tooltip_follows_the_hovered_card() {
+        let mut roster = RosterFixture::skirmish_30();
+        roster.elements[1].note = "Holds the bridge".to_string();
+        let (root, _store) = render(roster).await;
+        let tooltip = || gloo::utils::body().query_selector(".tooltip").unwrap();
+        assert!(tooltip().is_none());
+
+        dispatch(&get_cards(&root)[1], "mouseover");
+        wait(0).await;
+        let shown = tooltip().expect("the tooltip is shown");
+        assert_eq!(shown.query_selector(".tooltip-note").unwrap().and_then(|note| note.text_content()).as_deref(), Some("Holds the bridge"));
+
+        dispatch(&get_cards(&root)[1], "mouseout");
+        wait(0).await;
+        assert!(tooltip().is_none());
+    }
+
+    #[wasm_bindgen_test]
+    async fn reorder_groups_by_type_then_name_in_a_single_command() {
+        let mut roster = RosterFixture::standard_60();
+        roster.elements.reverse();
+        let ids: Vec<String> = roster.elements.iter().map(|entry| entry.id.clone()).collect();
+        let (root, store) = render(roster).await;
+
+        let button = root.query_selector(".reorder-button-area button").unwrap().unwrap();
+        button.unchecked_into::<web_sys::HtmlElement>().click();
+        wait(0).await;
+
+        let reordered = store.borrow().clone();
+        let rank = |element: &RosterElement| match element {
+            RosterElement::ElemCharacter(_) => 0,
+            RosterElement::ElemUnit(_) | RosterElement::ElemOther(_) => 1,
+            RosterElement::ElemSupport(_) => 2,
+        };
+        let keys: Vec<(u32, String)> = reordered.elements.iter()
+            .map(|entry| (rank(&entry.element), entry.element.get_name()))
+            .collect();
+        assert!(keys.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", keys);
+
+        let mut reordered_ids: Vec<String> = reordered.elements.iter().map(|entry| entry.id.clone()).collect();
+        reordered_ids.sort();
+        let mut sorted_ids = ids;
+        sorted_ids.sort();
+        assert_eq!(reordered_ids, sorted_ids);
+        assert_eq!(store.take_executed().len(), 1);
+        assert_eq!(reordered.check_invariants(), Ok(()));
+    }
 }
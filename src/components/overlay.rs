@@ -0,0 +1,59 @@
+use yew::prelude::*;
+
+use crate::models::presentation::{OverlayBackground, OverlayOptions};
+use crate::models::roster_store::RosterStore;
+use crate::render::card_svg;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub roster: RosterStore,
+    pub options: OverlayOptions,
+}
+
+// The roster in presentation mode: no menus, no cards, just big lines of
+// text on a background a streaming software can key out.
+pub struct Overlay {}
+
+impl Component for Overlay {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        Overlay {}
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let roster = ctx.props().roster.borrow();
+        let options = &ctx.props().options;
+        let total_points: u32 = roster.elements.iter().map(|entry| entry.get_total_points()).sum();
+
+        let class = classes!(
+            "overlay",
+            (options.background == OverlayBackground::Green).then_some("green-screen"),
+            options.auto_scroll.then_some("auto-scroll"));
+
+        html! {
+            <div class={class}>
+                <div class="overlay-list">
+                    <div class="overlay-title">{ &roster.metadata.name }</div>
+                    { for roster.elements.iter().map(|entry| {
+                        let elem = &entry.element;
+                        let attached: Vec<String> = elem.get_attached_elements().iter().map(|attached| attached.get_name()).collect();
+                        html! {
+                            <div class="overlay-entry">
+                                <span class="overlay-name">{ entry.get_counted_name() }</span>
+                                <span class="overlay-points">{ card_svg::points_label(entry.get_total_points()) }</span>
+                                if !attached.is_empty() {
+                                    <div class="overlay-attached">{ format!("with {}", attached.join(", ")) }</div>
+                                }
+                            </div>
+                        }
+                    }) }
+                    if !card_svg::are_points_hidden() {
+                        <div class="overlay-total">{ format!("Total: {}", card_svg::points_label(total_points)) }</div>
+                    }
+                </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,565 @@
+use yew::prelude::*;
+
+// A common definition for all messages:
+use crate::shared_messages::SharedMessage;
+
+use crate::models::clock;
+use crate::models::selection::{SelectionChange, SelectionContext};
+use crate::models::game::{format_clock, Deployment, GameState, LiveRecord};
+use crate::models::roster_store::RosterStore;
+use crate::export::audio::{self, Cue};
+use crate::export::clipboard;
+
+// Sharing the game live, see core::live
+use crate::core::ids;
+use crate::core::live::{FollowLink, LiveGame, RemoteGame};
+use crate::core::sync::PushReceipt;
+use crate::models::live;
+use crate::models::sync::{self, SyncError, SyncSettings};
+
+// The clock display is refreshed a few times per second while open.
+use gloo::timers::callback::Interval;
+
+// Render counts and timings for the dev overlay
+use crate::models::profiler;
+
+const CLOCK_REFRESH_MS: u32 = 250;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub roster: RosterStore,
+    pub on_play_action: Callback<SharedMessage>,
+    // Where the game is shared live, not shared without a server
+    pub sync: SyncSettings,
+}
+
+pub enum Msg {
+    SelectionChanged(SelectionContext),
+    ClockTick,
+    EnableClock(bool),
+    SetClockMinutes(u32),
+    SetClockAlerts(Vec<u32>),
+    SetPlayerName(usize, String),
+    PressClock(usize),
+    PauseClock,
+    ResetClock,
+    DragActivation(usize),
+    DropActivation(usize),
+    NextActivation,
+    ResetActivations,
+    SetDeployment(usize, Deployment),
+    AddCasualty(usize),
+    RemoveCasualty(usize),
+    ChangeScore(usize, i32),
+    ResetTracker,
+    StartLive,
+    StopLive,
+    CopyFollowLink,
+    LivePushed(Result<PushReceipt, SyncError>),
+}
+
+// Everything used at the table during a game. The game state is owned (and
+// saved) here, like the scenario library in the scenario editor.
+pub struct PlayMode {
+    game: GameState,
+    // Last refresh, to find the alert thresholds crossed since
+    last_tick: f64,
+    alert: Option<String>,
+    // Position in the activation order being dragged
+    dragged_activation: Option<usize>,
+    // Entries selected on the canvas, marked in the activation order
+    selection: SelectionContext,
+    _selection_handle: ContextHandle<SelectionContext>,
+    _clock_interval: Interval,
+    // One push of the live game at a time, the changes made meanwhile go
+    // with the next one
+    is_pushing: bool,
+    has_pending_push: bool,
+    // As last sent, the clock isn't followed and changes nothing
+    pushed_game: Option<LiveGame>,
+    live_error: Option<String>,
+}
+
+impl Component for PlayMode {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let (selection, selection_handle) = ctx.link()
+            .context::<SelectionContext>(ctx.link().callback(Msg::SelectionChanged))
+            .expect("The selection is provided by App");
+        let link = ctx.link().clone();
+        PlayMode {
+            game: GameState::load(),
+            last_tick: clock::now_ms(),
+            alert: None,
+            dragged_activation: None,
+            selection,
+            _selection_handle: selection_handle,
+            _clock_interval: Interval::new(CLOCK_REFRESH_MS, move || link.send_message(Msg::ClockTick)),
+            is_pushing: false,
+            has_pending_push: false,
+            pushed_game: None,
+            live_error: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let _timer = profiler::time_update("PlayMode");
+        let now = clock::now_ms();
+        let entry_count = ctx.props().roster.borrow().elements.len();
+        let clock = &mut self.game.clock;
+        match msg {
+            Msg::SelectionChanged(selection) => {
+                self.selection = selection;
+                true
+            }
+
+            Msg::ClockTick => {
+                let before = std::mem::replace(&mut self.last_tick, now);
+                let Some(active) = clock.active_player.filter(|_| clock.is_running()) else {
+                    return false;
+                };
+                let name = clock.players[active].name.clone();
+                if clock.is_expired(active, now) {
+                    clock.pause(now);
+                    self.alert = Some(format!("{}: time is up!", name));
+                    audio::play_cue(Cue::ClockExpired);
+                    self.save(ctx);
+                } else if let Some(minutes) = clock.get_crossed_alert(before, now) {
+                    self.alert = Some(format!("{}: {} minute{} left", name, minutes, if minutes == 1 { "" } else { "s" }));
+                    audio::play_cue(Cue::ClockAlert);
+                }
+                true
+            }
+
+            Msg::EnableClock(is_enabled) => {
+                clock.is_enabled = is_enabled;
+                if !is_enabled {
+                    clock.pause(now);
+                }
+                self.save(ctx);
+                true
+            }
+
+            Msg::SetClockMinutes(minutes) => {
+                clock.total_minutes = minutes.max(1);
+                clock.reset();
+                self.alert = None;
+                self.save(ctx);
+                true
+            }
+
+            Msg::SetClockAlerts(alert_minutes) => {
+                clock.alert_minutes = alert_minutes;
+                self.save(ctx);
+                true
+            }
+
+            Msg::SetPlayerName(player, name) => {
+                if let Some(clock) = clock.players.get_mut(player) {
+                    clock.name = name;
+                }
+                self.save(ctx);
+                true
+            }
+
+            // Pressing the clock of the running player ends their turn.
+            Msg::PressClock(player) => {
+                if clock.is_running() && clock.active_player == Some(player) {
+                    clock.switch(now);
+                    audio::play_cue(Cue::EndOfTurn);
+                } else {
+                    clock.start(player, now);
+                }
+                self.alert = None;
+                self.save(ctx);
+                true
+            }
+
+            Msg::PauseClock => {
+                clock.pause(now);
+                self.save(ctx);
+                true
+            }
+
+            Msg::ResetClock => {
+                clock.reset();
+                self.alert = None;
+                self.save(ctx);
+                true
+            }
+
+            Msg::DragActivation(position) => {
+                self.dragged_activation = Some(position);
+                false
+            }
+
+            Msg::DropActivation(position) => {
+                if let Some(from) = self.dragged_activation.take() {
+                    self.game.activation.move_entry(entry_count, from, position);
+                    self.save(ctx);
+                }
+                true
+            }
+
+            Msg::NextActivation => {
+                self.game.activation.next(&ctx.props().roster.borrow().get_quantities());
+                self.save(ctx);
+                true
+            }
+
+            Msg::ResetActivations => {
+                self.game.activation.reset();
+                self.save(ctx);
+                true
+            }
+
+            Msg::SetDeployment(index, deployment) => {
+                self.game.deployment.set(index, deployment);
+                self.save(ctx);
+                true
+            }
+
+            Msg::AddCasualty(index) => {
+                let quantity = ctx.props().roster.borrow().elements.get(index).map_or(0, |entry| entry.quantity);
+                self.game.tracker.add_casualty(index, quantity);
+                self.save(ctx);
+                true
+            }
+
+            Msg::RemoveCasualty(index) => {
+                self.game.tracker.remove_casualty(index);
+                self.save(ctx);
+                true
+            }
+
+            Msg::ChangeScore(player, change) => {
+                self.game.tracker.change_score(player, change);
+                self.save(ctx);
+                true
+            }
+
+            Msg::ResetTracker => {
+                self.game.tracker = Default::default();
+                self.save(ctx);
+                true
+            }
+
+            Msg::StartLive => {
+                self.game.live = Some(LiveRecord {id: ids::new_id(), revision: 0});
+                self.pushed_game = None;
+                self.live_error = None;
+                self.save(ctx);
+                true
+            }
+
+            // Spectators keep the game as it was last pushed.
+            Msg::StopLive => {
+                self.game.live = None;
+                self.live_error = None;
+                self.save(ctx);
+                true
+            }
+
+            Msg::CopyFollowLink => {
+                match self.get_follow_link(ctx).as_ref().and_then(live::get_follow_url) {
+                    Some(url) => clipboard::copy_text(&url),
+                    None => log::warn!("Error building the follow link"),
+                }
+                false
+            }
+
+            Msg::LivePushed(result) => {
+                self.is_pushing = false;
+                match result {
+                    Ok(receipt) => {
+                        if let Some(record) = &mut self.game.live {
+                            record.revision = receipt.revision;
+                        }
+                        self.live_error = None;
+                        self.game.save();
+                    }
+                    // Pushed from another device or tab, which shares it now.
+                    Err(SyncError::Conflict) => {
+                        self.game.live = None;
+                        self.has_pending_push = false;
+                        self.live_error = Some("The game was shared from somewhere else, share it again to take over".to_string());
+                        self.game.save();
+                    }
+                    Err(e) => {
+                        // Sent again with the next change
+                        self.pushed_game = None;
+                        self.live_error = Some(e.get_message());
+                    }
+                }
+                if std::mem::take(&mut self.has_pending_push) {
+                    self.push_live(ctx);
+                }
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        profiler::record_render("PlayMode");
+        html! {
+            <div class="modal-backdrop">
+                <div class="modal play-mode">
+                    <div class="modal-title">{"Play Mode"}</div>
+                    { self.render_deployment(ctx) }
+                    { self.render_clock(ctx) }
+                    { self.render_activations(ctx) }
+                    { self.render_tracker(ctx) }
+                    { self.render_live(ctx) }
+                    <button onclick={ctx.props().on_play_action.reform(|_| SharedMessage::TogglePlayMode)}>{"Close"}</button>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl PlayMode {
+    // Saved, and pushed to the spectators when the game is shared live.
+    fn save(&mut self, ctx: &Context<Self>) {
+        self.game.save();
+        self.push_live(ctx);
+    }
+
+    fn push_live(&mut self, ctx: &Context<Self>) {
+        let (Some(record), Some(client)) = (&self.game.live, sync::get_sync_client(&ctx.props().sync)) else {
+            return;
+        };
+        if self.is_pushing {
+            self.has_pending_push = true;
+            return;
+        }
+        let game = live::get_live_game(&ctx.props().roster.borrow(), &self.game);
+        if self.pushed_game.as_ref() == Some(&game) {
+            return;
+        }
+        self.is_pushing = true;
+        self.pushed_game = Some(game.clone());
+        client.push_game(&record.id, RemoteGame {revision: record.revision, game}, ctx.link().callback(Msg::LivePushed));
+    }
+
+    fn get_follow_link(&self, ctx: &Context<Self>) -> Option<FollowLink> {
+        let record = self.game.live.as_ref()?;
+        Some(FollowLink {server: ctx.props().sync.base_url.trim().to_string(), game_id: record.id.clone()})
+    }
+
+    // Entries lost and scores, marked as the game goes.
+    fn render_tracker(&self, ctx: &Context<Self>) -> Html {
+        let roster = ctx.props().roster.borrow();
+        let tracker = &self.game.tracker;
+
+        html! {
+            <div class="game-tracker">
+                <div class="modal-subtitle">{"Scores and Casualties"}</div>
+                <div class="tracker-scores">
+                    { for self.game.clock.players.iter().enumerate().map(|(player, clock)| html! {
+                        <div class="tracker-row">
+                            <span>{ &clock.name }</span>
+                            <button onclick={ctx.link().callback(move |_| Msg::ChangeScore(player, -1))}>{"-"}</button>
+                            <span class="tracker-count">{ tracker.get_score(player) }</span>
+                            <button onclick={ctx.link().callback(move |_| Msg::ChangeScore(player, 1))}>{"+"}</button>
+                        </div>
+                    }) }
+                </div>
+                { for roster.elements.iter().enumerate().map(|(index, entry)| {
+                    let casualties = tracker.get_casualties(index).min(entry.quantity);
+                    html! {
+                        <div class={classes!("tracker-row", (casualties >= entry.quantity).then_some("destroyed"))}>
+                            <span>{ entry.element.get_name() }</span>
+                            <button disabled={casualties == 0} onclick={ctx.link().callback(move |_| Msg::RemoveCasualty(index))}>{"-"}</button>
+                            <span class="tracker-count">{ format!("{} / {} lost", casualties, entry.quantity) }</span>
+                            <button disabled={casualties >= entry.quantity} onclick={ctx.link().callback(move |_| Msg::AddCasualty(index))}>{"+"}</button>
+                        </div>
+                    }
+                }) }
+                <button onclick={ctx.link().callback(|_| Msg::ResetTracker)}>{"Clear"}</button>
+            </div>
+        }
+    }
+
+    // The link for the spectators, once the game is on the sync server.
+    fn render_live(&self, ctx: &Context<Self>) -> Html {
+        if !ctx.props().sync.is_enabled() {
+            return html! {
+                <div class="live-share">{"Set a sync server in the settings to let spectators follow the game live."}</div>
+            };
+        }
+        let follow_url = self.get_follow_link(ctx).as_ref().and_then(live::get_follow_url);
+
+        html! {
+            <div class="live-share">
+                <div class="modal-subtitle">{"Spectators"}</div>
+                if let Some(error) = &self.live_error {
+                    <div class="over-limit">{ error }</div>
+                }
+                if let Some(url) = follow_url {
+                    <input type="text" readonly=true value={url} />
+                    <button onclick={ctx.link().callback(|_| Msg::CopyFollowLink)}>{"Copy Link"}</button>
+                    <button onclick={ctx.link().callback(|_| Msg::StopLive)}>{"Stop Sharing"}</button>
+                } else {
+                    <button title="Push the scores and casualties to the sync server, for a read-only link following them"
+                        onclick={ctx.link().callback(|_| Msg::StartLive)}>{"Share Live"}</button>
+                }
+            </div>
+        }
+    }
+
+    // Pre-game step: where each entry starts, checked against the limits.
+    fn render_deployment(&self, ctx: &Context<Self>) -> Html {
+        let roster = ctx.props().roster.borrow();
+        let deployment = &self.game.deployment;
+
+        html! {
+            <div class="deployment-plan">
+                <div class="modal-subtitle">{"Deployment"}</div>
+                { for deployment.validate(&roster.get_quantities()).iter().map(|error| html! { <div class="over-limit">{ error }</div> }) }
+                { for roster.elements.iter().enumerate().map(|(index, entry)| {
+                    let on_change = ctx.link().callback(move |event: Event| {
+                        let select: web_sys::HtmlSelectElement = event.target_unchecked_into();
+                        Msg::SetDeployment(index, Deployment::ALL.get(select.selected_index().max(0) as usize).copied().unwrap_or_default())
+                    });
+                    html! {
+                        <label class="settings-row">
+                            <span>{ entry.element.get_name() }</span>
+                            <select onchange={on_change}>
+                                { for Deployment::ALL.iter().map(|option| html! {
+                                    <option selected={*option == deployment.get(index)}>{ option.get_label() }</option>
+                                }) }
+                            </select>
+                        </label>
+                    }
+                }) }
+            </div>
+        }
+    }
+
+    // The planned activation order, rearranged by dragging the entries. A
+    // click selects the entry, on the canvas as well.
+    fn render_activations(&self, ctx: &Context<Self>) -> Html {
+        let roster = ctx.props().roster.borrow();
+        let activation = &self.game.activation;
+        let order = activation.get_order(roster.elements.len());
+
+        html! {
+            <div class="activation-plan">
+                <div class="modal-subtitle">
+                    { if activation.round > 0 { format!("Activation Order - Round {}", activation.round) } else { "Activation Order".to_string() } }
+                </div>
+                <ol class="activation-list">
+                    { for order.iter().enumerate().filter_map(|(position, index)| {
+                        let entry = roster.elements.get(*index)?;
+                        let class = classes!(
+                            "activation-entry",
+                            (activation.current == Some(position)).then_some("current"),
+                            activation.current.is_some_and(|current| position < current).then_some("done"),
+                            self.selection.selection.is_selected(&entry.id).then_some("selected"));
+                        let (index, id) = (*index, entry.id.clone());
+                        Some(html! {
+                            <li class={class}
+                                onclick={self.selection.on_change.reform(move |event: MouseEvent| {
+                                    if event.ctrl_key() || event.meta_key() { SelectionChange::Extend(id.clone()) } else { SelectionChange::Select(id.clone()) }
+                                })}
+                                draggable="true"
+                                ondragstart={ctx.link().callback(move |event: DragEvent| {
+                                    // Firefox doesn't start a drag without data.
+                                    if let Some(data) = event.data_transfer() {
+                                        let _ = data.set_data("text/plain", &position.to_string());
+                                    }
+                                    Msg::DragActivation(position)
+                                })}
+                                ondragover={Callback::from(|event: DragEvent| event.prevent_default())}
+                                ondrop={ctx.link().callback(move |event: DragEvent| {
+                                    event.prevent_default();
+                                    Msg::DropActivation(position)
+                                })}>
+                                { entry.element.get_name() }
+                                if activation.current == Some(position) && entry.quantity > 1 {
+                                    <span class="activation-copy">{ format!(" {} of {}", activation.copy.max(1), entry.quantity) }</span>
+                                }
+                                if self.game.deployment.get(index) != Deployment::Deployed {
+                                    <span class="deployment-tag">{ self.game.deployment.get(index).get_label() }</span>
+                                }
+                            </li>
+                        })
+                    }) }
+                </ol>
+                <button disabled={order.is_empty()} onclick={ctx.link().callback(|_| Msg::NextActivation)}>{"Next Activation"}</button>
+                <button onclick={ctx.link().callback(|_| Msg::ResetActivations)}>{"Restart"}</button>
+            </div>
+        }
+    }
+
+    fn render_clock(&self, ctx: &Context<Self>) -> Html {
+        let clock = &self.game.clock;
+        let now = clock::now_ms();
+
+        let on_enable = ctx.link().callback(|event: Event| {
+            let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+            Msg::EnableClock(input.checked())
+        });
+        let header = html! {
+            <label class="settings-row">
+                <span class="modal-subtitle">{"Game Clock"}</span>
+                <input type="checkbox" checked={clock.is_enabled} onchange={on_enable} />
+            </label>
+        };
+        if !clock.is_enabled {
+            return header;
+        }
+
+        let on_minutes_change = ctx.link().callback(|event: Event| {
+            let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+            Msg::SetClockMinutes(input.value().parse().unwrap_or(60))
+        });
+        let on_alerts_change = ctx.link().callback(|event: Event| {
+            let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+            Msg::SetClockAlerts(input.value().split(',').filter_map(|value| value.trim().parse().ok()).collect())
+        });
+
+        html! {
+            <div class="game-clock">
+                { header }
+                if let Some(alert) = &self.alert {
+                    <div class="over-limit clock-alert">{ alert }</div>
+                }
+                <div class="clock-faces">
+                    { for clock.players.iter().enumerate().map(|(index, player)| {
+                        let remaining = clock.get_remaining_ms(index, now);
+                        let class = classes!(
+                            "clock-face",
+                            (clock.active_player == Some(index) && clock.is_running()).then_some("running"),
+                            (remaining <= 0.0).then_some("over-limit"));
+                        let on_name_change = ctx.link().callback(move |event: Event| {
+                            let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                            Msg::SetPlayerName(index, input.value())
+                        });
+                        html! {
+                            <div class={class}>
+                                <input type="text" value={player.name.clone()} onchange={on_name_change} />
+                                <button class="clock-time" onclick={ctx.link().callback(move |_| Msg::PressClock(index))}>
+                                    { format_clock(remaining) }
+                                </button>
+                            </div>
+                        }
+                    }) }
+                </div>
+                <button disabled={!clock.is_running()} onclick={ctx.link().callback(|_| Msg::PauseClock)}>{"Pause"}</button>
+                <button onclick={ctx.link().callback(|_| Msg::ResetClock)}>{"Reset"}</button>
+                <label class="settings-row">
+                    <span>{"Minutes per player"}</span>
+                    <input type="number" min="1" value={clock.total_minutes.to_string()} onchange={on_minutes_change} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Alerts at (minutes left)"}</span>
+                    <input type="text" value={clock.alert_minutes.iter().map(|minutes| minutes.to_string()).collect::<Vec<_>>().join(", ")}
+                        onchange={on_alerts_change} />
+                </label>
+            </div>
+        }
+    }
+}
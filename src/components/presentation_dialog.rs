@@ -0,0 +1,72 @@
+use yew::prelude::*;
+
+// A common definition for all messages:
+use crate::shared_messages::SharedMessage;
+
+use crate::models::presentation::{OverlayBackground, OverlayOptions};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub on_presentation_action: Callback<SharedMessage>,
+}
+
+// Options of the streaming overlay, opened in a new tab or copied as a link
+// to paste into the browser source of the streaming software.
+pub struct PresentationDialog {
+    background_ref: NodeRef,
+    auto_scroll_ref: NodeRef,
+}
+
+impl Component for PresentationDialog {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        PresentationDialog {
+            background_ref: NodeRef::default(),
+            auto_scroll_ref: NodeRef::default(),
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_action = ctx.props().on_presentation_action.clone();
+
+        let (background_ref, auto_scroll_ref) = (self.background_ref.clone(), self.auto_scroll_ref.clone());
+        let read_options = move || {
+            let background_index = background_ref.cast::<web_sys::HtmlSelectElement>()
+                .map(|select| select.selected_index().max(0) as usize)
+                .unwrap_or(0);
+            OverlayOptions {
+                background: OverlayBackground::ALL.get(background_index).copied().unwrap_or_default(),
+                auto_scroll: auto_scroll_ref.cast::<web_sys::HtmlInputElement>().map(|input| input.checked()).unwrap_or(false),
+            }
+        };
+        let on_open = {
+            let read_options = read_options.clone();
+            on_action.reform(move |_| SharedMessage::OpenOverlay(read_options()))
+        };
+        let on_copy = on_action.reform(move |_| SharedMessage::CopyOverlayLink(read_options()));
+
+        html! {
+            <div class="modal-backdrop">
+                <div class="modal presentation-dialog">
+                    <div class="modal-title">{"Presentation Mode"}</div>
+                    <p>{"Shows the current roster alone, with large fonts, for use as a browser source while streaming."}</p>
+                    <label class="settings-row">
+                        <span>{"Background"}</span>
+                        <select ref={self.background_ref.clone()}>
+                            { for OverlayBackground::ALL.iter().map(|background| html! { <option>{ background.get_label() }</option> }) }
+                        </select>
+                    </label>
+                    <label class="settings-row">
+                        <span>{"Auto-scroll"}</span>
+                        <input type="checkbox" ref={self.auto_scroll_ref.clone()} />
+                    </label>
+                    <button onclick={on_open}>{"Open Overlay"}</button>
+                    <button onclick={on_copy}>{"Copy Link"}</button>
+                    <button onclick={on_action.reform(|_| SharedMessage::TogglePresentationDialog)}>{"Close"}</button>
+                </div>
+            </div>
+        }
+    }
+}
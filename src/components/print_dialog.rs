@@ -0,0 +1,141 @@
+use yew::prelude::*;
+
+// A common definition for all messages:
+use crate::shared_messages::SharedMessage;
+
+use crate::core::print_options::{PaperSize, PrintLayout, PrintOptions};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub options: PrintOptions,
+    // The document as it would be printed, shown in the preview pane
+    pub preview: String,
+    pub on_print_action: Callback<SharedMessage>,
+}
+
+// Modal with the print options of the current roster. Like the settings,
+// every change is sent up as a complete PrintOptions, and the App stores it
+// in the roster metadata so it's there the next time the roster is loaded.
+pub struct PrintDialog {}
+
+impl Component for PrintDialog {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        PrintDialog {}
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let options = ctx.props().options.clone();
+
+        let on_layout_change = {
+            let options = options.clone();
+            ctx.props().on_print_action.reform(move |event: Event| {
+                let select: web_sys::HtmlSelectElement = event.target_unchecked_into();
+                let mut new_options = options.clone();
+                new_options.layout = PrintLayout::ALL.get(select.selected_index().max(0) as usize).copied().unwrap_or_default();
+                SharedMessage::UpdatePrintOptions(new_options)
+            })
+        };
+
+        let on_paper_size_change = {
+            let options = options.clone();
+            ctx.props().on_print_action.reform(move |event: Event| {
+                let select: web_sys::HtmlSelectElement = event.target_unchecked_into();
+                let mut new_options = options.clone();
+                new_options.paper_size = PaperSize::ALL.get(select.selected_index().max(0) as usize).copied().unwrap_or_default();
+                SharedMessage::UpdatePrintOptions(new_options)
+            })
+        };
+
+        let on_margin_change = {
+            let options = options.clone();
+            ctx.props().on_print_action.reform(move |event: Event| {
+                let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                let mut new_options = options.clone();
+                new_options.margin_mm = input.value().parse().unwrap_or(options.margin_mm);
+                SharedMessage::UpdatePrintOptions(new_options)
+            })
+        };
+
+        let toggle = |apply: fn(&mut PrintOptions, bool)| {
+            let options = options.clone();
+            ctx.props().on_print_action.reform(move |event: Event| {
+                let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                let mut new_options = options.clone();
+                apply(&mut new_options, input.checked());
+                SharedMessage::UpdatePrintOptions(new_options)
+            })
+        };
+
+        html! {
+            <div class="modal-backdrop">
+                <div class="modal print-dialog">
+                    <div class="modal-title">{"Print Roster"}</div>
+                    <div class="print-dialog-body">
+                        <div class="print-dialog-options">
+                            <label class="settings-row">
+                                <span>{"Layout"}</span>
+                                <select onchange={on_layout_change}>
+                                    { for PrintLayout::ALL.iter().map(|layout| html! {
+                                        <option selected={*layout == options.layout}>{ layout.get_label() }</option>
+                                    }) }
+                                </select>
+                            </label>
+                            <label class="settings-row">
+                                <span>{"Paper size"}</span>
+                                <select onchange={on_paper_size_change}>
+                                    { for PaperSize::ALL.iter().map(|paper_size| html! {
+                                        <option selected={*paper_size == options.paper_size}>{ paper_size.get_label() }</option>
+                                    }) }
+                                </select>
+                            </label>
+                            <label class="settings-row">
+                                <span>{"Margins (mm)"}</span>
+                                <input type="number" min="0" value={options.get_margin_mm().to_string()} onchange={on_margin_change} />
+                            </label>
+                            <label class="settings-row">
+                                <span>{"Double-sided (rules on the back)"}</span>
+                                <input type="checkbox" checked={options.double_sided}
+                                    disabled={options.layout != PrintLayout::Cards}
+                                    onchange={toggle(|options, value| options.double_sided = value)} />
+                            </label>
+                            <label class="settings-row">
+                                <span>{"Room for notes under the cards"}</span>
+                                <input type="checkbox" checked={options.include_notes}
+                                    disabled={options.layout != PrintLayout::Cards || options.double_sided}
+                                    onchange={toggle(|options, value| options.include_notes = value)} />
+                            </label>
+                            <label class="settings-row">
+                                <span>{"Summary page"}</span>
+                                <input type="checkbox" checked={options.include_summary}
+                                    disabled={options.layout == PrintLayout::CheatSheet}
+                                    onchange={toggle(|options, value| options.include_summary = value)} />
+                            </label>
+                            <label class="settings-row">
+                                <span>{"Include images"}</span>
+                                <input type="checkbox" checked={options.include_images}
+                                    onchange={toggle(|options, value| options.include_images = value)} />
+                            </label>
+                            <label class="settings-row">
+                                <span>{"Include scenario"}</span>
+                                <input type="checkbox" checked={options.include_scenario}
+                                    onchange={toggle(|options, value| options.include_scenario = value)} />
+                            </label>
+                            <label class="settings-row">
+                                <span>{"Include campaign records"}</span>
+                                <input type="checkbox" checked={options.include_campaign}
+                                    onchange={toggle(|options, value| options.include_campaign = value)} />
+                            </label>
+                        </div>
+                        // Same document that gets printed, so what's seen here is what comes out.
+                        <iframe class="print-preview" title="Print preview" srcdoc={ctx.props().preview.clone()} />
+                    </div>
+                    <button onclick={ctx.props().on_print_action.reform(|_| SharedMessage::PrintRoster)}>{"Print"}</button>
+                    <button onclick={ctx.props().on_print_action.reform(|_| SharedMessage::TogglePrintDialog)}>{"Close"}</button>
+                </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use yew::prelude::*;
+
+use crate::models::profiler;
+
+// Refreshed on its own, the profiled components don't know about it.
+use gloo::timers::callback::Interval;
+
+const REFRESH_MS: u32 = 1000;
+
+// Developer overlay with the numbers of the profiler, see models::profiler.
+pub struct ProfilerOverlay {
+    _refresh_interval: Interval,
+}
+
+impl Component for ProfilerOverlay {
+    type Message = ();
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let link = ctx.link().clone();
+        ProfilerOverlay {
+            _refresh_interval: Interval::new(REFRESH_MS, move || link.send_message(())),
+        }
+    }
+
+    fn update(&mut self, _: &Context<Self>, _: Self::Message) -> bool {
+        true
+    }
+
+    fn view(&self, _: &Context<Self>) -> Html {
+        html! {
+            <div class="profiler-overlay">
+                <div>{ format!("{} messages/s", profiler::get_messages_per_second()) }</div>
+                <table>
+                    <tr>
+                        <th>{"Component"}</th>
+                        <th>{"Renders"}</th>
+                        <th>{"Updates"}</th>
+                        <th>{"Avg ms"}</th>
+                        <th>{"Max ms"}</th>
+                    </tr>
+                    { for profiler::get_stats().iter().map(|stats| html! {
+                        <tr>
+                            <td>{ stats.name }</td>
+                            <td>{ stats.renders }</td>
+                            <td>{ stats.updates }</td>
+                            <td>{ format!("{:.2}", stats.get_average_update_ms()) }</td>
+                            <td>{ format!("{:.2}", stats.max_update_ms) }</td>
+                        </tr>
+                    }) }
+                </table>
+            </div>
+        }
+    }
+}
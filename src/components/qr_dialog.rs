@@ -0,0 +1,45 @@
+use yew::prelude::*;
+
+// A common definition for all messages:
+use crate::shared_messages::SharedMessage;
+
+use crate::core::qr::QrCode;
+use crate::render::qr_svg;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    // Share link of the roster, None if it couldn't be built
+    pub url: Option<String>,
+    pub on_qr_action: Callback<SharedMessage>,
+}
+
+// The share link as a QR code, to show the phone to the opponent across the
+// table. Very long rosters may not fit in one.
+pub struct QrDialog {}
+
+impl Component for QrDialog {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        QrDialog {}
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let qr = ctx.props().url.as_ref().and_then(|url| QrCode::encode(url.as_bytes()));
+        html! {
+            <div class="modal-backdrop">
+                <div class="modal qr-dialog">
+                    <div class="modal-title">{"Scan to Open the Roster"}</div>
+                    if let Some(qr) = qr {
+                        <div class="qr-code-frame">{ Html::from_html_unchecked(qr_svg::qr_code_svg(&qr).into()) }</div>
+                    } else {
+                        <p>{"The roster is too long for a QR code, copy the share link instead."}</p>
+                    }
+                    <button onclick={ctx.props().on_qr_action.reform(|_| SharedMessage::CopyShareLink)}>{"Copy Link"}</button>
+                    <button onclick={ctx.props().on_qr_action.reform(|_| SharedMessage::ToggleQrDialog)}>{"Close"}</button>
+                </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use yew::prelude::*;
+
+// A common definition for all messages:
+use crate::shared_messages::SharedMessage;
+
+use crate::core::snapshots::Snapshot;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub snapshots: Vec<Snapshot>,
+    pub are_points_hidden: bool,
+    pub on_recovery_action: Callback<SharedMessage>,
+}
+
+// Shown at startup when the last session didn't end cleanly: the latest
+// states of the roster, newest first, to pick one instead of the roster
+// that was restored.
+pub struct RecoveryPrompt {}
+
+impl Component for RecoveryPrompt {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        RecoveryPrompt {}
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_action = ctx.props().on_recovery_action.clone();
+        html! {
+            <div class="modal-backdrop">
+                <div class="modal recovery-prompt">
+                    <div class="modal-title">{"Restore Previous Session?"}</div>
+                    <p>{"The app wasn't closed properly last time. These are the latest saved states of your roster."}</p>
+                    <table class="campaign-battles">
+                        <tr><th>{"Saved"}</th><th>{"Name"}</th><th>{"Entries"}</th><th></th></tr>
+                        { for ctx.props().snapshots.iter().map(|snapshot| {
+                            let entries = if ctx.props().are_points_hidden {
+                                snapshot.roster.elements.len().to_string()
+                            } else {
+                                format!("{} ({} pts)", snapshot.roster.elements.len(), snapshot.roster.get_total_points())
+                            };
+                            let id = snapshot.id.clone();
+                            html! {
+                                <tr key={snapshot.id.clone()}>
+                                    <td>{ &snapshot.taken }</td>
+                                    <td>{ &snapshot.roster.metadata.name }</td>
+                                    <td>{ entries }</td>
+                                    <td><button onclick={on_action.reform(move |_| SharedMessage::RestoreSnapshot(id.clone()))}>{"Restore"}</button></td>
+                                </tr>
+                            }
+                        }) }
+                    </table>
+                    <button onclick={on_action.reform(|_| SharedMessage::DismissRecovery)}>{"Keep Current Roster"}</button>
+                </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use yew::prelude::*;
+
+// A common definition for all messages:
+use crate::shared_messages::SharedMessage;
+
+use crate::models::integrity::NeedsAttention;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub items: Vec<NeedsAttention>,
+    pub on_repair_action: Callback<SharedMessage>,
+}
+
+// Stored data that failed the checks at startup. Each item is repaired step
+// by step, then either taken back into the editor or discarded.
+pub struct RepairWizard {}
+
+impl Component for RepairWizard {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        RepairWizard {}
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_action = ctx.props().on_repair_action.clone();
+        html! {
+            <div class="modal-backdrop">
+                <div class="modal repair-wizard">
+                    <div class="modal-title">{"Needs Attention"}</div>
+                    if ctx.props().items.is_empty() {
+                        <p>{"Nothing left to repair."}</p>
+                    }
+                    { for ctx.props().items.iter().enumerate().map(|(index, item)| html! {
+                        <div class="repair-item">
+                            <div class="modal-subtitle">
+                                { format!("{}: {} ({} elements)", item.source.get_label(), item.roster.metadata.name, item.roster.elements.len()) }
+                            </div>
+                            if item.problems.is_empty() {
+                                <p>{"No problems left."}</p>
+                            } else {
+                                <ul class="over-limit">
+                                    { for item.problems.iter().map(|problem| html! { <li>{problem}</li> }) }
+                                </ul>
+                            }
+                            <button onclick={on_action.reform(move |_| SharedMessage::RepairDropBadElements(index))}>{"Drop Bad Elements"}</button>
+                            <button onclick={on_action.reform(move |_| SharedMessage::RepairResetPoints(index))}>{"Reset Points"}</button>
+                            <button onclick={on_action.reform(move |_| SharedMessage::UseRepairedRoster(index))}>{"Use This Roster"}</button>
+                            <button onclick={on_action.reform(move |_| SharedMessage::DiscardNeedsAttention(index))}>{"Discard"}</button>
+                        </div>
+                    }) }
+                    <button onclick={on_action.reform(|_| SharedMessage::ToggleRepairWizard)}>{"Close"}</button>
+                </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use yew::prelude::*;
+
+// A common definition for all messages:
+use crate::shared_messages::SharedMessage;
+
+use crate::export::report::ISSUES_URL;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub report: String,
+    pub on_report_action: Callback<SharedMessage>,
+}
+
+// The problem report, shown in full so the user knows what they're sharing.
+// Nothing is sent from here, the text is copied and pasted by hand.
+pub struct ReportDialog {}
+
+impl Component for ReportDialog {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        ReportDialog {}
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_action = ctx.props().on_report_action.clone();
+        html! {
+            <div class="modal-backdrop">
+                <div class="modal report-dialog">
+                    <div class="modal-title">{"Report a Problem"}</div>
+                    <p>{"Copy this text into a new issue, after describing what happened. Roster and campaign names and notes are left out."}</p>
+                    <textarea readonly=true rows="16" value={ctx.props().report.clone()} />
+                    <button onclick={on_action.reform(|_| SharedMessage::CopyProblemReport)}>{"Copy"}</button>
+                    <a href={ISSUES_URL} target="_blank" rel="noopener noreferrer">{"Open an issue"}</a>
+                    <button onclick={on_action.reform(|_| SharedMessage::ToggleProblemReport)}>{"Close"}</button>
+                </div>
+            </div>
+        }
+    }
+}
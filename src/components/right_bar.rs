@@ -1,35 +1,61 @@
 use yew::prelude::*;
 
-use crate::models::roster::RosterElement;
+use crate::core::roster::RosterElement;
+use crate::components::icon::Icon;
+use crate::render::icons::IconName;
 
 // A common definition for all messages:
 use crate::shared_messages::SharedMessage;
 
+// Render counts and timings for the dev overlay
+use crate::models::profiler;
+
+// Characters are attached to the focused entry of the selection.
+use crate::models::selection::{SelectionChange, SelectionContext};
+
 #[derive(Properties, Clone, PartialEq)]
 pub struct Props {
     pub model: Vec<RosterElement>,
     pub on_element_action: Callback<SharedMessage>,
-    pub selected_element_index: Option<usize>, 
+    // Whether the focused entry is a unit, and has a character attached
     pub selected_element_is_unit: bool,
     pub selected_unit_has_character: bool,
-    pub on_deselect_elements: Callback<SharedMessage>,
 }
 
-pub struct RightBar {}
+pub enum Msg {
+    SelectionChanged(SelectionContext),
+}
+
+pub struct RightBar {
+    selection: SelectionContext,
+    _selection_handle: ContextHandle<SelectionContext>,
+}
 
 impl Component for RightBar {
-    type Message = ();
+    type Message = Msg;
     type Properties = Props;
 
-    fn create(_: &Context<Self>) -> Self {
-        RightBar {}
+    fn create(ctx: &Context<Self>) -> Self {
+        let (selection, selection_handle) = ctx.link()
+            .context::<SelectionContext>(ctx.link().callback(Msg::SelectionChanged))
+            .expect("The selection is provided by App");
+        RightBar {
+            selection,
+            _selection_handle: selection_handle,
+        }
     }
 
-    fn update(&mut self, _: &Context<Self>, _: Self::Message) -> bool {
-        false
+    fn update(&mut self, _: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::SelectionChanged(selection) => {
+                self.selection = selection;
+                true
+            }
+        }
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        profiler::record_render("RightBar");
         html! {
             <div class="right-bar">
                 { 
@@ -39,15 +65,11 @@ impl Component for RightBar {
                         let elem = elem.clone();
 
                         let callback = ctx.props().on_element_action.clone();
-                        let selected_index = ctx.props().selected_element_index;
+                        let selection = self.selection.clone();
+                        let focused_id = selection.selection.focused.clone();
 
                         let (name, points) = &elem.clone().get_name_and_points();
-                        let image = match &elem {
-                            RosterElement::ElemCharacter(_) => {"character.png".to_string()}
-                            RosterElement::ElemUnit(unit) => unit.image.clone(),
-                            RosterElement::ElemSupport(_) => {"support.png".to_string()}
-                            _ => {"".to_string()}
-                        };
+                        let icon = IconName::for_element(&elem);
 
                         // Must check what to grey out
                         let is_character = matches!(elem, RosterElement::ElemCharacter(_));
@@ -64,20 +86,23 @@ impl Component for RightBar {
                                 disabled={disable_button}
                                 onclick={Callback::from(move |_| {
                                     let mut should_be_attached: bool = false;
-                                    if let Some(index) = selected_index {
+                                    if let Some(id) = &focused_id {
                                         // Checking if attachable.
-                                        if let RosterElement::ElemCharacter(character) = elem.clone() {
+                                        if let RosterElement::ElemCharacter(_) = elem.clone() {
                                             should_be_attached = true;
-                                            callback.emit(SharedMessage::AddToElement(index, elem.clone()));
+                                            callback.emit(SharedMessage::AddToElement(id.clone(), elem.clone()));
                                         }
                                     }
                                     if !should_be_attached {
                                         // No element selected, add to roster
-                                        callback.emit(SharedMessage::DeselectElements);
+                                        selection.change(SelectionChange::Clear);
                                         callback.emit(SharedMessage::AddToRoster(elem.clone()));
                                     }
                                 })}
                                 >
+                                if let Some(icon) = icon {
+                                    <span class="catalog-icon"><Icon name={icon} size={16} /></span>
+                                }
                                 { name.to_uppercase() }
                                 <br />
                                 { format!("{} Points", &points) }
@@ -85,15 +110,33 @@ impl Component for RightBar {
                         }
                     })
                 }
+                // Campaign record of the selected entry, and its info as text
+                {
+                    if let Some(focused_id) = self.selection.selection.focused.clone() {
+                        let callback = ctx.props().on_element_action.clone();
+                        let copied_id = focused_id.clone();
+                        html! {
+                            <>
+                                <button onclick={callback.reform(move |_| SharedMessage::OpenCampaignEditor(focused_id.clone()))}>
+                                    { "CAMPAIGN RECORD" }
+                                </button>
+                                <button onclick={callback.reform(move |_| SharedMessage::CopyElementInfo(copied_id.clone()))}>
+                                    { "COPY INFO" }
+                                </button>
+                            </>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
                 // Add "Remove Character" button if applicable
                 {
-                    if ctx.props().selected_element_is_unit && ctx.props().selected_unit_has_character {
+                    if let Some(focused_id) = self.selection.selection.focused.clone().filter(|_| ctx.props().selected_element_is_unit && ctx.props().selected_unit_has_character) {
                         let callback = ctx.props().on_element_action.clone();
-                        let selected_index = ctx.props().selected_element_index.unwrap();
                         html! {
                             <button
                                 onclick={Callback::from(move |_| {
-                                    callback.emit(SharedMessage::RemoveCharacterFromElement(selected_index));
+                                    callback.emit(SharedMessage::RemoveCharacterFromElement(focused_id.clone()));
                                 })}
                                 >
                                 { "REMOVE CHARACTER" }
@@ -0,0 +1,131 @@
+use yew::prelude::*;
+
+// A common definition for all messages:
+use crate::shared_messages::SharedMessage;
+
+use crate::core::event_pack::RuleOverride;
+use crate::core::roster_slots::RosterSlots;
+use crate::core::rules::{self, Severity};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub slots: RosterSlots,
+    pub are_points_hidden: bool,
+    // Where the rosters are kept, see models::storage_backend
+    pub storage_name: &'static str,
+    // Lists the event asks for, None unless it's more than one
+    pub lists_per_entry: Option<u32>,
+    pub house_rules: Vec<RuleOverride>,
+    pub on_slot_action: Callback<SharedMessage>,
+}
+
+// The saved rosters, one of them being the one in the canvas. The name of a
+// slot is the name of its roster. When the event asks for several lists, the
+// ones making up the entry are checked against each other.
+pub struct RosterManager {}
+
+impl Component for RosterManager {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        RosterManager {}
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_action = ctx.props().on_slot_action.clone();
+        let slots = &ctx.props().slots;
+        html! {
+            <div class="modal-backdrop">
+                <div class="modal roster-manager">
+                    <div class="modal-title">{"Rosters"}</div>
+                    <table class="campaign-battles">
+                        <tr>
+                            <th>{"Name"}</th>
+                            if !ctx.props().are_points_hidden {
+                                <th>{"Points"}</th>
+                            }
+                            <th>{"Last Modified"}</th>
+                            if ctx.props().lists_per_entry.is_some() {
+                                <th>{"In Event Entry"}</th>
+                            }
+                            <th></th>
+                        </tr>
+                        { for slots.slots.iter().map(|slot| {
+                            let id = slot.id.clone();
+                            let is_active = slots.is_active(&id);
+                            let on_rename = {
+                                let id = id.clone();
+                                on_action.reform(move |event: Event| {
+                                    let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                                    SharedMessage::RenameRosterSlot(id.clone(), input.value())
+                                })
+                            };
+                            let on_switch = {
+                                let id = id.clone();
+                                on_action.reform(move |_: MouseEvent| SharedMessage::SwitchRosterSlot(id.clone()))
+                            };
+                            let on_delete = {
+                                let id = id.clone();
+                                on_action.reform(move |_: MouseEvent| SharedMessage::DeleteRosterSlot(id.clone()))
+                            };
+                            let on_toggle_entry = {
+                                let id = id.clone();
+                                on_action.reform(move |_: Event| SharedMessage::ToggleEventEntrySlot(id.clone()))
+                            };
+                            html! {
+                                <tr class={classes!(is_active.then_some("selected"))}>
+                                    <td><input type="text" value={slot.roster.metadata.name.clone()} onchange={on_rename} /></td>
+                                    if !ctx.props().are_points_hidden {
+                                        <td>{ slot.roster.get_total_points() }</td>
+                                    }
+                                    <td>{ &slot.modified }</td>
+                                    if ctx.props().lists_per_entry.is_some() {
+                                        <td><input type="checkbox" checked={slots.event_entry_ids.contains(&id)}
+                                            onchange={on_toggle_entry} /></td>
+                                    }
+                                    <td>
+                                        if is_active {
+                                            <span>{"Open"}</span>
+                                        } else {
+                                            <button onclick={on_switch}>{"Switch"}</button>
+                                        }
+                                        <button onclick={on_delete}>{"Delete"}</button>
+                                    </td>
+                                </tr>
+                            }
+                        }) }
+                    </table>
+                    if let Some(lists_required) = ctx.props().lists_per_entry {
+                        { self.view_entry_violations(ctx, lists_required) }
+                    }
+                    <div class="storage-note">{ format!("Stored in: {}", ctx.props().storage_name) }</div>
+                    <button onclick={on_action.reform(|_| SharedMessage::NewRosterSlot)}>{"New Roster"}</button>
+                    <button onclick={on_action.reform(|_| SharedMessage::ToggleRosterManager)}>{"Close"}</button>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl RosterManager {
+    fn view_entry_violations(&self, ctx: &Context<Self>, lists_required: u32) -> Html {
+        let violations = rules::get_entry_violations(&ctx.props().slots.get_event_entry(), lists_required, &ctx.props().house_rules);
+        html! {
+            <div class="violations">
+                if violations.is_empty() {
+                    <div>{ format!("The {} lists of the entry go together.", lists_required) }</div>
+                }
+                { for violations.iter().map(|violation| {
+                    let severity = violation.rule.severity;
+                    html! {
+                        <div class={classes!("violation", format!("severity-{}", severity.get_label().to_lowercase()), (severity == Severity::Error).then_some("over-limit"))}>
+                            <span class="severity">{ severity.get_label() }</span>
+                            <span>{ &violation.message }</span>
+                        </div>
+                    }
+                }) }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use yew::prelude::*;
+
+use crate::core::escalation::{self, EscalationProgress};
+use crate::core::event_pack::RuleOverride;
+use crate::core::validation::Validator;
+use crate::models::roster_store::{RosterAspect, RosterStore, Subscription};
+use crate::models::selection::SelectionContext;
+
+// Render counts and timings for the dev overlay
+use crate::models::profiler;
+
+// The header sticks to the top of the page, and turns compact once it does.
+use gloo::events::{EventListener, EventListenerOptions};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub roster: RosterStore,
+    pub are_points_hidden: bool,
+    // What the legality is checked against, as on the canvas
+    pub group_cap_percent: Option<u32>,
+    pub house_rules: Vec<RuleOverride>,
+}
+
+pub enum Msg {
+    TotalsChanged,
+    ElementsChanged,
+    SelectionChanged(SelectionContext),
+    Scrolled,
+}
+
+// Total points of the roster, or its number of entries while the points are
+// hidden, with the activations, whether the roster is legal and the progress
+// to the next escalation milestone. Only redrawn when the totals, the
+// milestones or the legality change, or when it gets stuck to the top or
+// leaves it. The entries selected are summed up next to the totals.
+pub struct RosterTotals {
+    header_ref: NodeRef,
+    is_stuck: bool,
+    // What makes the roster illegal, see Validator::get_errors
+    errors: Vec<String>,
+    selection: SelectionContext,
+    _selection_handle: ContextHandle<SelectionContext>,
+    _subscriptions: Vec<Subscription>,
+    _scroll_listener: EventListener,
+}
+
+impl Component for RosterTotals {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        // Scroll events don't bubble, see MainCanvas.
+        let link = ctx.link().clone();
+        let scroll_listener = EventListener::new_with_options(
+            &gloo::utils::window(),
+            "scroll",
+            EventListenerOptions::run_in_capture_phase(),
+            move |_| link.send_message(Msg::Scrolled));
+
+        let (selection, selection_handle) = ctx.link()
+            .context::<SelectionContext>(ctx.link().callback(Msg::SelectionChanged))
+            .expect("The selection is provided by App");
+
+        RosterTotals {
+            header_ref: NodeRef::default(),
+            is_stuck: false,
+            errors: RosterTotals::get_errors(ctx.props()),
+            selection,
+            _selection_handle: selection_handle,
+            _subscriptions: vec![
+                ctx.props().roster.subscribe(RosterAspect::Totals, ctx.link().callback(|_| Msg::TotalsChanged)),
+                ctx.props().roster.subscribe(RosterAspect::Metadata, ctx.link().callback(|_| Msg::TotalsChanged)),
+                ctx.props().roster.subscribe(RosterAspect::Elements, ctx.link().callback(|_| Msg::ElementsChanged)),
+            ],
+            _scroll_listener: scroll_listener,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::TotalsChanged => {
+                self.errors = RosterTotals::get_errors(ctx.props());
+                true
+            }
+            // Any entry may break a rule, the rest only changes with the totals.
+            Msg::ElementsChanged => {
+                let errors = RosterTotals::get_errors(ctx.props());
+                std::mem::replace(&mut self.errors, errors) != self.errors
+            }
+            Msg::SelectionChanged(selection) => {
+                self.selection = selection;
+                true
+            }
+            Msg::Scrolled => {
+                let is_stuck = self.header_ref.cast::<web_sys::Element>()
+                    .map(|header| header.get_bounding_client_rect().top() <= 0.0)
+                    .unwrap_or(false);
+                std::mem::replace(&mut self.is_stuck, is_stuck) != is_stuck
+            }
+        }
+    }
+
+    // The house rules may have changed.
+    fn changed(&mut self, ctx: &Context<Self>, _: &Self::Properties) -> bool {
+        self.errors = RosterTotals::get_errors(ctx.props());
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        profiler::record_render("RosterTotals");
+        let roster = ctx.props().roster.borrow();
+        let is_over_limit = roster.is_over_points_limit(roster.get_points_limit());
+        let (legality_label, legality_class) = if self.errors.is_empty() {
+            ("Legal", "legal")
+        } else if is_over_limit && !ctx.props().are_points_hidden {
+            ("Over Limit", "invalid")
+        } else {
+            ("Invalid", "invalid")
+        };
+
+        let total = if ctx.props().are_points_hidden {
+            format!("{} Entries", roster.get_activation_count())
+        } else {
+            format!("Total Points: {}", roster.get_total_points())
+        };
+
+        let selected = self.selection.selection.get_selected_entries(&roster);
+        let selection_summary = if ctx.props().are_points_hidden {
+            format!("{} Selected", selected.len())
+        } else {
+            format!("{} Selected: {} pts", selected.len(), selected.iter().map(|entry| entry.get_total_points()).sum::<u32>())
+        };
+
+        html! {
+            <div ref={self.header_ref.clone()}
+                class={classes!("total-points", (is_over_limit && !ctx.props().are_points_hidden).then_some("over-limit"), self.is_stuck.then_some("stuck"))}>
+                { total }
+                <span class="totals-summary">{ format!("{} Activations", roster.get_activation_count()) }</span>
+                <span class={classes!("legality-badge", legality_class)} title={self.errors.join("\n")}>{ legality_label }</span>
+                if !selected.is_empty() {
+                    <span class="totals-summary selection-summary">{ selection_summary }</span>
+                }
+                if !ctx.props().are_points_hidden {
+                    if let Some(progress) = escalation::get_progress(&roster) {
+                        { self.view_escalation(&progress) }
+                    }
+                }
+            </div>
+        }
+    }
+}
+
+impl RosterTotals {
+    fn get_errors(props: &Props) -> Vec<String> {
+        Validator::for_roster(props.group_cap_percent, &props.house_rules).get_errors(&props.roster.borrow())
+    }
+
+    fn view_escalation(&self, progress: &EscalationProgress) -> Html {
+        let label = match progress.next {
+            Some(next) if next == progress.total => format!("Milestone {} of {} reached: {} pts", progress.step, progress.step_count, next),
+            Some(next) => format!("{} / {} pts, milestone {} of {}", progress.total, next, progress.step, progress.step_count),
+            None => format!("{} pts, past the last milestone", progress.total),
+        };
+        html! {
+            <div class="escalation-progress">
+                <progress max="1" value={progress.get_fraction().to_string()} />
+                <span class="escalation-label">{ label }</span>
+                { for progress.warnings.iter().map(|warning| html! {
+                    <div class="escalation-warning over-limit">{ warning }</div>
+                }) }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use std::rc::Rc;
+
+use yew::prelude::*;
+
+// A common definition for all messages:
+use crate::shared_messages::SharedMessage;
+
+use crate::core::scenario::{Scenario, ScenarioLibrary};
+use crate::models::storage::SCENARIOS_KEY;
+use crate::models::storage_backend::{self, StorageBackend};
+
+#[derive(Properties, Clone)]
+pub struct Props {
+    // Where the library is kept, see models::storage_backend
+    pub storage_backend: Rc<dyn StorageBackend>,
+    pub attached_scenario: Option<Scenario>,
+    pub on_scenario_action: Callback<SharedMessage>,
+}
+
+pub enum Msg {
+    Loaded(ScenarioLibrary),
+    New,
+    Select(usize),
+    Update(usize, Scenario),
+    Delete(usize),
+}
+
+impl PartialEq for Props {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.storage_backend, &other.storage_backend)
+            && self.attached_scenario == other.attached_scenario
+            && self.on_scenario_action == other.on_scenario_action
+    }
+}
+
+// Editor of the custom missions. The library is owned (and saved) here,
+// the App only receives the scenario attached to the roster. It's empty
+// until the backend has loaded it.
+pub struct ScenarioEditor {
+    library: ScenarioLibrary,
+    selected: Option<usize>,
+}
+
+impl Component for ScenarioEditor {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        storage_backend::load_value(&*ctx.props().storage_backend, SCENARIOS_KEY, ctx.link().callback(Msg::Loaded));
+        ScenarioEditor {library: ScenarioLibrary::default(), selected: None}
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Loaded(library) => {
+                self.selected = if library.scenarios.is_empty() { None } else { Some(0) };
+                self.library = library;
+                true
+            }
+
+            Msg::New => {
+                self.library.scenarios.push(Scenario::new());
+                self.selected = Some(self.library.scenarios.len() - 1);
+                self.save(ctx);
+                true
+            }
+
+            Msg::Select(index) => {
+                self.selected = Some(index);
+                true
+            }
+
+            Msg::Update(index, scenario) => {
+                if let Some(target) = self.library.scenarios.get_mut(index) {
+                    *target = scenario;
+                    self.save(ctx);
+                }
+                true
+            }
+
+            Msg::Delete(index) => {
+                if index < self.library.scenarios.len() {
+                    self.library.scenarios.remove(index);
+                    self.save(ctx);
+                }
+                self.selected = if self.library.scenarios.is_empty() { None } else { Some(0) };
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_action = ctx.props().on_scenario_action.clone();
+        let attached_name = ctx.props().attached_scenario.as_ref().map(|scenario| scenario.name.clone());
+
+        html! {
+            <div class="modal-backdrop">
+                <div class="modal scenario-editor">
+                    <div class="modal-title">{"Scenarios"}</div>
+                    if let Some(name) = attached_name {
+                        <div class="campaign-summary">
+                            { format!("Next game: {}", name) }
+                            <button onclick={on_action.reform(|_| SharedMessage::AttachScenario(None))}>{"Detach"}</button>
+                        </div>
+                    }
+                    <div class="scenario-list">
+                        { for self.library.scenarios.iter().enumerate().map(|(index, scenario)| html! {
+                            <button class={if self.selected == Some(index) { "selected" } else { "" }}
+                                onclick={ctx.link().callback(move |_| Msg::Select(index))}>
+                                { &scenario.name }
+                            </button>
+                        }) }
+                        <button onclick={ctx.link().callback(|_| Msg::New)}>{"+ New Scenario"}</button>
+                    </div>
+                    {
+                        match self.selected.and_then(|index| self.library.scenarios.get(index).map(|scenario| (index, scenario))) {
+                            Some((index, scenario)) => self.render_scenario(ctx, index, scenario),
+                            None => html! {},
+                        }
+                    }
+                    <button onclick={on_action.reform(|_| SharedMessage::ToggleScenarioEditor)}>{"Close"}</button>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl ScenarioEditor {
+    fn render_scenario(&self, ctx: &Context<Self>, index: usize, scenario: &Scenario) -> Html {
+        // Each field updates its own part of a copy of the scenario.
+        let update = |apply: fn(&mut Scenario, String)| {
+            let scenario = scenario.clone();
+            ctx.link().callback(move |event: Event| {
+                let value = match event.target_dyn_into::<web_sys::HtmlInputElement>() {
+                    Some(input) => input.value(),
+                    None => event.target_unchecked_into::<web_sys::HtmlTextAreaElement>().value(),
+                };
+                let mut new_scenario = scenario.clone();
+                apply(&mut new_scenario, value);
+                Msg::Update(index, new_scenario)
+            })
+        };
+
+        let attached = scenario.clone();
+        html! {
+            <div class="scenario-fields">
+                <label class="settings-row">
+                    <span>{"Name"}</span>
+                    <input type="text" value={scenario.name.clone()}
+                        onchange={update(|scenario, value| scenario.name = value)} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Deployment"}</span>
+                    <textarea value={scenario.deployment.clone()}
+                        onchange={update(|scenario, value| scenario.deployment = value)} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Special rules (one per line)"}</span>
+                    <textarea value={scenario.special_rules.join("\n")}
+                        onchange={update(|scenario, value| scenario.special_rules = split_lines(&value))} />
+                </label>
+                <label class="settings-row">
+                    <span>{"Victory conditions (one per line)"}</span>
+                    <textarea value={scenario.victory_conditions.join("\n")}
+                        onchange={update(|scenario, value| scenario.victory_conditions = split_lines(&value))} />
+                </label>
+                <button onclick={ctx.props().on_scenario_action.reform(move |_| SharedMessage::AttachScenario(Some(attached.clone())))}>
+                    {"Use for next game"}
+                </button>
+                <button onclick={ctx.link().callback(move |_| Msg::Delete(index))}>{"Delete"}</button>
+            </div>
+        }
+    }
+}
+
+fn split_lines(value: &str) -> Vec<String> {
+    value.lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+impl ScenarioEditor {
+    fn save(&self, ctx: &Context<Self>) {
+        storage_backend::save_value(&*ctx.props().storage_backend, SCENARIOS_KEY, &self.library);
+    }
+}
@@ -0,0 +1,309 @@
+use yew::prelude::*;
+
+// A common definition for all messages:
+use crate::shared_messages::{MetadataMessage, SharedMessage};
+use crate::models::bus;
+use crate::core::roster::POINTS_LIMIT_PRESETS;
+
+use crate::models::settings::{DoubleClickAction, Settings};
+use crate::models::storage_backend::StorageKind;
+use crate::models::features::{Feature, FeatureFlags};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub settings: Settings,
+    // Flags in effect, including the url overrides
+    pub features: FeatureFlags,
+    // Of the roster being built, it goes with the roster rather than the settings
+    pub points_limit: u32,
+    pub on_update_settings: Callback<SharedMessage>,
+    pub on_close: Callback<SharedMessage>,
+}
+
+// Modal with the user settings. Every change is sent up right away as a
+// complete Settings, the App is in charge of saving and applying it.
+pub struct SettingsPanel {}
+
+impl Component for SettingsPanel {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        SettingsPanel {}
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let settings = ctx.props().settings.clone();
+
+        let on_asset_base_change = {
+            let settings = settings.clone();
+            ctx.props().on_update_settings.reform(move |event: Event| {
+                let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                let mut new_settings = settings.clone();
+                new_settings.asset_base = Some(input.value()).filter(|value| !value.trim().is_empty());
+                SharedMessage::UpdateSettings(new_settings)
+            })
+        };
+
+        let on_colorblind_safe_change = {
+            let settings = settings.clone();
+            ctx.props().on_update_settings.reform(move |event: Event| {
+                let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                let mut new_settings = settings.clone();
+                new_settings.colorblind_safe = input.checked();
+                SharedMessage::UpdateSettings(new_settings)
+            })
+        };
+
+        let on_color_code_types_change = {
+            let settings = settings.clone();
+            ctx.props().on_update_settings.reform(move |event: Event| {
+                let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                let mut new_settings = settings.clone();
+                new_settings.color_code_types = input.checked();
+                SharedMessage::UpdateSettings(new_settings)
+            })
+        };
+
+        let on_audio_cues_change = {
+            let settings = settings.clone();
+            ctx.props().on_update_settings.reform(move |event: Event| {
+                let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                let mut new_settings = settings.clone();
+                new_settings.audio_cues = input.checked();
+                SharedMessage::UpdateSettings(new_settings)
+            })
+        };
+
+        let on_cue_volume_change = {
+            let settings = settings.clone();
+            ctx.props().on_update_settings.reform(move |event: Event| {
+                let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                let mut new_settings = settings.clone();
+                new_settings.cue_volume_percent = input.value().parse().unwrap_or(settings.cue_volume_percent);
+                SharedMessage::UpdateSettings(new_settings)
+            })
+        };
+
+        let on_show_profiler_change = {
+            let settings = settings.clone();
+            ctx.props().on_update_settings.reform(move |event: Event| {
+                let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                let mut new_settings = settings.clone();
+                new_settings.show_profiler = input.checked();
+                SharedMessage::UpdateSettings(new_settings)
+            })
+        };
+
+        let on_group_cap_change = {
+            let settings = settings.clone();
+            ctx.props().on_update_settings.reform(move |event: Event| {
+                let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                let mut new_settings = settings.clone();
+                new_settings.group_cap_percent = input.value().trim().parse().ok().filter(|cap| (1..=100).contains(cap));
+                SharedMessage::UpdateSettings(new_settings)
+            })
+        };
+
+        let on_double_click_action_change = {
+            let settings = settings.clone();
+            ctx.props().on_update_settings.reform(move |event: Event| {
+                let select: web_sys::HtmlSelectElement = event.target_unchecked_into();
+                let mut new_settings = settings.clone();
+                new_settings.double_click_action = DoubleClickAction::ALL.get(select.selected_index().max(0) as usize).copied().unwrap_or_default();
+                SharedMessage::UpdateSettings(new_settings)
+            })
+        };
+
+        // The last option is the custom limit, typed in next to it.
+        let on_points_limit_preset_change = Callback::from(|event: Event| {
+            let select: web_sys::HtmlSelectElement = event.target_unchecked_into();
+            if let Some(points_limit) = POINTS_LIMIT_PRESETS.get(select.selected_index().max(0) as usize) {
+                bus::publish(MetadataMessage::SetPointsLimit(*points_limit));
+            }
+        });
+
+        let on_points_limit_change = Callback::from(|event: Event| {
+            let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+            if let Some(points_limit) = input.value().trim().parse::<u32>().ok().filter(|limit| *limit > 0) {
+                bus::publish(MetadataMessage::SetPointsLimit(points_limit));
+            }
+        });
+        let points_limit = ctx.props().points_limit;
+
+        let on_storage_change = {
+            let settings = settings.clone();
+            ctx.props().on_update_settings.reform(move |event: Event| {
+                let select: web_sys::HtmlSelectElement = event.target_unchecked_into();
+                let mut new_settings = settings.clone();
+                new_settings.storage = StorageKind::ALL.get(select.selected_index().max(0) as usize).copied().unwrap_or_default();
+                SharedMessage::UpdateSettings(new_settings)
+            })
+        };
+
+        // Fields of the print header, each with its own setter
+        let on_text_change = |set: fn(&mut Settings, String)| {
+            let settings = settings.clone();
+            ctx.props().on_update_settings.reform(move |event: Event| {
+                let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                let mut new_settings = settings.clone();
+                set(&mut new_settings, input.value());
+                SharedMessage::UpdateSettings(new_settings)
+            })
+        };
+
+        let on_logo_change = ctx.props().on_update_settings.reform(|event: Event| {
+            let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+            let file = input.files().and_then(|files| files.get(0));
+            input.set_value("");
+            file.map(SharedMessage::LogoFileSelected).unwrap_or(SharedMessage::NoOp)
+        });
+
+        let on_event_pack_change = ctx.props().on_update_settings.reform(|event: Event| {
+            let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+            let file = input.files().and_then(|files| files.get(0));
+            input.set_value("");
+            file.map(SharedMessage::EventPackFileSelected).unwrap_or(SharedMessage::NoOp)
+        });
+
+        let on_remove_logo = {
+            let settings = settings.clone();
+            ctx.props().on_update_settings.reform(move |_| {
+                let mut new_settings = settings.clone();
+                new_settings.print_branding.logo.clear();
+                SharedMessage::UpdateSettings(new_settings)
+            })
+        };
+
+        html! {
+            <div class="modal-backdrop">
+                <div class="modal settings-panel">
+                    <div class="modal-title">{"Settings"}</div>
+                    <label class="settings-row">
+                        <span>{"Assets base URL"}</span>
+                        <input type="text"
+                            placeholder="./static/"
+                            value={settings.asset_base.clone().unwrap_or_default()}
+                            onchange={on_asset_base_change} />
+                    </label>
+                    <div class="modal-subtitle">{"Controls"}</div>
+                    <label class="settings-row">
+                        <span>{"Double click on a card"}</span>
+                        <select onchange={on_double_click_action_change}>
+                            { for DoubleClickAction::ALL.iter().map(|action| html! {
+                                <option selected={*action == settings.double_click_action}>{ action.get_label() }</option>
+                            }) }
+                        </select>
+                    </label>
+                    <div class="modal-subtitle">{"Format"}</div>
+                    <div class="settings-row">
+                        if let Some(pack) = &settings.event_pack {
+                            <span>{ format!("Event: {}", pack.name) }</span>
+                            <button onclick={ctx.props().on_update_settings.reform(|_| SharedMessage::LeaveEvent)}>{"Leave Event"}</button>
+                        } else {
+                            <span>{"Event pack"}</span>
+                            <input type="file" accept=".json,application/json" onchange={on_event_pack_change} />
+                        }
+                    </div>
+                    <label class="settings-row">
+                        <span>{"Points limit of this roster"}</span>
+                        <select onchange={on_points_limit_preset_change}>
+                            { for POINTS_LIMIT_PRESETS.iter().map(|preset| html! {
+                                <option selected={*preset == points_limit}>{ format!("{} points", preset) }</option>
+                            }) }
+                            <option selected={!POINTS_LIMIT_PRESETS.contains(&points_limit)}>{"Custom"}</option>
+                        </select>
+                        <input type="number" min="1" value={points_limit.to_string()} onchange={on_points_limit_change} />
+                    </label>
+                    <label class="settings-row">
+                        <span>{"Largest share of one group (%)"}</span>
+                        <input type="number" min="1" max="100" placeholder="No cap"
+                            value={settings.group_cap_percent.map(|cap| cap.to_string()).unwrap_or_default()}
+                            onchange={on_group_cap_change} />
+                    </label>
+                    <div class="modal-subtitle">{"Print Header"}</div>
+                    <label class="settings-row">
+                        <span>{"Event name"}</span>
+                        <input type="text" value={settings.print_branding.event_name.clone()}
+                            onchange={on_text_change(|settings, value| settings.print_branding.event_name = value)} />
+                    </label>
+                    <label class="settings-row">
+                        <span>{"Organizer text"}</span>
+                        <input type="text" value={settings.print_branding.organizer_text.clone()}
+                            onchange={on_text_change(|settings, value| settings.print_branding.organizer_text = value)} />
+                    </label>
+                    <div class="settings-row">
+                        <span>{"Logo"}</span>
+                        if settings.print_branding.logo.is_empty() {
+                            <input type="file" accept="image/*" onchange={on_logo_change} />
+                        } else {
+                            <img class="branding-logo" src={settings.print_branding.logo.clone()} alt="Logo" />
+                            <button onclick={on_remove_logo}>{"Remove Logo"}</button>
+                        }
+                    </div>
+                    <div class="modal-subtitle">{"Sync"}</div>
+                    <label class="settings-row">
+                        <span>{"Server address"}</span>
+                        <input type="url" placeholder="Not syncing" value={settings.sync.base_url.clone()}
+                            onchange={on_text_change(|settings, value| settings.sync.base_url = value)} />
+                    </label>
+                    <label class="settings-row">
+                        <span>{"Access token"}</span>
+                        <input type="password" value={settings.sync.token.clone()}
+                            onchange={on_text_change(|settings, value| settings.sync.token = value)} />
+                    </label>
+                    <label class="settings-row">
+                        <span>{"Keep rosters in"}</span>
+                        <select onchange={on_storage_change}>
+                            { for StorageKind::ALL.iter().map(|kind| html! {
+                                <option selected={*kind == settings.storage}>{ kind.get_label() }</option>
+                            }) }
+                        </select>
+                    </label>
+                    <div class="modal-subtitle">{"Accessibility"}</div>
+                    <label class="settings-row">
+                        <span>{"Colorblind-safe warnings"}</span>
+                        <input type="checkbox" checked={settings.colorblind_safe} onchange={on_colorblind_safe_change} />
+                    </label>
+                    <label class="settings-row">
+                        <span>{"Card borders colored by type"}</span>
+                        <input type="checkbox" checked={settings.color_code_types} onchange={on_color_code_types_change} />
+                    </label>
+                    <label class="settings-row">
+                        <span>{"Audio cues (clock, points limit)"}</span>
+                        <input type="checkbox" checked={settings.audio_cues} onchange={on_audio_cues_change} />
+                    </label>
+                    <label class="settings-row">
+                        <span>{"Cue volume"}</span>
+                        <input type="range" min="0" max="100" disabled={!settings.audio_cues}
+                            value={settings.cue_volume_percent.to_string()} onchange={on_cue_volume_change} />
+                    </label>
+                    <div class="modal-subtitle">{"Experimental"}</div>
+                    { for Feature::ALL.iter().map(|feature| {
+                        let feature = *feature;
+                        let settings = settings.clone();
+                        let on_change = ctx.props().on_update_settings.reform(move |event: Event| {
+                            let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                            let mut new_settings = settings.clone();
+                            new_settings.feature_flags.insert(feature.get_key().to_string(), input.checked());
+                            SharedMessage::UpdateSettings(new_settings)
+                        });
+                        html! {
+                            <label class="settings-row">
+                                <span>{ feature.get_label() }</span>
+                                <input type="checkbox" checked={ctx.props().features.is_enabled(feature)} onchange={on_change} />
+                            </label>
+                        }
+                    }) }
+                    <div class="modal-subtitle">{"Developer"}</div>
+                    <label class="settings-row">
+                        <span>{"Performance overlay (also with ?profiler=1)"}</span>
+                        <input type="checkbox" checked={settings.show_profiler} onchange={on_show_profiler_change} />
+                    </label>
+                    <button onclick={ctx.props().on_close.reform(|_| SharedMessage::ToggleProblemReport)}>{"Report a Problem"}</button>
+                    <button onclick={ctx.props().on_close.reform(|_| SharedMessage::ToggleSettings)}>{"Close"}</button>
+                </div>
+            </div>
+        }
+    }
+}
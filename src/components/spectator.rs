@@ -0,0 +1,117 @@
+use yew::prelude::*;
+
+use gloo::timers::callback::Interval;
+
+use crate::core::live::{FollowLink, LiveGame, RemoteGame};
+use crate::models::live::FOLLOW_REFRESH_MS;
+use crate::models::settings::Settings;
+use crate::models::sync::{self, SyncError, SyncSettings};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub link: FollowLink,
+}
+
+pub enum Msg {
+    Refresh,
+    GamePulled(Result<RemoteGame, SyncError>),
+}
+
+// Read-only view of a game shared live from the play mode, opened from a
+// follow link instead of the app. The game is asked for every few seconds,
+// the server has nothing to push it with.
+pub struct Spectator {
+    settings: SyncSettings,
+    game: Option<LiveGame>,
+    revision: u32,
+    error: Option<String>,
+    is_pulling: bool,
+    _refresh_interval: Interval,
+}
+
+impl Component for Spectator {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        // The token of this browser goes along if it syncs with that server.
+        let local = Settings::load().sync;
+        let server = ctx.props().link.server.clone();
+        let token = if local.base_url.trim().trim_end_matches('/') == server.trim_end_matches('/') { local.token } else { String::new() };
+        ctx.link().send_message(Msg::Refresh);
+        let link = ctx.link().clone();
+        Spectator {
+            settings: SyncSettings {base_url: server, token},
+            game: None,
+            revision: 0,
+            error: None,
+            is_pulling: false,
+            _refresh_interval: Interval::new(FOLLOW_REFRESH_MS, move || link.send_message(Msg::Refresh)),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Refresh => {
+                let Some(client) = sync::get_sync_client(&self.settings) else {
+                    return false;
+                };
+                if !self.is_pulling {
+                    self.is_pulling = true;
+                    client.pull_game(&ctx.props().link.game_id, ctx.link().callback(Msg::GamePulled));
+                }
+                false
+            }
+
+            Msg::GamePulled(result) => {
+                self.is_pulling = false;
+                match result {
+                    Ok(remote) => {
+                        self.error = None;
+                        // An answer overtaken by a later one is dropped.
+                        if self.game.is_some() && remote.revision < self.revision {
+                            return false;
+                        }
+                        self.revision = remote.revision;
+                        self.game = Some(remote.game);
+                    }
+                    Err(SyncError::NotFound) => self.error = Some("The game isn't on the server, it may not be shared anymore".to_string()),
+                    Err(e) => self.error = Some(e.get_message()),
+                }
+                true
+            }
+        }
+    }
+
+    fn view(&self, _: &Context<Self>) -> Html {
+        html! {
+            <div class="spectator">
+                if let Some(error) = &self.error {
+                    <div class="over-limit">{ error }</div>
+                }
+                if let Some(game) = &self.game {
+                    <div class="spectator-title">{ &game.roster_name }</div>
+                    if game.round > 0 {
+                        <div class="modal-subtitle">{ format!("Round {}", game.round) }</div>
+                    }
+                    <div class="tracker-scores">
+                        { for game.players.iter().map(|player| html! {
+                            <div class="tracker-row">
+                                <span>{ &player.name }</span>
+                                <span class="tracker-count">{ player.score }</span>
+                            </div>
+                        }) }
+                    </div>
+                    { for game.entries.iter().map(|entry| html! {
+                        <div class={classes!("tracker-row", entry.is_destroyed().then_some("destroyed"))}>
+                            <span>{ &entry.name }</span>
+                            <span class="tracker-count">{ format!("{} / {} lost", entry.casualties, entry.quantity) }</span>
+                        </div>
+                    }) }
+                } else if self.error.is_none() {
+                    <div>{"Waiting for the game..."}</div>
+                }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use yew::prelude::*;
+
+// A common definition for all messages:
+use crate::shared_messages::SharedMessage;
+
+use crate::core::armylist::Faction;
+use crate::core::roster::{Roster, RosterElement};
+use crate::models::roster_store::RosterStore;
+use crate::core::swap;
+use crate::render::card_svg;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub roster: RosterStore,
+    pub on_swap_action: Callback<SharedMessage>,
+}
+
+pub enum Msg {
+    SelectSource(usize),
+    SelectReplacement(usize),
+}
+
+// Replaces every copy of an element with another catalog entry. The choice
+// is kept here until applied, with a preview of what it does to the points.
+pub struct SwapDialog {
+    replacements: Vec<(Faction, RosterElement)>,
+    source_index: usize,
+    replacement_index: usize,
+}
+
+impl Component for SwapDialog {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        SwapDialog {
+            replacements: swap::get_replacements(),
+            source_index: 0,
+            replacement_index: 0,
+        }
+    }
+
+    fn update(&mut self, _: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::SelectSource(index) => {
+                self.source_index = index;
+                true
+            }
+
+            Msg::SelectReplacement(index) => {
+                self.replacement_index = index;
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_action = ctx.props().on_swap_action.clone();
+        let names = swap::get_swappable_names(&ctx.props().roster.borrow());
+        let source = names.get(self.source_index).or(names.first()).cloned();
+        let replacement = self.replacements.get(self.replacement_index).map(|(_, element)| element.clone());
+
+        let on_source_change = ctx.link().callback(|event: Event| {
+            let select: web_sys::HtmlSelectElement = event.target_unchecked_into();
+            Msg::SelectSource(select.selected_index().max(0) as usize)
+        });
+        let on_replacement_change = ctx.link().callback(|event: Event| {
+            let select: web_sys::HtmlSelectElement = event.target_unchecked_into();
+            Msg::SelectReplacement(select.selected_index().max(0) as usize)
+        });
+
+        html! {
+            <div class="modal-backdrop">
+                <div class="modal swap-dialog">
+                    <div class="modal-title">{"Swap Elements"}</div>
+                    if names.is_empty() {
+                        <p>{"The roster is empty, there's nothing to swap."}</p>
+                    } else {
+                        <label class="settings-row">
+                            <span>{"Replace every"}</span>
+                            <select onchange={on_source_change}>
+                                { for names.iter().enumerate().map(|(index, name)| html! {
+                                    <option selected={index == self.source_index}>{ name }</option>
+                                }) }
+                            </select>
+                        </label>
+                        <label class="settings-row">
+                            <span>{"With"}</span>
+                            <select onchange={on_replacement_change}>
+                                { for self.replacements.iter().enumerate().map(|(index, (faction, element))| html! {
+                                    <option selected={index == self.replacement_index}>
+                                        { format!("{} ({:?} {})", element.get_name(), faction, element.get_type_name()) }
+                                    </option>
+                                }) }
+                            </select>
+                        </label>
+                        if let (Some(source), Some(replacement)) = (source, replacement) {
+                            { self.view_preview(&ctx.props().roster.borrow(), &source, &replacement) }
+                            <button onclick={on_action.reform(move |_| SharedMessage::SwapElements(source.clone(), replacement.clone()))}>
+                                {"Swap"}
+                            </button>
+                        }
+                    }
+                    <button onclick={on_action.reform(|_| SharedMessage::ToggleSwapDialog)}>{"Close"}</button>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl SwapDialog {
+    fn view_preview(&self, roster: &Roster, source: &str, replacement: &RosterElement) -> Html {
+        let preview = swap::get_swap_preview(roster, source, replacement);
+        let copies = if preview.count == 1 { "1 entry".to_string() } else { format!("{} entries", preview.count) };
+        let points = if card_svg::are_points_hidden() {
+            String::new()
+        } else {
+            format!(", {} pts to {} pts ({:+})", preview.points_before, preview.points_after, preview.get_points_delta())
+        };
+        html! {
+            <div class="swap-preview">
+                <div>{ format!("{} swapped{}", copies, points) }</div>
+                if preview.dropped_attachments > 0 {
+                    <div class="escalation-warning">
+                        { format!("{} attached element(s) will be dropped, the replacement can't carry them", preview.dropped_attachments) }
+                    </div>
+                }
+            </div>
+        }
+    }
+}
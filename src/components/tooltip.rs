@@ -0,0 +1,38 @@
+use yew::prelude::*;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub x: i32,
+    pub y: i32,
+    pub is_dark_mode: bool,
+    #[prop_or_default]
+    pub children: Children,
+}
+
+// Floating tooltip. It's rendered straight into document.body through a
+// portal, so no scrolling or overflow container can clip it.
+pub struct Tooltip {
+    host: web_sys::Element,
+}
+
+impl Component for Tooltip {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        Tooltip {
+            host: gloo::utils::body().into(),
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let content = html! {
+            <div class={if props.is_dark_mode { "tooltip dark-mode" } else { "tooltip" }}
+                style={format!("left: {}px; top: {}px;", props.x, props.y)}>
+                { for props.children.iter() }
+            </div>
+        };
+        create_portal(content, self.host.clone())
+    }
+}
@@ -3,6 +3,8 @@ use yew::prelude::*;
 // A common definition for all messages:
 use crate::shared_messages::SharedMessage;
 
+use crate::models::features::{Feature, FeatureFlags};
+
 pub struct TopMenu{
 }
 
@@ -12,12 +14,35 @@ pub struct Props {
     pub on_save_roster: Callback<SharedMessage>,
     pub on_clear_roster: Callback<SharedMessage>,
     pub on_toggle_theme: Callback<SharedMessage>,
+    pub on_toggle_settings: Callback<SharedMessage>,
+    pub on_toggle_campaign: Callback<SharedMessage>,
+    pub on_toggle_scenarios: Callback<SharedMessage>,
+    pub on_toggle_print: Callback<SharedMessage>,
+    pub on_read_aloud: Callback<SharedMessage>,
+    pub on_toggle_history: Callback<SharedMessage>,
+    pub on_toggle_hide_points: Callback<SharedMessage>,
+    pub on_toggle_presentation: Callback<SharedMessage>,
+    pub on_toggle_play_mode: Callback<SharedMessage>,
+    pub on_toggle_repair_wizard: Callback<SharedMessage>,
+    pub on_toggle_roster_manager: Callback<SharedMessage>,
+    pub on_copy_share_link: Callback<SharedMessage>,
+    pub on_copy_roster: Callback<SharedMessage>,
+    pub on_export_image: Callback<SharedMessage>,
+    pub on_toggle_qr: Callback<SharedMessage>,
+    pub on_push_roster: Callback<SharedMessage>,
+    pub on_pull_roster: Callback<SharedMessage>,
+    pub on_toggle_swap: Callback<SharedMessage>,
 
     pub is_dark_mode: bool,
+    pub are_points_hidden: bool,
+    pub features: FeatureFlags,
+    pub needs_attention_count: usize,
+    // A sync server is set, see models::sync
+    pub is_sync_enabled: bool,
 }
 
 impl Component for TopMenu {
-    type Message = SharedMessage;
+    type Message = ();
     type Properties = Props;
 
     fn create(_ctx: &Context<Self>) -> Self {
@@ -25,16 +50,9 @@ impl Component for TopMenu {
         }    
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, _msg: Self::Message) -> bool {
-        // match msg {
-
-        //     _ => panic!("Unhandled message!")
-        // }
-        false
-    }
-
     fn view(&self, ctx: &Context<Self>) -> Html {
         let dark_mode_label = if ctx.props().is_dark_mode { "Light Mode" } else { "Dark Mode" };
+        let points_label = if ctx.props().are_points_hidden { "Show Points" } else { "Hide Points" };
 
 
         html! {
@@ -43,10 +61,44 @@ impl Component for TopMenu {
                     <span class="title">{"FULL SPECTRUM DOMINANCE - ARMY BUILDER"}</span>
                 </div>
                 <div class="menu">
+                    <button onclick={ctx.props().on_toggle_roster_manager.reform(|_| SharedMessage::ToggleRosterManager)}>{"Rosters"}</button>
                     <button onclick={ctx.props().on_clear_roster.reform(|_| SharedMessage::ClearRoster)}>{"Clear Roster"}</button>
-                    <button onclick={ctx.props().on_load_roster.reform(|_| SharedMessage::LoadRoster)}>{"Load Roster"}</button>
-                    <button onclick={ctx.props().on_save_roster.reform(|_| SharedMessage::SaveRoster)}>{"Save Roster"}</button>
+                    <button title="Open a roster file (also BattleScribe .ros and .rosz)" onclick={ctx.props().on_load_roster.reform(|_| SharedMessage::LoadRoster)}>{"Open File…"}</button>
+                    <button title="Download the roster as a file, to keep it or move it to another device" onclick={ctx.props().on_save_roster.reform(|_| SharedMessage::SaveRoster)}>{"Save to File…"}</button>
+                    <button title="Copy a link opening this roster" onclick={ctx.props().on_copy_share_link.reform(|_| SharedMessage::CopyShareLink)}>{"Share Link"}</button>
+                    <button title="Copy the roster as text, with its data for the apps that read it" onclick={ctx.props().on_copy_roster.reform(|_| SharedMessage::CopyRoster)}>{"Copy Roster"}</button>
+                    <button title="Save the roster as a picture, for posting it" onclick={ctx.props().on_export_image.reform(|_| SharedMessage::ExportImage)}>{"Export Image"}</button>
+                    if cfg!(feature = "qr") {
+                        <button title="Show the share link as a QR code" onclick={ctx.props().on_toggle_qr.reform(|_| SharedMessage::ToggleQrDialog)}>{"QR Code"}</button>
+                    }
+                    if ctx.props().is_sync_enabled {
+                        <button title="Send the roster to the sync server" onclick={ctx.props().on_push_roster.reform(|_| SharedMessage::PushRoster)}>{"Push"}</button>
+                        <button title="Get the roster back from the sync server" onclick={ctx.props().on_pull_roster.reform(|_| SharedMessage::PullRoster)}>{"Pull"}</button>
+                    }
+                    <button title="Replace every copy of an element with another one" onclick={ctx.props().on_toggle_swap.reform(|_| SharedMessage::ToggleSwapDialog)}>{"Swap"}</button>
+                    if cfg!(feature = "print") {
+                        <button onclick={ctx.props().on_toggle_print.reform(|_| SharedMessage::TogglePrintDialog)}>{"Print"}</button>
+                    }
+                    <button onclick={ctx.props().on_read_aloud.reform(|_| SharedMessage::ReadRosterAloud)}>{"Read Aloud"}</button>
+                    <button onclick={ctx.props().on_toggle_history.reform(|_| SharedMessage::ToggleHistoryPanel)}>{"History"}</button>
                     <button onclick={ctx.props().on_toggle_theme.reform(|_| SharedMessage::ToggleTheme)}>{dark_mode_label}</button> // TODO implement Light mode, depending on which one is on!
+                    <button onclick={ctx.props().on_toggle_hide_points.reform(|_| SharedMessage::ToggleHidePoints)}>{points_label}</button>
+                    if ctx.props().features.is_enabled(Feature::Presentation) {
+                        <button onclick={ctx.props().on_toggle_presentation.reform(|_| SharedMessage::TogglePresentationDialog)}>{"Presentation"}</button>
+                    }
+                    if ctx.props().features.is_enabled(Feature::PlayMode) {
+                        <button onclick={ctx.props().on_toggle_play_mode.reform(|_| SharedMessage::TogglePlayMode)}>{"Play"}</button>
+                    }
+                    if cfg!(feature = "editors") {
+                        <button onclick={ctx.props().on_toggle_campaign.reform(|_| SharedMessage::ToggleCampaignDashboard)}>{"Campaign"}</button>
+                        <button onclick={ctx.props().on_toggle_scenarios.reform(|_| SharedMessage::ToggleScenarioEditor)}>{"Scenarios"}</button>
+                    }
+                    <button onclick={ctx.props().on_toggle_settings.reform(|_| SharedMessage::ToggleSettings)}>{"Settings"}</button>
+                    if ctx.props().needs_attention_count > 0 {
+                        <button class="over-limit" onclick={ctx.props().on_toggle_repair_wizard.reform(|_| SharedMessage::ToggleRepairWizard)}>
+                            { format!("Needs Attention ({})", ctx.props().needs_attention_count) }
+                        </button>
+                    }
                     </div>
             </div>
         }
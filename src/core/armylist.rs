@@ -1,14 +1,26 @@
-// TODO this should be moved in a different folder than 
-// "models", more like "data" or indeed "catalogue"
 // 5-01-2024 MTL, updated points to match cards v1.3
-use crate::models::character::Character;
-use crate::models::unit::Unit;
-use crate::models::support::Support;
-use crate::models::roster::RosterElement;
+use crate::core::character::Character;
+use crate::core::unit::Unit;
+use crate::core::support::Support;
+use crate::core::roster::RosterElement;
+use crate::core::canonical;
+use crate::core::catalog_delta::{CatalogChange, CatalogDelta};
+
+use std::cell::RefCell;
 
 // For serialization
 use serde::{Serialize, Deserialize};
 
+// Edition of the cards the points below come from.
+pub const CATALOG_EDITION: &str = "cards v1.3";
+
+thread_local! {
+    // Update applied on top of the lists below, see core::catalog_delta
+    static CATALOG_DELTA: RefCell<Option<CatalogDelta>> = const { RefCell::new(None) };
+    // Hash of the catalog as it is, with the update
+    static CATALOG_HASH: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Faction {
     Tech,
@@ -17,6 +29,73 @@ pub enum Faction {
     Union,
 }
 
+impl Faction {
+    pub const ALL: [Faction; 4] = [Faction::Tech, Faction::Enlisted, Faction::Conglomerate, Faction::Union];
+
+    // Faction whose list has an element with the given name, if any.
+    pub fn find_by_element_name(name: &str) -> Option<Faction> {
+        Faction::ALL.into_iter().find(|faction| {
+            let list = ArmyList::new(*faction);
+            list.units.iter().any(|unit| unit.name == name)
+                || list.characters.iter().any(|character| character.name == name)
+                || list.supports.iter().any(|support| support.name == name)
+        })
+    }
+}
+
+// Points of the catalog element with the given name, in any faction.
+pub fn get_catalog_points(name: &str) -> Option<u32> {
+    Faction::ALL.into_iter().find_map(|faction| {
+        let list = ArmyList::new(faction);
+        list.get_units().iter().find(|unit| unit.name == name).map(|unit| unit.points)
+            .or_else(|| list.get_characters().iter().find(|character| character.name == name).map(|character| character.points))
+            .or_else(|| list.get_supports().iter().find(|support| support.name == name).map(|support| support.points))
+    })
+}
+
+// Hash of the whole catalog, pinned in the rosters built with it (see
+// RosterMetadata). Points fixed without a new edition change it as well.
+pub fn get_catalog_hash() -> String {
+    CATALOG_HASH.with(|hash| hash.borrow_mut().get_or_insert_with(|| get_lists_hash(ArmyList::new)).clone())
+}
+
+fn get_lists_hash(get_list: impl Fn(Faction) -> ArmyList) -> String {
+    let lists: Vec<(Faction, ArmyList)> = Faction::ALL.into_iter().map(|faction| (faction, get_list(faction))).collect();
+    canonical::to_canonical_json(&lists).map(|json| canonical::content_hash(&json)).unwrap_or_default()
+}
+
+// The edition of the update when there's one.
+pub fn get_catalog_edition() -> String {
+    CATALOG_DELTA.with(|delta| delta.borrow().as_ref().map(|delta| delta.edition.clone()))
+        .unwrap_or_else(|| CATALOG_EDITION.to_string())
+}
+
+// Hashes of the built-in catalog and of the one the changes of the update
+// give, to be written in it.
+pub fn get_delta_hashes(delta: &CatalogDelta) -> (String, String) {
+    (get_lists_hash(ArmyList::get_base_list),
+        get_lists_hash(|faction| ArmyList::get_base_list(faction).with_delta(faction, delta)))
+}
+
+// Applies the update on top of the built-in catalog, None goes back to the
+// built-in one. An update made for another catalog, or that doesn't give
+// the catalog it announces, is refused and the catalog left as it was.
+pub fn set_catalog_delta(delta: Option<CatalogDelta>) -> Result<(), String> {
+    if let Some(delta) = &delta {
+        let (base_hash, result_hash) = get_delta_hashes(delta);
+        if base_hash != delta.base_hash {
+            return Err(format!("The catalog update to {} is for another version of the catalog", delta.edition));
+        }
+        if result_hash != delta.result_hash {
+            return Err(format!("The catalog update to {} is damaged", delta.edition));
+        }
+    }
+    CATALOG_DELTA.with(|current| *current.borrow_mut() = delta);
+    CATALOG_HASH.with(|hash| *hash.borrow_mut() = None);
+    Ok(())
+}
+
+#[derive(Serialize)]
 pub struct ArmyList {
     characters : Vec<Character>,
     units: Vec<Unit>,
@@ -38,6 +117,14 @@ impl ArmyList {
     pub fn get_supports (&self) -> Vec<Support> {
         self.supports.clone()
     }
+
+    // All the images referenced by the list, relative to the images folder.
+    pub fn get_images (&self) -> Vec<String> {
+        self.units.iter()
+            .map(|unit| unit.image.clone())
+            .filter(|image| !image.is_empty())
+            .collect()
+    }
 }
 
 
@@ -48,7 +135,38 @@ impl ArmyList {
 // The Elements should have a get_rules that returns a closure as part of the Trait.
 // This allows special rules for different units to be implemented code wise.
 impl ArmyList {
-    pub fn new( faction : Faction) -> ArmyList {
+    // The built-in list, with the catalog update if there's one.
+    pub fn new(faction: Faction) -> ArmyList {
+        let list = ArmyList::get_base_list(faction);
+        CATALOG_DELTA.with(|delta| match delta.borrow().as_ref() {
+            Some(delta) => list.with_delta(faction, delta),
+            None => list,
+        })
+    }
+
+    fn with_delta(mut self, faction: Faction, delta: &CatalogDelta) -> ArmyList {
+        for change in &delta.changes {
+            match change {
+                CatalogChange::SetPoints {name, points} => {
+                    self.characters.iter_mut().filter(|character| character.name == *name).for_each(|character| character.points = *points);
+                    self.units.iter_mut().filter(|unit| unit.name == *name).for_each(|unit| unit.points = *points);
+                    self.supports.iter_mut().filter(|support| support.name == *name).for_each(|support| support.points = *points);
+                }
+                CatalogChange::AddCharacter {faction: target, character} if *target == faction => self.characters.push(character.clone()),
+                CatalogChange::AddUnit {faction: target, unit} if *target == faction => self.units.push(unit.clone()),
+                CatalogChange::AddSupport {faction: target, support} if *target == faction => self.supports.push(support.clone()),
+                CatalogChange::Remove {name} => {
+                    self.characters.retain(|character| character.name != *name);
+                    self.units.retain(|unit| unit.name != *name);
+                    self.supports.retain(|support| support.name != *name);
+                }
+                _ => (),
+            }
+        }
+        self
+    }
+
+    fn get_base_list(faction: Faction) -> ArmyList {
         let mut new_list = ArmyList {
             characters: Vec::<Character>::new(),
             units: Vec::<Unit>::new(),
@@ -0,0 +1,453 @@
+// Reading BattleScribe rosters: the .ros XML, or the .rosz zip holding
+// one. Selections found in the catalog become the catalog element, others
+// are typed after their primary category, and anything else is kept as an
+// Other element. Wargear and upgrades selected under them are kept in the
+// note of the entry, identical selections become one entry with copies. What
+// couldn't be carried over is listed in the notes, the exporter
+// (export::battlescribe) writes files this reads back as they were.
+use std::collections::HashSet;
+
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+
+use crate::core::armylist::{ArmyList, Faction};
+use crate::core::character::Character;
+use crate::core::roster::{Roster, RosterElement, RosterEntry, Wave, MAX_QUANTITY};
+use crate::core::support::Support;
+use crate::core::unit::Unit;
+
+// Far more than any roster, a .rosz inflating past this isn't one.
+const MAX_ROSTER_BYTES: usize = 16 * 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BattleScribeImport {
+    pub roster : Roster,
+    // What was left out or changed on the way
+    pub notes : Vec<String>,
+}
+
+pub fn is_battlescribe_file(file_name: &str) -> bool {
+    let file_name = file_name.to_lowercase();
+    file_name.ends_with(".ros") || file_name.ends_with(".rosz")
+}
+
+pub fn import_roster(bytes: &[u8]) -> Result<BattleScribeImport, String> {
+    let bytes = if bytes.starts_with(b"PK\x03\x04") { read_zipped_roster(bytes)? } else { bytes.to_vec() };
+    let text = String::from_utf8(bytes).map_err(|_| "The roster isn't text".to_string())?;
+    let root = parse_xml(text.trim_start_matches('\u{feff}'))?;
+    if root.name != "roster" {
+        return Err(format!("Expected a roster, found <{}>", root.name));
+    }
+
+    let mut roster = Roster::new();
+    let mut notes = Vec::new();
+    roster.metadata.name = root.get_attribute("name").unwrap_or_default().trim().to_string();
+    for force in get_forces(&root) {
+        let mut entries = Vec::new();
+        for selection in force.get_grandchildren("selections", "selection") {
+            import_selection(selection, &mut entries, &mut notes);
+        }
+        for entry in merge_copies(entries) {
+            roster.add_entry(entry);
+        }
+    }
+    if roster.elements.is_empty() {
+        return Err("The roster has nothing in it".to_string());
+    }
+    // Copies of a selection bring the same notes.
+    let mut seen = HashSet::new();
+    notes.retain(|note| seen.insert(note.clone()));
+    Ok(BattleScribeImport {roster, notes})
+}
+
+// Forces, including the ones nested in other forces.
+fn get_forces(element: &XmlElement) -> Vec<&XmlElement> {
+    element.get_grandchildren("forces", "force")
+        .flat_map(|force| std::iter::once(force).chain(get_forces(force)))
+        .collect()
+}
+
+fn import_selection(selection: &XmlElement, entries: &mut Vec<RosterEntry>, notes: &mut Vec<String>) {
+    // Nothing is said about a selection without copies, it's left out.
+    let quantity = get_number(selection, notes);
+    if quantity == 0 {
+        return;
+    }
+    let Some(mut element) = map_element(selection, notes) else {
+        return;
+    };
+    let (mut characters, mut upgrades) = (Vec::new(), Vec::new());
+    collect_nested(selection, &mut characters, &mut upgrades, notes);
+    // A unit is joined by one character at most, the rest stand on their own.
+    let mut separate = Vec::new();
+    match &mut element {
+        RosterElement::ElemUnit(unit) => {
+            for character in characters {
+                if unit.attached_elements.is_empty() {
+                    unit.attached_elements.push(character);
+                } else {
+                    notes.push(format!("{}: {} was added as an entry of its own, a unit is only joined by one character",
+                        unit.name, character.get_name()));
+                    separate.push(character);
+                }
+            }
+        }
+        _ if !characters.is_empty() => {
+            let names: Vec<String> = characters.iter().map(RosterElement::get_name).collect();
+            notes.push(format!("{}: {} left out, only units can have characters attached", element.get_name(), names.join(", ")));
+        }
+        _ => {}
+    }
+
+    let mut entry = RosterEntry::from(element);
+    entry.note = selection.get_attribute("customNotes").unwrap_or_default().to_string();
+    if !upgrades.is_empty() {
+        notes.push(format!("{}: {} kept in the note, there's nothing to attach them as", entry.element.get_name(), upgrades.join(", ")));
+        let upgrades = format!("With {}", upgrades.join(", "));
+        entry.note = if entry.note.is_empty() { upgrades } else { format!("{}; {}", entry.note, upgrades) };
+    }
+    if selection.has_category(Wave::Second.get_label()) {
+        entry.wave = Wave::Second;
+    }
+    entry.quantity = quantity;
+    let separate: Vec<RosterEntry> = separate.into_iter().map(|element| RosterEntry {element, ..entry.renewed()}).collect();
+    entries.push(entry);
+    entries.extend(separate);
+}
+
+// What's selected under an entry, however deep it was: the characters, and
+// the names of the rest (wargear, upgrades...).
+fn collect_nested(selection: &XmlElement, characters: &mut Vec<RosterElement>, upgrades: &mut Vec<String>, notes: &mut Vec<String>) {
+    for child in selection.get_grandchildren("selections", "selection") {
+        let name = child.get_attribute("name").unwrap_or_default().trim();
+        let number = get_number(child, notes);
+        // Neither it nor what's under it was taken.
+        if number == 0 {
+            continue;
+        }
+        if is_character(child) {
+            if let Some(element) = map_element(child, notes) {
+                characters.extend(std::iter::repeat_n(element, number as usize));
+            }
+        } else if !name.is_empty() {
+            upgrades.push(if number == 1 { name.to_string() } else { format!("{}x {}", number, name) });
+        }
+        collect_nested(child, characters, upgrades, notes);
+    }
+}
+
+fn is_character(selection: &XmlElement) -> bool {
+    let name = selection.get_attribute("name").unwrap_or_default().trim();
+    match find_catalog_element(name) {
+        Some(element) => matches!(element, RosterElement::ElemCharacter(_)),
+        None => selection.get_primary_category() == Some("Character"),
+    }
+}
+
+// Identical selections next to each other, or further apart, made into one
+// entry with their copies added up, the way the exporter wrote it.
+fn merge_copies(entries: Vec<RosterEntry>) -> Vec<RosterEntry> {
+    let is_copy = |entry: &RosterEntry, other: &RosterEntry| entry.element == other.element
+        && entry.wave == other.wave && entry.note == other.note && entry.campaign == other.campaign;
+    let mut merged: Vec<RosterEntry> = Vec::new();
+    for entry in entries {
+        match merged.iter_mut().find(|other| is_copy(other, &entry) && other.quantity + entry.quantity <= MAX_QUANTITY) {
+            Some(other) => other.quantity += entry.quantity,
+            None => merged.push(entry),
+        }
+    }
+    merged
+}
+
+fn map_element(selection: &XmlElement, notes: &mut Vec<String>) -> Option<RosterElement> {
+    let name = selection.get_attribute("name").unwrap_or_default().trim().to_string();
+    if name.is_empty() {
+        notes.push("A selection without a name was left out".to_string());
+        return None;
+    }
+    let points = get_points(selection);
+
+    if let Some(element) = find_catalog_element(&name) {
+        let catalog_points = element.get_name_and_points().1;
+        if points.is_some_and(|points| points != catalog_points) {
+            notes.push(format!("{}: {} pts in the file, the catalog cost of {} pts is used", name, points.unwrap_or_default(), catalog_points));
+        }
+        return Some(element);
+    }
+
+    let points = points.unwrap_or_default();
+    Some(match selection.get_primary_category() {
+        Some("Character") => Character {name, points}.into(),
+        Some("Unit") => Unit {name, points, attached_elements: Vec::new(), image: String::new()}.into(),
+        Some("Support") => Support {name, points}.into(),
+        _ => {
+            notes.push(format!("{} isn't in the catalog, it was added as Other", name));
+            RosterElement::ElemOther((name, points, Vec::new(), String::new()))
+        }
+    })
+}
+
+fn find_catalog_element(name: &str) -> Option<RosterElement> {
+    let list = ArmyList::new(Faction::find_by_element_name(name)?);
+    list.get_characters().into_iter().find(|character| character.name == name).map(RosterElement::from)
+        .or_else(|| list.get_units().into_iter().find(|unit| unit.name == name).map(RosterElement::from))
+        .or_else(|| list.get_supports().into_iter().find(|support| support.name == name).map(RosterElement::from))
+}
+
+// The points cost, other costs (command points and such) are left aside.
+fn get_points(selection: &XmlElement) -> Option<u32> {
+    selection.get_grandchildren("costs", "cost")
+        .find(|cost| {
+            let name = cost.get_attribute("name").unwrap_or_default().to_lowercase();
+            name == "pts" || name == "points" || cost.get_attribute("typeId") == Some("points")
+        })
+        .and_then(|cost| cost.get_attribute("value")?.trim().parse::<f64>().ok())
+        .map(|value| value.max(0.0).round() as u32)
+}
+
+// Copies of the selection, at most MAX_QUANTITY.
+fn get_number(selection: &XmlElement, notes: &mut Vec<String>) -> u32 {
+    let number = selection.get_attribute("number").and_then(|number| number.trim().parse::<u64>().ok()).unwrap_or(1);
+    if number > MAX_QUANTITY as u64 {
+        let name = selection.get_attribute("name").unwrap_or_default().trim();
+        notes.push(format!("{}: {} copies in the file, {} were kept", name, number, MAX_QUANTITY));
+        return MAX_QUANTITY;
+    }
+    number as u32
+}
+
+// Just enough of a zip reader for .rosz files: the first .ros entry, stored
+// or deflated, found through the central directory.
+fn read_zipped_roster(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let damaged = || "The .rosz file is damaged".to_string();
+    // Offsets and sizes are read from the file, they can point anywhere.
+    let add = |at: usize, offset: usize| at.checked_add(offset).ok_or_else(damaged);
+    let read = |at: usize, length: usize| bytes.get(at..add(at, length)?).ok_or_else(damaged);
+    let read_u16 = |at: usize, offset: usize| read(add(at, offset)?, 2).map(|b| u16::from_le_bytes([b[0], b[1]]) as usize);
+    let read_u32 = |at: usize, offset: usize| read(add(at, offset)?, 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize);
+
+    // The end of the central directory, only a comment can come after it.
+    let end = (0..bytes.len().saturating_sub(21)).rev()
+        .find(|at| bytes[*at..].starts_with(b"PK\x05\x06"))
+        .ok_or_else(damaged)?;
+    let mut at = read_u32(end, 16)?;
+    for _ in 0..read_u16(end, 10)? {
+        if !bytes.get(at..).is_some_and(|rest| rest.starts_with(b"PK\x01\x02")) {
+            return Err(damaged());
+        }
+        let method = read_u16(at, 10)?;
+        let compressed_size = read_u32(at, 20)?;
+        let name_length = read_u16(at, 28)?;
+        let name = read(add(at, 46)?, name_length)?;
+        if String::from_utf8_lossy(name).to_lowercase().ends_with(".ros") {
+            let local = read_u32(at, 42)?;
+            let start = [30, read_u16(local, 26)?, read_u16(local, 28)?].into_iter().try_fold(local, add)?;
+            let data = read(start, compressed_size)?;
+            return match method {
+                0 => Ok(data.to_vec()),
+                8 => decompress_to_vec_with_limit(data, MAX_ROSTER_BYTES).map_err(|_| damaged()),
+                _ => Err(format!("The .rosz file uses an unsupported compression ({})", method)),
+            };
+        }
+        at = [46 + name_length, read_u16(at, 30)?, read_u16(at, 32)?].into_iter().try_fold(at, add)?;
+    }
+    Err("There's no roster in the .rosz file".to_string())
+}
+
+// Just enough of an XML reader for rosters: elements and their attributes,
+// text is skipped as nothing is read from it.
+#[derive(Clone, Debug, PartialEq)]
+struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<XmlElement>,
+}
+
+impl XmlElement {
+    fn get_attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    // Children of the first child with the given name, e.g. the <selection>
+    // elements of <selections>.
+    fn get_grandchildren<'a>(&'a self, parent: &'a str, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter()
+            .filter(move |child| child.name == parent)
+            .take(1)
+            .flat_map(move |child| child.children.iter().filter(move |grandchild| grandchild.name == name))
+    }
+
+    fn get_primary_category(&self) -> Option<&str> {
+        self.get_grandchildren("categories", "category")
+            .find(|category| category.get_attribute("primary") == Some("true"))
+            .and_then(|category| category.get_attribute("name"))
+    }
+
+    fn has_category(&self, name: &str) -> bool {
+        self.get_grandchildren("categories", "category").any(|category| category.get_attribute("name") == Some(name))
+    }
+}
+
+fn parse_xml(text: &str) -> Result<XmlElement, String> {
+    let mut open: Vec<XmlElement> = Vec::new();
+    let mut root: Option<XmlElement> = None;
+    let mut rest = text;
+    let skip_to = |rest: &str, end: &str| rest.find(end).map(|at| at + end.len()).ok_or_else(|| format!("Unclosed {} in the roster", end));
+
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        if rest.starts_with("<?") {
+            rest = &rest[skip_to(rest, "?>")?..];
+        } else if rest.starts_with("<!--") {
+            rest = &rest[skip_to(rest, "-->")?..];
+        } else if rest.starts_with("<![CDATA[") {
+            rest = &rest[skip_to(rest, "]]>")?..];
+        } else if rest.starts_with("<!") {
+            rest = &rest[skip_to(rest, ">")?..];
+        } else if let Some(closing) = rest.strip_prefix("</") {
+            let end = closing.find('>').ok_or("Unclosed tag in the roster")?;
+            let name = closing[..end].trim();
+            let element = open.pop().filter(|element| element.name == name)
+                .ok_or_else(|| format!("Unexpected </{}> in the roster", name))?;
+            rest = &closing[end + 1..];
+            match open.last_mut() {
+                Some(parent) => parent.children.push(element),
+                None => root = root.or(Some(element)),
+            }
+        } else {
+            let end = find_tag_end(rest).ok_or("Unclosed tag in the roster")?;
+            let content = &rest[1..end];
+            rest = &rest[end + 1..];
+            let is_empty = content.ends_with('/');
+            let element = parse_tag(content.trim_end_matches('/'))?;
+            if !is_empty {
+                open.push(element);
+            } else {
+                match open.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => root = root.or(Some(element)),
+                }
+            }
+        }
+    }
+    match (root, open.last()) {
+        (_, Some(element)) => Err(format!("<{}> is never closed in the roster", element.name)),
+        (Some(root), None) => Ok(root),
+        (None, None) => Err("The file isn't a roster".to_string()),
+    }
+}
+
+// Position of the '>' ending the tag, skipping the ones inside quotes.
+fn find_tag_end(tag: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    tag.char_indices().find(|(_, c)| {
+        match quote {
+            Some(open) if *c == open => quote = None,
+            Some(_) => {}
+            None if *c == '"' || *c == '\'' => quote = Some(*c),
+            None => return *c == '>',
+        }
+        false
+    }).map(|(at, _)| at)
+}
+
+fn parse_tag(content: &str) -> Result<XmlElement, String> {
+    let content = content.trim();
+    let name_end = content.find(char::is_whitespace).unwrap_or(content.len());
+    let mut element = XmlElement {name: content[..name_end].to_string(), attributes: Vec::new(), children: Vec::new()};
+    let mut rest = content[name_end..].trim_start();
+    while !rest.is_empty() {
+        let equals = rest.find('=').ok_or_else(|| format!("Bad attribute in <{}>", element.name))?;
+        let key = rest[..equals].trim().to_string();
+        let value = rest[equals + 1..].trim_start();
+        let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')
+            .ok_or_else(|| format!("Unquoted attribute {} in <{}>", key, element.name))?;
+        let value_end = value[1..].find(quote).ok_or_else(|| format!("Unclosed attribute {} in <{}>", key, element.name))?;
+        element.attributes.push((key, decode_entities(&value[1..value_end + 1])));
+        rest = value[value_end + 2..].trim_start();
+    }
+    Ok(element)
+}
+
+fn decode_entities(value: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest.find(';').map(|end| (&rest[1..end], end));
+        let character = entity.and_then(|(entity, _)| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => match entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                None => entity.strip_prefix('#').and_then(|number| number.parse().ok()).and_then(char::from_u32),
+            },
+        });
+        match (character, entity) {
+            (Some(character), Some((_, end))) => {
+                decoded.push(character);
+                rest = &rest[end + 1..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROS: &[u8] = include_bytes!("../../tests/fixtures/battlescribe/skirmish.ros");
+    const ROSZ: &[u8] = include_bytes!("../../tests/fixtures/battlescribe/skirmish.rosz");
+    const TRUNCATED_ROSZ: &[u8] = include_bytes!("../../tests/fixtures/battlescribe/truncated.rosz");
+
+    fn get_names(import: &BattleScribeImport) -> Vec<String> {
+        import.roster.elements.iter().map(|entry| entry.get_counted_name()).collect()
+    }
+
+    #[test]
+    fn plain_roster_is_read() {
+        let import = import_roster(ROS).unwrap();
+        assert_eq!(import.roster.metadata.name, "Skirmish");
+        assert_eq!(get_names(&import), vec!["2x Battle Robots", "Sentient AI", "Mystery Box"]);
+        assert_eq!(import.roster.elements[0].note, "Painted; With Flamer");
+        assert_eq!(import.roster.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn notes_are_only_about_what_is_kept() {
+        let import = import_roster(ROS).unwrap();
+        assert_eq!(import.notes.len(), 3, "{:?}", import.notes);
+        assert!(import.notes.iter().all(|note| !note.contains("Heavy Robots") && !note.contains("Missiles")), "{:?}", import.notes);
+    }
+
+    #[test]
+    fn zipped_roster_is_read() {
+        let zipped = import_roster(ROSZ).unwrap();
+        let plain = import_roster(ROS).unwrap();
+        assert!(zipped.roster.is_same_list(&plain.roster));
+        assert_eq!(zipped.notes, plain.notes);
+    }
+
+    #[test]
+    fn damaged_archives_are_refused() {
+        assert_eq!(import_roster(TRUNCATED_ROSZ).unwrap_err(), "The .rosz file is damaged");
+
+        // Offsets pointing past the end of the file, or of the memory.
+        let end = ROSZ.windows(4).rposition(|window| window == b"PK\x05\x06").unwrap();
+        let central = u32::from_le_bytes(ROSZ[end + 16..end + 20].try_into().unwrap()) as usize;
+        for offset in [end + 16, central + 20, central + 42] {
+            let mut corrupt = ROSZ.to_vec();
+            corrupt[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+            assert_eq!(import_roster(&corrupt).unwrap_err(), "The .rosz file is damaged");
+        }
+    }
+}
@@ -0,0 +1,129 @@
+// Compact binary form of a roster, for where every byte counts (share links,
+// QR codes). Files stay JSON, readable and editable by hand. The JSON layout
+// (flattened entries, empty fields left out) can't be read back from a
+// binary format, so the roster goes through a plain mirror of itself. The
+// ids of the entries aren't carried, they would take most of a QR code: the
+// receiver gives the entries ids of its own.
+use bincode::Options;
+use serde::{Serialize, Deserialize};
+
+use crate::core::campaign::CampaignRecord;
+use crate::core::ids;
+use crate::core::migration::CURRENT_ROSTER_VERSION;
+use crate::core::roster::{Roster, RosterElement, RosterEntry, RosterMetadata, Wave, MAX_QUANTITY};
+use crate::core::print_options::PrintOptions;
+use crate::core::rules::Suppression;
+use crate::core::scenario::Scenario;
+
+// Leading byte of the data, to bump whenever the layout below changes, as
+// for the JSON (see core::migration).
+const BINARY_FORMAT_VERSION: u8 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct BinaryEntry {
+    element : RosterElement,
+    campaign : Option<CampaignRecord>,
+    wave : Wave,
+    quantity : u32,
+    note : String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BinaryMetadata {
+    name : String,
+    print_options : PrintOptions,
+    escalation_milestones : Vec<u32>,
+    suppressions : Vec<Suppression>,
+    points_limit : u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BinaryRoster {
+    elements : Vec<BinaryEntry>,
+    scenario : Option<Scenario>,
+    metadata : BinaryMetadata,
+}
+
+impl Roster {
+    pub fn to_binary(&self) -> Result<Vec<u8>, bincode::Error> {
+        let binary = BinaryRoster {
+            elements: self.elements.iter()
+                .map(|entry| BinaryEntry {
+                    element: entry.element.clone(),
+                    campaign: entry.campaign.clone(),
+                    wave: entry.wave,
+                    quantity: entry.quantity,
+                    note: entry.note.clone(),
+                })
+                .collect(),
+            scenario: self.scenario.clone(),
+            metadata: BinaryMetadata {
+                name: self.metadata.name.clone(),
+                print_options: self.metadata.print_options.clone(),
+                escalation_milestones: self.metadata.escalation_milestones.clone(),
+                suppressions: self.metadata.suppressions.clone(),
+                points_limit: self.metadata.points_limit,
+            },
+        };
+        let mut bytes = vec![BINARY_FORMAT_VERSION];
+        bytes.extend(bincode::DefaultOptions::new().serialize(&binary)?);
+        Ok(bytes)
+    }
+
+    pub fn from_binary(bytes: &[u8]) -> Result<Roster, bincode::Error> {
+        let options = bincode::DefaultOptions::new();
+        let binary: BinaryRoster = match bytes.split_first() {
+            Some((&BINARY_FORMAT_VERSION, data)) => options.deserialize(data)?,
+            _ => return Err(Box::new(bincode::ErrorKind::Custom("Not a binary roster".to_string()))),
+        };
+        Ok(Roster {
+            version: CURRENT_ROSTER_VERSION,
+            elements: binary.elements.into_iter()
+                .map(|entry| RosterEntry {campaign: entry.campaign, wave: entry.wave, quantity: entry.quantity.clamp(1, MAX_QUANTITY), note: entry.note, ..RosterEntry::from(entry.element)})
+                .collect(),
+            scenario: binary.scenario,
+            metadata: RosterMetadata {
+                // Not carried, the copy is a roster of its own
+                id: ids::new_id(),
+                name: binary.metadata.name,
+                print_options: binary.metadata.print_options,
+                escalation_milestones: binary.metadata.escalation_milestones,
+                suppressions: binary.metadata.suppressions,
+                points_limit: binary.metadata.points_limit,
+                // Not carried, the catalog of the sender isn't known
+                catalog_hash: String::new(),
+                // Where the sender syncs it isn't for the receiver
+                sync: None,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures::FIXTURES;
+
+    #[test]
+    fn binary_round_trip() {
+        for (name, build) in FIXTURES {
+            let mut roster = build();
+            roster.elements[0].note = "Proxy".to_string();
+            roster.elements[0].quantity = 2;
+            roster.metadata.points_limit = 45;
+            let bytes = roster.to_binary().unwrap();
+            assert_eq!(bytes[0], BINARY_FORMAT_VERSION);
+            let read = Roster::from_binary(&bytes).unwrap();
+            assert!(read.is_same_list(&roster), "{}", name);
+            assert_eq!(read.check_invariants(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn unknown_versions_are_refused() {
+        let mut bytes = FIXTURES[0].1().to_binary().unwrap();
+        bytes[0] = BINARY_FORMAT_VERSION + 1;
+        assert!(Roster::from_binary(&bytes).is_err());
+        assert!(Roster::from_binary(&[]).is_err());
+    }
+}
@@ -0,0 +1,72 @@
+// Where the points of a roster go: subtotals of its groups (element types,
+// deployment waves) and their share of the whole list, checked against the
+// largest share a format allows a single group.
+use crate::core::roster::{Roster, RosterEntry, Wave};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Grouping {
+    Type,
+    Wave,
+}
+
+impl Grouping {
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Grouping::Type => "By Type",
+            Grouping::Wave => "By Wave",
+        }
+    }
+
+    pub fn get_group(&self, entry: &RosterEntry) -> &'static str {
+        match self {
+            Grouping::Type => entry.element.get_type_name(),
+            Grouping::Wave => entry.wave.get_label(),
+        }
+    }
+
+    // Every group, empty ones included, in a stable order.
+    pub fn get_groups(&self) -> Vec<&'static str> {
+        match self {
+            Grouping::Type => vec!["Character", "Unit", "Support", "Other"],
+            Grouping::Wave => Wave::ALL.iter().map(Wave::get_label).collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GroupSubtotal {
+    pub label : &'static str,
+    pub entries : usize,
+    pub points : u32,
+    // Share of the total points, in percent
+    pub share_percent : f64,
+    pub is_over_cap : bool,
+}
+
+// Subtotals of the groups with at least one entry.
+pub fn get_breakdown(roster: &Roster, grouping: Grouping, cap_percent: Option<u32>) -> Vec<GroupSubtotal> {
+    let total = roster.get_total_points();
+    grouping.get_groups().into_iter().filter_map(|label| {
+        let entries: Vec<&RosterEntry> = roster.elements.iter().filter(|entry| grouping.get_group(entry) == label).collect();
+        if entries.is_empty() {
+            return None;
+        }
+        let points: u32 = entries.iter().map(|entry| entry.get_total_points()).sum();
+        let share_percent = if total == 0 { 0.0 } else { points as f64 * 100.0 / total as f64 };
+        Some(GroupSubtotal {
+            label,
+            entries: entries.len(),
+            points,
+            share_percent,
+            is_over_cap: cap_percent.is_some_and(|cap| share_percent > cap as f64),
+        })
+    }).collect()
+}
+
+// The groupings worth showing: waves only once the deployment board is used.
+pub fn get_groupings(roster: &Roster) -> Vec<Grouping> {
+    let has_waves = roster.elements.iter().any(|entry| !entry.wave.is_first());
+    [Grouping::Type, Grouping::Wave].into_iter()
+        .filter(|grouping| *grouping != Grouping::Wave || has_waves)
+        .collect()
+}
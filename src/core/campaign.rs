@@ -0,0 +1,211 @@
+// Campaign play: the record of each roster entry (experience, rank and
+// whatever happened to it over the games) and the campaign itself.
+use serde::{Serialize, Deserialize};
+
+use crate::core::roster::Roster;
+
+// For custom serde errors:
+use serde::de;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Rank {
+    #[default]
+    Green,
+    Regular,
+    Veteran,
+    Elite,
+}
+
+impl Rank {
+    pub const ALL: [Rank; 4] = [Rank::Green, Rank::Regular, Rank::Veteran, Rank::Elite];
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Rank::Green => "Green",
+            Rank::Regular => "Regular",
+            Rank::Veteran => "Veteran",
+            Rank::Elite => "Elite",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CampaignRecord {
+    pub xp : u32,
+    pub rank : Rank,
+    pub injuries : Vec<String>,
+    pub honors : Vec<String>,
+}
+
+impl CampaignRecord {
+    // Short text for the badge on the roster card, e.g. "XP 4 · VETERAN".
+    pub fn get_badge_label(&self) -> String {
+        format!("XP {} · {}", self.xp, self.rank.get_label().to_uppercase())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BattleResult {
+    #[default]
+    Win,
+    Draw,
+    Loss,
+}
+
+impl BattleResult {
+    pub const ALL: [BattleResult; 3] = [BattleResult::Win, BattleResult::Draw, BattleResult::Loss];
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            BattleResult::Win => "Win",
+            BattleResult::Draw => "Draw",
+            BattleResult::Loss => "Loss",
+        }
+    }
+}
+
+// One game played during the campaign, with the roster as it was fielded.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BattleRecord {
+    pub date : String,
+    pub opponent : String,
+    pub result : BattleResult,
+    pub resource_points : i32,
+    #[serde(default)]
+    pub notes : String,
+    #[serde(default)]
+    pub scenario : Option<String>,
+    pub roster : Roster,
+}
+
+// A campaign links a roster to the games played with it. Between two games
+// only a limited number of roster changes is allowed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Campaign {
+    pub name : String,
+    #[serde(default)]
+    pub player : String,
+    #[serde(default)]
+    pub territories : u32,
+    pub roster : Roster,
+    pub battles : Vec<BattleRecord>,
+    pub allowed_changes_between_games : u32,
+    pub changes_since_last_battle : u32,
+}
+
+impl Campaign {
+    pub fn new(name: String, roster: Roster) -> Campaign {
+        Campaign {
+            name,
+            player: String::new(),
+            territories: 0,
+            roster,
+            battles: Vec::<BattleRecord>::new(),
+            allowed_changes_between_games: 3,
+            changes_since_last_battle: 0,
+        }
+    }
+
+    // Keeps the linked roster up to date, counting the change.
+    pub fn register_roster_change(&mut self, roster: &Roster) {
+        if self.roster != *roster {
+            self.roster = roster.clone();
+            self.changes_since_last_battle += 1;
+        }
+    }
+
+    pub fn is_over_change_limit(&self) -> bool {
+        !self.battles.is_empty() && self.changes_since_last_battle > self.allowed_changes_between_games
+    }
+
+    pub fn record_battle(&mut self, battle: BattleRecord) {
+        self.battles.push(battle);
+        self.changes_since_last_battle = 0;
+    }
+
+    // Experience gathered by all the tracked entries of the roster.
+    pub fn get_total_xp(&self) -> u32 {
+        self.roster.elements.iter()
+            .filter_map(|entry| entry.campaign.as_ref())
+            .map(|record| record.xp)
+            .sum()
+    }
+
+    pub fn get_resource_points(&self) -> i32 {
+        self.battles.iter().map(|battle| battle.resource_points).sum()
+    }
+
+    // Wins, draws and losses.
+    pub fn get_results(&self) -> (usize, usize, usize) {
+        let count = |result: BattleResult| self.battles.iter().filter(|battle| battle.result == result).count();
+        (count(BattleResult::Win), count(BattleResult::Draw), count(BattleResult::Loss))
+    }
+}
+
+// File exchanged between participants and organizer. The tag makes sure a
+// roster or something else isn't mistaken for a campaign.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CampaignExport {
+    pub format : String,
+    pub version : u32,
+    pub campaign : Campaign,
+}
+
+const CAMPAIGN_EXPORT_FORMAT: &str = "fsd_builder.campaign";
+
+impl CampaignExport {
+    pub fn to_json(campaign: &Campaign) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&CampaignExport {
+            format: CAMPAIGN_EXPORT_FORMAT.to_string(),
+            version: 1,
+            campaign: campaign.clone(),
+        })
+    }
+
+    pub fn from_json(json_str: &str) -> Result<Campaign, serde_json::Error> {
+        let export: CampaignExport = serde_json::from_str(json_str)?;
+        if export.format != CAMPAIGN_EXPORT_FORMAT {
+            return Err(de::Error::custom("Not a campaign file"));
+        }
+        Ok(export.campaign)
+    }
+}
+
+// One line of the standings table built by the organizer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StandingsRow {
+    pub player : String,
+    pub campaign : String,
+    pub games : usize,
+    pub wins : usize,
+    pub draws : usize,
+    pub losses : usize,
+    pub resource_points : i32,
+    pub xp : u32,
+    pub territories : u32,
+}
+
+// Standings of the imported campaigns: most wins first, then territories,
+// then resource points.
+pub fn compute_standings(campaigns: &[Campaign]) -> Vec<StandingsRow> {
+    let mut rows: Vec<StandingsRow> = campaigns.iter().map(|campaign| {
+        let (wins, draws, losses) = campaign.get_results();
+        StandingsRow {
+            player: if campaign.player.is_empty() { "Unknown".to_string() } else { campaign.player.clone() },
+            campaign: campaign.name.clone(),
+            games: campaign.battles.len(),
+            wins,
+            draws,
+            losses,
+            resource_points: campaign.get_resource_points(),
+            xp: campaign.get_total_xp(),
+            territories: campaign.territories,
+        }
+    }).collect();
+
+    rows.sort_by(|a, b| b.wins.cmp(&a.wins)
+        .then(b.territories.cmp(&a.territories))
+        .then(b.resource_points.cmp(&a.resource_points)));
+    rows
+}
@@ -0,0 +1,34 @@
+// Canonical JSON: the same content always gives the same text, whatever the
+// order the fields were filled in, the serde features enabled or the
+// platform. Meant for hashing and diffing, saved files keep the usual format.
+use serde::Serialize;
+use serde_json::{Map, Number, Value};
+
+pub fn to_canonical_json<T: Serialize>(value: &T) -> Result<String, serde_json::Error> {
+    Ok(canonicalize(serde_json::to_value(value)?).to_string())
+}
+
+// Object keys sorted, floats without a negative zero.
+fn canonicalize(value: Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<(String, Value)> = object.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(entries.into_iter().map(|(key, value)| (key, canonicalize(value))).collect::<Map<String, Value>>())
+        }
+        Value::Array(array) => Value::Array(array.into_iter().map(canonicalize).collect()),
+        Value::Number(number) if number.is_f64() && number.as_f64() == Some(0.0) => {
+            Number::from_f64(0.0).map(Value::Number).unwrap_or(Value::Null)
+        }
+        other => other,
+    }
+}
+
+// FNV-1a, 64 bits, as hex: short, stable across builds and platforms (unlike
+// the std hasher) and plenty for telling two rosters apart.
+pub fn content_hash(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
@@ -0,0 +1,46 @@
+// Catalog updates between releases: a small file of changes applied on top
+// of the catalog built into the app (core::armylist), rather than a whole
+// new release for a few points. A delta names the catalog it applies to and
+// the one it gives, by hash (armylist::get_catalog_hash), and is refused
+// unless both match. For example:
+//
+//   {
+//     "edition": "cards v1.3.1",
+//     "base_hash": "5f0c2a91d4e7b388",
+//     "result_hash": "c19e07f2a8b34d60",
+//     "changes": [
+//       {"SetPoints": {"name": "Heavy Robots", "points": 5}},
+//       {"AddSupport": {"faction": "Tech", "support": {"name": "Smoke Screen", "points": 2}}},
+//       {"Remove": {"name": "Pilot AI"}}
+//     ]
+//   }
+use serde::{Serialize, Deserialize};
+
+use crate::core::armylist::Faction;
+use crate::core::character::Character;
+use crate::core::support::Support;
+use crate::core::unit::Unit;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CatalogChange {
+    SetPoints { name : String, points : u32 },
+    AddCharacter { faction : Faction, character : Character },
+    AddUnit { faction : Faction, unit : Unit },
+    AddSupport { faction : Faction, support : Support },
+    Remove { name : String },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CatalogDelta {
+    // Edition of the catalog once updated, shown instead of the built-in one
+    pub edition : String,
+    pub base_hash : String,
+    pub result_hash : String,
+    pub changes : Vec<CatalogChange>,
+}
+
+impl CatalogDelta {
+    pub fn from_json(json: &str) -> Result<CatalogDelta, String> {
+        serde_json::from_str(json).map_err(|e| format!("Not a catalog update: {}", e))
+    }
+}
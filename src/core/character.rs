@@ -1,5 +1,3 @@
-use std::string;
-
 use super::element::Element;
 use serde::{Serialize, Deserialize};
 
@@ -0,0 +1,321 @@
+// Every edit of a roster as a value: applied to the roster, turned into the
+// command that undoes it, and labelled for the history. The app runs its
+// edits through RosterStore::execute, the history, autosave, sync and
+// validation all follow from the commands executed there.
+use crate::core::history;
+use crate::core::print_options::PrintOptions;
+use crate::core::roster::{Roster, RosterEntry, Wave, MAX_QUANTITY};
+use crate::core::rules::Suppression;
+use crate::core::scenario::Scenario;
+use crate::core::sync::SyncRecord;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RosterCommand {
+    InsertEntry(usize, RosterEntry),
+    RemoveEntry(usize),
+    // See Roster::move_entry
+    MoveEntry(usize /* From */, usize /* To */),
+    // The entry as a whole: options, attachments, wave and campaign record
+    ReplaceEntry(usize, RosterEntry),
+    SetQuantity(usize, u32),
+    SetNameAndPoints(usize, String, u32),
+    SetNote(usize, String),
+    SetWave(usize, Wave),
+    // Another roster taking the place of this one, see transform
+    SetId(String),
+    SetName(String),
+    SetPointsLimit(u32),
+    SetEscalationMilestones(Vec<u32>),
+    SetSuppressions(Vec<Suppression>),
+    SetScenario(Option<Scenario>),
+    SetPrintOptions(PrintOptions),
+    // See Roster::has_catalog_changed
+    SetCatalogHash(String),
+    SetSyncRecord(Option<SyncRecord>),
+    // Applied and undone as a single edit, all or nothing
+    Batch(Vec<RosterCommand>),
+}
+
+// A command once executed, with what undoes it and how the history calls it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExecutedCommand {
+    pub command: RosterCommand,
+    pub inverse: RosterCommand,
+    pub label: String,
+}
+
+impl RosterCommand {
+    // False if the command doesn't apply to the roster, e.g. an index out of
+    // it or no change at all. The roster is then left as it was.
+    pub fn apply(&self, roster: &mut Roster) -> bool {
+        match self {
+            RosterCommand::InsertEntry(index, entry) if *index <= roster.elements.len() => {
+                roster.elements.insert(*index, entry.clone());
+                true
+            }
+            RosterCommand::RemoveEntry(index) if *index < roster.elements.len() => {
+                roster.elements.remove(*index);
+                true
+            }
+            RosterCommand::MoveEntry(from, to) => roster.move_entry(*from, *to),
+            RosterCommand::ReplaceEntry(index, new_entry) => match roster.elements.get_mut(*index) {
+                Some(entry) if entry != new_entry => {
+                    *entry = new_entry.clone();
+                    true
+                }
+                _ => false,
+            },
+            RosterCommand::SetQuantity(index, quantity) => match roster.elements.get_mut(*index) {
+                Some(entry) if (1..=MAX_QUANTITY).contains(quantity) && entry.quantity != *quantity => {
+                    entry.quantity = *quantity;
+                    true
+                }
+                _ => false,
+            },
+            RosterCommand::SetNameAndPoints(index, name, points) => match roster.elements.get_mut(*index) {
+                Some(entry) if entry.element.get_name_and_points() != (name.clone(), *points) => {
+                    entry.element.set_name_and_points(name, *points);
+                    true
+                }
+                _ => false,
+            },
+            RosterCommand::SetNote(index, note) => match roster.elements.get_mut(*index) {
+                Some(entry) => set_if_changed(&mut entry.note, note),
+                None => false,
+            },
+            RosterCommand::SetWave(index, wave) => match roster.elements.get_mut(*index) {
+                Some(entry) => set_if_changed(&mut entry.wave, wave),
+                None => false,
+            },
+            RosterCommand::SetId(id) if !id.is_empty() => set_if_changed(&mut roster.metadata.id, id),
+            RosterCommand::SetName(name) => set_if_changed(&mut roster.metadata.name, name),
+            RosterCommand::SetPointsLimit(points_limit) if *points_limit > 0 => set_if_changed(&mut roster.metadata.points_limit, points_limit),
+            RosterCommand::SetEscalationMilestones(milestones) => set_if_changed(&mut roster.metadata.escalation_milestones, milestones),
+            RosterCommand::SetSuppressions(suppressions) => set_if_changed(&mut roster.metadata.suppressions, suppressions),
+            RosterCommand::SetScenario(scenario) => set_if_changed(&mut roster.scenario, scenario),
+            RosterCommand::SetPrintOptions(print_options) => set_if_changed(&mut roster.metadata.print_options, print_options),
+            RosterCommand::SetCatalogHash(catalog_hash) => set_if_changed(&mut roster.metadata.catalog_hash, catalog_hash),
+            RosterCommand::SetSyncRecord(sync) => set_if_changed(&mut roster.metadata.sync, sync),
+            RosterCommand::Batch(commands) => {
+                let mut edited = roster.clone();
+                // Every command has to apply, on the roster left by the previous ones.
+                let is_applied = commands.iter().all(|command| command.apply(&mut edited));
+                is_applied && set_if_changed(roster, &edited)
+            }
+            _ => false,
+        }
+    }
+
+    // The command taking the roster back from after this one to before it,
+    // None if this one doesn't apply to it.
+    pub fn invert(&self, before: &Roster) -> Option<RosterCommand> {
+        let entry = |index: &usize| before.elements.get(*index).cloned();
+        let inverse = match self {
+            RosterCommand::InsertEntry(index, _) => RosterCommand::RemoveEntry(*index),
+            RosterCommand::RemoveEntry(index) => RosterCommand::InsertEntry(*index, entry(index)?),
+            RosterCommand::MoveEntry(from, to) => RosterCommand::MoveEntry(*to, *from),
+            RosterCommand::ReplaceEntry(index, _) => RosterCommand::ReplaceEntry(*index, entry(index)?),
+            RosterCommand::SetQuantity(index, _) => RosterCommand::SetQuantity(*index, entry(index)?.quantity),
+            RosterCommand::SetNameAndPoints(index, _, _) => {
+                let (name, points) = entry(index)?.element.get_name_and_points();
+                RosterCommand::SetNameAndPoints(*index, name, points)
+            }
+            RosterCommand::SetNote(index, _) => RosterCommand::SetNote(*index, entry(index)?.note),
+            RosterCommand::SetWave(index, _) => RosterCommand::SetWave(*index, entry(index)?.wave),
+            RosterCommand::SetId(_) => RosterCommand::SetId(before.metadata.id.clone()),
+            RosterCommand::SetName(_) => RosterCommand::SetName(before.metadata.name.clone()),
+            RosterCommand::SetPointsLimit(_) => RosterCommand::SetPointsLimit(before.metadata.points_limit),
+            RosterCommand::SetEscalationMilestones(_) => RosterCommand::SetEscalationMilestones(before.metadata.escalation_milestones.clone()),
+            RosterCommand::SetSuppressions(_) => RosterCommand::SetSuppressions(before.metadata.suppressions.clone()),
+            RosterCommand::SetScenario(_) => RosterCommand::SetScenario(before.scenario.clone()),
+            RosterCommand::SetPrintOptions(_) => RosterCommand::SetPrintOptions(before.metadata.print_options.clone()),
+            RosterCommand::SetCatalogHash(_) => RosterCommand::SetCatalogHash(before.metadata.catalog_hash.clone()),
+            RosterCommand::SetSyncRecord(_) => RosterCommand::SetSyncRecord(before.metadata.sync.clone()),
+            RosterCommand::Batch(commands) => {
+                let mut edited = before.clone();
+                let mut inverses = Vec::new();
+                for command in commands {
+                    inverses.push(command.invert(&edited)?);
+                    command.apply(&mut edited);
+                }
+                inverses.reverse();
+                RosterCommand::Batch(inverses)
+            }
+        };
+        Some(inverse)
+    }
+
+    // How the history calls the command, on the roster before it.
+    pub fn get_label(&self, before: &Roster) -> String {
+        let name = |index: &usize| before.elements.get(*index).map(|entry| entry.element.get_name()).unwrap_or_default();
+        match self {
+            RosterCommand::InsertEntry(_, entry) => format!("Added {}", entry.element.get_name()),
+            RosterCommand::RemoveEntry(index) => format!("Removed {}", name(index)),
+            RosterCommand::MoveEntry(from, _) => format!("Moved {}", name(from)),
+            RosterCommand::SetQuantity(index, quantity) => format!("Took {}x {}", quantity, name(index)),
+            RosterCommand::SetNameAndPoints(index, new_name, _) if *new_name != name(index) => format!("Changed {} to {}", name(index), new_name),
+            RosterCommand::SetNameAndPoints(index, _, points) => format!("Set {} to {} points", name(index), points),
+            RosterCommand::SetNote(index, note) if note.is_empty() => format!("Removed the note of {}", name(index)),
+            RosterCommand::SetNote(index, _) => format!("Noted on {}", name(index)),
+            RosterCommand::SetWave(index, wave) => format!("Moved {} to the {}", name(index), wave.get_label()),
+            RosterCommand::SetId(_) => "Opened another roster".to_string(),
+            RosterCommand::SetName(new_name) => format!("Renamed to {}", new_name),
+            RosterCommand::SetPointsLimit(points_limit) => format!("Set the limit to {} points", points_limit),
+            RosterCommand::SetEscalationMilestones(_) => "Changed escalation milestones".to_string(),
+            RosterCommand::SetSuppressions(_) => "Changed set aside rules".to_string(),
+            RosterCommand::SetScenario(Some(scenario)) => format!("Attached scenario {}", scenario.name),
+            RosterCommand::SetScenario(None) => "Detached scenario".to_string(),
+            RosterCommand::SetPrintOptions(_) => "Changed print options".to_string(),
+            RosterCommand::SetCatalogHash(_) => "Moved to the current catalog".to_string(),
+            RosterCommand::SetSyncRecord(_) => "Synced with the server".to_string(),
+            // Worked out from what changed.
+            RosterCommand::ReplaceEntry(..) | RosterCommand::Batch(_) => {
+                let mut after = before.clone();
+                self.apply(&mut after);
+                history::describe_change(before, &after)
+            }
+        }
+    }
+
+    // Applies the command, None if it doesn't apply to the roster.
+    pub fn execute(self, roster: &mut Roster) -> Option<ExecutedCommand> {
+        let inverse = self.invert(roster)?;
+        let label = self.get_label(roster);
+        self.apply(roster).then_some(ExecutedCommand {command: self, inverse, label})
+    }
+
+    // The commands taking the roster from before to after, for rosters that
+    // come whole (pulled, restored, reordered...): the entries gone removed,
+    // the others moved into place and replaced where they differ, the new
+    // ones inserted, then the metadata field by field. Entries are matched
+    // by their ids. None if there is nothing to change.
+    pub fn transform(before: &Roster, after: &Roster) -> Option<RosterCommand> {
+        let mut edited = before.clone();
+        let mut commands = Vec::new();
+        let mut push = |command: RosterCommand, edited: &mut Roster| {
+            if command.apply(edited) {
+                commands.push(command);
+            }
+        };
+        // From the last one, the indexes of the ones left to check don't move.
+        for index in (0..before.elements.len()).rev() {
+            if after.find_entry(&before.elements[index].id).is_none() {
+                push(RosterCommand::RemoveEntry(index), &mut edited);
+            }
+        }
+        // The entries up to the index are the ones of after, the one wanted
+        // next is searched among the rest.
+        for (index, entry) in after.elements.iter().enumerate() {
+            let current = edited.elements.iter().skip(index).position(|other| other.id == entry.id).map(|position| position + index);
+            match current {
+                Some(current) => {
+                    push(RosterCommand::MoveEntry(current, index), &mut edited);
+                    push(RosterCommand::ReplaceEntry(index, entry.clone()), &mut edited);
+                }
+                None => push(RosterCommand::InsertEntry(index, entry.clone()), &mut edited),
+            }
+        }
+        let (metadata, scenario) = (&after.metadata, &after.scenario);
+        push(RosterCommand::SetId(metadata.id.clone()), &mut edited);
+        push(RosterCommand::SetName(metadata.name.clone()), &mut edited);
+        push(RosterCommand::SetPointsLimit(metadata.points_limit), &mut edited);
+        push(RosterCommand::SetEscalationMilestones(metadata.escalation_milestones.clone()), &mut edited);
+        push(RosterCommand::SetSuppressions(metadata.suppressions.clone()), &mut edited);
+        push(RosterCommand::SetScenario(scenario.clone()), &mut edited);
+        push(RosterCommand::SetPrintOptions(metadata.print_options.clone()), &mut edited);
+        push(RosterCommand::SetCatalogHash(metadata.catalog_hash.clone()), &mut edited);
+        push(RosterCommand::SetSyncRecord(metadata.sync.clone()), &mut edited);
+        (!commands.is_empty()).then_some(RosterCommand::Batch(commands))
+    }
+
+    // The wave of the entry given to its copies that aren't in it, see
+    // Roster::get_copies. None if there are none.
+    pub fn wave_to_copies(roster: &Roster, index: usize) -> Option<RosterCommand> {
+        let wave = roster.elements.get(index)?.wave;
+        let commands: Vec<RosterCommand> = roster.get_copies(index).into_iter()
+            .filter(|copy| roster.elements[*copy].wave != wave)
+            .map(|copy| RosterCommand::SetWave(copy, wave))
+            .collect();
+        (!commands.is_empty()).then_some(RosterCommand::Batch(commands))
+    }
+}
+
+fn set_if_changed<T: Clone + PartialEq>(value: &mut T, new_value: &T) -> bool {
+    let is_changed = value != new_value;
+    if is_changed {
+        *value = new_value.clone();
+    }
+    is_changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures::{RosterFixture, FIXTURES};
+
+    // Executed on the roster, then undone by its inverse.
+    fn assert_round_trip(command: RosterCommand, roster: &Roster) {
+        let mut edited = roster.clone();
+        let executed = command.clone().execute(&mut edited).unwrap_or_else(|| panic!("{:?} doesn't apply", command));
+        assert_ne!(&edited, roster);
+        assert!(executed.inverse.apply(&mut edited));
+        assert_eq!(&edited, roster);
+    }
+
+    #[test]
+    fn inverse_undoes_the_command() {
+        let roster = RosterFixture::standard_60();
+        let entry = roster.elements[0].renewed();
+        let last = roster.elements.len() - 1;
+        let commands = [
+            RosterCommand::InsertEntry(1, entry.clone()),
+            RosterCommand::RemoveEntry(0),
+            RosterCommand::MoveEntry(0, last),
+            RosterCommand::ReplaceEntry(last, entry),
+            RosterCommand::SetQuantity(0, 3),
+            RosterCommand::SetNameAndPoints(0, "Renamed".to_string(), 7),
+            RosterCommand::SetNote(0, "Proxy".to_string()),
+            RosterCommand::SetWave(0, Wave::Second),
+            RosterCommand::SetName("Renamed".to_string()),
+            RosterCommand::SetPointsLimit(45),
+            RosterCommand::Batch(vec![RosterCommand::RemoveEntry(0), RosterCommand::SetQuantity(0, 2)]),
+        ];
+        for command in commands {
+            assert_round_trip(command, &roster);
+        }
+    }
+
+    #[test]
+    fn commands_without_change_are_refused() {
+        let mut roster = RosterFixture::skirmish_30();
+        let before = roster.clone();
+        let count = roster.elements.len();
+        assert!(RosterCommand::RemoveEntry(count).execute(&mut roster).is_none());
+        assert!(RosterCommand::SetQuantity(0, 1).execute(&mut roster).is_none());
+        assert!(RosterCommand::SetQuantity(0, MAX_QUANTITY + 1).execute(&mut roster).is_none());
+        assert!(RosterCommand::SetPointsLimit(0).execute(&mut roster).is_none());
+        // The first command applies, the second doesn't: neither is kept.
+        let batch = RosterCommand::Batch(vec![RosterCommand::SetName("Renamed".to_string()), RosterCommand::RemoveEntry(count)]);
+        assert!(batch.execute(&mut roster).is_none());
+        assert_eq!(roster, before);
+    }
+
+    #[test]
+    fn transform_leads_from_one_roster_to_the_other() {
+        for (name, build) in FIXTURES {
+            let before = build();
+            let mut after = before.clone();
+            after.elements.reverse();
+            after.elements.remove(0);
+            after.add_entry(before.elements[0].renewed());
+            after.metadata.name = "Transformed".to_string();
+
+            let command = RosterCommand::transform(&before, &after).unwrap();
+            assert_round_trip(command.clone(), &before);
+            let mut edited = before.clone();
+            assert!(command.apply(&mut edited));
+            assert_eq!(edited, after, "{}", name);
+            assert_eq!(RosterCommand::transform(&after, &after), None);
+        }
+    }
+}
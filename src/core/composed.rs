@@ -0,0 +1,46 @@
+// Read-only view of a roster entry merged with whatever is attached to it.
+// Cards (and anything else showing a whole entry) should go through this
+// instead of digging into the attached elements themselves.
+use crate::core::roster::{CompositionRole, RosterElement};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComposedElement {
+    pub name: String,
+    pub type_name: &'static str,
+    pub image: String,
+    pub parts: Vec<(CompositionRole, RosterElement)>,
+    pub total_points: u32,
+    // Abilities the entry gains from its composition, e.g. the leadership
+    // of an attached character.
+    pub abilities: Vec<String>,
+}
+
+impl ComposedElement {
+    pub fn is_grouped(&self) -> bool {
+        self.parts.len() > 1
+    }
+}
+
+impl From<&RosterElement> for ComposedElement {
+    fn from(elem: &RosterElement) -> Self {
+        let parts = elem.compose();
+
+        let mut abilities = Vec::<String>::new();
+        for (role, part) in &parts {
+            match role {
+                CompositionRole::Leader => abilities.push(format!("Led by {}", part.get_name())),
+                CompositionRole::Upgrade => abilities.push(format!("Upgraded with {}", part.get_name())),
+                CompositionRole::Squad => {}
+            }
+        }
+
+        ComposedElement {
+            name: elem.get_name(),
+            type_name: elem.get_type_name(),
+            image: elem.get_image(),
+            total_points: elem.get_total_points(),
+            parts,
+            abilities,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+// Deflated and base64 encoded data, for the payloads that have to fit
+// somewhere small: share links and the browser storage quota.
+use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::inflate::{decompress_to_vec_with_limit, TINFLStatus};
+
+const COMPRESSION_LEVEL: u8 = 9;
+// Far more than any roster, a link or a stored value inflating past this
+// is a decompression bomb rather than something the app wrote.
+const MAX_DECOMPRESSED_BYTES: usize = 4 * 1024 * 1024;
+
+// Url safe, so the result can go straight into a link.
+pub fn compress(text: &str) -> String {
+    compress_bytes(text.as_bytes())
+}
+
+// Also reads plain base64 text, as written before compression was added.
+pub fn decompress(encoded: &str) -> Result<String, String> {
+    String::from_utf8(decompress_bytes(encoded)?).map_err(|e| e.to_string())
+}
+
+pub fn compress_bytes(bytes: &[u8]) -> String {
+    base64::encode_config(compress_to_vec(bytes, COMPRESSION_LEVEL), base64::URL_SAFE_NO_PAD)
+}
+
+pub fn decompress_bytes(encoded: &str) -> Result<Vec<u8>, String> {
+    let bytes = base64::decode_config(encoded, base64::URL_SAFE_NO_PAD).map_err(|e| e.to_string())?;
+    match decompress_to_vec_with_limit(&bytes, MAX_DECOMPRESSED_BYTES) {
+        Ok(decompressed) => Ok(decompressed),
+        Err(TINFLStatus::HasMoreOutput) => Err(format!("Decompressed data over {} bytes", MAX_DECOMPRESSED_BYTES)),
+        Err(_) => Ok(bytes),
+    }
+}
@@ -1,11 +1,15 @@
 // Elements represent all card-like components in the game. be it characters, supports or units.
 
+#[cfg(feature = "card-images")]
 use image::RgbImage;
 
+#[allow(dead_code)] // Not used by the UI yet, see the TODOs in armylist.rs
 pub trait Element {
     fn get_name(&self) -> String;
     fn get_points(&self) -> u32;
+    #[cfg(feature = "card-images")]
     fn get_icon(&self) -> Option<RgbImage> {None}
     fn get_attached(&self) -> Vec<String>;
+    #[cfg(feature = "card-images")]
     fn generate_card(&self) -> Option<RgbImage> {None}
 }
\ No newline at end of file
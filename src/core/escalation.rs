@@ -0,0 +1,77 @@
+// Escalation leagues: the roster grows game after game through a series of
+// point milestones, each list built on top of the previous one.
+use crate::core::roster::Roster;
+
+// Milestones typed as a list of numbers, "30, 45, 60". Anything that isn't a
+// positive number is ignored, the rest sorted without duplicates.
+pub fn parse_milestones(text: &str) -> Vec<u32> {
+    let mut milestones: Vec<u32> = text.split([',', ';', ' '])
+        .filter_map(|part| part.trim().parse().ok())
+        .filter(|milestone| *milestone > 0)
+        .collect();
+    milestones.sort_unstable();
+    milestones.dedup();
+    milestones
+}
+
+pub fn format_milestones(milestones: &[u32]) -> String {
+    milestones.iter().map(|milestone| milestone.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+// Where the roster stands on the league, for the progress widget.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EscalationProgress {
+    pub total : u32,
+    // Last milestone reached, 0 before the first one
+    pub reached : u32,
+    // Milestone to build up to, None once past the last one
+    pub next : Option<u32>,
+    // Position of the next milestone, from 1, and how many there are
+    pub step : usize,
+    pub step_count : usize,
+    // Reasons the list can't legally be carried on to the next milestone
+    pub warnings : Vec<String>,
+}
+
+impl EscalationProgress {
+    // Share of the way from the last milestone reached to the next one.
+    pub fn get_fraction(&self) -> f64 {
+        match self.next {
+            Some(next) if next > self.reached => (self.total - self.reached) as f64 / (next - self.reached) as f64,
+            _ => 1.0,
+        }
+    }
+}
+
+// None when the roster has no milestones.
+pub fn get_progress(roster: &Roster) -> Option<EscalationProgress> {
+    // Saved sorted, but files can be edited by hand.
+    let mut milestones = roster.metadata.escalation_milestones.clone();
+    milestones.sort_unstable();
+    let last = *milestones.last()?;
+    let total = roster.get_total_points();
+    let step = milestones.iter().position(|milestone| *milestone >= total);
+    let next = step.map(|step| milestones[step]);
+    let reached = milestones.iter().copied().rev().find(|milestone| *milestone <= total).unwrap_or(0);
+
+    let mut warnings = Vec::<String>::new();
+    if let Err(errors) = roster.check_invariants() {
+        warnings.push(format!("The core list breaks the rules ({}), it can't be carried on", errors.join("; ")));
+    }
+    match next {
+        None => warnings.push(format!("{} points over the last milestone of {}", total - last, last)),
+        Some(next) if next > roster.get_points_limit() => {
+            warnings.push(format!("The next milestone of {} points is over the limit of {}", next, roster.get_points_limit()));
+        }
+        Some(_) => {}
+    }
+
+    Some(EscalationProgress {
+        total,
+        reached,
+        next,
+        step: step.map_or(milestones.len(), |step| step + 1),
+        step_count: milestones.len(),
+        warnings,
+    })
+}
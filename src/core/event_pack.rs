@@ -0,0 +1,75 @@
+// Everything an organizer sets for an event, in one file handed out to the
+// players: the format, the catalog edition the event is played with, the
+// house rules and the header of the printed rosters. For example:
+//
+//   {
+//     "name": "Spring Clash",
+//     "catalog_edition": "cards v1.3",
+//     "group_cap_percent": 50,
+//     "submission_deadline": "2026-05-01T18:00:00+02:00",
+//     "lists_per_entry": 2,
+//     "house_rules": [
+//       {"rule": "group-cap", "severity": "Error"},
+//       {"rule": "unspent-points", "severity": null}
+//     ],
+//     "branding": {"event_name": "Spring Clash", "organizer_text": "Hosted by the club"}
+//   }
+use serde::{Serialize, Deserialize};
+
+use crate::core::armylist::get_catalog_edition;
+use crate::core::print_options::PrintBranding;
+use crate::core::rules::{Severity, RULES};
+
+// A rule of core::rules played differently at the event: with another
+// severity, or not at all (no severity).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleOverride {
+    pub rule : String,
+    pub severity : Option<Severity>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventPack {
+    pub name : String,
+    // Edition of the catalog the event is played with, empty if any goes
+    pub catalog_edition : String,
+    // Largest share of the points a single group may take, see core::breakdown
+    pub group_cap_percent : Option<u32>,
+    // When the lists are due, in ISO 8601 with the offset of the event, see
+    // models::deadline
+    pub submission_deadline : Option<String>,
+    // Lists each player brings, checked against each other (core::rules)
+    // when there's more than one
+    pub lists_per_entry : Option<u32>,
+    pub house_rules : Vec<RuleOverride>,
+    pub branding : PrintBranding,
+}
+
+impl EventPack {
+    pub fn from_json(json: &str) -> Result<EventPack, String> {
+        let pack: EventPack = serde_json::from_str(json).map_err(|e| format!("Not an event pack: {}", e))?;
+        if pack.name.trim().is_empty() {
+            return Err("The event pack has no name".to_string());
+        }
+        Ok(pack)
+    }
+
+    // What the players should know once the pack is imported: a catalog
+    // other than the one of the event, house rules this version doesn't know.
+    pub fn get_import_notes(&self) -> Vec<String> {
+        let mut notes = Vec::new();
+        let edition = get_catalog_edition();
+        if !self.catalog_edition.is_empty() && self.catalog_edition != edition {
+            notes.push(format!(
+                "{} is played with the catalog {}, this builder has {}: check the points against the event rules",
+                self.name, self.catalog_edition, edition));
+        }
+        for house_rule in &self.house_rules {
+            if !RULES.iter().any(|rule| rule.id == house_rule.rule) {
+                notes.push(format!("The house rule on {} isn't known to this builder, it's left out", house_rule.rule));
+            }
+        }
+        notes
+    }
+}
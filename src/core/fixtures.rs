@@ -0,0 +1,107 @@
+// Rosters made from the catalog, for trying the builder on something known.
+// The command line tool writes them out, to be loaded in the app, with the
+// memory storage (see models::storage_backend) to leave what was saved alone:
+//
+//   cargo run --features cli --bin fsd_builder-cli -- fixture skirmish-30 > skirmish.json
+//
+// Elements are picked in catalog order, a fixture is the same from one run
+// to the next as long as the catalog is.
+use crate::core::armylist::{ArmyList, Faction};
+use crate::core::roster::{Roster, RosterElement, Wave};
+
+pub struct RosterFixture {
+    faction: Faction,
+    // Points the roster is filled up to, without going over
+    points: u32,
+    name: String,
+    // Copies of the first unit, put in before anything else
+    unit_copies: usize,
+    // Every other entry in the second wave
+    is_split_in_waves: bool,
+}
+
+type NamedFixture = (&'static str, fn() -> Roster);
+
+// Named fixtures, by the name the command line tool takes.
+pub const FIXTURES: [NamedFixture; 4] = [
+    ("skirmish-30", RosterFixture::skirmish_30),
+    ("standard-60", RosterFixture::standard_60),
+    ("over-limit", RosterFixture::over_limit),
+    ("unit-copies", RosterFixture::unit_copies),
+];
+
+impl RosterFixture {
+    pub fn new(faction: Faction, points: u32) -> RosterFixture {
+        RosterFixture {faction, points, name: String::new(), unit_copies: 0, is_split_in_waves: false}
+    }
+
+    pub fn named(mut self, name: &str) -> RosterFixture {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn with_unit_copies(mut self, copies: usize) -> RosterFixture {
+        self.unit_copies = copies;
+        self
+    }
+
+    pub fn split_in_waves(mut self) -> RosterFixture {
+        self.is_split_in_waves = true;
+        self
+    }
+
+    // A character, then units and supports over and over while they fit.
+    pub fn build(&self) -> Roster {
+        let list = ArmyList::new(self.faction);
+        let mut roster = Roster::new();
+        roster.metadata.name = self.name.clone();
+
+        let first_unit = list.get_units().into_iter().next().map(RosterElement::from);
+        let copies = first_unit.into_iter().flat_map(|unit| std::iter::repeat_n(unit, self.unit_copies));
+        let character = list.get_characters().into_iter().next().map(RosterElement::from);
+        self.add_fitting(&mut roster, copies.chain(character));
+
+        // Free elements would be added forever.
+        let repeated: Vec<RosterElement> = list.get_units().into_iter().map(RosterElement::from)
+            .chain(list.get_supports().into_iter().map(RosterElement::from))
+            .filter(|element| element.get_total_points() > 0)
+            .collect();
+        while self.add_fitting(&mut roster, repeated.iter().cloned()) {}
+
+        if self.is_split_in_waves {
+            for entry in roster.elements.iter_mut().skip(1).step_by(2) {
+                entry.wave = Wave::Second;
+            }
+        }
+        roster
+    }
+
+    // Returns whether anything was added.
+    fn add_fitting(&self, roster: &mut Roster, elements: impl Iterator<Item = RosterElement>) -> bool {
+        let mut is_added = false;
+        for element in elements {
+            if roster.get_total_points() + element.get_total_points() <= self.points {
+                roster.add_element(element);
+                is_added = true;
+            }
+        }
+        is_added
+    }
+
+    pub fn skirmish_30() -> Roster {
+        RosterFixture::new(Faction::Tech, 30).named("Skirmish 30").build()
+    }
+
+    pub fn standard_60() -> Roster {
+        RosterFixture::new(Faction::Enlisted, 60).named("Standard 60").split_in_waves().build()
+    }
+
+    // Over the standard points limit, for the validation.
+    pub fn over_limit() -> Roster {
+        RosterFixture::new(Faction::Union, 75).named("Over Limit").build()
+    }
+
+    pub fn unit_copies() -> Roster {
+        RosterFixture::new(Faction::Conglomerate, 60).named("Unit Copies").with_unit_copies(3).split_in_waves().build()
+    }
+}
@@ -0,0 +1,249 @@
+// History of the roster edits, for undoing and redoing them and for jumping
+// back to named checkpoints. Every entry keeps the command executed there and
+// the one undoing it (core::commands), moving through the history replays
+// them on the roster.
+use crate::core::commands::{ExecutedCommand, RosterCommand};
+use crate::core::roster::{Roster, RosterElement, RosterEntry};
+
+// Oldest edits are forgotten past this, with the checkpoints before them.
+const MAX_HISTORY_ENTRIES: usize = 50;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistoryEntry {
+    pub label : String,
+    // None for the roster the history starts from and for checkpoints
+    pub executed : Option<ExecutedCommand>,
+    pub is_checkpoint : bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct History {
+    pub entries : Vec<HistoryEntry>,
+    // Entry matching the roster currently shown. Entries after it are the
+    // ones undone, until something else is recorded.
+    pub position : usize,
+}
+
+impl History {
+    pub fn new(label: &str) -> History {
+        History {
+            entries: vec![HistoryEntry {label: label.to_string(), executed: None, is_checkpoint: false}],
+            position: 0,
+        }
+    }
+
+    pub fn record(&mut self, executed: ExecutedCommand) {
+        self.push(HistoryEntry {label: executed.label.clone(), executed: Some(executed), is_checkpoint: false});
+    }
+
+    pub fn add_checkpoint(&mut self, name: String) {
+        self.push(HistoryEntry {label: name, executed: None, is_checkpoint: true});
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.get_last_edit().is_some()
+    }
+
+    // The command undoing the last edit, if any.
+    pub fn undo(&mut self) -> Option<RosterCommand> {
+        let index = self.get_last_edit()?;
+        self.jump_to(index - 1)
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.get_next_edit().is_some()
+    }
+
+    // The command redoing the last edit undone, if any.
+    pub fn redo(&mut self) -> Option<RosterCommand> {
+        let index = self.get_next_edit()?;
+        self.jump_to(index)
+    }
+
+    // The commands taking the roster from the current entry to the one at
+    // the index, as one. None if there's no edit in between, e.g. between
+    // two checkpoints: the position still moves.
+    pub fn jump_to(&mut self, index: usize) -> Option<RosterCommand> {
+        if index >= self.entries.len() {
+            return None;
+        }
+        let commands: Vec<RosterCommand> = if index < self.position {
+            self.entries[index + 1..=self.position].iter().rev()
+                .filter_map(|entry| entry.executed.as_ref().map(|executed| executed.inverse.clone()))
+                .collect()
+        } else {
+            self.entries[self.position + 1..=index].iter()
+                .filter_map(|entry| entry.executed.as_ref().map(|executed| executed.command.clone()))
+                .collect()
+        };
+        self.position = index;
+        match commands.len() {
+            0 => None,
+            1 => commands.into_iter().next(),
+            _ => Some(RosterCommand::Batch(commands)),
+        }
+    }
+
+    // Index of the last edit up to the current entry.
+    fn get_last_edit(&self) -> Option<usize> {
+        self.entries[..=self.position].iter().rposition(|entry| entry.executed.is_some())
+    }
+
+    // Index of the first edit after the current entry.
+    fn get_next_edit(&self) -> Option<usize> {
+        let offset = self.entries[self.position + 1..].iter().position(|entry| entry.executed.is_some())?;
+        Some(self.position + 1 + offset)
+    }
+
+    fn push(&mut self, entry: HistoryEntry) {
+        self.entries.truncate(self.position + 1);
+        self.entries.push(entry);
+        if self.entries.len() > MAX_HISTORY_ENTRIES {
+            // The oldest edit becomes where the history starts, it can't be
+            // undone any more and neither can be jumped to what was before.
+            if let Some(oldest) = self.entries.iter().skip(1).position(|entry| entry.executed.is_some()) {
+                self.entries.drain(..=oldest);
+                self.entries[0].executed = None;
+            }
+        }
+        self.position = self.entries.len() - 1;
+    }
+}
+
+// Human readable summary of what happened between two versions of the roster.
+pub(crate) fn describe_change(before: &Roster, after: &Roster) -> String {
+    let name_of = |entries: &[RosterEntry], index: usize| {
+        entries.get(index).map(|entry| entry.element.get_name()).unwrap_or_default()
+    };
+    // First entry that doesn't match between the two lists.
+    let first_difference = before.elements.iter().zip(after.elements.iter())
+        .position(|(a, b)| a != b)
+        .unwrap_or(before.elements.len().min(after.elements.len()));
+
+    if after.elements.is_empty() && !before.elements.is_empty() {
+        return "Cleared roster".to_string();
+    }
+    if after.elements.len() > before.elements.len() {
+        return format!("Added {}", name_of(&after.elements, first_difference));
+    }
+    if after.elements.len() < before.elements.len() {
+        return format!("Removed {}", name_of(&before.elements, first_difference));
+    }
+    if before.elements != after.elements {
+        let (old, new) = (&before.elements[first_difference], &after.elements[first_difference]);
+        if old.element.get_name() != new.element.get_name() {
+            let changed = before.elements.iter().zip(after.elements.iter()).filter(|(a, b)| a != b).count();
+            return match changed {
+                1 => format!("Changed {} to {}", old.element.get_name(), new.element.get_name()),
+                _ => "Reordered roster".to_string(),
+            };
+        }
+        if old.quantity != new.quantity {
+            return format!("Took {}x {}", new.quantity, new.element.get_name());
+        }
+        if old.note != new.note {
+            return format!("Noted on {}", new.element.get_name());
+        }
+        if old.campaign != new.campaign {
+            return format!("Updated campaign record of {}", new.element.get_name());
+        }
+        let (old_attached, new_attached) = (old.element.get_attached_elements(), new.element.get_attached_elements());
+        if new_attached.len() > old_attached.len() {
+            let added = new_attached.last().map(RosterElement::get_name).unwrap_or_default();
+            return format!("Attached {} to {}", added, new.element.get_name());
+        }
+        if new_attached.len() < old_attached.len() {
+            return format!("Detached from {}", new.element.get_name());
+        }
+        return format!("Edited {}", new.element.get_name());
+    }
+    if before.scenario != after.scenario {
+        return match &after.scenario {
+            Some(scenario) => format!("Attached scenario {}", scenario.name),
+            None => "Detached scenario".to_string(),
+        };
+    }
+    if before.metadata.name != after.metadata.name {
+        return format!("Renamed to {}", after.metadata.name);
+    }
+    if before.metadata.points_limit != after.metadata.points_limit {
+        return format!("Set the limit to {} points", after.metadata.points_limit);
+    }
+    if before.metadata.catalog_hash != after.metadata.catalog_hash {
+        return "Moved to the current catalog".to_string();
+    }
+    if before.metadata.sync != after.metadata.sync {
+        return "Synced with the server".to_string();
+    }
+    if before.metadata != after.metadata {
+        return "Changed print options".to_string();
+    }
+    "Edited roster".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures::RosterFixture;
+
+    fn record(history: &mut History, roster: &mut Roster, command: RosterCommand) {
+        history.record(command.execute(roster).unwrap());
+    }
+
+    #[test]
+    fn undo_and_redo_replay_the_edits() {
+        let start = RosterFixture::standard_60();
+        let mut roster = start.clone();
+        let mut history = History::new("Loaded");
+        record(&mut history, &mut roster, RosterCommand::SetQuantity(0, 2));
+        record(&mut history, &mut roster, RosterCommand::RemoveEntry(1));
+        let edited = roster.clone();
+
+        while let Some(command) = history.undo() {
+            assert!(command.apply(&mut roster));
+        }
+        assert_eq!(roster, start);
+        assert!(!history.can_undo());
+
+        while let Some(command) = history.redo() {
+            assert!(command.apply(&mut roster));
+        }
+        assert_eq!(roster, edited);
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn recording_forgets_what_was_undone() {
+        let mut roster = RosterFixture::skirmish_30();
+        let mut history = History::new("Loaded");
+        record(&mut history, &mut roster, RosterCommand::SetName("First".to_string()));
+        history.undo().unwrap().apply(&mut roster);
+        record(&mut history, &mut roster, RosterCommand::SetName("Second".to_string()));
+        assert!(!history.can_redo());
+        assert_eq!(history.entries.len(), 2);
+    }
+
+    #[test]
+    fn jumping_over_checkpoints_takes_every_edit() {
+        let start = RosterFixture::skirmish_30();
+        let mut roster = start.clone();
+        let mut history = History::new("Loaded");
+        record(&mut history, &mut roster, RosterCommand::SetName("Renamed".to_string()));
+        history.add_checkpoint("Before the limit".to_string());
+        record(&mut history, &mut roster, RosterCommand::SetPointsLimit(45));
+        assert!(history.jump_to(0).unwrap().apply(&mut roster));
+        assert_eq!(roster, start);
+        assert_eq!(history.jump_to(0), None);
+    }
+
+    #[test]
+    fn oldest_edits_are_forgotten() {
+        let mut roster = RosterFixture::skirmish_30();
+        let mut history = History::new("Loaded");
+        for points_limit in 1..=MAX_HISTORY_ENTRIES as u32 + 10 {
+            record(&mut history, &mut roster, RosterCommand::SetPointsLimit(points_limit));
+        }
+        assert_eq!(history.entries.len(), MAX_HISTORY_ENTRIES);
+        assert!(history.entries[0].executed.is_none());
+    }
+}
@@ -0,0 +1,60 @@
+// Identities of the things kept or shared, as UUIDs (version 4): the rosters
+// and their entries, the slots, the snapshots and the rosters on the sync
+// server. They come
+// from a single generator, seeded at random by default. Seeded with a known
+// number instead, it gives the same ids in the same order every time, for
+// the tests and the command line tool only: ids made by the app must differ
+// from anyone else's.
+//
+// Nothing here reads the clock or the browser: the app seeds the generator
+// with the randomness of the browser at startup, see App::get_random_seed.
+use std::cell::RefCell;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+// SplitMix64, small and good enough for ids that only need to differ.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdGenerator {
+    state: u64,
+}
+
+impl IdGenerator {
+    pub fn new(seed: u64) -> IdGenerator {
+        IdGenerator {state: seed}
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // xxxxxxxx-xxxx-4xxx-yxxx-xxxxxxxxxxxx, y being one of 8, 9, a and b.
+    pub fn next_id(&mut self) -> String {
+        let high = (self.next_u64() & !0xf000) | 0x4000;
+        let low = (self.next_u64() & !(0b11 << 62)) | (0b10 << 62);
+        format!("{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            high >> 32, (high >> 16) & 0xffff, high & 0xffff, low >> 48, low & 0xffff_ffff_ffff)
+    }
+}
+
+thread_local! {
+    static GENERATOR: RefCell<Option<IdGenerator>> = const { RefCell::new(None) };
+}
+
+// Ids made from now on follow from the seed.
+pub fn seed(seed: u64) {
+    GENERATOR.with(|generator| *generator.borrow_mut() = Some(IdGenerator::new(seed)));
+}
+
+// Natively, the seed of the hash maps of the standard library is random
+// enough when the generator wasn't seeded.
+pub fn new_id() -> String {
+    GENERATOR.with(|generator| {
+        generator.borrow_mut()
+            .get_or_insert_with(|| IdGenerator::new(RandomState::new().build_hasher().finish()))
+            .next_id()
+    })
+}
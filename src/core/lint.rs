@@ -0,0 +1,63 @@
+// Likely data entry mistakes, as opposed to broken rules: the roster is
+// legal, but something in it probably isn't what the player meant. Each
+// suggestion comes with a fix when there's an obvious one, applied like the
+// quick fixes of the rules (core::quick_fix). Elements have no
+// options in this catalog, so there are no mandatory ones to check.
+use crate::core::armylist::Faction;
+use crate::core::quick_fix::QuickFix;
+use crate::core::roster::{Roster, RosterElement};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LintSuggestion {
+    pub index : usize,
+    pub message : String,
+    pub fix : Option<QuickFix>,
+}
+
+fn is_custom(element: &RosterElement) -> bool {
+    Faction::find_by_element_name(&element.get_name()).is_none()
+}
+
+// The images that failed to load are only known to the browser, it tells
+// through has_image_failed (given the image path, as stored in the element).
+pub fn lint_roster(roster: &Roster, has_image_failed: impl Fn(&str) -> bool) -> Vec<LintSuggestion> {
+    let mut suggestions = Vec::new();
+    let custom: Vec<bool> = roster.elements.iter().map(|entry| is_custom(&entry.element)).collect();
+    for (index, entry) in roster.elements.iter().enumerate() {
+        let element = &entry.element;
+        let name = element.get_name();
+        if custom[index] && element.get_total_points() == 0 {
+            suggestions.push(LintSuggestion {
+                index,
+                message: format!("{} is a custom entry worth 0 points", name),
+                fix: Some(QuickFix::RemoveEntry(index)),
+            });
+        }
+
+        // Copies of the same element are fine, different ones sharing a
+        // name are hard to tell apart on the table.
+        let first_index = roster.elements.iter().enumerate()
+            .position(|(other_index, other)| custom[other_index] && other.element.get_name() == name);
+        if let Some(first_index) = first_index.filter(|first_index| custom[index] && roster.elements[*first_index].element != *element) {
+            suggestions.push(LintSuggestion {
+                index,
+                message: format!("{} shares its name with a different custom entry (#{})", name, first_index + 1),
+                fix: Some(QuickFix::RenameEntry(index, get_free_name(roster, &name))),
+            });
+        }
+
+        let image = element.get_image();
+        if !image.is_empty() && has_image_failed(&image) {
+            let fix = matches!(element, RosterElement::ElemUnit(_) | RosterElement::ElemOther(_)).then_some(QuickFix::ClearImage(index));
+            suggestions.push(LintSuggestion {index, message: format!("The image of {} couldn't be loaded", name), fix});
+        }
+    }
+    suggestions
+}
+
+// "Name 2", "Name 3"... the first one nothing in the roster is called.
+fn get_free_name(roster: &Roster, name: &str) -> String {
+    (2..).map(|number| format!("{} {}", name, number))
+        .find(|candidate| !roster.elements.iter().any(|entry| entry.element.get_name() == *candidate))
+        .unwrap_or_default()
+}
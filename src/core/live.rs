@@ -0,0 +1,98 @@
+// A game followed live by spectators: what they see of the table, pushed
+// to the sync server by the player after every change, and read back by the
+// spectators every few seconds. The spectators get a link to it
+// (#follow?server=...&game=...) and don't need the roster, the document
+// carries what they see of it. The client side is in models::live.
+use serde::{Serialize, Deserialize};
+
+const FOLLOW_FRAGMENT: &str = "#follow";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LiveEntry {
+    pub name : String,
+    pub quantity : u32,
+    // Copies of the entry lost so far
+    pub casualties : u32,
+}
+
+impl LiveEntry {
+    pub fn is_destroyed(&self) -> bool {
+        self.casualties >= self.quantity
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LivePlayer {
+    pub name : String,
+    pub score : u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LiveGame {
+    pub roster_name : String,
+    pub entries : Vec<LiveEntry>,
+    pub players : Vec<LivePlayer>,
+    // 0 until the first activation
+    pub round : u32,
+}
+
+// Body of a push and of a pull, as for the rosters (see core::sync).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RemoteGame {
+    // On a push, the revision the game was changed from
+    pub revision : u32,
+    pub game : LiveGame,
+}
+
+// Where a followed game is: the sync server and the id of the game on it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FollowLink {
+    pub server : String,
+    pub game_id : String,
+}
+
+impl FollowLink {
+    // The server address is encoded, it has its own slashes and queries.
+    pub fn to_fragment(&self) -> String {
+        format!("{}?server={}&game={}",
+            FOLLOW_FRAGMENT, base64::encode_config(&self.server, base64::URL_SAFE_NO_PAD), self.game_id)
+    }
+
+    // None if the fragment isn't a follow link, or misses a part.
+    pub fn from_fragment(fragment: &str) -> Option<FollowLink> {
+        let query = fragment.strip_prefix(FOLLOW_FRAGMENT)?.strip_prefix('?')?;
+        let (mut server, mut game_id) = (None, None);
+        for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+            match key {
+                "server" => server = base64::decode_config(value, base64::URL_SAFE_NO_PAD).ok().and_then(|bytes| String::from_utf8(bytes).ok()),
+                "game" => game_id = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        Some(FollowLink {server: server?, game_id: game_id.filter(|id| !id.is_empty())?})
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follow_link_round_trip() {
+        let link = FollowLink {server: "https://sync.example/api?team=a&b=1".to_string(), game_id: "0b6e4a1c-2f3d-4e5f-8a9b-0c1d2e3f4a5b".to_string()};
+        let fragment = link.to_fragment();
+        assert!(fragment.starts_with("#follow?"));
+        assert_eq!(FollowLink::from_fragment(&fragment), Some(link));
+    }
+
+    #[test]
+    fn other_fragments_are_not_follow_links() {
+        assert_eq!(FollowLink::from_fragment("#roster=abc"), None);
+        assert_eq!(FollowLink::from_fragment("#follow"), None);
+        assert_eq!(FollowLink::from_fragment("#follow?game=1"), None);
+        assert_eq!(FollowLink::from_fragment("#follow?server=aHR0cHM6Ly9h&game="), None);
+    }
+}
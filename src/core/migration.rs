@@ -0,0 +1,148 @@
+// Every format rosters were ever saved with, and the steps bringing each one
+// to the next. Rosters are read through here wherever they come from (files,
+// the autosave, the slots, a campaign), so an old save loads as the current
+// roster instead of failing to deserialize.
+//
+// When the roster changes shape: bump CURRENT_ROSTER_VERSION, freeze the
+// types of the previous format below (copying the element types too if they
+// are the ones changing), and add the step from it to the new one.
+use serde::{de, Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::core::campaign::CampaignRecord;
+use crate::core::roster::{Roster, RosterElement, RosterEntry, RosterMetadata};
+use crate::core::scenario::Scenario;
+
+pub const CURRENT_ROSTER_VERSION: u32 = 2;
+
+// Version 1: entries with their campaign record.
+#[derive(Deserialize)]
+pub struct RosterEntryV1 {
+    #[serde(flatten)]
+    pub element : RosterElement,
+    #[serde(default)]
+    pub campaign : Option<CampaignRecord>,
+}
+
+#[derive(Deserialize)]
+pub struct RosterFileV1 {
+    pub elements : Vec<RosterEntryV1>,
+    #[serde(default)]
+    pub scenario : Option<Scenario>,
+    #[serde(default)]
+    pub metadata : RosterMetadata,
+}
+
+// Version 2: entries deployed in a wave, see the deployment board.
+#[derive(Deserialize)]
+pub struct RosterFileV2 {
+    pub elements : Vec<RosterEntry>,
+    #[serde(default)]
+    pub scenario : Option<Scenario>,
+    #[serde(default)]
+    pub metadata : RosterMetadata,
+}
+
+// A saved roster in whatever version it was saved with.
+pub enum RosterFile {
+    V1(RosterFileV1),
+    V2(RosterFileV2),
+}
+
+impl RosterFileV1 {
+    // Every entry starts in the first wave, with an id of its own.
+    fn migrate(self) -> RosterFileV2 {
+        RosterFileV2 {
+            elements: self.elements.into_iter()
+                .map(|entry| RosterEntry {campaign: entry.campaign, ..RosterEntry::from(entry.element)})
+                .collect(),
+            scenario: self.scenario,
+            metadata: self.metadata,
+        }
+    }
+}
+
+impl RosterFile {
+    pub fn into_current(self) -> RosterFileV2 {
+        match self {
+            RosterFile::V1(file) => file.migrate(),
+            RosterFile::V2(file) => file,
+        }
+    }
+}
+
+// The version tag decides how the rest is read. Versions from a newer app
+// are refused rather than read wrong.
+impl<'de> Deserialize<'de> for RosterFile {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let version = value.get("version").ok_or_else(|| de::Error::missing_field("version"))?;
+        match version.as_u64() {
+            Some(1) => serde_json::from_value(value).map(RosterFile::V1).map_err(de::Error::custom),
+            Some(2) => serde_json::from_value(value).map(RosterFile::V2).map_err(de::Error::custom),
+            Some(version) if version > CURRENT_ROSTER_VERSION as u64 =>
+                Err(de::Error::custom(format!("Roster version {} is from a newer version of the app", version))),
+            _ => Err(de::Error::custom(format!("Unknown roster version {}", version))),
+        }
+    }
+}
+
+impl From<RosterFile> for Roster {
+    fn from(file: RosterFile) -> Self {
+        let file = file.into_current();
+        Roster {
+            version: CURRENT_ROSTER_VERSION,
+            elements: file.elements,
+            scenario: file.scenario,
+            metadata: file.metadata,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures::RosterFixture;
+    use crate::core::roster::Wave;
+    use serde_json::json;
+
+    // A roster as version 1 saved it: no ids, no waves.
+    fn get_version_1() -> Value {
+        let roster = RosterFixture::standard_60();
+        let elements: Vec<Value> = roster.elements.iter().map(|entry| serde_json::to_value(&entry.element).unwrap()).collect();
+        json!({"version": 1, "elements": elements, "metadata": {"name": "Old Save"}})
+    }
+
+    #[test]
+    fn version_1_is_migrated() {
+        let roster = Roster::from_json(&get_version_1().to_string()).unwrap();
+        assert_eq!(roster.version, CURRENT_ROSTER_VERSION);
+        assert_eq!(roster.metadata.name, "Old Save");
+        assert_eq!(roster.elements.len(), RosterFixture::standard_60().elements.len());
+        assert!(roster.elements.iter().all(|entry| entry.wave == Wave::First && entry.quantity == 1));
+        assert_eq!(roster.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn current_version_is_read_as_saved() {
+        let roster = RosterFixture::unit_copies();
+        assert_eq!(Roster::from_json(&roster.to_json().unwrap()).unwrap(), roster);
+    }
+
+    #[test]
+    fn newer_versions_are_refused() {
+        let mut file = get_version_1();
+        file["version"] = json!(CURRENT_ROSTER_VERSION + 1);
+        let error = Roster::from_json(&file.to_string()).unwrap_err();
+        assert!(error.to_string().contains("newer version of the app"), "{}", error);
+    }
+
+    #[test]
+    fn unversioned_files_are_refused() {
+        let mut file = get_version_1();
+        file.as_object_mut().unwrap().remove("version");
+        assert!(Roster::from_json(&file.to_string()).is_err());
+        file["version"] = json!("two");
+        assert!(Roster::from_json(&file.to_string()).unwrap_err().to_string().contains("Unknown roster version"));
+    }
+}
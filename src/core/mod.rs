@@ -0,0 +1,42 @@
+// The army builder itself: rosters, the army lists and the rules checked on
+// them. Nothing in here may depend on yew, web-sys or the browser storage,
+// so it builds and runs natively as well (tests, command line tools). The
+// browser side of these types lives in the models module.
+pub mod roster;
+mod character;
+mod support;
+mod unit;
+mod element;
+pub mod armylist;
+pub mod composed;
+pub mod campaign;
+pub mod scenario;
+pub mod print_options;
+pub mod history;
+pub mod roster_names;
+pub mod canonical;
+pub mod compression;
+pub mod binary;
+pub mod repair;
+pub mod roster_slots;
+pub mod migration;
+pub mod escalation;
+pub mod breakdown;
+pub mod snapshots;
+pub mod swap;
+pub mod battlescribe;
+pub mod lint;
+pub mod quick_fix;
+pub mod rules;
+pub mod event_pack;
+#[cfg(feature = "qr")]
+pub mod qr;
+pub mod sync;
+pub mod live;
+pub mod provenance;
+pub mod revisions;
+pub mod catalog_delta;
+pub mod fixtures;
+pub mod ids;
+pub mod commands;
+pub mod validation;
@@ -0,0 +1,116 @@
+// Options of the printed roster, remembered per roster in its metadata.
+use serde::{Serialize, Deserialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PrintLayout {
+    #[default]
+    Cards,
+    List,
+    // Everything on a single dense page, for reference during the game
+    CheatSheet,
+}
+
+impl PrintLayout {
+    pub const ALL: [PrintLayout; 3] = [PrintLayout::Cards, PrintLayout::List, PrintLayout::CheatSheet];
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            PrintLayout::Cards => "Cards",
+            PrintLayout::List => "List",
+            PrintLayout::CheatSheet => "Cheat Sheet",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaperSize {
+    #[default]
+    A4,
+    Letter,
+    // 4x6 inches index card stock, one card per sheet
+    CardStock,
+}
+
+impl PaperSize {
+    pub const ALL: [PaperSize; 3] = [PaperSize::A4, PaperSize::Letter, PaperSize::CardStock];
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            PaperSize::A4 => "A4",
+            PaperSize::Letter => "Letter",
+            PaperSize::CardStock => "Card Stock (4x6 in)",
+        }
+    }
+
+    // Width and height of the sheet, in millimeters.
+    pub fn get_dimensions_mm(&self) -> (f32, f32) {
+        match self {
+            PaperSize::A4 => (210.0, 297.0),
+            PaperSize::Letter => (215.9, 279.4),
+            PaperSize::CardStock => (101.6, 152.4),
+        }
+    }
+
+    // Card stock gets a single card per sheet.
+    pub fn is_card_stock(&self) -> bool {
+        matches!(self, PaperSize::CardStock)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrintOptions {
+    pub layout : PrintLayout,
+    pub paper_size : PaperSize,
+    pub include_images : bool,
+    pub include_scenario : bool,
+    pub include_campaign : bool,
+    pub margin_mm : u32,
+    // Cards only: every sheet of fronts is followed by a sheet of backs.
+    pub double_sided : bool,
+    // Cards only: room under each card for notes taken during the game.
+    pub include_notes : bool,
+    // A last page with the points of each type and the total.
+    pub include_summary : bool,
+}
+
+// Margins can't take more than a third of the smaller side of the sheet.
+impl PrintOptions {
+    pub fn get_margin_mm(&self) -> u32 {
+        let (width, height) = self.paper_size.get_dimensions_mm();
+        self.margin_mm.min((width.min(height) / 3.0) as u32)
+    }
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        PrintOptions {
+            layout: PrintLayout::Cards,
+            paper_size: PaperSize::A4,
+            include_images: true,
+            include_scenario: true,
+            include_campaign: false,
+            margin_mm: 10,
+            double_sided: false,
+            include_notes: false,
+            include_summary: true,
+        }
+    }
+}
+
+// Header a club or an event puts on every printed roster. Applies to all the
+// rosters, unlike the options above.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrintBranding {
+    pub event_name : String,
+    // Address of the image, or the image itself as a data url
+    pub logo : String,
+    pub organizer_text : String,
+}
+
+impl PrintBranding {
+    pub fn is_empty(&self) -> bool {
+        self.event_name.trim().is_empty() && self.logo.is_empty() && self.organizer_text.trim().is_empty()
+    }
+}
@@ -0,0 +1,84 @@
+// Where the data of an element comes from: the catalog of this builder, the
+// catalog with other points (a house rule), or nowhere (homebrew). Elements
+// don't keep it, it's found again by name from the catalog, so a roster
+// edited by hand is told apart as well. Shown on the stat cards and checked
+// as a rule (core::rules), for organizers to spot unofficial content.
+use crate::core::armylist::{get_catalog_edition, get_catalog_points};
+use crate::core::roster::{Roster, RosterElement};
+
+// From the most official to the least.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Provenance {
+    Catalog,
+    HouseRuled { catalog_points : u32 },
+    Homebrew,
+}
+
+impl Provenance {
+    pub fn get_label(&self) -> String {
+        match self {
+            Provenance::Catalog => format!("Catalog {}", get_catalog_edition()),
+            Provenance::HouseRuled {..} => "House-ruled".to_string(),
+            Provenance::Homebrew => "Homebrew".to_string(),
+        }
+    }
+
+    pub fn is_official(&self) -> bool {
+        *self == Provenance::Catalog
+    }
+}
+
+// The element alone, without what's attached to it.
+pub fn get_provenance(element: &RosterElement) -> Provenance {
+    if matches!(element, RosterElement::ElemOther(_)) {
+        return Provenance::Homebrew;
+    }
+    let (name, points) = element.get_name_and_points();
+    match get_catalog_points(&name) {
+        None => Provenance::Homebrew,
+        Some(catalog_points) if catalog_points != points => Provenance::HouseRuled {catalog_points},
+        Some(_) => Provenance::Catalog,
+    }
+}
+
+// The least official of the element and its attachments.
+pub fn get_entry_provenance(element: &RosterElement) -> Provenance {
+    element.get_attached_elements().iter()
+        .map(get_provenance)
+        .fold(get_provenance(element), Provenance::max)
+}
+
+// Elements of the roster off the catalog, attachments included, once per
+// name whatever the number of copies.
+pub fn get_unofficial_elements(roster: &Roster) -> Vec<(RosterElement, Provenance)> {
+    let mut names: Vec<String> = Vec::new();
+    let mut unofficial = Vec::new();
+    for entry in &roster.elements {
+        for element in std::iter::once(entry.element.clone()).chain(entry.element.get_attached_elements()) {
+            let name = element.get_name();
+            if names.contains(&name) {
+                continue;
+            }
+            names.push(name);
+            let provenance = get_provenance(&element);
+            if !provenance.is_official() {
+                unofficial.push((element, provenance));
+            }
+        }
+    }
+    unofficial
+}
+
+// Elements of the roster that don't match the catalog of now, for a roster
+// built with another one (see Roster::has_catalog_changed). The old catalog
+// isn't kept, only its hash: homebrew elements can't be told apart from
+// the ones taken out of the catalog, and are left aside.
+pub fn get_catalog_changes(roster: &Roster) -> Vec<String> {
+    get_unofficial_elements(roster).into_iter()
+        .filter_map(|(element, provenance)| match provenance {
+            Provenance::HouseRuled {catalog_points} => Some(format!("{}: {} points in the roster, {} in the catalog now",
+                element.get_name(), element.get_name_and_points().1, catalog_points)),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,480 @@
+// QR codes of the share links, to show a roster from a phone at the table.
+// Byte mode only, which is all an url needs, at the medium error correction
+// level, or the low one when the roster is too long for it. The layout
+// follows ISO/IEC 18004: function patterns, interleaved Reed-Solomon blocks,
+// and the mask with the smallest penalty.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorCorrection {
+    Low,
+    Medium,
+}
+
+impl ErrorCorrection {
+    fn get_format_bits(&self) -> u32 {
+        match self {
+            ErrorCorrection::Low => 1,
+            ErrorCorrection::Medium => 0,
+        }
+    }
+
+    // Error correction codewords of each block, and number of blocks, by version.
+    fn get_block_layout(&self, version: usize) -> (usize, usize) {
+        match self {
+            ErrorCorrection::Low => (ECC_CODEWORDS_PER_BLOCK_LOW[version], ERROR_CORRECTION_BLOCKS_LOW[version]),
+            ErrorCorrection::Medium => (ECC_CODEWORDS_PER_BLOCK_MEDIUM[version], ERROR_CORRECTION_BLOCKS_MEDIUM[version]),
+        }
+    }
+}
+
+// Indexed by version, the first entry is unused.
+const ECC_CODEWORDS_PER_BLOCK_LOW: [usize; 41] = [0,
+    7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28,
+    28, 28, 30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30];
+const ECC_CODEWORDS_PER_BLOCK_MEDIUM: [usize; 41] = [0,
+    10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26,
+    26, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28];
+const ERROR_CORRECTION_BLOCKS_LOW: [usize; 41] = [0,
+    1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8,
+    8, 9, 9, 10, 12, 12, 12, 13, 14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25];
+const ERROR_CORRECTION_BLOCKS_MEDIUM: [usize; 41] = [0,
+    1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16,
+    17, 17, 18, 20, 21, 23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49];
+
+const MAX_VERSION: usize = 40;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QrCode {
+    pub size : usize,
+    // Row by row, true for dark
+    modules : Vec<bool>,
+    // Modules of the function patterns, left out of the data and the mask
+    is_function : Vec<bool>,
+}
+
+impl QrCode {
+    // None if the data doesn't fit in the largest version.
+    pub fn encode(data: &[u8]) -> Option<QrCode> {
+        [ErrorCorrection::Medium, ErrorCorrection::Low].into_iter()
+            .find_map(|ecc| (1..=MAX_VERSION).find(|version| get_data_capacity_bits(*version, ecc) >= get_data_bits(*version, data.len()))
+                .map(|version| QrCode::build(version, ecc, data)))
+    }
+
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    fn build(version: usize, ecc: ErrorCorrection, data: &[u8]) -> QrCode {
+        let mut qr = QrCode::draw_unmasked(version, ecc, data);
+        let mask = (0..8).min_by_key(|mask| {
+            qr.apply_mask(*mask);
+            qr.draw_format_bits(ecc, *mask);
+            let penalty = qr.get_penalty();
+            qr.apply_mask(*mask);
+            penalty
+        }).unwrap_or_default();
+        qr.apply_mask(mask);
+        qr.draw_format_bits(ecc, mask);
+        qr
+    }
+
+    // Function patterns and data, before any mask.
+    fn draw_unmasked(version: usize, ecc: ErrorCorrection, data: &[u8]) -> QrCode {
+        let size = version * 4 + 17;
+        let mut qr = QrCode {size, modules: vec![false; size * size], is_function: vec![false; size * size]};
+        qr.draw_function_patterns(version, ecc);
+        let codewords = add_error_correction(&get_data_codewords(version, ecc, data), version, ecc);
+        qr.draw_codewords(&codewords);
+        qr
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, is_dark: bool) {
+        self.modules[y * self.size + x] = is_dark;
+        self.is_function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self, version: usize, ecc: ErrorCorrection) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        for (x, y) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -4i32..=4 {
+                for dx in -4i32..=4 {
+                    let (xx, yy) = (x as i32 + dx, y as i32 + dy);
+                    if (0..size as i32).contains(&xx) && (0..size as i32).contains(&yy) {
+                        let distance = dx.abs().max(dy.abs());
+                        self.set_function(xx as usize, yy as usize, distance != 2 && distance != 4);
+                    }
+                }
+            }
+        }
+
+        // Alignment patterns everywhere but over the finder patterns
+        let positions = get_alignment_positions(version);
+        let last = positions.len().saturating_sub(1);
+        for (i, y) in positions.iter().enumerate() {
+            for (j, x) in positions.iter().enumerate() {
+                if (i == 0 && (j == 0 || j == last)) || (i == last && j == 0) {
+                    continue;
+                }
+                for dy in -2i32..=2 {
+                    for dx in -2i32..=2 {
+                        self.set_function((*x as i32 + dx) as usize, (*y as i32 + dy) as usize, dx.abs().max(dy.abs()) != 1);
+                    }
+                }
+            }
+        }
+
+        // Reserves the format areas, drawn for real once the mask is known
+        self.draw_format_bits(ecc, 0);
+        if version >= 7 {
+            let mut remainder = version as u32;
+            for _ in 0..12 {
+                remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1F25);
+            }
+            let bits = (version as u32) << 12 | remainder;
+            for i in 0..18 {
+                let is_dark = (bits >> i) & 1 != 0;
+                let (a, b) = (size - 11 + i % 3, i / 3);
+                self.set_function(a, b, is_dark);
+                self.set_function(b, a, is_dark);
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, ecc: ErrorCorrection, mask: u32) {
+        let data = ecc.get_format_bits() << 3 | mask;
+        let mut remainder = data;
+        for _ in 0..10 {
+            remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+        }
+        let bits = (data << 10 | remainder) ^ 0x5412;
+        let bit = |i: usize| (bits >> i) & 1 != 0;
+        let size = self.size;
+
+        for i in 0..6 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    // Two columns at a time from the right, going up then down, skipping
+    // the vertical timing pattern.
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let mut i = 0;
+        let mut right = size - 1;
+        while right >= 1 {
+            if right == 6 {
+                right = 5;
+            }
+            for vertical in 0..size {
+                for j in 0..2 {
+                    let x = right - j;
+                    let is_upward = (right + 1) & 2 == 0;
+                    let y = if is_upward { size - 1 - vertical } else { vertical };
+                    if !self.is_function[y * size + x] && i < codewords.len() * 8 {
+                        self.modules[y * size + x] = (codewords[i >> 3] >> (7 - (i & 7))) & 1 != 0;
+                        i += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    // Applying a mask twice undoes it.
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let is_inverted = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let index = y * self.size + x;
+                self.modules[index] ^= is_inverted && !self.is_function[index];
+            }
+        }
+    }
+
+    // Long runs, 2x2 blocks, patterns looking like the finders, and the
+    // imbalance between dark and light.
+    fn get_penalty(&self) -> u32 {
+        let size = self.size;
+        let mut penalty = 0;
+        let lines = (0..size).map(|y| (0..size).map(|x| self.is_dark(x, y)).collect::<Vec<bool>>())
+            .chain((0..size).map(|x| (0..size).map(|y| self.is_dark(x, y)).collect()));
+        for line in lines {
+            let mut run = 1;
+            for i in 1..=size {
+                if i < size && line[i] == line[i - 1] {
+                    run += 1;
+                    continue;
+                }
+                if run >= 5 {
+                    penalty += run - 2;
+                }
+                run = 1;
+            }
+            const FINDER_LIKE: [bool; 11] = [true, false, true, true, true, false, true, false, false, false, false];
+            penalty += 40 * line.windows(11)
+                .filter(|window| window.iter().eq(FINDER_LIKE.iter()) || window.iter().rev().eq(FINDER_LIKE.iter()))
+                .count() as u32;
+        }
+
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let color = self.is_dark(x, y);
+                if color == self.is_dark(x + 1, y) && color == self.is_dark(x, y + 1) && color == self.is_dark(x + 1, y + 1) {
+                    penalty += 3;
+                }
+            }
+        }
+
+        let total = (size * size) as i64;
+        let dark = self.modules.iter().filter(|module| **module).count() as i64;
+        let k = ((dark * 20 - total * 10).abs() + total - 1) / total - 1;
+        penalty + k as u32 * 10
+    }
+}
+
+fn get_alignment_positions(version: usize) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let count = version / 7 + 2;
+    let step = if version == 32 { 26 } else { (version * 4 + count * 2 + 1) / (count * 2 - 2) * 2 };
+    // Evenly spaced back from the last one, the first is always 6.
+    let last = version * 4 + 17 - 7;
+    std::iter::once(6).chain((0..count - 1).rev().map(|i| last - i * step)).collect()
+}
+
+// Modules left for the data and its error correction.
+fn get_raw_data_modules(version: usize) -> usize {
+    let mut modules = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignments = version / 7 + 2;
+        modules -= (25 * alignments - 10) * alignments - 55;
+        if version >= 7 {
+            modules -= 36;
+        }
+    }
+    modules
+}
+
+fn get_data_capacity_bits(version: usize, ecc: ErrorCorrection) -> usize {
+    let (block_ecc, blocks) = ecc.get_block_layout(version);
+    (get_raw_data_modules(version) / 8 - block_ecc * blocks) * 8
+}
+
+fn get_count_bits(version: usize) -> usize {
+    if version < 10 { 8 } else { 16 }
+}
+
+fn get_data_bits(version: usize, length: usize) -> usize {
+    if length >= 1 << get_count_bits(version) {
+        return usize::MAX;
+    }
+    4 + get_count_bits(version) + length * 8
+}
+
+// Mode, length and bytes, then the terminator and the padding.
+fn get_data_codewords(version: usize, ecc: ErrorCorrection, data: &[u8]) -> Vec<u8> {
+    let mut bits: Vec<bool> = Vec::new();
+    let mut push = |value: u32, count: usize| bits.extend((0..count).rev().map(|i| (value >> i) & 1 != 0));
+    push(0b0100, 4);
+    push(data.len() as u32, get_count_bits(version));
+    for byte in data {
+        push(*byte as u32, 8);
+    }
+
+    let capacity = get_data_capacity_bits(version, ecc);
+    let terminator = (capacity - bits.len()).min(4);
+    bits.extend(std::iter::repeat_n(false, terminator));
+    bits.extend(std::iter::repeat_n(false, (8 - bits.len() % 8) % 8));
+    let mut codewords: Vec<u8> = bits.chunks(8).map(|byte| byte.iter().fold(0, |value, bit| value << 1 | *bit as u8)).collect();
+    for pad in [0xEC, 0x11].into_iter().cycle() {
+        if codewords.len() * 8 >= capacity {
+            break;
+        }
+        codewords.push(pad);
+    }
+    codewords
+}
+
+// Splits the data in blocks, the short ones first, and interleaves them
+// with their error correction codewords.
+fn add_error_correction(data: &[u8], version: usize, ecc: ErrorCorrection) -> Vec<u8> {
+    let (block_ecc, block_count) = ecc.get_block_layout(version);
+    let raw_codewords = get_raw_data_modules(version) / 8;
+    let short_blocks = block_count - raw_codewords % block_count;
+    let short_block_length = raw_codewords / block_count;
+    let divisor = get_reed_solomon_divisor(block_ecc);
+
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    for i in 0..block_count {
+        let length = short_block_length - block_ecc + if i < short_blocks { 0 } else { 1 };
+        let mut block = data[start..start + length].to_vec();
+        start += length;
+        let remainder = get_reed_solomon_remainder(&block, &divisor);
+        if i < short_blocks {
+            block.push(0);
+        }
+        block.extend(remainder);
+        blocks.push(block);
+    }
+
+    let mut codewords = Vec::with_capacity(raw_codewords);
+    for i in 0..blocks[0].len() {
+        for (j, block) in blocks.iter().enumerate() {
+            // The padding byte of the short blocks is left out
+            if i != short_block_length - block_ecc || j >= short_blocks {
+                codewords.push(block[i]);
+            }
+        }
+    }
+    codewords
+}
+
+fn get_reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut divisor = vec![0u8; degree];
+    divisor[degree - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..degree {
+        for j in 0..degree {
+            divisor[j] = multiply(divisor[j], root);
+            if j + 1 < degree {
+                divisor[j] ^= divisor[j + 1];
+            }
+        }
+        root = multiply(root, 0x02);
+    }
+    divisor
+}
+
+fn get_reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut remainder = vec![0u8; divisor.len()];
+    for byte in data {
+        let factor = byte ^ remainder.remove(0);
+        remainder.push(0);
+        for (value, coefficient) in remainder.iter_mut().zip(divisor) {
+            *value ^= multiply(*coefficient, factor);
+        }
+    }
+    remainder
+}
+
+// Product in GF(2^8), modulo x^8 + x^4 + x^3 + x^2 + 1.
+fn multiply(x: u8, y: u8) -> u8 {
+    let mut product: u32 = 0;
+    for i in (0..8).rev() {
+        product = (product << 1) ^ ((product >> 7) * 0x11D);
+        product ^= ((y as u32 >> i) & 1) * x as u32;
+    }
+    product as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_rows(qr: &QrCode) -> Vec<String> {
+        (0..qr.size).map(|y| (0..qr.size).map(|x| if qr.is_dark(x, y) { '#' } else { '.' }).collect()).collect()
+    }
+
+    // The 1-M example of ISO/IEC 18004 (numeric "01234567"): its data
+    // codewords followed by their error correction.
+    #[test]
+    fn error_correction_of_the_standard_example() {
+        let data = [0x10, 0x20, 0x0C, 0x56, 0x61, 0x80, 0xEC, 0x11, 0xEC, 0x11, 0xEC, 0x11, 0xEC, 0x11, 0xEC, 0x11];
+        let error_correction = [0xA5, 0x24, 0xD4, 0xC1, 0xED, 0x36, 0xC7, 0x87, 0x2C, 0x55];
+        assert_eq!(add_error_correction(&data, 1, ErrorCorrection::Medium), [&data[..], &error_correction[..]].concat());
+    }
+
+    #[test]
+    fn data_codewords_in_byte_mode() {
+        let mut expected = vec![0x40, 0x14, 0x10];
+        expected.extend([0xEC, 0x11].iter().cycle().take(13));
+        assert_eq!(get_data_codewords(1, ErrorCorrection::Medium, b"A"), expected);
+    }
+
+    // As drawn by another encoder with the same mask, 2-M.
+    #[test]
+    fn modules_match_a_reference() {
+        let reference = [
+        "#######.##.##.#...#######",
+        "#.....#..#.#..#...#.....#",
+        "#.###.#.##.#.####.#.###.#",
+        "#.###.#...#..##.#.#.###.#",
+        "#.###.#...#####...#.###.#",
+        "#.....#.##....#.#.#.....#",
+        "#######.#.#.#.#.#.#######",
+        ".........#.##.#.#........",
+        "#.#...##..#...#.#..#..#.#",
+        ".......##..###.#####.#.##",
+        ".##.###..#.#...#.###.##.#",
+        "#.###...#.#.#.#..###.#...",
+        "...#.##.#..#..###.##....#",
+        ".###...........##.##...##",
+        "###.#.####.#..###.#..##.#",
+        ".....#.#.#....#....###...",
+        "####.##....##...#####..#.",
+        "........#...#.###...#...#",
+        "#######.#.#.#.#.#.#.#...#",
+        "#.....#..####..##...#....",
+        "#.###.#.....#.#######..#.",
+        "#.###.#..##..###....#.##.",
+        "#.###.#.##.#.#..##.###.##",
+        "#.....#...#...#.#####....",
+        "#######.##.#..#.##...#..#",
+        ];
+        let mut qr = QrCode::draw_unmasked(2, ErrorCorrection::Medium, b"https://fsd.example/#r=1");
+        qr.apply_mask(1);
+        qr.draw_format_bits(ErrorCorrection::Medium, 1);
+        assert_eq!(get_rows(&qr), reference);
+    }
+
+    // Largest data of a version, and one byte more.
+    #[test]
+    fn versions_and_levels_by_length() {
+        let size = |length: usize| QrCode::encode(&vec![b'a'; length]).map(|qr| qr.size);
+        // 1-M, then 2-M
+        assert_eq!(size(14), Some(21));
+        assert_eq!(size(15), Some(25));
+        // 9-M, then 10-M and its longer length field
+        assert_eq!(size(180), Some(53));
+        assert_eq!(size(181), Some(57));
+        // 40-M, then 36-L
+        assert_eq!(size(2331), Some(177));
+        assert_eq!(size(2332), Some(161));
+        // 40-L, then nothing
+        assert_eq!(size(2953), Some(177));
+        assert_eq!(size(2954), None);
+    }
+}
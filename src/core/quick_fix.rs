@@ -0,0 +1,51 @@
+// One-click remedies for the rules the roster breaks (core::rules) and the
+// suggestions of core::lint. Fixes are commands like any other edit
+// (core::commands), so they go through the history and can be undone.
+use crate::core::commands::RosterCommand;
+use crate::core::roster::{Roster, RosterElement};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum QuickFix {
+    RemoveEntry(usize),
+    RenameEntry(usize, String),
+    // Shows the icon of the element instead of its image
+    ClearImage(usize),
+}
+
+impl QuickFix {
+    pub fn get_label(&self, roster: &Roster) -> String {
+        let name = |index: &usize| roster.elements.get(*index).map(|entry| entry.element.get_name()).unwrap_or_default();
+        match self {
+            QuickFix::RemoveEntry(index) => format!("Remove {}", name(index)),
+            QuickFix::RenameEntry(_, new_name) => format!("Rename to {}", new_name),
+            QuickFix::ClearImage(_) => "Use Icon".to_string(),
+        }
+    }
+}
+
+fn get_image_mut(element: &mut RosterElement) -> Option<&mut String> {
+    match element {
+        RosterElement::ElemUnit(unit) => Some(&mut unit.image),
+        RosterElement::ElemOther(other) => Some(&mut other.3),
+        _ => None,
+    }
+}
+
+impl QuickFix {
+    // None if the roster changed since the fix was worked out.
+    pub fn get_command(&self, roster: &Roster) -> Option<RosterCommand> {
+        match self {
+            QuickFix::RemoveEntry(index) if *index < roster.elements.len() => Some(RosterCommand::RemoveEntry(*index)),
+            QuickFix::RenameEntry(index, new_name) => {
+                let points = roster.elements.get(*index)?.element.get_name_and_points().1;
+                Some(RosterCommand::SetNameAndPoints(*index, new_name.clone(), points))
+            }
+            QuickFix::ClearImage(index) => {
+                let mut entry = roster.elements.get(*index)?.clone();
+                get_image_mut(&mut entry.element)?.clear();
+                Some(RosterCommand::ReplaceEntry(*index, entry))
+            }
+            _ => None,
+        }
+    }
+}
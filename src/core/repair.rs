@@ -0,0 +1,100 @@
+// Checks and repairs of rosters coming from storage or files, for the ones
+// that can't be trusted any more: entries that no longer match the format,
+// broken invariants, points that don't match the catalog.
+use serde_json::Value;
+
+use crate::core::armylist::get_catalog_points;
+use crate::core::roster::{Roster, RosterElement, RosterEntry};
+
+fn get_points_mut(element: &mut RosterElement) -> &mut u32 {
+    match element {
+        RosterElement::ElemCharacter(character) => &mut character.points,
+        RosterElement::ElemUnit(unit) => &mut unit.points,
+        RosterElement::ElemSupport(support) => &mut support.points,
+        RosterElement::ElemOther(other) => &mut other.1,
+    }
+}
+
+// Elements of the entry with their attachments, flattened.
+fn for_each_element(entry: &mut RosterEntry, mut action: impl FnMut(&mut RosterElement)) {
+    action(&mut entry.element);
+    if let RosterElement::ElemUnit(unit) = &mut entry.element {
+        unit.attached_elements.iter_mut().for_each(action);
+    }
+}
+
+// Whatever the roster can be blamed for, as messages for the player.
+pub fn find_problems(roster: &Roster) -> Vec<String> {
+    let mut problems = roster.check_invariants().err().unwrap_or_default();
+    let mut roster = roster.clone();
+    for entry in &mut roster.elements {
+        for_each_element(entry, |element| {
+            let (name, points) = element.get_name_and_points();
+            if let Some(catalog_points) = get_catalog_points(&name).filter(|catalog_points| *catalog_points != points) {
+                problems.push(format!("{} costs {} points, {} in the catalog", name, points, catalog_points));
+            }
+        });
+    }
+    problems
+}
+
+// Reads what can be read of a roster that doesn't load as a whole: the
+// entries that can't be read are left out, and listed.
+pub fn salvage_roster(value: &Value) -> (Roster, Vec<String>) {
+    let mut roster = Roster::new();
+    let mut problems = Vec::<String>::new();
+    if let Some(name) = value.pointer("/metadata/name").and_then(Value::as_str) {
+        roster.metadata.name = name.to_string();
+    }
+    let Some(elements) = value.get("elements").and_then(Value::as_array) else {
+        problems.push("No entries could be found".to_string());
+        return (roster, problems);
+    };
+    for (index, element) in elements.iter().enumerate() {
+        match serde_json::from_value::<RosterEntry>(element.clone()) {
+            Ok(entry) => roster.elements.push(entry),
+            Err(e) => problems.push(format!("Entry {} couldn't be read and was left out ({})", index, e)),
+        }
+    }
+    (roster, problems)
+}
+
+// Drops the attachments breaking the invariants: anything but a character,
+// characters past the first one, attachments of attachments. Returns how
+// many were dropped.
+pub fn drop_bad_elements(roster: &mut Roster) -> usize {
+    let mut dropped = 0;
+    for entry in &mut roster.elements {
+        let RosterElement::ElemUnit(unit) = &mut entry.element else {
+            continue;
+        };
+        let count = unit.attached_elements.len();
+        let mut has_character = false;
+        unit.attached_elements.retain(|attached| {
+            let is_valid = matches!(attached, RosterElement::ElemCharacter(_))
+                && !has_character
+                && attached.get_attached_elements().is_empty();
+            has_character |= is_valid;
+            is_valid
+        });
+        dropped += count - unit.attached_elements.len();
+    }
+    dropped
+}
+
+// Sets the points of every catalog element back to the catalog ones.
+// Returns how many were changed.
+pub fn reset_points(roster: &mut Roster) -> usize {
+    let mut changed = 0;
+    for entry in &mut roster.elements {
+        for_each_element(entry, |element| {
+            let name = element.get_name();
+            let points = get_points_mut(element);
+            if let Some(catalog_points) = get_catalog_points(&name).filter(|catalog_points| catalog_points != points) {
+                *points = catalog_points;
+                changed += 1;
+            }
+        });
+    }
+    changed
+}
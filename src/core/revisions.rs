@@ -0,0 +1,66 @@
+// Versions of a roster kept with it for good, unlike the history of the
+// session (core::history): named by the player before a change worth going
+// back from, or taken automatically, the roster as it was left each day.
+// They live in the roster slot, see core::roster_slots.
+use serde::{Serialize, Deserialize};
+
+use crate::core::roster::Roster;
+
+// Oldest automatic revisions are dropped past this, named ones are kept.
+const MAX_DAILY_REVISIONS: usize = 30;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Revision {
+    pub name : String,
+    // Date and time, as "YYYY-MM-DD HH:MM"
+    pub taken : String,
+    pub roster : Roster,
+    #[serde(default)]
+    pub is_daily : bool,
+}
+
+// Oldest first.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Revisions {
+    pub revisions : Vec<Revision>,
+}
+
+impl Revisions {
+    pub fn is_empty(&self) -> bool {
+        self.revisions.is_empty()
+    }
+
+    pub fn add(&mut self, name: &str, roster: &Roster, taken: &str) {
+        self.revisions.push(Revision {name: name.to_string(), taken: taken.to_string(), roster: roster.clone(), is_daily: false});
+    }
+
+    // The roster as it was last changed on an earlier day than now, once per
+    // day. Returns whether it was kept.
+    pub fn add_daily(&mut self, roster: &Roster, modified: &str, now: &str) -> bool {
+        let day = get_day(modified);
+        if day.is_empty() || day == get_day(now) || roster.elements.is_empty()
+            || self.revisions.iter().any(|revision| revision.is_daily && get_day(&revision.taken) == day) {
+            return false;
+        }
+        self.revisions.push(Revision {name: format!("End of {}", day), taken: modified.to_string(), roster: roster.clone(), is_daily: true});
+        self.revisions.sort_by(|a, b| a.taken.cmp(&b.taken));
+        if self.revisions.iter().filter(|revision| revision.is_daily).count() > MAX_DAILY_REVISIONS {
+            if let Some(oldest) = self.revisions.iter().position(|revision| revision.is_daily) {
+                self.revisions.remove(oldest);
+            }
+        }
+        true
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.revisions.len() {
+            self.revisions.remove(index);
+        }
+    }
+}
+
+// "2026-05-01" of "2026-05-01 18:00".
+fn get_day(taken: &str) -> &str {
+    taken.split(' ').next().unwrap_or_default()
+}
@@ -0,0 +1,570 @@
+use crate::core::character::Character;
+use crate::core::unit::Unit;
+use crate::core::support::Support;
+use crate::core::campaign::CampaignRecord;
+use crate::core::scenario::Scenario;
+use crate::core::print_options::PrintOptions;
+use crate::core::canonical;
+use crate::core::armylist::get_catalog_hash;
+use crate::core::migration::{RosterFile, CURRENT_ROSTER_VERSION};
+use crate::core::rules::Suppression;
+use crate::core::sync::SyncRecord;
+use crate::core::ids;
+use crate::core::breakdown::{self, Grouping};
+use std::collections::HashSet;
+
+// For serialization
+use serde::{Serialize, Deserialize, Deserializer};
+use serde_json;
+
+
+#[allow(clippy::enum_variant_names)] // The variant names are part of the saved roster format.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RosterElement {
+    ElemCharacter(Character),
+    ElemUnit(Unit),
+    ElemSupport(Support),
+    ElemOther((String, u32, Vec<String>, String)),
+}
+
+impl From<Character> for RosterElement {
+    fn from(character: Character) -> Self {
+        RosterElement::ElemCharacter(character)
+    }
+}
+
+impl From<Unit> for RosterElement {
+    fn from(unit: Unit) -> Self {
+        RosterElement::ElemUnit(unit)
+    }
+}
+
+impl From<Support> for RosterElement {
+    fn from(support: Support) -> Self {
+        RosterElement::ElemSupport(support)
+    }
+}
+
+// Role of each piece of a grouped entry, see RosterElement::compose.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompositionRole {
+    Leader,
+    Squad,
+    Upgrade,
+}
+
+impl CompositionRole {
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            CompositionRole::Leader => "Leader",
+            CompositionRole::Squad => "Squad",
+            CompositionRole::Upgrade => "Upgrade",
+        }
+    }
+}
+
+impl RosterElement {
+    pub fn get_name_and_points(&self) -> (String, u32) {
+        match self {
+            RosterElement::ElemCharacter(elem) => {(elem.name.clone(), elem.points)}
+            RosterElement::ElemUnit(elem) => {(elem.name.clone(), elem.points)}
+            RosterElement::ElemSupport(elem) => {(elem.name.clone(), elem.points)}
+            RosterElement::ElemOther(elem) => {(elem.0.clone(), elem.1)}
+        }
+    }
+
+    pub fn get_name(&self) -> String {
+        self.get_name_and_points().0
+    }
+
+    // The element's own name and points, its attachments keep theirs. An
+    // element edited that way is a house rule or homebrew, see
+    // core::provenance.
+    pub fn set_name_and_points(&mut self, name: &str, points: u32) {
+        match self {
+            RosterElement::ElemCharacter(elem) => (elem.name, elem.points) = (name.to_string(), points),
+            RosterElement::ElemUnit(elem) => (elem.name, elem.points) = (name.to_string(), points),
+            RosterElement::ElemSupport(elem) => (elem.name, elem.points) = (name.to_string(), points),
+            RosterElement::ElemOther(elem) => (elem.0, elem.1) = (name.to_string(), points),
+        }
+    }
+
+    // Points of the element, including whatever is attached to it.
+    pub fn get_total_points(&self) -> u32 {
+        match self {
+            RosterElement::ElemUnit(unit) => {
+                unit.attached_elements.iter().map(|elem| elem.get_name_and_points().1).sum::<u32>() + unit.points}
+            _ => self.get_name_and_points().1,
+        }
+    }
+
+    // Image path, relative to the static images folder.
+    pub fn get_image(&self) -> String {
+        match self {
+            RosterElement::ElemCharacter(_) => "character.png".to_string(),
+            RosterElement::ElemUnit(unit) => unit.image.clone(),
+            RosterElement::ElemSupport(_) => "support.png".to_string(),
+            RosterElement::ElemOther((_, _, _, image)) => image.clone(),
+        }
+    }
+
+    // Human readable category, used as a label on the cards.
+    pub fn get_type_name(&self) -> &'static str {
+        match self {
+            RosterElement::ElemCharacter(_) => "Character",
+            RosterElement::ElemUnit(_) => "Unit",
+            RosterElement::ElemSupport(_) => "Support",
+            RosterElement::ElemOther(_) => "Other",
+        }
+    }
+
+    pub fn get_attached_elements(&self) -> Vec<RosterElement> {
+        match self {
+            RosterElement::ElemUnit(unit) => unit.attached_elements.clone(),
+            _ => Vec::<RosterElement>::new(),
+        }
+    }
+
+    // Breaks a grouped entry down into its pieces: attached characters lead
+    // the squad, anything else attached counts as an upgrade. Entries without
+    // attachments are a single squad piece.
+    pub fn compose(&self) -> Vec<(CompositionRole, RosterElement)> {
+        let attached = self.get_attached_elements();
+        let mut parts: Vec<(CompositionRole, RosterElement)> = attached.iter()
+            .filter(|elem| matches!(elem, RosterElement::ElemCharacter(_)))
+            .map(|elem| (CompositionRole::Leader, elem.clone()))
+            .collect();
+
+        // The squad alone, without the attachments already listed.
+        let mut squad = self.clone();
+        if let RosterElement::ElemUnit(unit) = &mut squad {
+            unit.attached_elements.clear();
+        }
+        parts.push((CompositionRole::Squad, squad));
+
+        parts.extend(attached.iter()
+            .filter(|elem| !matches!(elem, RosterElement::ElemCharacter(_)))
+            .map(|elem| (CompositionRole::Upgrade, elem.clone())));
+        parts
+    }
+}
+
+
+// Half of the roster an entry is deployed with, see the deployment board.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Wave {
+    #[default]
+    First,
+    Second,
+}
+
+impl Wave {
+    pub const ALL: [Wave; 2] = [Wave::First, Wave::Second];
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Wave::First => "First Wave",
+            Wave::Second => "Second Wave",
+        }
+    }
+
+    pub fn get_short_label(&self) -> &'static str {
+        match self {
+            Wave::First => "1st",
+            Wave::Second => "2nd",
+        }
+    }
+
+    pub fn is_first(&self) -> bool {
+        *self == Wave::First
+    }
+}
+
+// Most copies a single entry holds, more is a broken or hand edited file.
+pub const MAX_QUANTITY: u32 = 99;
+
+// One entry of the roster: the element itself plus everything the player
+// tracks about that specific copy. The element is flattened so that rosters
+// saved before entries existed still load.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RosterEntry {
+    // Stays with the entry wherever it's moved, see core::ids. Rosters saved
+    // before entries had one get a new one when loaded.
+    #[serde(default = "ids::new_id")]
+    pub id : String,
+    #[serde(flatten)]
+    pub element : RosterElement,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub campaign : Option<CampaignRecord>,
+    #[serde(default, skip_serializing_if = "Wave::is_first")]
+    pub wave : Wave,
+    // Identical copies taken as one entry, e.g. three of the same unit
+    #[serde(default = "RosterEntry::get_single_quantity", skip_serializing_if = "RosterEntry::is_single", deserialize_with = "RosterEntry::deserialize_quantity")]
+    pub quantity : u32,
+    // Free text of the player, e.g. a loadout reminder or a model standing in
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note : String,
+}
+
+impl From<RosterElement> for RosterEntry {
+    fn from(element: RosterElement) -> Self {
+        RosterEntry {id: ids::new_id(), element, campaign: None, wave: Wave::First, quantity: 1, note: String::new()}
+    }
+}
+
+impl RosterEntry {
+    fn get_single_quantity() -> u32 {
+        1
+    }
+
+    fn is_single(quantity: &u32) -> bool {
+        *quantity == 1
+    }
+
+    // Brought back between 1 and MAX_QUANTITY, whatever the file says.
+    fn deserialize_quantity<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+        u32::deserialize(deserializer).map(|quantity| quantity.clamp(1, MAX_QUANTITY))
+    }
+
+    // Points of all the copies together, at most u32::MAX: check_invariants
+    // tells about the overflow.
+    pub fn get_total_points(&self) -> u32 {
+        self.element.get_total_points().saturating_mul(self.quantity)
+    }
+
+    // The same entry under a new id, e.g. for a duplicate of it.
+    pub fn renewed(&self) -> RosterEntry {
+        RosterEntry {id: ids::new_id(), ..self.clone()}
+    }
+
+    // The name with the number of copies in front, e.g. "3x Tachi Tank".
+    pub fn get_counted_name(&self) -> String {
+        match self.quantity {
+            1 => self.element.get_name(),
+            quantity => format!("{}x {}", quantity, self.element.get_name()),
+        }
+    }
+}
+
+// Points allowed in a standard game.
+pub const DEFAULT_POINTS_LIMIT: u32 = 60;
+// Usual game sizes, any other limit can be typed in
+pub const POINTS_LIMIT_PRESETS: [u32; 3] = [45, 60, 100];
+
+// Everything about the roster that isn't part of the list itself.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RosterMetadata {
+    // Stays with the roster through its slot, its files and its history,
+    // see core::ids. Rosters saved before they had one get a new one when
+    // loaded.
+    pub id : String,
+    pub name : String,
+    // Game size the roster is built for, the standard one unless changed
+    #[serde(skip_serializing_if = "RosterMetadata::is_default_points_limit")]
+    pub points_limit : u32,
+    pub print_options : PrintOptions,
+    // Point milestones of an escalation league, see core::escalation
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub escalation_milestones : Vec<u32>,
+    // Rules set aside for this roster, see core::rules
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub suppressions : Vec<Suppression>,
+    // Catalog the roster was built with, see armylist::get_catalog_hash.
+    // Empty for the rosters saved before it was kept.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub catalog_hash : String,
+    // Copy of the roster on a sync server, see core::sync
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sync : Option<SyncRecord>,
+}
+
+impl Default for RosterMetadata {
+    fn default() -> Self {
+        RosterMetadata {
+            id: ids::new_id(),
+            name: String::new(),
+            points_limit: DEFAULT_POINTS_LIMIT,
+            print_options: PrintOptions::default(),
+            escalation_milestones: Vec::new(),
+            suppressions: Vec::new(),
+            catalog_hash: String::new(),
+            sync: None,
+        }
+    }
+}
+
+impl RosterMetadata {
+    fn is_default_points_limit(points_limit: &u32) -> bool {
+        *points_limit == DEFAULT_POINTS_LIMIT
+    }
+}
+
+// Read through core::migration, whatever version it was saved with.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RosterFile")]
+pub struct Roster {
+    pub version : u32,
+    pub elements : Vec<RosterEntry>,
+    // Mission chosen for the next game
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scenario : Option<Scenario>,
+    #[serde(default)]
+    pub metadata : RosterMetadata,
+}
+
+impl Default for Roster {
+    fn default() -> Self {
+        Roster::new()
+    }
+}
+
+impl Roster {
+    pub fn new() -> Roster {
+        let metadata = RosterMetadata {catalog_hash: get_catalog_hash(), ..RosterMetadata::default()};
+        Roster {elements: Vec::<RosterEntry>::new(), version: CURRENT_ROSTER_VERSION, scenario: None, metadata}
+    }
+
+    // A cleared roster is built anew, with the catalog of now.
+    pub fn clear(&mut self) {
+        self.elements.clear();
+        self.pin_catalog();
+    }
+
+    // Built with another catalog than this one, as far as it's known.
+    pub fn has_catalog_changed(&self) -> bool {
+        !self.metadata.catalog_hash.is_empty() && self.metadata.catalog_hash != get_catalog_hash()
+    }
+
+    pub fn pin_catalog(&mut self) {
+        self.metadata.catalog_hash = get_catalog_hash();
+    }
+
+    pub fn add_element(&mut self, element: RosterElement) {
+        self.add_entry(element.into());
+    }
+
+    pub fn add_entry(&mut self, entry: RosterEntry) {
+        self.elements.push(entry);
+    }
+
+    pub fn get_total_points(&self) -> u32 {
+        self.elements.iter().map(RosterEntry::get_total_points).fold(0, u32::saturating_add)
+    }
+
+    // Every copy of an entry activates, and deploys, on its own.
+    pub fn get_activation_count(&self) -> u32 {
+        self.elements.iter().map(|entry| entry.quantity).sum()
+    }
+
+    // Copies of each entry, by index.
+    pub fn get_quantities(&self) -> Vec<u32> {
+        self.elements.iter().map(|entry| entry.quantity).collect()
+    }
+
+    // The entry taken out and put back at the index, the ones in between
+    // shift by one. False if either index is out of the roster.
+    pub fn move_entry(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.elements.len() || to >= self.elements.len() || from == to {
+            return false;
+        }
+        let entry = self.elements.remove(from);
+        self.elements.insert(to, entry);
+        true
+    }
+
+    // Other entries of the same catalog element, e.g. the other copies of a
+    // unit, whatever is attached to them or their campaign records.
+    pub fn get_copies(&self, index: usize) -> Vec<usize> {
+        let Some(entry) = self.elements.get(index) else {
+            return Vec::new();
+        };
+        let is_copy = |other: &RosterEntry| other.element.get_type_name() == entry.element.get_type_name()
+            && other.element.get_name() == entry.element.get_name();
+        self.elements.iter().enumerate()
+            .filter(|(other_index, other)| *other_index != index && is_copy(other))
+            .map(|(other_index, _)| other_index)
+            .collect()
+    }
+
+    pub fn is_over_points_limit(&self, points_limit: u32) -> bool {
+        self.get_total_points() > points_limit
+    }
+
+    pub fn get_points_limit(&self) -> u32 {
+        self.metadata.points_limit
+    }
+
+    // Rules any roster must follow whatever the edits made to it, as a list
+    // of the broken ones. Checked after every change in debug builds.
+    pub fn check_invariants(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::<String>::new();
+        if self.version != CURRENT_ROSTER_VERSION {
+            errors.push(format!("Unknown roster version {}", self.version));
+        }
+
+        // Totals are unsigned, they can only go wrong by overflowing.
+        let total = self.elements.iter().try_fold(0u32, |total, entry| {
+            let points = match &entry.element {
+                RosterElement::ElemUnit(unit) => unit.attached_elements.iter()
+                    .try_fold(unit.points, |points, attached| points.checked_add(attached.get_name_and_points().1)),
+                element => Some(element.get_name_and_points().1),
+            };
+            points.and_then(|points| points.checked_mul(entry.quantity)).and_then(|points| total.checked_add(points))
+        });
+        if total.is_none() {
+            errors.push("Total points overflow".to_string());
+        }
+
+        // Ids are how an entry is found again after moves, see core::ids.
+        let mut ids = HashSet::new();
+        for (index, entry) in self.elements.iter().enumerate() {
+            if entry.id.is_empty() {
+                errors.push(format!("Entry {} ({}) has no id", index, entry.element.get_name()));
+            } else if !ids.insert(&entry.id) {
+                errors.push(format!("Entry {} ({}) has the id of another entry", index, entry.element.get_name()));
+            }
+        }
+
+        // Every entry counts in one group of each grouping, and only one, or
+        // the subtotals of core::breakdown don't add up to the roster.
+        for grouping in [Grouping::Type, Grouping::Wave] {
+            let groups = grouping.get_groups();
+            for (index, entry) in self.elements.iter().enumerate() {
+                let group = grouping.get_group(entry);
+                if !groups.contains(&group) {
+                    errors.push(format!("Entry {} ({}) is in the unknown group {}", index, entry.element.get_name(), group));
+                }
+            }
+            // Subtotals can't be summed past an overflow, reported above.
+            if total.is_none() {
+                continue;
+            }
+            let subtotals = breakdown::get_breakdown(self, grouping, None);
+            let counted: usize = subtotals.iter().map(|subtotal| subtotal.entries).sum();
+            if counted != self.elements.len() {
+                errors.push(format!("{} entries counted {}, out of {}", counted, grouping.get_label().to_lowercase(), self.elements.len()));
+            }
+            let points = subtotals.iter().try_fold(0u32, |points, subtotal| points.checked_add(subtotal.points));
+            if points != total {
+                errors.push(format!("Points counted {} don't add up to the total", grouping.get_label().to_lowercase()));
+            }
+        }
+
+        for (index, entry) in self.elements.iter().enumerate() {
+            if entry.quantity == 0 {
+                errors.push(format!("Entry {} ({}) has no copies", index, entry.element.get_name()));
+            }
+            if entry.quantity > MAX_QUANTITY {
+                errors.push(format!("Entry {} ({}) has {} copies, at most {} allowed", index, entry.element.get_name(), entry.quantity, MAX_QUANTITY));
+            }
+            let RosterElement::ElemUnit(unit) = &entry.element else {
+                continue;
+            };
+            // Only characters join a unit, one at most, and they can't lead
+            // a group of their own.
+            let characters = unit.attached_elements.iter()
+                .filter(|attached| matches!(attached, RosterElement::ElemCharacter(_)))
+                .count();
+            if characters > 1 {
+                errors.push(format!("Entry {} ({}) has {} characters attached", index, unit.name, characters));
+            }
+            for attached in &unit.attached_elements {
+                if !matches!(attached, RosterElement::ElemCharacter(_)) {
+                    errors.push(format!("Entry {} ({}) has a {} attached", index, unit.name, attached.get_type_name()));
+                }
+                if !attached.get_attached_elements().is_empty() {
+                    errors.push(format!("Entry {} ({}) has nested attachments", index, unit.name));
+                }
+            }
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    // JSON serialization (static methods):
+    // Older versions are migrated, newer ones refused, see core::migration.
+    pub fn from_json(json_str: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json_str)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    // The same list with the same settings, whatever the ids of the roster
+    // and its entries, the catalog it was built with and where it's synced:
+    // what a share link carries, see core::binary.
+    pub fn is_same_list(&self, other: &Roster) -> bool {
+        let shared = |roster: &Roster| {
+            let mut roster = roster.clone();
+            roster.metadata.id.clear();
+            roster.elements.iter_mut().for_each(|entry| entry.id.clear());
+            roster.metadata.catalog_hash.clear();
+            roster.metadata.sync = None;
+            roster
+        };
+        shared(self) == shared(other)
+    }
+
+    // Index of the entry with the given id, wherever it was moved.
+    pub fn find_entry(&self, id: &str) -> Option<usize> {
+        self.elements.iter().position(|entry| entry.id == id)
+    }
+
+    // For comparing rosters, see core::canonical.
+    pub fn to_canonical_json(&self) -> Result<String, serde_json::Error> {
+        canonical::to_canonical_json(self)
+    }
+
+    pub fn get_content_hash(&self) -> Result<String, serde_json::Error> {
+        Ok(canonical::content_hash(&self.to_canonical_json()?))
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures::{RosterFixture, FIXTURES};
+
+    #[test]
+    fn fixtures_keep_the_invariants() {
+        for (name, build) in FIXTURES {
+            assert_eq!(build().check_invariants(), Ok(()), "{}", name);
+        }
+    }
+
+    #[test]
+    fn total_counts_every_copy() {
+        let mut roster = RosterFixture::skirmish_30();
+        let before = roster.get_total_points();
+        let points = roster.elements[0].element.get_total_points();
+        roster.elements[0].quantity = 3;
+        assert_eq!(roster.get_total_points(), before + 2 * points);
+        assert_eq!(roster.get_activation_count(), roster.elements.len() as u32 + 2);
+    }
+
+    #[test]
+    fn duplicate_ids_break_the_invariants() {
+        let mut roster = RosterFixture::standard_60();
+        let copy = roster.elements[0].clone();
+        roster.add_entry(copy);
+        assert!(roster.check_invariants().is_err());
+    }
+
+    #[test]
+    fn moved_entries_are_found_by_id() {
+        let mut roster = RosterFixture::standard_60();
+        let id = roster.elements[0].id.clone();
+        let last = roster.elements.len() - 1;
+        assert!(roster.move_entry(0, last));
+        assert_eq!(roster.find_entry(&id), Some(last));
+        assert!(!roster.move_entry(0, last + 1));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let roster = RosterFixture::unit_copies();
+        let json = roster.to_json().unwrap();
+        assert_eq!(Roster::from_json(&json).unwrap(), roster);
+    }
+}
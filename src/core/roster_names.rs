@@ -0,0 +1,48 @@
+// Default names of the rosters, themed after the faction they belong to,
+// e.g. "Iron Vanguard - Tech 60 - 2026-10-15".
+use crate::core::armylist::Faction;
+use crate::core::roster::Roster;
+
+const ADJECTIVES: [&str; 12] = [
+    "Iron", "Crimson", "Silent", "Burning", "Hollow", "Shattered",
+    "Steel", "Last", "Grim", "Restless", "Broken", "Distant",
+];
+
+// Each faction gets its own nouns, falling back to generic ones.
+fn get_nouns(faction: Option<Faction>) -> &'static [&'static str] {
+    match faction {
+        Some(Faction::Tech) => &["Protocol", "Algorithm", "Array", "Lattice", "Uplink", "Singularity"],
+        Some(Faction::Enlisted) => &["Vanguard", "Battalion", "Brigade", "Regiment", "Spearhead", "Garrison"],
+        Some(Faction::Conglomerate) => &["Acquisition", "Directive", "Portfolio", "Merger", "Contract", "Dividend"],
+        Some(Faction::Union) => &["Shift", "Picket", "Local", "Crew", "Walkout", "Assembly"],
+        None => &["Task Force", "Detachment", "Company", "Column", "Strike Group", "Expedition"],
+    }
+}
+
+// The faction most of the roster comes from, None if nothing is recognized.
+pub fn get_main_faction(roster: &Roster) -> Option<Faction> {
+    let mut counts = [0usize; Faction::ALL.len()];
+    for entry in &roster.elements {
+        if let Some(faction) = Faction::find_by_element_name(&entry.element.get_name()) {
+            if let Some(index) = Faction::ALL.iter().position(|candidate| *candidate == faction) {
+                counts[index] += 1;
+            }
+        }
+    }
+    let (index, count) = counts.iter().enumerate().max_by_key(|(_, count)| **count)?;
+    if *count == 0 { None } else { Some(Faction::ALL[index]) }
+}
+
+// A name for the roster. The roll picks the words, so rerolling is only a
+// matter of passing a different number.
+pub fn generate_roster_name(roster: &Roster, points_limit: u32, date: &str, roll: u32) -> String {
+    let faction = get_main_faction(roster);
+    let nouns = get_nouns(faction);
+    let adjective = ADJECTIVES[roll as usize % ADJECTIVES.len()];
+    let noun = nouns[(roll as usize / ADJECTIVES.len()) % nouns.len()];
+    let format = match faction {
+        Some(faction) => format!("{:?} {}", faction, points_limit),
+        None => format!("{} pts", points_limit),
+    };
+    format!("{} {} - {} - {}", adjective, noun, format, date)
+}
@@ -0,0 +1,178 @@
+// Several rosters kept side by side, one of them being edited at a time.
+// Slots are referenced by an id of their own (see core::ids), which stays
+// the same when others are deleted. The library is kept in the browser, see
+// models::storage.
+use std::collections::HashMap;
+
+use serde::{Serialize, Deserialize};
+
+use crate::core::ids;
+use crate::core::roster::Roster;
+use crate::core::revisions::Revisions;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RosterSlot {
+    pub id : String,
+    pub roster : Roster,
+    // Date and time of the last change, as "YYYY-MM-DD HH:MM"
+    pub modified : String,
+    // Versions kept by name or by day, see core::revisions
+    #[serde(default, skip_serializing_if = "Revisions::is_empty")]
+    pub revisions : Revisions,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, from = "RosterSlotsFile")]
+pub struct RosterSlots {
+    pub slots : Vec<RosterSlot>,
+    pub active_id : String,
+    // The slot whose roster is linked to the running campaign, if any
+    pub campaign_slot_id : Option<String>,
+    // The slots whose rosters make up the entry to the event, when it asks
+    // for several lists
+    pub event_entry_ids : Vec<String>,
+}
+
+impl RosterSlots {
+    pub fn add(&mut self, roster: Roster, modified: &str) -> String {
+        let id = ids::new_id();
+        self.slots.push(RosterSlot {id: id.clone(), roster, modified: modified.to_string(), revisions: Revisions::default()});
+        id
+    }
+
+    pub fn find(&self, id: &str) -> Option<&RosterSlot> {
+        self.slots.iter().find(|slot| slot.id == id)
+    }
+
+    pub fn find_mut(&mut self, id: &str) -> Option<&mut RosterSlot> {
+        self.slots.iter_mut().find(|slot| slot.id == id)
+    }
+
+    pub fn is_active(&self, id: &str) -> bool {
+        self.active_id == id && self.find(id).is_some()
+    }
+
+    pub fn is_campaign_slot_active(&self) -> bool {
+        self.campaign_slot_id.as_ref() == Some(&self.active_id)
+    }
+
+    // Keeps the active slot up to date with the roster being edited.
+    // Returns whether anything changed.
+    pub fn update_active(&mut self, roster: &Roster, modified: &str) -> bool {
+        let active_id = self.active_id.clone();
+        match self.find_mut(&active_id) {
+            Some(slot) if slot.roster != *roster => {
+                slot.revisions.add_daily(&slot.roster, &slot.modified, modified);
+                slot.roster = roster.clone();
+                slot.modified = modified.to_string();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn get_active_revisions(&self) -> Option<&Revisions> {
+        self.find(&self.active_id).map(|slot| &slot.revisions)
+    }
+
+    pub fn get_active_revisions_mut(&mut self) -> Option<&mut Revisions> {
+        let active_id = self.active_id.clone();
+        self.find_mut(&active_id).map(|slot| &mut slot.revisions)
+    }
+
+    pub fn toggle_event_entry(&mut self, id: &str) {
+        match self.event_entry_ids.iter().position(|entry_id| entry_id == id) {
+            Some(index) => { self.event_entry_ids.remove(index); }
+            None if self.find(id).is_some() => self.event_entry_ids.push(id.to_string()),
+            None => (),
+        }
+    }
+
+    pub fn get_event_entry(&self) -> Vec<&Roster> {
+        self.event_entry_ids.iter().filter_map(|id| self.find(id)).map(|slot| &slot.roster).collect()
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<RosterSlot> {
+        let index = self.slots.iter().position(|slot| slot.id == id)?;
+        if self.campaign_slot_id.as_deref() == Some(id) {
+            self.campaign_slot_id = None;
+        }
+        self.event_entry_ids.retain(|entry_id| entry_id != id);
+        Some(self.slots.remove(index))
+    }
+}
+
+// Slots were numbered before they had ids, the numbers of a library saved
+// then are replaced by new ids, the same one wherever a number is found.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredId {
+    Id(String),
+    Number(u32),
+}
+
+#[derive(Deserialize)]
+struct StoredSlot {
+    id : StoredId,
+    roster : Roster,
+    modified : String,
+    #[serde(default)]
+    revisions : Revisions,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct RosterSlotsFile {
+    slots : Vec<StoredSlot>,
+    active_id : Option<StoredId>,
+    campaign_slot_id : Option<StoredId>,
+    event_entry_ids : Vec<StoredId>,
+}
+
+impl From<RosterSlotsFile> for RosterSlots {
+    fn from(file: RosterSlotsFile) -> Self {
+        let mut renewed = HashMap::new();
+        let mut get_id = |id: StoredId| match id {
+            StoredId::Id(id) => id,
+            StoredId::Number(number) => renewed.entry(number).or_insert_with(ids::new_id).clone(),
+        };
+        RosterSlots {
+            slots: file.slots.into_iter()
+                .map(|slot| RosterSlot {id: get_id(slot.id), roster: slot.roster, modified: slot.modified, revisions: slot.revisions})
+                .collect(),
+            active_id: file.active_id.map(&mut get_id).unwrap_or_default(),
+            campaign_slot_id: file.campaign_slot_id.map(&mut get_id),
+            event_entry_ids: file.event_entry_ids.into_iter().map(get_id).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures::RosterFixture;
+    use serde_json::json;
+
+    #[test]
+    fn numbered_slots_get_ids() {
+        let roster = serde_json::to_value(RosterFixture::skirmish_30()).unwrap();
+        let slot = |id: u32| json!({"id": id, "roster": roster, "modified": "2026-01-01 10:00"});
+        let file = json!({"slots": [slot(1), slot(2)], "active_id": 2, "next_id": 2, "campaign_slot_id": 1, "event_entry_ids": [2, 1]});
+        let slots: RosterSlots = serde_json::from_value(file).unwrap();
+
+        let (first, second) = (slots.slots[0].id.clone(), slots.slots[1].id.clone());
+        assert_ne!(first, second);
+        assert_eq!(first.len(), 36);
+        assert_eq!(slots.active_id, second);
+        assert_eq!(slots.campaign_slot_id, Some(first.clone()));
+        assert_eq!(slots.event_entry_ids, vec![second, first]);
+    }
+
+    #[test]
+    fn ids_are_kept_once_saved() {
+        let mut slots = RosterSlots::default();
+        slots.active_id = slots.add(RosterFixture::skirmish_30(), "2026-01-01 10:00");
+        let json = serde_json::to_string(&slots).unwrap();
+        assert_eq!(serde_json::from_str::<RosterSlots>(&json).unwrap(), slots);
+    }
+}
@@ -0,0 +1,131 @@
+// Rules the roster is checked against, each with its severity, and the ones
+// the player chose to set aside for this roster (the organizer allowed it),
+// kept with the reason. Set aside violations are still listed, faded, and
+// noted in the exports. They are checked on a roster by core::validation.
+use serde::{Serialize, Deserialize};
+
+use crate::core::event_pack::RuleOverride;
+use crate::core::quick_fix::QuickFix;
+use crate::core::roster::Roster;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Severity::Error => "Error",
+            Severity::Warning => "Warning",
+            Severity::Info => "Info",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule {
+    pub id : &'static str,
+    pub label : &'static str,
+    pub severity : Severity,
+}
+
+pub const POINTS_LIMIT_RULE: Rule = Rule {id: "points-limit", label: "Points limit", severity: Severity::Error};
+pub const GROUP_CAP_RULE: Rule = Rule {id: "group-cap", label: "Group cap", severity: Severity::Warning};
+pub const UNSPENT_POINTS_RULE: Rule = Rule {id: "unspent-points", label: "Unspent points", severity: Severity::Info};
+// Elements not as in the catalog, see core::provenance
+pub const UNOFFICIAL_CONTENT_RULE: Rule = Rule {id: "unofficial-content", label: "Unofficial content", severity: Severity::Info};
+// Between the lists of an event entry, see get_entry_violations
+pub const SHARED_CHARACTERS_RULE: Rule = Rule {id: "shared-characters", label: "Characters shared between lists", severity: Severity::Error};
+pub const ENTRY_LISTS_RULE: Rule = Rule {id: "entry-lists", label: "Lists in the event entry", severity: Severity::Error};
+pub const DUPLICATE_CHARACTERS_RULE: Rule = Rule {id: "duplicate-characters", label: "Duplicate characters", severity: Severity::Warning};
+pub const RULES: [Rule; 7] = [POINTS_LIMIT_RULE, GROUP_CAP_RULE, UNSPENT_POINTS_RULE, UNOFFICIAL_CONTENT_RULE, SHARED_CHARACTERS_RULE, ENTRY_LISTS_RULE, DUPLICATE_CHARACTERS_RULE];
+
+// A rule set aside, the key is the one of the violation (see Violation).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Suppression {
+    pub key : String,
+    pub reason : String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violation {
+    pub rule : Rule,
+    // The rule id, followed by what it's about for rules checked more than
+    // once (e.g. "group-cap:Unit"), so each can be set aside on its own
+    pub key : String,
+    pub message : String,
+    pub fix : Option<QuickFix>,
+    pub suppression : Option<Suppression>,
+    // Indexes of the entries it's about, none for the roster as a whole
+    pub entries : Vec<usize>,
+}
+
+impl Violation {
+    pub fn new(rule: Rule, key: String, message: String, fix: Option<QuickFix>, entries: Vec<usize>) -> Violation {
+        Violation {rule, key, message, fix, suppression: None, entries}
+    }
+}
+
+// The rule as played at the event, None if it's left out.
+pub(crate) fn apply_house_rules(mut rule: Rule, house_rules: &[RuleOverride]) -> Option<Rule> {
+    if let Some(house_rule) = house_rules.iter().find(|house_rule| house_rule.rule == rule.id) {
+        rule.severity = house_rule.severity?;
+    }
+    Some(rule)
+}
+
+// Rules between the lists an event asks for: as many lists as required, and
+// no character in more than one of them. Set asides are per roster, these
+// can't be.
+pub fn get_entry_violations(rosters: &[&Roster], lists_required: u32, house_rules: &[RuleOverride]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut add = |rule: Rule, key: String, message: String| {
+        if let Some(rule) = apply_house_rules(rule, house_rules) {
+            violations.push(Violation::new(rule, key, message, None, Vec::new()));
+        }
+    };
+
+    if rosters.len() != lists_required as usize {
+        add(ENTRY_LISTS_RULE, ENTRY_LISTS_RULE.id.to_string(),
+            format!("{} lists in the entry, the event asks for {}", rosters.len(), lists_required));
+    }
+
+    let mut characters: Vec<(String, Vec<String>)> = Vec::new();
+    for roster in rosters {
+        for name in get_character_names(roster) {
+            match characters.iter_mut().find(|(character, _)| *character == name) {
+                Some((_, lists)) if !lists.contains(&roster.metadata.name) => lists.push(roster.metadata.name.clone()),
+                Some(_) => (),
+                None => characters.push((name, vec![roster.metadata.name.clone()])),
+            }
+        }
+    }
+    for (name, lists) in characters.into_iter().filter(|(_, lists)| lists.len() > 1) {
+        add(SHARED_CHARACTERS_RULE, format!("{}:{}", SHARED_CHARACTERS_RULE.id, name),
+            format!("{} is in {}", name, lists.join(", ")));
+    }
+    violations.sort_by_key(|violation| violation.rule.severity);
+    violations
+}
+
+// The characters of the roster, on their own or attached to a unit.
+fn get_character_names(roster: &Roster) -> Vec<String> {
+    roster.elements.iter()
+        .flat_map(|entry| std::iter::once(entry.element.clone()).chain(entry.element.get_attached_elements()))
+        .filter(|element| element.get_type_name() == "Character")
+        .map(|element| element.get_name())
+        .collect()
+}
+
+// What was set aside on the roster and why, as lines for the exports.
+pub fn get_suppression_notes(roster: &Roster) -> Vec<String> {
+    roster.metadata.suppressions.iter().map(|suppression| {
+        let (id, subject) = suppression.key.split_once(':').unwrap_or((&suppression.key, ""));
+        let label = RULES.iter().find(|rule| rule.id == id).map(|rule| rule.label).unwrap_or(id);
+        let label = if subject.is_empty() { label.to_string() } else { format!("{} ({})", label, subject) };
+        format!("{} set aside: {}", label, suppression.reason)
+    }).collect()
+}
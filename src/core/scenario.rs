@@ -0,0 +1,24 @@
+// Custom missions written by the players, attachable to the next game of a
+// roster. The library is kept in the browser, see models::storage.
+use serde::{Serialize, Deserialize};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scenario {
+    pub name : String,
+    pub deployment : String,
+    pub special_rules : Vec<String>,
+    pub victory_conditions : Vec<String>,
+}
+
+impl Scenario {
+    pub fn new() -> Scenario {
+        Scenario {name: "New Scenario".to_string(), ..Default::default()}
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScenarioLibrary {
+    pub scenarios : Vec<Scenario>,
+}
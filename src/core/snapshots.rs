@@ -0,0 +1,46 @@
+// The last few states of the roster, kept in storage to recover from a
+// session that ended badly (a crash, a killed tab), see models::session.
+use serde::{Serialize, Deserialize};
+
+use crate::core::ids;
+use crate::core::roster::Roster;
+
+const MAX_SNAPSHOTS: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    // See core::ids, snapshots saved before they had one get a new one
+    #[serde(default = "ids::new_id")]
+    pub id : String,
+    pub taken : String,
+    pub roster : Roster,
+}
+
+// Oldest first.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Snapshots {
+    pub snapshots : Vec<Snapshot>,
+}
+
+impl Snapshots {
+    // Returns false if the roster is already the latest one.
+    pub fn push(&mut self, roster: &Roster, taken: &str) -> bool {
+        if self.snapshots.last().is_some_and(|snapshot| snapshot.roster == *roster) {
+            return false;
+        }
+        self.snapshots.push(Snapshot {id: ids::new_id(), taken: taken.to_string(), roster: roster.clone()});
+        if self.snapshots.len() > MAX_SNAPSHOTS {
+            self.snapshots.remove(0);
+        }
+        true
+    }
+
+    // The ones worth offering instead of the given roster, newest first.
+    pub fn get_alternatives(&self, current: &Roster) -> Vec<Snapshot> {
+        self.snapshots.iter().rev()
+            .filter(|snapshot| snapshot.roster != *current && !snapshot.roster.elements.is_empty())
+            .cloned()
+            .collect()
+    }
+}
@@ -0,0 +1,74 @@
+// Swapping every copy of an element in the roster for another catalog
+// entry. What can carry over does: the wave and campaign record of each
+// entry, and what's attached to a unit when the replacement is a unit too.
+use crate::core::armylist::{ArmyList, Faction};
+use crate::core::roster::{Roster, RosterElement};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SwapPreview {
+    pub count : usize,
+    pub points_before : u32,
+    pub points_after : u32,
+    // Attachments lost because the replacement can't carry them
+    pub dropped_attachments : usize,
+}
+
+impl SwapPreview {
+    pub fn get_points_delta(&self) -> i64 {
+        self.points_after as i64 - self.points_before as i64
+    }
+}
+
+// Names of the elements in the roster, once each, in roster order.
+pub fn get_swappable_names(roster: &Roster) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for entry in &roster.elements {
+        let name = entry.element.get_name();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+// Every catalog entry that can be swapped in, by faction.
+pub fn get_replacements() -> Vec<(Faction, RosterElement)> {
+    Faction::ALL.iter().flat_map(|faction| {
+        let list = ArmyList::new(*faction);
+        let characters = list.get_characters().into_iter().map(RosterElement::from);
+        let units = list.get_units().into_iter().map(RosterElement::from);
+        let supports = list.get_supports().into_iter().map(RosterElement::from);
+        characters.chain(units).chain(supports).map(|element| (*faction, element)).collect::<Vec<_>>()
+    }).collect()
+}
+
+// The replacement for one element, keeping its attachments if it can.
+fn replace_element(element: &RosterElement, replacement: &RosterElement) -> RosterElement {
+    let mut new_element = replacement.clone();
+    if let (RosterElement::ElemUnit(old_unit), RosterElement::ElemUnit(new_unit)) = (element, &mut new_element) {
+        new_unit.attached_elements = old_unit.attached_elements.clone();
+    }
+    new_element
+}
+
+pub fn get_swap_preview(roster: &Roster, name: &str, replacement: &RosterElement) -> SwapPreview {
+    let mut preview = SwapPreview::default();
+    for entry in roster.elements.iter().filter(|entry| entry.element.get_name() == name) {
+        let new_element = replace_element(&entry.element, replacement);
+        preview.count += 1;
+        preview.points_before += entry.get_total_points();
+        preview.points_after += new_element.get_total_points() * entry.quantity;
+        preview.dropped_attachments += entry.element.get_attached_elements().len() - new_element.get_attached_elements().len();
+    }
+    preview
+}
+
+// Returns how many entries were swapped.
+pub fn swap_elements(roster: &mut Roster, name: &str, replacement: &RosterElement) -> usize {
+    let mut count = 0;
+    for entry in roster.elements.iter_mut().filter(|entry| entry.element.get_name() == name) {
+        entry.element = replace_element(&entry.element, replacement);
+        count += 1;
+    }
+    count
+}
@@ -0,0 +1,89 @@
+// Rosters kept on a server as well, to carry them from one device to
+// another. Each roster is a document of its own with a revision counter,
+// raised by the server on every push. A push made from an older revision
+// than the one on the server is refused: the roster was pushed from
+// somewhere else in between, and has to be pulled first. The client side
+// is in models::sync.
+use serde::{Serialize, Deserialize};
+
+use crate::core::roster::Roster;
+
+// Where the roster is on the server and what it was like when last synced.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncRecord {
+    pub id : String,
+    pub revision : u32,
+    // Content hash of the roster at the last push or pull, see core::canonical
+    pub content_hash : String,
+}
+
+// Body of a push and of a pull.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RemoteRoster {
+    // On a push, the revision the roster was edited from
+    pub revision : u32,
+    pub roster : Roster,
+}
+
+// Answer of the server to an accepted push.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PushReceipt {
+    pub revision : u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PullOutcome {
+    UpToDate,
+    // The roster of the server, nothing was changed here since the last sync
+    Updated(Roster),
+    // Changed on both sides: the player decides which one is kept
+    Conflict(Roster),
+}
+
+// The roster without its sync record, which isn't part of the content.
+pub fn get_synced_hash(roster: &Roster) -> String {
+    let mut roster = roster.clone();
+    roster.metadata.sync = None;
+    roster.get_content_hash().unwrap_or_default()
+}
+
+// Changed since the last push or pull (or never synced).
+pub fn has_local_changes(roster: &Roster) -> bool {
+    roster.metadata.sync.as_ref().is_none_or(|record| record.content_hash != get_synced_hash(roster))
+}
+
+// The roster as it is now, at that revision of the server.
+pub fn mark_synced(roster: &mut Roster, id: &str, revision: u32) {
+    let content_hash = get_synced_hash(roster);
+    roster.metadata.sync = Some(SyncRecord {id: id.to_string(), revision, content_hash});
+}
+
+// After a conflict, the roster here is kept: the next push overwrites the
+// one of the server at that revision.
+pub fn keep_local(roster: &mut Roster, remote_revision: u32) {
+    if let Some(record) = &mut roster.metadata.sync {
+        record.revision = remote_revision;
+    }
+}
+
+pub fn get_push_body(roster: &Roster) -> RemoteRoster {
+    let mut roster = roster.clone();
+    let revision = roster.metadata.sync.take().map_or(0, |record| record.revision);
+    RemoteRoster {revision, roster}
+}
+
+// Only rosters pushed before have somewhere to be pulled from.
+pub fn get_pull_outcome(local: &Roster, remote: RemoteRoster) -> PullOutcome {
+    let Some(record) = &local.metadata.sync else {
+        return PullOutcome::UpToDate;
+    };
+    if remote.revision <= record.revision {
+        return PullOutcome::UpToDate;
+    }
+    let mut roster = remote.roster;
+    mark_synced(&mut roster, &record.id, remote.revision);
+    match has_local_changes(local) {
+        true => PullOutcome::Conflict(roster),
+        false => PullOutcome::Updated(roster),
+    }
+}
@@ -1,6 +1,6 @@
 use super::element::Element;
 use serde::{Serialize, Deserialize};
-use crate::models::roster::RosterElement;
+use crate::core::roster::RosterElement;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Unit {
@@ -20,6 +20,6 @@ impl Element for Unit {
     }
 
     fn get_attached (&self) -> Vec<String> {
-        self.attached_elements.iter().map(|elem| {self.get_name()}).collect()
+        self.attached_elements.iter().map(|elem| {elem.get_name()}).collect()
     }
 }
\ No newline at end of file
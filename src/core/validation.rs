@@ -0,0 +1,235 @@
+// The rules checked on a roster after every change, each on its own, run
+// together by a Validator. A rule only says what it finds; the house rules
+// of the event and what the player set aside are applied by the Validator,
+// the same for every rule. The rules themselves are in core::rules.
+use crate::core::breakdown;
+use crate::core::event_pack::RuleOverride;
+use crate::core::provenance::{self, Provenance};
+use crate::core::quick_fix::QuickFix;
+use crate::core::roster::{Roster, RosterEntry};
+use crate::core::rules::{self, Severity, Violation};
+use crate::core::rules::{DUPLICATE_CHARACTERS_RULE, GROUP_CAP_RULE, POINTS_LIMIT_RULE, UNOFFICIAL_CONTENT_RULE, UNSPENT_POINTS_RULE};
+
+pub trait ValidationRule {
+    // What the roster breaks, the suppressions left to the Validator.
+    fn check(&self, roster: &Roster) -> Vec<Violation>;
+}
+
+// The roster's points limit, over it or with points left to spend.
+pub struct PointsLimitCheck;
+
+impl ValidationRule for PointsLimitCheck {
+    fn check(&self, roster: &Roster) -> Vec<Violation> {
+        let total = roster.get_total_points();
+        let points_limit = roster.get_points_limit();
+        if total > points_limit {
+            let excess = total - points_limit;
+            vec![Violation::new(POINTS_LIMIT_RULE, POINTS_LIMIT_RULE.id.to_string(),
+                format!("{} points, {} over the limit of {}", total, excess, points_limit),
+                get_smallest_removal(roster, |points| points >= excess, |_| true), Vec::new())]
+        } else if total < points_limit && !roster.elements.is_empty() {
+            vec![Violation::new(UNSPENT_POINTS_RULE, UNSPENT_POINTS_RULE.id.to_string(),
+                format!("{} points left to spend", points_limit - total), None, Vec::new())]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+// The largest share of the points a group of entries may take, see
+// core::breakdown. Nothing to check without a cap.
+pub struct GroupCapCheck {
+    pub cap_percent: Option<u32>,
+}
+
+impl ValidationRule for GroupCapCheck {
+    fn check(&self, roster: &Roster) -> Vec<Violation> {
+        let total = roster.get_total_points();
+        let cap = self.cap_percent.unwrap_or_default() as u64;
+        let mut violations = Vec::new();
+        for grouping in breakdown::get_groupings(roster) {
+            for group in breakdown::get_breakdown(roster, grouping, self.cap_percent).into_iter().filter(|group| group.is_over_cap) {
+                let is_in_group = |entry: &RosterEntry| grouping.get_group(entry) == group.label;
+                let fits_cap = |points: u32| (group.points - points) as u64 * 100 <= cap * (total - points) as u64;
+                violations.push(Violation::new(GROUP_CAP_RULE, format!("{}:{}", GROUP_CAP_RULE.id, group.label),
+                    format!("{}: {:.0}% of the points, over the cap of {}%", group.label, group.share_percent, cap),
+                    get_smallest_removal(roster, fits_cap, is_in_group),
+                    get_entries(roster, is_in_group)));
+            }
+        }
+        violations
+    }
+}
+
+// Elements off the catalog, see core::provenance.
+pub struct UnofficialContentCheck;
+
+impl ValidationRule for UnofficialContentCheck {
+    fn check(&self, roster: &Roster) -> Vec<Violation> {
+        provenance::get_unofficial_elements(roster).into_iter().map(|(element, provenance)| {
+            let name = element.get_name();
+            let message = match provenance {
+                Provenance::HouseRuled {catalog_points} => format!("{} is house-ruled: {} points, {} in the catalog",
+                    name, element.get_name_and_points().1, catalog_points),
+                _ => format!("{} is homebrew, it isn't in the catalog", name),
+            };
+            Violation::new(UNOFFICIAL_CONTENT_RULE, format!("{}:{}", UNOFFICIAL_CONTENT_RULE.id, name), message, None,
+                get_entries(roster, |entry| has_element(entry, &name)))
+        }).collect()
+    }
+}
+
+// The same character more than once, on its own, with copies or attached to
+// several units.
+pub struct DuplicateCharactersCheck;
+
+impl ValidationRule for DuplicateCharactersCheck {
+    fn check(&self, roster: &Roster) -> Vec<Violation> {
+        let mut counts: Vec<(String, u32)> = Vec::new();
+        for entry in &roster.elements {
+            let characters = std::iter::once(entry.element.clone()).chain(entry.element.get_attached_elements())
+                .filter(|element| element.get_type_name() == "Character");
+            for character in characters {
+                let name = character.get_name();
+                match counts.iter_mut().find(|(counted, _)| *counted == name) {
+                    Some((_, count)) => *count += entry.quantity,
+                    None => counts.push((name, entry.quantity)),
+                }
+            }
+        }
+        counts.into_iter().filter(|(_, count)| *count > 1).map(|(name, count)| {
+            Violation::new(DUPLICATE_CHARACTERS_RULE, format!("{}:{}", DUPLICATE_CHARACTERS_RULE.id, name),
+                format!("{} is in the roster {} times", name, count), None,
+                get_entries(roster, |entry| has_element(entry, &name)))
+        }).collect()
+    }
+}
+
+// Runs its rules in the order they were added, with the house rules of the
+// event if there's one.
+pub struct Validator {
+    rules: Vec<Box<dyn ValidationRule>>,
+    house_rules: Vec<RuleOverride>,
+}
+
+impl Validator {
+    pub fn new(house_rules: &[RuleOverride]) -> Validator {
+        Validator {rules: Vec::new(), house_rules: house_rules.to_vec()}
+    }
+
+    // The rules checked on the canvas: points limit, group cap, duplicates
+    // and content off the catalog.
+    pub fn for_roster(group_cap_percent: Option<u32>, house_rules: &[RuleOverride]) -> Validator {
+        Validator::new(house_rules)
+            .with_rule(PointsLimitCheck)
+            .with_rule(GroupCapCheck {cap_percent: group_cap_percent})
+            .with_rule(DuplicateCharactersCheck)
+            .with_rule(UnofficialContentCheck)
+    }
+
+    pub fn with_rule(mut self, rule: impl ValidationRule + 'static) -> Validator {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    // Errors first, then warnings and infos. A violation set aside is kept,
+    // with its suppression.
+    pub fn validate(&self, roster: &Roster) -> Vec<Violation> {
+        let mut violations: Vec<Violation> = self.rules.iter()
+            .flat_map(|rule| rule.check(roster))
+            .filter_map(|mut violation| {
+                violation.rule = rules::apply_house_rules(violation.rule, &self.house_rules)?;
+                violation.suppression = roster.metadata.suppressions.iter().find(|suppression| suppression.key == violation.key).cloned();
+                Some(violation)
+            })
+            .collect();
+        violations.sort_by_key(|violation| violation.rule.severity);
+        violations
+    }
+
+    // What makes the roster illegal, none when it's legal: the rules any
+    // roster must follow, then the errors not set aside.
+    pub fn get_errors(&self, roster: &Roster) -> Vec<String> {
+        let mut errors = roster.check_invariants().err().unwrap_or_default();
+        errors.extend(self.validate(roster).into_iter()
+            .filter(|violation| violation.rule.severity == Severity::Error && violation.suppression.is_none())
+            .map(|violation| violation.message));
+        errors
+    }
+}
+
+// The cheapest entry whose removal is enough, the last one of a tie.
+fn get_smallest_removal(roster: &Roster, is_enough: impl Fn(u32) -> bool, is_candidate: impl Fn(&RosterEntry) -> bool) -> Option<QuickFix> {
+    roster.elements.iter().enumerate()
+        .filter(|(_, entry)| is_candidate(entry) && is_enough(entry.get_total_points()))
+        .min_by_key(|(index, entry)| (entry.get_total_points(), usize::MAX - index))
+        .map(|(index, _)| QuickFix::RemoveEntry(index))
+}
+
+fn get_entries(roster: &Roster, is_concerned: impl Fn(&RosterEntry) -> bool) -> Vec<usize> {
+    roster.elements.iter().enumerate()
+        .filter(|(_, entry)| is_concerned(entry))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+// The element itself or one attached to it.
+fn has_element(entry: &RosterEntry, name: &str) -> bool {
+    entry.element.get_name() == name || entry.element.get_attached_elements().iter().any(|element| element.get_name() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures::RosterFixture;
+    use crate::core::rules::Suppression;
+
+    fn get_keys(violations: &[Violation]) -> Vec<&str> {
+        violations.iter().map(|violation| violation.key.as_str()).collect()
+    }
+
+    #[test]
+    fn over_the_limit_is_an_error() {
+        let roster = RosterFixture::over_limit();
+        let violations = Validator::for_roster(None, &[]).validate(&roster);
+        assert_eq!(violations[0].key, POINTS_LIMIT_RULE.id);
+        assert_eq!(violations[0].rule.severity, Severity::Error);
+        assert!(matches!(violations[0].fix, Some(QuickFix::RemoveEntry(_))));
+        assert_eq!(Validator::for_roster(None, &[]).get_errors(&roster).len(), 1);
+    }
+
+    #[test]
+    fn within_the_limit_is_legal() {
+        let roster = RosterFixture::skirmish_30();
+        assert!(Validator::for_roster(None, &[]).get_errors(&roster).is_empty());
+        assert!(get_keys(&Validator::for_roster(None, &[]).validate(&roster)).contains(&UNSPENT_POINTS_RULE.id));
+    }
+
+    #[test]
+    fn suppressed_errors_are_kept_but_not_counted() {
+        let mut roster = RosterFixture::over_limit();
+        roster.metadata.suppressions.push(Suppression {key: POINTS_LIMIT_RULE.id.to_string(), reason: "Narrative game".to_string()});
+        let validator = Validator::for_roster(None, &[]);
+        assert!(validator.validate(&roster)[0].suppression.is_some());
+        assert!(validator.get_errors(&roster).is_empty());
+    }
+
+    #[test]
+    fn house_rules_change_or_drop_a_rule() {
+        let roster = RosterFixture::over_limit();
+        let lowered = [RuleOverride {rule: POINTS_LIMIT_RULE.id.to_string(), severity: Some(Severity::Warning)}];
+        assert!(Validator::for_roster(None, &lowered).get_errors(&roster).is_empty());
+        let dropped = [RuleOverride {rule: POINTS_LIMIT_RULE.id.to_string(), severity: None}];
+        assert!(!get_keys(&Validator::for_roster(None, &dropped).validate(&roster)).contains(&POINTS_LIMIT_RULE.id));
+    }
+
+    #[test]
+    fn copies_of_a_character_are_duplicates() {
+        let mut roster = RosterFixture::skirmish_30();
+        let character = roster.elements.iter().position(|entry| entry.element.get_type_name() == "Character").unwrap();
+        roster.elements[character].quantity = 2;
+        let violations = DuplicateCharactersCheck.check(&roster);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].entries, vec![character]);
+    }
+}
@@ -0,0 +1,79 @@
+// Short audio cues, synthesized with the Web Audio API so no sound files
+// have to be shipped. Like the assets base, the volume is set once from the
+// settings and read by whoever plays a cue.
+use std::cell::RefCell;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cue {
+    // A clock reached one of its alert thresholds
+    ClockAlert,
+    ClockExpired,
+    EndOfTurn,
+    OverPointsLimit,
+}
+
+impl Cue {
+    // Notes of the cue, as frequency (Hz) and duration (s).
+    fn get_notes(&self) -> &'static [(f32, f64)] {
+        match self {
+            Cue::ClockAlert => &[(880.0, 0.12), (880.0, 0.12)],
+            Cue::ClockExpired => &[(988.0, 0.2), (784.0, 0.2), (523.0, 0.4)],
+            Cue::EndOfTurn => &[(660.0, 0.08)],
+            Cue::OverPointsLimit => &[(220.0, 0.15), (196.0, 0.25)],
+        }
+    }
+}
+
+thread_local! {
+    // None when the cues are off, the volume from 0 to 1 otherwise.
+    static CUE_VOLUME: RefCell<Option<f32>> = const { RefCell::new(None) };
+    // Created on the first cue, browsers limit how many can exist.
+    static AUDIO_CONTEXT: RefCell<Option<web_sys::AudioContext>> = const { RefCell::new(None) };
+}
+
+pub fn set_cue_volume(volume: Option<f32>) {
+    CUE_VOLUME.with(|cue_volume| *cue_volume.borrow_mut() = volume.map(|volume| volume.clamp(0.0, 1.0)));
+}
+
+pub fn play_cue(cue: Cue) {
+    let Some(volume) = CUE_VOLUME.with(|cue_volume| *cue_volume.borrow()).filter(|volume| *volume > 0.0) else {
+        return;
+    };
+    AUDIO_CONTEXT.with(|audio_context| {
+        let mut audio_context = audio_context.borrow_mut();
+        if audio_context.is_none() {
+            *audio_context = web_sys::AudioContext::new().ok();
+        }
+        match audio_context.as_ref() {
+            Some(context) => {
+                if let Err(e) = play_notes(context, cue.get_notes(), volume) {
+                    log::warn!("Error playing a cue: {:?}", e);
+                }
+            }
+            None => log::info!("Web Audio not available"),
+        }
+    });
+}
+
+// One oscillator per note, each faded out to avoid clicks.
+fn play_notes(context: &web_sys::AudioContext, notes: &[(f32, f64)], volume: f32) -> Result<(), wasm_bindgen::JsValue> {
+    // Contexts start suspended until the page has been interacted with.
+    let _ = context.resume()?;
+    let mut start = context.current_time();
+    for (frequency, duration) in notes {
+        let oscillator = context.create_oscillator()?;
+        oscillator.set_type(web_sys::OscillatorType::Sine);
+        oscillator.frequency().set_value(*frequency);
+
+        let gain = context.create_gain()?;
+        gain.gain().set_value_at_time(volume, start)?;
+        gain.gain().exponential_ramp_to_value_at_time(0.001, start + duration)?;
+
+        oscillator.connect_with_audio_node(&gain)?;
+        gain.connect_with_audio_node(&context.destination())?;
+        oscillator.start_with_when(start)?;
+        oscillator.stop_with_when(start + duration)?;
+        start += duration + 0.03;
+    }
+    Ok(())
+}
@@ -0,0 +1,152 @@
+// BattleScribe roster (.ros): the XML its roster files use, for groups
+// keeping their lists there. There's no BattleScribe data for the game, so
+// the ids are made up from the names: the file reads fine as a roster but
+// isn't linked to any catalogue entry. Entries are put in one force per
+// faction, in the order they first appear.
+use crate::core::armylist::Faction;
+use crate::core::roster::{Roster, RosterElement, RosterEntry};
+use crate::render::card_svg::escape;
+
+const ROSTER_NAMESPACE: &str = "http://www.battlescribe.net/schema/rosterSchema";
+const BATTLESCRIBE_VERSION: &str = "2.03";
+const GAME_SYSTEM_NAME: &str = "Full Spectrum Dominance";
+const CUSTOM_FORCE_NAME: &str = "Custom Elements";
+
+pub fn build_roster_xml(roster: &Roster) -> String {
+    let mut xml = RosterXml::default();
+    xml.line(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#);
+    xml.open(&format!(r#"<roster id="fsd-roster" name="{}" battleScribeVersion="{}" gameSystemId="{}" gameSystemName="{}" gameSystemRevision="1" xmlns="{}">"#,
+        escape(&roster.metadata.name), BATTLESCRIBE_VERSION, get_entry_id(GAME_SYSTEM_NAME), GAME_SYSTEM_NAME, ROSTER_NAMESPACE));
+    xml.costs("costs", "cost", roster.get_total_points());
+    xml.costs("costLimits", "costLimit", roster.get_points_limit());
+
+    xml.open("<forces>");
+    for (index, (force_name, entries)) in get_forces(roster).into_iter().enumerate() {
+        xml.open(&format!(r#"<force id="fsd-force-{}" name="{}" entryId="{}" catalogueId="{}" catalogueRevision="1" catalogueName="{}">"#,
+            index + 1, escape(&force_name), get_entry_id("force"), get_entry_id(&force_name), escape(&force_name)));
+        xml.open("<selections>");
+        for entry in entries {
+            xml.entry(entry);
+        }
+        xml.close("</selections>");
+        xml.close("</force>");
+    }
+    xml.close("</forces>");
+    xml.close("</roster>");
+    xml.lines.join("\n")
+}
+
+// Entries grouped by the faction they come from, custom ones last.
+fn get_forces(roster: &Roster) -> Vec<(String, Vec<&RosterEntry>)> {
+    let mut forces: Vec<(String, Vec<&RosterEntry>)> = Vec::new();
+    let mut custom: Vec<&RosterEntry> = Vec::new();
+    for entry in &roster.elements {
+        match Faction::find_by_element_name(&entry.element.get_name()) {
+            Some(faction) => {
+                let force_name = format!("{:?}", faction);
+                match forces.iter_mut().find(|(name, _)| *name == force_name) {
+                    Some((_, entries)) => entries.push(entry),
+                    None => forces.push((force_name, vec![entry])),
+                }
+            }
+            None => custom.push(entry),
+        }
+    }
+    if !custom.is_empty() {
+        forces.push((CUSTOM_FORCE_NAME.to_string(), custom));
+    }
+    forces
+}
+
+// Stable id for a name, e.g. "Assault Squad" gives "fsd-assault-squad".
+fn get_entry_id(name: &str) -> String {
+    let slug: Vec<String> = name.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect();
+    format!("fsd-{}", slug.join("-"))
+}
+
+fn get_selection_type(element: &RosterElement) -> &'static str {
+    match element {
+        RosterElement::ElemCharacter(_) => "model",
+        _ => "unit",
+    }
+}
+
+// The document as indented lines, selections are numbered as they're written.
+#[derive(Default)]
+struct RosterXml {
+    lines: Vec<String>,
+    depth: usize,
+    selection_count: usize,
+}
+
+impl RosterXml {
+    fn line(&mut self, content: &str) {
+        self.lines.push(format!("{}{}", "  ".repeat(self.depth), content));
+    }
+
+    fn open(&mut self, tag: &str) {
+        self.line(tag);
+        self.depth += 1;
+    }
+
+    fn close(&mut self, tag: &str) {
+        self.depth -= 1;
+        self.line(tag);
+    }
+
+    fn costs(&mut self, tag: &str, item_tag: &str, points: u32) {
+        self.open(&format!("<{}>", tag));
+        self.line(&format!(r#"<{} name="pts" typeId="points" value="{}.0"/>"#, item_tag, points));
+        self.close(&format!("</{}>", tag));
+    }
+
+    // A roster entry, its attachments nested as selections of their own. The
+    // wave goes in a second category, BattleScribe has nothing closer.
+    fn entry(&mut self, entry: &RosterEntry) {
+        let mut squad = entry.element.clone();
+        if let RosterElement::ElemUnit(unit) = &mut squad {
+            unit.attached_elements.clear();
+        }
+        let mut categories = vec![entry.element.get_type_name()];
+        if !entry.wave.is_first() {
+            categories.push(entry.wave.get_label());
+        }
+        // One selection per copy, as BattleScribe itself writes units.
+        for _ in 0..entry.quantity {
+            self.selection(&squad, get_selection_type(&squad), &entry.element.get_attached_elements(), &categories, &entry.note);
+        }
+    }
+
+    // The note of the entry as the custom notes of the selection.
+    fn selection(&mut self, element: &RosterElement, selection_type: &str, attached: &[RosterElement], categories: &[&str], note: &str) {
+        self.selection_count += 1;
+        let id = format!("fsd-selection-{}", self.selection_count);
+        let (name, points) = element.get_name_and_points();
+        let notes = if note.is_empty() { String::new() } else { format!(r#" customNotes="{}""#, escape(note)) };
+        self.open(&format!(r#"<selection id="{}" name="{}" entryId="{}" number="1" type="{}"{}>"#,
+            id, escape(&name), get_entry_id(&name), selection_type, notes));
+        if !attached.is_empty() {
+            self.open("<selections>");
+            for attached_element in attached {
+                let attached_type = match attached_element {
+                    RosterElement::ElemCharacter(_) => "model",
+                    _ => "upgrade",
+                };
+                self.selection(attached_element, attached_type, &[], &[attached_element.get_type_name()], "");
+            }
+            self.close("</selections>");
+        }
+        self.costs("costs", "cost", points);
+        self.open("<categories>");
+        for (index, category) in categories.iter().enumerate() {
+            self.line(&format!(r#"<category id="{}-category-{}" name="{}" entryId="{}" primary="{}"/>"#,
+                id, index + 1, escape(category), get_entry_id(category), index == 0));
+        }
+        self.close("</categories>");
+        self.close("</selection>");
+    }
+}
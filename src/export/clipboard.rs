@@ -0,0 +1,61 @@
+// Copying generated text to the system clipboard.
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsValue;
+use yew::Callback;
+
+// Custom clipboard formats of the web must be prefixed so.
+const JSON_CLIPBOARD_TYPE: &str = "web application/json";
+
+// The write happens in the background, failures (e.g. no permission) are
+// only logged.
+pub fn copy_text(content: &str) {
+    let promise = gloo::utils::window().navigator().clipboard().write_text(content);
+    let on_error = wasm_bindgen::closure::Closure::once(move |e: wasm_bindgen::JsValue| {
+        log::warn!("Error copying to the clipboard: {:?}", e);
+    });
+    let _ = promise.catch(&on_error);
+    on_error.forget();
+}
+
+// Text for the apps that only take text, with the JSON alongside for those
+// that read the custom format. Browsers without custom formats get the text
+// alone. on_done is told whether the JSON went along, or why nothing did.
+pub fn copy_text_and_json(text: &str, json: &str, on_done: Callback<Result<bool, String>>) {
+    let clipboard = gloo::utils::window().navigator().clipboard();
+    let promise = match get_clipboard_item(text, json) {
+        Ok(item) => clipboard.write(&js_sys::Array::of1(&item)),
+        Err(_) => return write_text_only(text, on_done),
+    };
+
+    let text = text.to_string();
+    let on_done_ok = on_done.clone();
+    let on_ok = Closure::once(move |_: JsValue| on_done_ok.emit(Ok(true)));
+    let on_error = Closure::once(move |_: JsValue| write_text_only(&text, on_done));
+    let _ = promise.then2(&on_ok, &on_error);
+    on_ok.forget();
+    on_error.forget();
+}
+
+fn get_clipboard_item(text: &str, json: &str) -> Result<web_sys::ClipboardItem, JsValue> {
+    let record = js_sys::Object::new();
+    for (mime_type, content) in [("text/plain", text), (JSON_CLIPBOARD_TYPE, json)] {
+        let options = web_sys::BlobPropertyBag::new();
+        options.set_type(mime_type.trim_start_matches("web "));
+        let blob = web_sys::Blob::new_with_str_sequence_and_options(&js_sys::Array::of1(&content.into()), &options)?;
+        js_sys::Reflect::set(&record, &mime_type.into(), &blob)?;
+    }
+    web_sys::ClipboardItem::new_with_record_from_str_to_blob_promise(&record)
+}
+
+fn write_text_only(text: &str, on_done: Callback<Result<bool, String>>) {
+    let promise = gloo::utils::window().navigator().clipboard().write_text(text);
+    let on_done_ok = on_done.clone();
+    let on_ok = Closure::once(move |_: JsValue| on_done_ok.emit(Ok(false)));
+    let on_error = Closure::once(move |e: JsValue| {
+        log::warn!("Error copying to the clipboard: {:?}", e);
+        on_done.emit(Err("the browser didn't allow it".to_string()));
+    });
+    let _ = promise.then2(&on_ok, &on_error);
+    on_ok.forget();
+    on_error.forget();
+}
@@ -0,0 +1,43 @@
+// Browser downloads of generated content.
+use wasm_bindgen::JsCast;
+
+// Offers the given text as a file download, through a temporary <a> element.
+pub fn download_text(content: &str, filename: &str, mime_type: &str) {
+    let blob_parts = web_sys::BlobPropertyBag::new();
+    blob_parts.set_type(mime_type);
+    match web_sys::Blob::new_with_str_sequence_and_options(&js_sys::Array::of1(&content.into()), &blob_parts) {
+        Ok(blob) => download_blob(&blob, filename),
+        Err(e) => log::warn!("Error creating the download: {:?}", e),
+    }
+}
+
+// Name of the file for a roster (or campaign) name, without the characters
+// some systems don't allow in file names.
+pub fn get_file_name(name: &str, extension: &str) -> String {
+    let name: String = name.chars()
+        .map(|c| if c.is_control() || "\\/:*?\"<>|".contains(c) { '_' } else { c })
+        .collect();
+    let name = name.trim().trim_matches('.');
+    format!("{}.{}", if name.is_empty() { "roster" } else { name }, extension)
+}
+
+pub fn download_blob(blob: &web_sys::Blob, filename: &str) {
+    let document = gloo::utils::document();
+    let a = document.create_element("a")
+        .unwrap()
+        .dyn_into::<web_sys::HtmlAnchorElement>()
+        .unwrap();
+
+    // Create an Object URL from the Blob
+    let url = web_sys::Url::create_object_url_with_blob(blob).unwrap();
+
+    a.set_href(&url);
+    a.set_download(filename);
+    a.set_attribute("style", "display: none").unwrap();
+    document.body().unwrap().append_child(&a).unwrap();
+    a.click();
+    a.remove();
+
+    // Clean up the Object URL to free resources
+    web_sys::Url::revoke_object_url(&url).unwrap();
+}
@@ -0,0 +1,102 @@
+// The roster as a PNG picture, for posting it: the roster sheet of
+// render::card_svg drawn on an offscreen canvas. An SVG drawn as an image
+// can't load anything, so the artwork goes in as data urls first, taken
+// from the preloaded images (see assets::preloader). Artwork from another
+// origin without CORS can't be read back, the cards go without it then.
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement};
+use yew::Callback;
+
+use crate::assets::{paths, preloader};
+use crate::core::roster::Roster;
+use crate::export::download;
+use crate::render::card_svg::{self, CardTheme};
+use crate::render::icons::IconName;
+
+pub fn download_roster_image(roster: &Roster, theme: &CardTheme, on_done: Callback<Result<(), String>>) {
+    let mut urls: Vec<String> = Vec::new();
+    for entry in &roster.elements {
+        let image = entry.element.get_image();
+        let url = paths::image_url(&image);
+        if !image.is_empty() && IconName::for_element(&entry.element).is_none() && !urls.contains(&url) {
+            urls.push(url);
+        }
+    }
+
+    let roster = roster.clone();
+    let theme = theme.clone();
+    let is_started = Rc::new(Cell::new(false));
+    let artwork_urls = urls.clone();
+    preloader::preload_images(urls, Callback::from(move |(done, total)| {
+        if done < total || is_started.replace(true) {
+            return;
+        }
+        let data_urls: HashMap<&String, String> = artwork_urls.iter()
+            .filter_map(|url| Some((url, get_data_url(&preloader::get_loaded_image(url)?)?)))
+            .collect();
+        // Icons are drawn inline, any href but an empty one will do.
+        let (svg, width, height) = card_svg::roster_sheet(&roster, &theme, |entry| {
+            let url = paths::image_url(&entry.element.get_image());
+            data_urls.get(&url).cloned().unwrap_or(url)
+        });
+        let file_name = download::get_file_name(&roster.metadata.name, "png");
+        if let Err(e) = draw_svg_as_png(&svg, width, height, file_name, on_done.clone()) {
+            on_done.emit(Err(e));
+        }
+    }));
+}
+
+fn create_canvas(width: u32, height: u32) -> Result<(HtmlCanvasElement, CanvasRenderingContext2d), String> {
+    let canvas: HtmlCanvasElement = gloo::utils::document().create_element("canvas")
+        .map_err(|e| format!("{:?}", e))?
+        .unchecked_into();
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context = canvas.get_context("2d")
+        .map_err(|e| format!("{:?}", e))?
+        .ok_or("no 2D canvas in this browser")?
+        .unchecked_into();
+    Ok((canvas, context))
+}
+
+// None for an image of another origin, the canvas can't be read then.
+fn get_data_url(image: &HtmlImageElement) -> Option<String> {
+    let (canvas, context) = create_canvas(image.natural_width(), image.natural_height()).ok()?;
+    context.draw_image_with_html_image_element(image, 0.0, 0.0).ok()?;
+    canvas.to_data_url().ok()
+}
+
+fn draw_svg_as_png(svg: &str, width: u32, height: u32, file_name: String, on_done: Callback<Result<(), String>>) -> Result<(), String> {
+    let image = HtmlImageElement::new().map_err(|e| format!("{:?}", e))?;
+    let loaded_image = image.clone();
+    let on_loaded_done = on_done.clone();
+    let on_loaded = Closure::once_into_js(move || {
+        let result = create_canvas(width, height).and_then(|(canvas, context)| {
+            context.draw_image_with_html_image_element(&loaded_image, 0.0, 0.0).map_err(|e| format!("{:?}", e))?;
+            let on_blob_done = on_loaded_done.clone();
+            let on_blob = Closure::once_into_js(move |blob: JsValue| {
+                match blob.dyn_into::<web_sys::Blob>() {
+                    Ok(blob) => {
+                        download::download_blob(&blob, &file_name);
+                        on_blob_done.emit(Ok(()));
+                    }
+                    Err(_) => on_blob_done.emit(Err("the picture couldn't be encoded".to_string())),
+                }
+            });
+            canvas.to_blob(on_blob.unchecked_ref()).map_err(|e| format!("{:?}", e))
+        });
+        if let Err(e) = result {
+            on_loaded_done.emit(Err(e));
+        }
+    });
+    let on_failed = Closure::once_into_js(move || on_done.emit(Err("the roster couldn't be drawn".to_string())));
+    image.set_onload(Some(on_loaded.unchecked_ref()));
+    image.set_onerror(Some(on_failed.unchecked_ref()));
+    image.set_src(&format!("data:image/svg+xml;charset=utf-8,{}", js_sys::encode_uri_component(svg)));
+    Ok(())
+}
@@ -0,0 +1,11 @@
+// Everything that leaves the app: files, the clipboard, problem reports, and
+// sound for the readout and the audio cues.
+pub mod audio;
+pub mod battlescribe;
+pub mod clipboard;
+pub mod download;
+pub mod image;
+#[cfg(feature = "print")]
+pub mod print;
+pub mod report;
+pub mod speech;
@@ -0,0 +1,366 @@
+// Printing of the roster. A standalone HTML document is built from the
+// same SVG cards used on screen, then printed from a hidden iframe so the
+// app itself is left untouched.
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+
+use crate::assets::paths;
+use crate::core::breakdown::{self, Grouping};
+use crate::core::print_options::{PrintBranding, PrintLayout, PrintOptions};
+use crate::core::composed::ComposedElement;
+use crate::core::roster::{Roster, RosterEntry, Wave};
+use crate::core::rules;
+use crate::render::card_svg::{self, escape, CardTheme};
+
+const FONT_FAMILY: &str = "'Gill Sans', 'Gill Sans MT', Calibri, 'Trebuchet MS', sans-serif";
+
+// Size of a card on the double-sided sheets (poker size, same ratio as the
+// stat card) and the space between two of them.
+const CARD_WIDTH_MM: f32 = 63.5;
+const CARD_HEIGHT_MM: f32 = 88.9;
+const CARD_GAP_MM: f32 = 4.0;
+
+pub fn build_print_document(roster: &Roster, options: &PrintOptions, branding: &PrintBranding) -> String {
+    let total_points: u32 = roster.elements.iter().map(|entry| entry.get_total_points()).sum();
+
+    let mut body = r#"<h1>Full Spectrum Dominance - Roster</h1>"#.to_string();
+    if !card_svg::are_points_hidden() {
+        body.push_str(&format!(r#"<div class="total">Total Points: {}</div>"#, total_points));
+    }
+    if options.include_scenario {
+        if let Some(scenario) = &roster.scenario {
+            body.push_str(&render_scenario(scenario));
+        }
+    }
+
+    // Campaign records are dropped from the entries unless requested.
+    let entries: Vec<RosterEntry> = roster.elements.iter().map(|entry| {
+        let mut entry = entry.clone();
+        if !options.include_campaign {
+            entry.campaign = None;
+        }
+        entry
+    }).collect();
+
+    match options.layout {
+        PrintLayout::Cards if options.double_sided => body.push_str(&render_double_sided(&entries, options)),
+        PrintLayout::Cards => body.push_str(&render_cards(&entries, options)),
+        PrintLayout::List => body.push_str(&render_list(&entries)),
+        PrintLayout::CheatSheet => body = render_cheat_sheet(roster, &entries),
+    }
+    if !branding.is_empty() {
+        body.insert_str(0, &render_branding(branding));
+    }
+    // The cheat sheet already is its own summary. Otherwise the rules set
+    // aside, for the organizer to see, go with the summary when there's one.
+    if options.include_summary && options.layout != PrintLayout::CheatSheet {
+        body.push_str(&render_summary(roster));
+    } else {
+        for note in rules::get_suppression_notes(roster) {
+            body.push_str(&format!(r#"<div class="suppression">{}</div>"#, escape(&note)));
+        }
+    }
+
+    format!(
+        concat!(
+            r#"<!DOCTYPE html><html><head><meta charset="utf-8"><title>Roster</title>"#,
+            r#"<style>{}</style></head><body><div class="sheet">{}</div></body></html>"#),
+        print_styles(options), body)
+}
+
+// Prints the given document through a temporary iframe.
+pub fn print_document(html: &str) {
+    let document = gloo::utils::document();
+    let iframe = match document.create_element("iframe").map(|element| element.dyn_into::<web_sys::HtmlIFrameElement>()) {
+        Ok(Ok(iframe)) => iframe,
+        _ => {
+            log::warn!("Error creating the print frame");
+            return;
+        }
+    };
+    iframe.set_attribute("style", "position: fixed; width: 0; height: 0; border: none;").unwrap();
+
+    // Printing as soon as everything (images included) is loaded, then
+    // removing the frame once the print dialog is closed.
+    let iframe_clone = iframe.clone();
+    let on_load = Closure::once(move || {
+        if let Some(window) = iframe_clone.content_window() {
+            if let Err(e) = window.print() {
+                log::warn!("Error printing: {:?}", e);
+            }
+        }
+        iframe_clone.remove();
+    });
+    iframe.set_onload(Some(on_load.as_ref().unchecked_ref()));
+    on_load.forget();
+
+    iframe.set_srcdoc(html);
+    document.body().unwrap().append_child(&iframe).unwrap();
+}
+
+// Waves are only printed once the deployment board has been used.
+fn has_waves(entries: &[RosterEntry]) -> bool {
+    entries.iter().any(|entry| !entry.wave.is_first())
+}
+
+fn render_cards(entries: &[RosterEntry], options: &PrintOptions) -> String {
+    let theme = CardTheme::light();
+    let render = |entries: &mut dyn Iterator<Item = &RosterEntry>| -> String {
+        let cards: String = entries.map(|entry| {
+            let href = if options.include_images { paths::image_url(&entry.element.get_image()) } else { String::new() };
+            let card = card_svg::stat_card(entry, &theme, &href);
+            if options.include_notes { render_card_notes(entry, &card) } else { card }
+        }).collect();
+        format!(r#"<div class="cards">{}</div>"#, cards)
+    };
+    if !has_waves(entries) {
+        return render(&mut entries.iter());
+    }
+    Wave::ALL.iter().map(|wave| format!(
+        r#"<h2 class="wave">{}</h2>{}"#,
+        wave.get_label(), render(&mut entries.iter().filter(|entry| entry.wave == *wave)))).collect()
+}
+
+// The card with a box under it to write in, the note and the campaign record
+// of the entry already filled in (when printed, see include_campaign).
+fn render_card_notes(entry: &RosterEntry, card: &str) -> String {
+    let lines: String = (!entry.note.is_empty()).then(|| entry.note.clone()).into_iter()
+        .chain(entry.campaign.iter()
+            .flat_map(|record| record.injuries.iter().map(|injury| format!("Injury: {}", injury))
+                .chain(record.honors.iter().map(|honor| format!("Honor: {}", honor)))))
+        .map(|line| format!("<div>{}</div>", escape(&line)))
+        .collect();
+    format!(r#"<div class="card-with-notes">{}<div class="card-notes"><b>Notes</b>{}</div></div>"#, card, lines)
+}
+
+// Fronts and backs on alternating sheets. The backs of each row are laid
+// out right to left, so that once the sheet is flipped on its long edge
+// every back lands behind its own front. The title goes on a sheet of its
+// own, with an empty back, to keep the pairs of sheets together.
+fn render_double_sided(entries: &[RosterEntry], options: &PrintOptions) -> String {
+    let theme = CardTheme::light();
+    let (columns, rows) = get_card_grid(options);
+
+    let render_sheet = |slots: &[Option<usize>], back: bool| -> String {
+        let cards: String = slots.iter().map(|slot| match slot.and_then(|index| entries.get(index)) {
+            Some(entry) if back => card_svg::card_back(entry, &theme),
+            Some(entry) => {
+                let href = if options.include_images { paths::image_url(&entry.element.get_image()) } else { String::new() };
+                card_svg::stat_card(entry, &theme, &href)
+            }
+            None => r#"<div class="card-slot"></div>"#.to_string(),
+        }).collect();
+        format!(r#"<div class="page card-grid">{}</div>"#, cards)
+    };
+
+    let mut sheets = r#"<div class="page"></div>"#.to_string();
+    for (fronts, backs) in impose_cards(entries.len(), columns, rows) {
+        sheets.push_str(&render_sheet(&fronts, false));
+        sheets.push_str(&render_sheet(&backs, true));
+    }
+    sheets
+}
+
+// How many cards fit on a sheet, as columns and rows (at least one each).
+fn get_card_grid(options: &PrintOptions) -> (usize, usize) {
+    let (width, height) = options.paper_size.get_dimensions_mm();
+    let margin = options.get_margin_mm() as f32;
+    let fit = |available: f32, size: f32| (((available + CARD_GAP_MM) / (size + CARD_GAP_MM)).floor() as usize).max(1);
+    (fit(width - 2.0 * margin, CARD_WIDTH_MM), fit(height - 2.0 * margin, CARD_HEIGHT_MM))
+}
+
+// Index of the card in each slot of a sheet, None for an empty slot.
+type SheetSlots = Vec<Option<usize>>;
+
+// Splits the cards into sheets, each with the slots of its fronts and of its
+// backs in reading order. Rows are always full, with None for the empty
+// slots, otherwise the mirroring of a partial row would be off.
+fn impose_cards(count: usize, columns: usize, rows: usize) -> Vec<(SheetSlots, SheetSlots)> {
+    let per_sheet = columns * rows;
+    (0..count).step_by(per_sheet).map(|first| {
+        let last = (first + per_sheet).min(count);
+        let used_rows = (last - first).div_ceil(columns);
+        let fronts: SheetSlots = (0..used_rows * columns)
+            .map(|slot| Some(first + slot).filter(|index| *index < last))
+            .collect();
+        let backs = fronts.chunks(columns)
+            .flat_map(|row| row.iter().rev().copied())
+            .collect();
+        (fronts, backs)
+    }).collect()
+}
+
+fn render_list(entries: &[RosterEntry]) -> String {
+    let show_waves = has_waves(entries);
+    let mut rows = String::new();
+    for entry in entries {
+        let elem = &entry.element;
+        let attached: Vec<String> = elem.get_attached_elements().iter().map(|attached| attached.get_name()).collect();
+        let campaign = entry.campaign.as_ref().map(|record| record.get_badge_label()).unwrap_or_default();
+        rows.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td>{}<td>{}</td></tr>",
+            escape(&entry.get_counted_name()), elem.get_type_name(), escape(&attached.join(", ")),
+            escape(&campaign), if show_waves { format!("<td>{}</td>", entry.wave.get_label()) } else { String::new() },
+            card_svg::points_label(entry.get_total_points())));
+    }
+    format!(
+        r#"<table class="list"><tr><th>Name</th><th>Type</th><th>Attached</th><th>Campaign</th>{}<th>Points</th></tr>{}</table>"#,
+        if show_waves { "<th>Wave</th>" } else { "" }, rows)
+}
+
+// Single page summary for the table: one short row per piece of every
+// entry, grouped under the entry itself, and the scenario boiled down to
+// its victory conditions. Replaces the whole body, title included.
+fn render_cheat_sheet(roster: &Roster, entries: &[RosterEntry]) -> String {
+    let total_points: u32 = entries.iter().map(|entry| entry.get_total_points()).sum();
+    let show_waves = has_waves(entries);
+    let mut rows = String::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let composed = ComposedElement::from(&entry.element);
+        let rank = entry.campaign.as_ref().map(|record| record.rank.get_label()).unwrap_or_default();
+        let wave = if show_waves { format!(" ({})", entry.wave.get_short_label()) } else { String::new() };
+        rows.push_str(&format!(
+            r#"<tr class="entry"><td>{}</td><td>{}{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>"#,
+            index + 1, escape(&composed.name), wave, composed.type_name, escape(&composed.abilities.join("; ")),
+            rank, points_value(composed.total_points)));
+        if composed.is_grouped() {
+            for (role, part) in &composed.parts {
+                rows.push_str(&format!(
+                    r#"<tr class="part"><td></td><td>{} {}</td><td>{}</td><td></td><td></td><td>{}</td></tr>"#,
+                    role.get_label(), escape(&part.get_name()), part.get_type_name(), points_value(part.get_name_and_points().1)));
+            }
+        }
+    }
+
+    let scenario = roster.scenario.as_ref().map(|scenario| format!(
+        r#"<div class="cheat-scenario"><b>{}:</b> {}</div>"#,
+        escape(&scenario.name), escape(&scenario.victory_conditions.join("; ")))).unwrap_or_default();
+
+    let title = if card_svg::are_points_hidden() {
+        format!("Roster - {} entries", entries.len())
+    } else {
+        format!("Roster - {} pts - {} entries", total_points, entries.len())
+    };
+
+    format!(
+        concat!(
+            r#"<div class="cheat-sheet"><div class="cheat-title">{}</div>{}"#,
+            r#"<table><tr><th>#</th><th>Name</th><th>Type</th><th>Abilities</th><th>Rank</th><th>Pts</th></tr>{}</table></div>"#),
+        title, scenario, rows)
+}
+
+// Last page: entries and points of each type (and wave, once used), the
+// total against the limit, and the rules set aside.
+fn render_summary(roster: &Roster) -> String {
+    let are_points_hidden = card_svg::are_points_hidden();
+    let title = if roster.metadata.name.is_empty() { "Summary".to_string() } else { format!("Summary - {}", roster.metadata.name) };
+    let mut summary = format!(r#"<div class="page summary"><h2>{}</h2>"#, escape(&title));
+    for grouping in breakdown::get_groupings(roster) {
+        let rows: String = breakdown::get_breakdown(roster, grouping, None).iter().map(|group| format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+            group.label, group.entries, points_value(group.points))).collect();
+        let heading = if grouping == Grouping::Type { "Type" } else { "Wave" };
+        summary.push_str(&format!(
+            r#"<table class="list"><tr><th>{}</th><th>Entries</th><th>{}</th></tr>{}</table>"#,
+            heading, if are_points_hidden { "" } else { "Points" }, rows));
+    }
+
+    let total = roster.get_total_points();
+    if are_points_hidden {
+        summary.push_str(&format!(r#"<div class="total">{} entries</div>"#, roster.elements.len()));
+    } else {
+        let points_limit = roster.get_points_limit();
+        summary.push_str(&format!(r#"<div class="total">Total: {} / {} pts</div>"#, total, points_limit));
+        if roster.is_over_points_limit(points_limit) {
+            summary.push_str(&format!("<div>Over the limit by {} pts</div>", total - points_limit));
+        }
+    }
+    for note in rules::get_suppression_notes(roster) {
+        summary.push_str(&format!(r#"<div class="suppression">{}</div>"#, escape(&note)));
+    }
+    summary.push_str("</div>");
+    summary
+}
+
+// Header of the club or event, above everything else.
+fn render_branding(branding: &PrintBranding) -> String {
+    let mut header = r#"<header class="branding">"#.to_string();
+    if !branding.logo.is_empty() {
+        header.push_str(&format!(r#"<img class="logo" src="{}" alt="" />"#, escape(&branding.logo)));
+    }
+    header.push_str("<div>");
+    if !branding.event_name.trim().is_empty() {
+        header.push_str(&format!(r#"<div class="event-name">{}</div>"#, escape(&branding.event_name)));
+    }
+    if !branding.organizer_text.trim().is_empty() {
+        header.push_str(&format!(r#"<div class="organizer">{}</div>"#, escape(&branding.organizer_text)));
+    }
+    header.push_str("</div></header>");
+    header
+}
+
+// Bare number for the tables, empty when the points are hidden.
+fn points_value(points: u32) -> String {
+    if card_svg::are_points_hidden() { String::new() } else { points.to_string() }
+}
+
+fn render_scenario(scenario: &crate::core::scenario::Scenario) -> String {
+    let list = |items: &[String]| -> String {
+        items.iter().map(|item| format!("<li>{}</li>", escape(item))).collect()
+    };
+    format!(
+        concat!(
+            r#"<div class="scenario"><h2>Scenario: {}</h2><p>{}</p>"#,
+            "<h3>Special Rules</h3><ul>{}</ul><h3>Victory Conditions</h3><ul>{}</ul></div>"),
+        escape(&scenario.name), escape(&scenario.deployment),
+        list(&scenario.special_rules), list(&scenario.victory_conditions))
+}
+
+fn print_styles(options: &PrintOptions) -> String {
+    let (width, height) = options.paper_size.get_dimensions_mm();
+    let margin = options.get_margin_mm();
+    // On card stock every card gets its own sheet.
+    let card_break = if options.paper_size.is_card_stock() { "break-after: page;" } else { "" };
+    let (columns, _) = get_card_grid(options);
+    format!(
+        concat!(
+            "@page {{ size: {width}mm {height}mm; margin: {margin}mm; }} ",
+            "body {{ font-family: {font}; margin: 0; }} ",
+            "h1 {{ text-transform: uppercase; font-size: 20px; margin: 0; }} ",
+            ".total {{ font-weight: bold; font-size: 16px; margin-bottom: 12px; }} ",
+            ".suppression {{ font-style: italic; font-size: 12px; margin-top: 6px; }} ",
+            ".card-with-notes {{ display: flex; flex-direction: column; break-inside: avoid; }} ",
+            ".card-notes {{ min-height: 30mm; border: 1px solid #999; padding: 2mm; font-size: 11px; ",
+            "background-image: repeating-linear-gradient(transparent, transparent 6mm, #ddd 6mm, #ddd calc(6mm + 1px)); }} ",
+            ".branding {{ display: flex; align-items: center; gap: 4mm; margin-bottom: 4mm; padding-bottom: 2mm; border-bottom: 1px solid #999; }} ",
+            ".branding .logo {{ max-height: 18mm; max-width: 40mm; }} ",
+            ".branding .event-name {{ font-weight: bold; font-size: 18px; }} ",
+            ".branding .organizer {{ font-size: 12px; color: #555; white-space: pre-line; }} ",
+            ".summary h2 {{ text-transform: uppercase; font-size: 18px; }} ",
+            ".summary .list {{ margin-bottom: 12px; }} ",
+            ".cards {{ display: flex; flex-wrap: wrap; gap: 4mm; }} ",
+            ".card-svg {{ break-inside: avoid; max-width: 100%; height: auto; {card_break} }} ",
+            ".list {{ width: 100%; border-collapse: collapse; }} ",
+            ".list th, .list td {{ border-bottom: 1px solid #ccc; padding: 4px; text-align: left; }} ",
+            ".scenario {{ break-inside: avoid; margin-bottom: 12px; }} ",
+            ".wave {{ text-transform: uppercase; font-size: 16px; margin: 12px 0 6px; }} ",
+            ".page {{ break-before: page; }} ",
+            ".cheat-sheet {{ font-size: 8px; }} ",
+            ".cheat-title {{ font-weight: bold; font-size: 11px; text-transform: uppercase; }} ",
+            ".cheat-sheet table {{ width: 100%; border-collapse: collapse; }} ",
+            ".cheat-sheet th, .cheat-sheet td {{ padding: 1px 3px; text-align: left; border-bottom: 1px solid #ddd; }} ",
+            ".cheat-sheet .entry {{ font-weight: bold; }} ",
+            ".cheat-sheet .part td {{ color: #555; border-bottom: none; }} ",
+            ".card-grid {{ display: grid; grid-template-columns: repeat({columns}, {card_width}mm); ",
+            "grid-auto-rows: {card_height}mm; gap: {card_gap}mm; justify-content: center; }} ",
+            ".card-grid .card-svg {{ width: 100%; height: 100%; break-after: auto; }} ",
+            // On screen, i.e. in the preview, the sheet is drawn with its margins.
+            "@media screen {{ ",
+            "html {{ background: #888; zoom: 0.5; }} ",
+            ".sheet {{ box-sizing: border-box; width: {width}mm; min-height: {height}mm; padding: {margin}mm; ",
+            "margin: 8mm auto; background: white; box-shadow: 0 0 4mm rgba(0, 0, 0, 0.5); ",
+            "background-image: linear-gradient(to bottom, transparent calc({height}mm - 1px), #ccc calc({height}mm - 1px), #ccc {height}mm); ",
+            "background-size: 100% {height}mm; }} }}"),
+        width = width, height = height, margin = margin, card_break = card_break, font = FONT_FAMILY,
+        columns = columns, card_width = CARD_WIDTH_MM, card_height = CARD_HEIGHT_MM, card_gap = CARD_GAP_MM)
+}
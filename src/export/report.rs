@@ -0,0 +1,61 @@
+// Problem report: what's needed to look into a bug, as text to paste into a
+// GitHub issue. It's only built when asked for and shown to the user before
+// anything leaves the app, and it leaves out the names and notes written by
+// the user.
+use crate::core::armylist::get_catalog_edition;
+use crate::core::roster::{Roster, RosterElement};
+use crate::models::features::{Feature, FeatureFlags};
+use crate::models::log_buffer;
+use crate::models::settings::Settings;
+
+pub const ISSUES_URL: &str = concat!(env!("CARGO_PKG_REPOSITORY"), "/issues/new");
+
+pub fn build_report(roster: &Roster, settings: &Settings, features: &FeatureFlags) -> String {
+    let user_agent = gloo::utils::window().navigator().user_agent().unwrap_or_default();
+    let enabled_features: Vec<&str> = Feature::ALL.iter()
+        .filter(|feature| features.is_enabled(**feature))
+        .map(|feature| feature.get_key())
+        .collect();
+
+    let mut lines = vec![
+        "### Problem report".to_string(),
+        "(Describe what happened here)".to_string(),
+        String::new(),
+        format!("App version: {}", env!("CARGO_PKG_VERSION")),
+        format!("Catalog edition: {}", get_catalog_edition()),
+        format!("Browser: {}", user_agent),
+        format!("Features: {}", enabled_features.join(", ")),
+        format!("Custom assets: {}, points hidden: {}, audio cues: {}",
+            settings.asset_base.is_some(), settings.hide_points, settings.audio_cues),
+        String::new(),
+        format!("Roster: {} entries, {} points, scenario: {}",
+            roster.elements.len(), roster.get_total_points(), roster.scenario.is_some()),
+    ];
+    lines.extend(roster.elements.iter().map(|entry| {
+        let mut line = format!("- {}", describe_element(&entry.element));
+        let attached = entry.element.get_attached_elements();
+        if !attached.is_empty() {
+            let attached: Vec<String> = attached.iter().map(describe_element).collect();
+            line.push_str(&format!(", with {}", attached.join(", ")));
+        }
+        if entry.campaign.is_some() {
+            line.push_str(", campaign record");
+        }
+        line
+    }));
+    if let Err(errors) = roster.check_invariants() {
+        lines.push(format!("Invariants broken: {}", errors.join("; ")));
+    }
+
+    lines.push(String::new());
+    lines.push("Recent log:".to_string());
+    lines.push("```".to_string());
+    lines.extend(log_buffer::get_recent_entries());
+    lines.push("```".to_string());
+    lines.join("\n")
+}
+
+// Only the kind and the points: any name can be changed by the user.
+fn describe_element(element: &RosterElement) -> String {
+    format!("{} ({} pts)", element.get_type_name(), element.get_name_and_points().1)
+}
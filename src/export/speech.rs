@@ -0,0 +1,59 @@
+// Reading the roster aloud through the Web Speech API, to check a list
+// without looking at the screen.
+use crate::core::roster::Roster;
+use crate::render::card_svg;
+
+// The text read aloud: every entry with what's attached to it, then the total.
+pub fn build_readout(roster: &Roster) -> String {
+    if roster.elements.is_empty() {
+        return "The roster is empty.".to_string();
+    }
+
+    let mut sentences = Vec::<String>::new();
+    for entry in &roster.elements {
+        let elem = &entry.element;
+        let mut sentence = entry.get_counted_name();
+        if !card_svg::are_points_hidden() {
+            sentence.push_str(&format!(", {}", card_svg::points_label(entry.get_total_points())));
+        }
+        let attached: Vec<String> = elem.get_attached_elements().iter().map(|attached| attached.get_name()).collect();
+        if !attached.is_empty() {
+            sentence.push_str(&format!(", with {}", attached.join(" and ")));
+        }
+        sentences.push(sentence);
+    }
+
+    let total_points: u32 = roster.elements.iter().map(|entry| entry.get_total_points()).sum();
+    if card_svg::are_points_hidden() {
+        sentences.push(format!("{} entries in total", roster.elements.len()));
+    } else {
+        sentences.push(format!("{} entries, {} in total", roster.elements.len(), card_svg::points_label(total_points)));
+    }
+    sentences.join(". ") + "."
+}
+
+// Starts reading, cutting short anything still being read.
+pub fn speak(text: &str) {
+    let synthesis = match gloo::utils::window().speech_synthesis() {
+        Ok(synthesis) => synthesis,
+        Err(e) => {
+            log::info!("Speech synthesis not available: {:?}", e);
+            return;
+        }
+    };
+    synthesis.cancel();
+    match web_sys::SpeechSynthesisUtterance::new_with_text(text) {
+        Ok(utterance) => synthesis.speak(&utterance),
+        Err(e) => log::warn!("Error creating the readout: {:?}", e),
+    }
+}
+
+pub fn stop() {
+    if let Ok(synthesis) = gloo::utils::window().speech_synthesis() {
+        synthesis.cancel();
+    }
+}
+
+pub fn is_speaking() -> bool {
+    gloo::utils::window().speech_synthesis().map(|synthesis| synthesis.speaking()).unwrap_or(false)
+}
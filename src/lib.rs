@@ -1,22 +1,67 @@
+// The builds without some of the panels leave what only they use behind,
+// see the features in Cargo.toml.
+#![cfg_attr(not(all(feature = "qr", feature = "print", feature = "editors")), allow(dead_code))]
+
 mod components {
     pub mod top_menu;
     pub mod left_bar;
     pub mod right_bar;
     pub mod main_canvas;
+    pub mod icon;
+    pub mod settings_panel;
+    pub mod tooltip;
+    pub mod campaign_editor;
+    #[cfg(feature = "editors")]
+    pub mod campaign_dashboard;
+    #[cfg(feature = "editors")]
+    pub mod campaign_standings;
+    #[cfg(feature = "editors")]
+    pub mod scenario_editor;
+    #[cfg(feature = "print")]
+    pub mod print_dialog;
+    pub mod history_panel;
+    pub mod overlay;
+    pub mod presentation_dialog;
+    #[cfg(feature = "qr")]
+    pub mod qr_dialog;
+    pub mod play_mode;
+    pub mod roster_totals;
+    pub mod profiler_overlay;
+    pub mod report_dialog;
+    pub mod repair_wizard;
+    pub mod roster_manager;
+    pub mod recovery_prompt;
+    pub mod swap_dialog;
+    pub mod spectator;
 }
 mod app;
+mod assets;
+mod export;
+pub mod core;
 mod models;
+mod render;
 mod shared_messages;
 
-use wasm_bindgen::prelude::*;
 use crate::app::App;
+use crate::components::spectator::{self, Spectator};
 use web_sys::window;
 
-#[wasm_bindgen(start)]
+// Not in the tests, they render what they need themselves.
+#[cfg_attr(not(test), wasm_bindgen::prelude::wasm_bindgen(start))]
 pub fn run_app() {
+    models::log_buffer::init();
+    models::session::install_panic_hook();
     let window = window().expect("no global `window` exists");
     let document = window.document().expect("should have a document on window");
     let element = document.get_element_by_id("app").expect("no element with id 'app'");
 
-    yew::Renderer::<App>::with_root(element).render();
+    // Spectators of a game shared live only get the game, not the app.
+    match models::live::get_page_follow_link() {
+        Some(link) => {
+            yew::Renderer::<Spectator>::with_root_and_props(element, spectator::Props {link}).render();
+        }
+        None => {
+            yew::Renderer::<App>::with_root(element).render();
+        }
+    }
 }
@@ -0,0 +1,70 @@
+// A small typed pub/sub bus, for components that aren't parent and child.
+// Every message type is its own topic: a MetadataMessage only reaches the
+// subscribers of MetadataMessage, so a new domain gets a type of its own
+// instead of new arms in the match of every component.
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use yew::Callback;
+
+struct Subscribers<T> {
+    next_id: usize,
+    list: Vec<(usize, Callback<T>)>,
+}
+
+thread_local! {
+    static TOPICS: RefCell<HashMap<TypeId, Rc<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+fn get_topic<T: 'static>() -> Rc<RefCell<Subscribers<T>>> {
+    let topic = TOPICS.with(|topics| {
+        topics.borrow_mut()
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Rc::new(RefCell::new(Subscribers::<T> {next_id: 0, list: Vec::new()})))
+            .clone()
+    });
+    topic.downcast::<RefCell<Subscribers<T>>>().expect("Topics are keyed by their message type")
+}
+
+// Ends the subscription when dropped, to be kept as long as the component.
+pub struct Subscription {
+    unsubscribe: Option<Box<dyn FnOnce()>>,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(unsubscribe) = self.unsubscribe.take() {
+            unsubscribe();
+        }
+    }
+}
+
+pub fn subscribe<T: 'static>(callback: Callback<T>) -> Subscription {
+    let topic = get_topic::<T>();
+    let id = {
+        let mut subscribers = topic.borrow_mut();
+        let id = subscribers.next_id;
+        subscribers.next_id += 1;
+        subscribers.list.push((id, callback));
+        id
+    };
+    let topic = Rc::downgrade(&topic);
+    Subscription {
+        unsubscribe: Some(Box::new(move || {
+            if let Some(topic) = topic.upgrade() {
+                topic.borrow_mut().list.retain(|(subscriber, _)| *subscriber != id);
+            }
+        })),
+    }
+}
+
+// The subscribers are called in the order they subscribed. One of them may
+// subscribe or publish in turn, the list is copied before.
+pub fn publish<T: Clone + 'static>(message: T) {
+    let callbacks: Vec<Callback<T>> = get_topic::<T>().borrow().list.iter().map(|(_, callback)| callback.clone()).collect();
+    for callback in callbacks {
+        callback.emit(message.clone());
+    }
+}
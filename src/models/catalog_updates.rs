@@ -0,0 +1,54 @@
+// Catalog updates published next to the static assets, as
+// catalog/delta.json (see core::catalog_delta). The last one is kept in
+// the browser storage and applied at startup, before anything is read from
+// the catalog, then the published one is checked for in the background: a
+// few hundred bytes, rather than the whole app again.
+use gloo::net::http::Request;
+use gloo::storage::{LocalStorage, Storage};
+use web_sys::RequestCache;
+use yew::Callback;
+
+use crate::assets::paths;
+use crate::core::armylist;
+use crate::core::catalog_delta::CatalogDelta;
+
+const DELTA_KEY: &str = "fsd_builder.catalog_delta";
+const DELTA_PATH: &str = "catalog/delta.json";
+
+// An update kept from before that doesn't apply any more (the app itself
+// was updated since) is dropped.
+pub fn apply_saved_delta() {
+    let Ok(delta) = LocalStorage::get::<CatalogDelta>(DELTA_KEY) else {
+        return;
+    };
+    if let Err(e) = armylist::set_catalog_delta(Some(delta)) {
+        log::warn!("Dropping the saved catalog update: {}", e);
+        LocalStorage::delete(DELTA_KEY);
+    }
+}
+
+// The edition of the update once applied, None when there's nothing new.
+pub fn check_for_update(on_done: Callback<Result<Option<String>, String>>) {
+    let request = Request::get(&paths::asset_url(DELTA_PATH)).cache(RequestCache::NoCache);
+    yew::platform::spawn_local(async move {
+        let result = async {
+            let response = request.send().await.map_err(|e| e.to_string())?;
+            if response.status() == 404 {
+                return Ok(None);
+            }
+            if !response.ok() {
+                return Err(format!("{} {}", response.status(), response.status_text()));
+            }
+            let delta = CatalogDelta::from_json(&response.text().await.map_err(|e| e.to_string())?)?;
+            if delta.result_hash == armylist::get_catalog_hash() {
+                return Ok(None);
+            }
+            armylist::set_catalog_delta(Some(delta.clone()))?;
+            if let Err(e) = LocalStorage::set(DELTA_KEY, &delta) {
+                log::warn!("Error saving the catalog update: {:?}", e);
+            }
+            Ok(Some(delta.edition))
+        };
+        on_done.emit(result.await);
+    });
+}
@@ -0,0 +1,76 @@
+// The time as the app sees it: timestamps of the slots, snapshots and
+// revisions, the game clock of the play mode, the submission deadline and
+// the profiler timings. The browser clock unless another one is set, e.g.
+// a shifted one from the url (?clock=2026-05-01T17:55:00Z) to try how the
+// deadline or the daily revisions behave without waiting for the day. Only
+// debug builds take another clock, a shared link can't move the deadline.
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub trait Clock {
+    // Milliseconds since the epoch, as Date.now()
+    fn now_ms(&self) -> f64;
+
+    // For measuring durations only, from an arbitrary start.
+    fn monotonic_ms(&self) -> f64 {
+        gloo::utils::window().performance().map(|performance| performance.now()).unwrap_or_else(|| self.now_ms())
+    }
+}
+
+pub struct BrowserClock;
+
+impl Clock for BrowserClock {
+    fn now_ms(&self) -> f64 {
+        js_sys::Date::now()
+    }
+}
+
+// Runs from another moment than now, at the same pace.
+#[cfg(debug_assertions)]
+pub struct ShiftedClock {
+    offset_ms: f64,
+}
+
+#[cfg(debug_assertions)]
+impl ShiftedClock {
+    // None if the time can't be read.
+    pub fn starting_at(time: &str) -> Option<ShiftedClock> {
+        let start = js_sys::Date::parse(time);
+        (!start.is_nan()).then(|| ShiftedClock {offset_ms: start - js_sys::Date::now()})
+    }
+}
+
+#[cfg(debug_assertions)]
+impl Clock for ShiftedClock {
+    fn now_ms(&self) -> f64 {
+        js_sys::Date::now() + self.offset_ms
+    }
+}
+
+thread_local! {
+    static CLOCK: RefCell<Rc<dyn Clock>> = RefCell::new(Rc::new(BrowserClock));
+}
+
+#[cfg(debug_assertions)]
+pub fn set_clock(clock: Rc<dyn Clock>) {
+    CLOCK.with(|current| *current.borrow_mut() = clock);
+}
+
+pub fn now_ms() -> f64 {
+    CLOCK.with(|clock| clock.borrow().now_ms())
+}
+
+pub fn monotonic_ms() -> f64 {
+    CLOCK.with(|clock| clock.borrow().monotonic_ms())
+}
+
+// Local date and time, as "YYYY-MM-DD HH:MM".
+pub fn get_timestamp() -> String {
+    let now = js_sys::Date::new(&now_ms().into());
+    format!("{:04}-{:02}-{:02} {:02}:{:02}", now.get_full_year(), now.get_month() + 1, now.get_date(), now.get_hours(), now.get_minutes())
+}
+
+// Local date, as "YYYY-MM-DD".
+pub fn get_date() -> String {
+    get_timestamp().chars().take(10).collect()
+}
@@ -0,0 +1,66 @@
+// Submission deadline of the event the builder was set up for, see
+// core::event_pack. The deadline is read by the browser, so a time with an
+// offset (2026-05-01T18:00:00+02:00) is the same moment wherever the player
+// is, and shown in their own time zone.
+use std::cell::RefCell;
+
+use wasm_bindgen::JsValue;
+
+use crate::models::clock;
+
+thread_local! {
+    // Milliseconds since the epoch, and the deadline as written in the pack
+    static DEADLINE: RefCell<Option<(f64, String)>> = const { RefCell::new(None) };
+}
+
+const MINUTE_MS: f64 = 60.0 * 1000.0;
+
+// Returns false if the deadline can't be read.
+pub fn set_deadline(deadline: Option<&str>) -> bool {
+    let parsed = deadline.map(|deadline| (js_sys::Date::parse(deadline), deadline.to_string()));
+    let is_valid = parsed.as_ref().is_none_or(|(time, _)| !time.is_nan());
+    DEADLINE.with(|current| *current.borrow_mut() = parsed.filter(|(time, _)| !time.is_nan()));
+    is_valid
+}
+
+fn get_deadline_ms() -> Option<f64> {
+    DEADLINE.with(|deadline| deadline.borrow().as_ref().map(|(time, _)| *time))
+}
+
+pub fn is_past_deadline() -> bool {
+    get_deadline_ms().is_some_and(|deadline| clock::now_ms() >= deadline)
+}
+
+// The deadline in the time zone of the player.
+fn get_local_label(deadline: f64) -> String {
+    js_sys::Date::new(&JsValue::from_f64(deadline))
+        .to_locale_string("default", &JsValue::UNDEFINED)
+        .as_string()
+        .unwrap_or_default()
+}
+
+// "Lists are due in 2 d 4 h (01/05/2026, 18:00:00)", None without a deadline.
+pub fn get_countdown_label() -> Option<String> {
+    let deadline = get_deadline_ms()?;
+    let local = get_local_label(deadline);
+    let minutes = ((deadline - clock::now_ms()) / MINUTE_MS).ceil();
+    if minutes <= 0.0 {
+        return Some(format!("The submission deadline has passed ({})", local));
+    }
+    let minutes = minutes as u64;
+    let remaining = match (minutes / (24 * 60), minutes / 60 % 24, minutes % 60) {
+        (0, 0, minutes) => format!("{} min", minutes),
+        (0, hours, minutes) => format!("{} h {} min", hours, minutes),
+        (days, hours, _) => format!("{} d {} h", days, hours),
+    };
+    Some(format!("Lists are due in {} ({})", remaining, local))
+}
+
+// Past the deadline, the player is asked before anything is exported.
+pub fn confirm_export() -> bool {
+    let Some(deadline) = get_deadline_ms().filter(|_| is_past_deadline()) else {
+        return true;
+    };
+    let message = format!("The submission deadline ({}) has passed. Export anyway?", get_local_label(deadline));
+    gloo::utils::window().confirm_with_message(&message).unwrap_or(true)
+}
@@ -0,0 +1,79 @@
+// Runtime feature flags, so experimental parts of the app can ship turned
+// off and be turned on by whoever wants to try them, without a separate
+// build. Each flag can be set in the settings and overridden from the url,
+// e.g. ?features=play-mode,-presentation (a leading minus turns it off).
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Feature {
+    PlayMode,
+    Presentation,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 2] = [Feature::PlayMode, Feature::Presentation];
+
+    // Name in the settings and in the url.
+    pub fn get_key(&self) -> &'static str {
+        match self {
+            Feature::PlayMode => "play-mode",
+            Feature::Presentation => "presentation",
+        }
+    }
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Feature::PlayMode => "Play mode (clock, deployment, activations)",
+            Feature::Presentation => "Presentation mode for streaming",
+        }
+    }
+
+    // Whether the feature is on for users who didn't choose.
+    pub fn is_enabled_by_default(&self) -> bool {
+        match self {
+            Feature::PlayMode => true,
+            Feature::Presentation => true,
+        }
+    }
+
+    fn find_by_key(key: &str) -> Option<Feature> {
+        Feature::ALL.into_iter().find(|feature| feature.get_key() == key)
+    }
+}
+
+// The flags in effect: the defaults, then the settings, then the url.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FeatureFlags {
+    overrides : Vec<(Feature, bool)>,
+}
+
+impl FeatureFlags {
+    // Unknown keys are ignored, they may come from a newer or older version.
+    pub fn new(settings: &BTreeMap<String, bool>, query: &str) -> FeatureFlags {
+        let mut overrides: Vec<(Feature, bool)> = settings.iter()
+            .filter_map(|(key, is_enabled)| Some((Feature::find_by_key(key)?, *is_enabled)))
+            .collect();
+
+        let url_flags = query.trim_start_matches('?').split('&')
+            .filter_map(|pair| pair.strip_prefix("features="))
+            .flat_map(|value| value.split(','));
+        for flag in url_flags {
+            let (key, is_enabled) = match flag.strip_prefix('-') {
+                Some(key) => (key, false),
+                None => (flag, true),
+            };
+            if let Some(feature) = Feature::find_by_key(key) {
+                overrides.push((feature, is_enabled));
+            }
+        }
+        FeatureFlags {overrides}
+    }
+
+    // The last override wins.
+    pub fn is_enabled(&self, feature: Feature) -> bool {
+        self.overrides.iter().rev()
+            .find(|(overridden, _)| *overridden == feature)
+            .map(|(_, is_enabled)| *is_enabled)
+            .unwrap_or(feature.is_enabled_by_default())
+    }
+}
@@ -0,0 +1,337 @@
+// State of the game being played with the roster, kept in the browser so a
+// reload in the middle of a game loses nothing.
+use serde::{Serialize, Deserialize};
+use gloo::storage::{LocalStorage, Storage};
+
+const GAME_KEY: &str = "fsd_builder.game";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlayerClock {
+    pub name : String,
+    // Time left when the clock was last stopped
+    pub remaining_ms : f64,
+}
+
+// Chess clock: one countdown per player, only the active one running.
+// Times are timestamps in milliseconds (from models::clock), so a running
+// clock keeps counting while the page is closed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameClock {
+    pub is_enabled : bool,
+    pub total_minutes : u32,
+    // Minutes left at which the player is warned
+    pub alert_minutes : Vec<u32>,
+    pub players : Vec<PlayerClock>,
+    pub active_player : Option<usize>,
+    pub running_since : Option<f64>,
+}
+
+impl Default for GameClock {
+    fn default() -> Self {
+        let mut clock = GameClock {
+            is_enabled: false,
+            total_minutes: 60,
+            alert_minutes: vec![10, 1],
+            players: vec![
+                PlayerClock {name: "Player 1".to_string(), remaining_ms: 0.0},
+                PlayerClock {name: "Player 2".to_string(), remaining_ms: 0.0},
+            ],
+            active_player: None,
+            running_since: None,
+        };
+        clock.reset();
+        clock
+    }
+}
+
+impl GameClock {
+    // Every player back to the full time, clock stopped.
+    pub fn reset(&mut self) {
+        let total_ms = self.total_minutes as f64 * 60_000.0;
+        for player in &mut self.players {
+            player.remaining_ms = total_ms;
+        }
+        self.active_player = None;
+        self.running_since = None;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    pub fn get_remaining_ms(&self, player: usize, now: f64) -> f64 {
+        let Some(clock) = self.players.get(player) else {
+            return 0.0;
+        };
+        let elapsed = match (self.active_player, self.running_since) {
+            (Some(active), Some(since)) if active == player => now - since,
+            _ => 0.0,
+        };
+        (clock.remaining_ms - elapsed).max(0.0)
+    }
+
+    pub fn is_expired(&self, player: usize, now: f64) -> bool {
+        self.get_remaining_ms(player, now) <= 0.0
+    }
+
+    // Starts the clock of the given player, stopping the running one.
+    pub fn start(&mut self, player: usize, now: f64) {
+        self.pause(now);
+        if player < self.players.len() {
+            self.active_player = Some(player);
+            self.running_since = Some(now);
+        }
+    }
+
+    // Stops the running clock, the active player stays the same.
+    pub fn pause(&mut self, now: f64) {
+        if let Some(active) = self.active_player {
+            let remaining = self.get_remaining_ms(active, now);
+            self.players[active].remaining_ms = remaining;
+        }
+        self.running_since = None;
+    }
+
+    // End of the turn: the clock of the next player starts.
+    pub fn switch(&mut self, now: f64) {
+        let next = self.active_player.map(|active| (active + 1) % self.players.len()).unwrap_or(0);
+        self.start(next, now);
+    }
+
+    // The alert threshold (in minutes) crossed by the active player between
+    // the two instants, if any.
+    pub fn get_crossed_alert(&self, before: f64, now: f64) -> Option<u32> {
+        let active = self.active_player?;
+        let (remaining_before, remaining_now) = (self.get_remaining_ms(active, before), self.get_remaining_ms(active, now));
+        self.alert_minutes.iter().copied().find(|minutes| {
+            let threshold = *minutes as f64 * 60_000.0;
+            remaining_before > threshold && remaining_now <= threshold
+        })
+    }
+}
+
+// Order in which the roster entries are planned to activate, independent
+// from the order of the list. Entries are referenced by their index in the
+// roster, so the plan is fitted back to the roster whenever it's used. Each
+// copy of an entry activates on its own, one after the other.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActivationPlan {
+    pub order : Vec<usize>,
+    // Position in the order of the entry activating now
+    pub current : Option<usize>,
+    // Copy of that entry activating now, from 1
+    pub copy : u32,
+    pub round : u32,
+}
+
+impl ActivationPlan {
+    // The order for a roster of the given size: entries no longer there are
+    // dropped, new ones are activated last.
+    pub fn get_order(&self, entry_count: usize) -> Vec<usize> {
+        let mut order: Vec<usize> = self.order.iter().copied().filter(|index| *index < entry_count).collect();
+        for index in 0..entry_count {
+            if !order.contains(&index) {
+                order.push(index);
+            }
+        }
+        order
+    }
+
+    // Moves the entry at one position of the order to another one.
+    pub fn move_entry(&mut self, entry_count: usize, from: usize, to: usize) {
+        let mut order = self.get_order(entry_count);
+        if from >= order.len() || to >= order.len() {
+            return;
+        }
+        let entry = order.remove(from);
+        order.insert(to, entry);
+        self.order = order;
+    }
+
+    // Next copy of the entry activating, or the next entry in the order,
+    // starting a new round after the last one. The quantities are the copies
+    // of each entry, by index in the roster.
+    pub fn next(&mut self, quantities: &[u32]) {
+        if quantities.is_empty() {
+            return;
+        }
+        let order = self.get_order(quantities.len());
+        let (current, copy) = match self.current {
+            None => {
+                self.round = self.round.max(1);
+                (0, 1)
+            }
+            Some(position) if order.get(position).is_some_and(|index| self.copy < quantities[*index]) => (position, self.copy + 1),
+            Some(position) if position + 1 >= order.len() => {
+                self.round += 1;
+                (0, 1)
+            }
+            Some(position) => (position + 1, 1),
+        };
+        self.current = Some(current);
+        self.copy = copy;
+    }
+
+    pub fn reset(&mut self) {
+        self.current = None;
+        self.copy = 0;
+        self.round = 0;
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Deployment {
+    #[default]
+    Deployed,
+    Reserve,
+    Infiltrating,
+}
+
+impl Deployment {
+    pub const ALL: [Deployment; 3] = [Deployment::Deployed, Deployment::Reserve, Deployment::Infiltrating];
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Deployment::Deployed => "Deployed",
+            Deployment::Reserve => "Reserve",
+            Deployment::Infiltrating => "Infiltrating",
+        }
+    }
+}
+
+// Share of the activations of the roster (copies of its entries) allowed off
+// the table at the start of a standard game, in percent.
+const MAX_RESERVE_PERCENT: u32 = 50;
+const MAX_INFILTRATING_PERCENT: u32 = 25;
+
+// Where each roster entry starts the game, by index in the roster like the
+// activation plan. Entries without an assignment are deployed.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeploymentPlan {
+    pub assignments : Vec<Deployment>,
+}
+
+impl DeploymentPlan {
+    pub fn get(&self, index: usize) -> Deployment {
+        self.assignments.get(index).copied().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, index: usize, deployment: Deployment) {
+        if self.assignments.len() <= index {
+            self.assignments.resize(index + 1, Deployment::default());
+        }
+        self.assignments[index] = deployment;
+    }
+
+    // Copies starting the game so, the quantities being the copies of each
+    // entry by index in the roster.
+    pub fn count(&self, quantities: &[u32], deployment: Deployment) -> u32 {
+        quantities.iter().enumerate()
+            .filter(|(index, _)| self.get(*index) == deployment)
+            .map(|(_, quantity)| quantity)
+            .sum()
+    }
+
+    // Broken limits, as messages for the player.
+    pub fn validate(&self, quantities: &[u32]) -> Vec<String> {
+        let mut errors = Vec::<String>::new();
+        let copies: u32 = quantities.iter().sum();
+        let limits = [(Deployment::Reserve, MAX_RESERVE_PERCENT), (Deployment::Infiltrating, MAX_INFILTRATING_PERCENT)];
+        for (deployment, max_percent) in limits {
+            let count = self.count(quantities, deployment);
+            let max_count = copies * max_percent / 100;
+            if count > max_count {
+                errors.push(format!(
+                    "{} {} activations, at most {} allowed ({}% of the roster)",
+                    count, deployment.get_label(), max_count, max_percent));
+            }
+        }
+        errors
+    }
+}
+
+// Losses and scores marked during the game, what the spectators follow
+// (see models::live). Entries by index in the roster like the plans above,
+// players by index in the clock.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameTracker {
+    // Copies of each entry lost
+    pub casualties : Vec<u32>,
+    pub scores : Vec<u32>,
+}
+
+impl GameTracker {
+    pub fn get_casualties(&self, index: usize) -> u32 {
+        self.casualties.get(index).copied().unwrap_or(0)
+    }
+
+    // One more copy of the entry lost, up to all of them.
+    pub fn add_casualty(&mut self, index: usize, quantity: u32) {
+        if self.casualties.len() <= index {
+            self.casualties.resize(index + 1, 0);
+        }
+        self.casualties[index] = (self.casualties[index] + 1).min(quantity);
+    }
+
+    pub fn remove_casualty(&mut self, index: usize) {
+        if let Some(casualties) = self.casualties.get_mut(index) {
+            *casualties = casualties.saturating_sub(1);
+        }
+    }
+
+    pub fn get_score(&self, player: usize) -> u32 {
+        self.scores.get(player).copied().unwrap_or(0)
+    }
+
+    pub fn change_score(&mut self, player: usize, change: i32) {
+        if self.scores.len() <= player {
+            self.scores.resize(player + 1, 0);
+        }
+        self.scores[player] = self.scores[player].saturating_add_signed(change);
+    }
+}
+
+// Where the game is shared live on the sync server, see core::live.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiveRecord {
+    pub id : String,
+    // Of the last accepted push
+    pub revision : u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameState {
+    pub clock : GameClock,
+    pub activation : ActivationPlan,
+    pub deployment : DeploymentPlan,
+    pub tracker : GameTracker,
+    pub live : Option<LiveRecord>,
+}
+
+impl GameState {
+    pub fn load() -> GameState {
+        LocalStorage::get(GAME_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        if let Err(e) = LocalStorage::set(GAME_KEY, self) {
+            log::warn!("Error saving game: {:?}", e);
+        }
+    }
+}
+
+// Remaining time as m:ss (or h:mm:ss past the hour).
+pub fn format_clock(ms: f64) -> String {
+    let seconds = (ms / 1000.0).ceil() as u64;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
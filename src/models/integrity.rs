@@ -0,0 +1,103 @@
+// Checks of the stored data at startup. What's found corrupt isn't dropped
+// nor trusted blindly: it's set aside as "needs attention" for the player to
+// repair, see components::repair_wizard.
+use serde_json::Value;
+
+use crate::core::campaign::Campaign;
+use crate::core::repair;
+use crate::core::roster::Roster;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum IntegritySource {
+    // The stored campaign loaded, but its roster is broken
+    CampaignRoster,
+    // The stored campaign couldn't be loaded at all
+    UnreadableCampaign,
+    // The roster saved at the last change, loaded or not
+    AutosavedRoster,
+    UnreadableAutosave,
+}
+
+impl IntegritySource {
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            IntegritySource::CampaignRoster => "Campaign roster",
+            IntegritySource::UnreadableCampaign => "Unreadable campaign",
+            IntegritySource::AutosavedRoster => "Last roster",
+            IntegritySource::UnreadableAutosave => "Unreadable last roster",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeedsAttention {
+    pub source : IntegritySource,
+    // As much of the roster as could be read
+    pub roster : Roster,
+    pub problems : Vec<String>,
+}
+
+impl IntegritySource {
+    // Whether the stored value is kept as it is until it's repaired, rather
+    // than being saved over.
+    pub fn is_autosave(&self) -> bool {
+        matches!(self, IntegritySource::AutosavedRoster | IntegritySource::UnreadableAutosave)
+    }
+}
+
+impl NeedsAttention {
+    // Problems left once the roster is repaired.
+    pub fn recheck(&mut self) {
+        self.problems = repair::find_problems(&self.roster);
+    }
+}
+
+// The stored JSON, whether it's still readable as what it should be or not.
+// Null once the value was deleted.
+fn parse_json(json: Option<String>) -> Option<Value> {
+    json.and_then(|json| serde_json::from_str::<Value>(&json).ok()).filter(|value| !value.is_null())
+}
+
+// Reads the stored campaign, with whatever needs attention in it.
+pub fn load_campaign(json: Option<String>) -> (Option<Campaign>, Vec<NeedsAttention>) {
+    let Some(json) = parse_json(json) else {
+        return (None, Vec::new());
+    };
+    if let Ok(campaign) = serde_json::from_value::<Campaign>(json.clone()) {
+        let problems = repair::find_problems(&campaign.roster);
+        let items = if problems.is_empty() {
+            Vec::new()
+        } else {
+            vec![NeedsAttention {source: IntegritySource::CampaignRoster, roster: campaign.roster.clone(), problems}]
+        };
+        return (Some(campaign), items);
+    }
+
+    log::warn!("The stored campaign couldn't be loaded, setting it aside");
+    let (roster, mut problems) = json.get("roster")
+        .map(repair::salvage_roster)
+        .unwrap_or_else(|| (Roster::new(), vec!["The campaign has no roster".to_string()]));
+    problems.extend(repair::find_problems(&roster));
+    problems.insert(0, "The campaign couldn't be loaded".to_string());
+    (None, vec![NeedsAttention {source: IntegritySource::UnreadableCampaign, roster, problems}])
+}
+
+// Reads the autosaved roster, with whatever needs attention in it.
+pub fn load_autosave(json: Option<String>) -> (Option<Roster>, Vec<NeedsAttention>) {
+    let Some(json) = parse_json(json) else {
+        return (None, Vec::new());
+    };
+    if let Ok(roster) = serde_json::from_value::<Roster>(json.clone()) {
+        let problems = repair::find_problems(&roster);
+        if problems.is_empty() {
+            return (Some(roster), Vec::new());
+        }
+        return (None, vec![NeedsAttention {source: IntegritySource::AutosavedRoster, roster, problems}]);
+    }
+
+    log::warn!("The autosaved roster couldn't be loaded, setting it aside");
+    let (roster, mut problems) = repair::salvage_roster(&json);
+    problems.extend(repair::find_problems(&roster));
+    problems.insert(0, "The roster couldn't be loaded".to_string());
+    (None, vec![NeedsAttention {source: IntegritySource::UnreadableAutosave, roster, problems}])
+}
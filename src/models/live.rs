@@ -0,0 +1,38 @@
+// Sharing the game live, see core::live: the game as the spectators see it,
+// and the page address they open to follow it.
+use crate::core::live::{FollowLink, LiveEntry, LiveGame, LivePlayer};
+use crate::core::roster::Roster;
+use crate::models::game::GameState;
+
+// How often the spectators ask the server for the game.
+pub const FOLLOW_REFRESH_MS: u32 = 2000;
+
+pub fn get_live_game(roster: &Roster, game: &GameState) -> LiveGame {
+    LiveGame {
+        roster_name: roster.metadata.name.clone(),
+        entries: roster.elements.iter().enumerate()
+            .map(|(index, entry)| LiveEntry {
+                name: entry.element.get_name(),
+                quantity: entry.quantity,
+                casualties: game.tracker.get_casualties(index).min(entry.quantity),
+            })
+            .collect(),
+        players: game.clock.players.iter().enumerate()
+            .map(|(index, player)| LivePlayer {name: player.name.clone(), score: game.tracker.get_score(index)})
+            .collect(),
+        round: game.activation.round,
+    }
+}
+
+// Full address of the page following the game, for the spectators.
+pub fn get_follow_url(link: &FollowLink) -> Option<String> {
+    let location = gloo::utils::window().location();
+    Some(format!("{}{}{}{}",
+        location.origin().ok()?, location.pathname().ok()?, location.search().unwrap_or_default(), link.to_fragment()))
+}
+
+// The game the page was opened to follow, if any.
+pub fn get_page_follow_link() -> Option<FollowLink> {
+    let fragment = gloo::utils::window().location().hash().ok()?;
+    FollowLink::from_fragment(&fragment)
+}
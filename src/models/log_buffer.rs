@@ -0,0 +1,56 @@
+// Logger of the app: everything logged through the log crate goes to the
+// browser console, and the latest entries are also kept in memory for the
+// problem reports, see export::report.
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+const MAX_LOG_ENTRIES: usize = 50;
+
+thread_local! {
+    static RECENT_ENTRIES: RefCell<VecDeque<String>> = const { RefCell::new(VecDeque::new()) };
+}
+
+struct BufferedLogger;
+
+static LOGGER: BufferedLogger = BufferedLogger;
+
+impl Log for BufferedLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Info
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let entry = format!("[{}] {}: {}", record.level(), record.target(), record.args());
+        match record.level() {
+            Level::Error => web_sys::console::error_1(&entry.clone().into()),
+            Level::Warn => web_sys::console::warn_1(&entry.clone().into()),
+            _ => web_sys::console::log_1(&entry.clone().into()),
+        }
+        RECENT_ENTRIES.with(|entries| {
+            let mut entries = entries.borrow_mut();
+            if entries.len() == MAX_LOG_ENTRIES {
+                entries.pop_front();
+            }
+            entries.push_back(entry);
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+// Only the first call does anything.
+pub fn init() {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(LevelFilter::Info);
+    }
+}
+
+// Oldest first.
+pub fn get_recent_entries() -> Vec<String> {
+    RECENT_ENTRIES.with(|entries| entries.borrow().iter().cloned().collect())
+}
@@ -1,6 +1,22 @@
-pub mod roster;
-mod character;
-mod support;
-mod unit;
-mod element;
-pub mod armylist;
\ No newline at end of file
+// Browser side state of the app. The army building types themselves are in
+// the core module.
+pub mod settings;
+pub mod presentation;
+pub mod game;
+pub mod storage;
+pub mod roster_store;
+pub mod profiler;
+pub mod features;
+pub mod log_buffer;
+pub mod integrity;
+pub mod tabs;
+pub mod share;
+pub mod storage_backend;
+pub mod session;
+pub mod deadline;
+pub mod sync;
+pub mod live;
+pub mod catalog_updates;
+pub mod clock;
+pub mod bus;
+pub mod selection;
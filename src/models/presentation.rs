@@ -0,0 +1,78 @@
+// Presentation mode: the roster alone, with big fonts, meant to be used as a
+// browser source in streaming software. The mode and its options live in
+// the url fragment (e.g. #overlay?background=green&scroll=1&roster=...,
+// with the roster in binary, compressed), so the overlay can be opened in a
+// browser that doesn't share the storage of the app.
+use crate::core::compression;
+use crate::core::roster::Roster;
+
+const OVERLAY_FRAGMENT: &str = "#overlay";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverlayBackground {
+    #[default]
+    Transparent,
+    // Chroma key green
+    Green,
+}
+
+impl OverlayBackground {
+    pub const ALL: [OverlayBackground; 2] = [OverlayBackground::Transparent, OverlayBackground::Green];
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            OverlayBackground::Transparent => "Transparent",
+            OverlayBackground::Green => "Green Screen",
+        }
+    }
+
+    fn get_key(&self) -> &'static str {
+        match self {
+            OverlayBackground::Transparent => "transparent",
+            OverlayBackground::Green => "green",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OverlayOptions {
+    pub background : OverlayBackground,
+    pub auto_scroll : bool,
+}
+
+impl OverlayOptions {
+    // The fragment opening the overlay, with the roster embedded.
+    pub fn to_fragment(&self, roster: &Roster) -> Result<String, bincode::Error> {
+        let encoded = compression::compress_bytes(&roster.to_binary()?);
+        Ok(format!(
+            "{}?background={}&scroll={}&roster={}",
+            OVERLAY_FRAGMENT, self.background.get_key(), if self.auto_scroll { 1 } else { 0 }, encoded))
+    }
+
+    // Options and roster found in the fragment, None if it isn't an overlay
+    // one. A roster that can't be read is simply left out.
+    pub fn from_fragment(fragment: &str) -> Option<(OverlayOptions, Option<Roster>)> {
+        let query = fragment.strip_prefix(OVERLAY_FRAGMENT)?;
+        let mut options = OverlayOptions::default();
+        let mut roster = None;
+        for (key, value) in query.trim_start_matches('?').split('&').filter_map(|pair| pair.split_once('=')) {
+            match key {
+                "background" => {
+                    options.background = OverlayBackground::ALL.into_iter()
+                        .find(|background| background.get_key() == value)
+                        .unwrap_or_default();
+                }
+                "scroll" => options.auto_scroll = value == "1",
+                "roster" => {
+                    // Older links carry the roster as JSON.
+                    roster = compression::decompress_bytes(value).ok().and_then(|bytes| {
+                        Roster::from_binary(&bytes).ok()
+                            .or_else(|| String::from_utf8(bytes).ok().and_then(|json| Roster::from_json(&json).ok()))
+                    });
+                }
+                _ => {}
+            }
+        }
+        Some((options, roster))
+    }
+}
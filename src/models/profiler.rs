@@ -0,0 +1,120 @@
+// Local performance profiler, for diagnosing slow rosters: how often each
+// component renders, how long its updates take and how many messages go
+// through. Everything stays in memory, nothing is sent anywhere, and nothing
+// is measured at all while it's off.
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use crate::models::clock;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ComponentStats {
+    pub name : &'static str,
+    pub renders : u32,
+    pub updates : u32,
+    pub total_update_ms : f64,
+    pub max_update_ms : f64,
+}
+
+impl ComponentStats {
+    pub fn get_average_update_ms(&self) -> f64 {
+        if self.updates == 0 { 0.0 } else { self.total_update_ms / self.updates as f64 }
+    }
+}
+
+#[derive(Default)]
+struct Profiler {
+    components: Vec<ComponentStats>,
+    // Times of the messages handled during the last second
+    recent_messages: VecDeque<f64>,
+}
+
+impl Profiler {
+    fn get_component(&mut self, name: &'static str) -> &mut ComponentStats {
+        let index = match self.components.iter().position(|stats| stats.name == name) {
+            Some(index) => index,
+            None => {
+                self.components.push(ComponentStats {name, ..Default::default()});
+                self.components.len() - 1
+            }
+        };
+        &mut self.components[index]
+    }
+}
+
+thread_local! {
+    static PROFILER: RefCell<Option<Profiler>> = const { RefCell::new(None) };
+}
+
+fn now() -> f64 {
+    clock::monotonic_ms()
+}
+
+// Turning the profiler on starts from empty stats.
+pub fn set_enabled(is_enabled: bool) {
+    PROFILER.with(|profiler| {
+        let mut profiler = profiler.borrow_mut();
+        if is_enabled != profiler.is_some() {
+            *profiler = is_enabled.then(Profiler::default);
+        }
+    });
+}
+
+pub fn is_enabled() -> bool {
+    PROFILER.with(|profiler| profiler.borrow().is_some())
+}
+
+// To call at the start of the view of a component.
+pub fn record_render(name: &'static str) {
+    PROFILER.with(|profiler| {
+        if let Some(profiler) = profiler.borrow_mut().as_mut() {
+            profiler.get_component(name).renders += 1;
+        }
+    });
+}
+
+// Measures an update until dropped, counting its message.
+pub struct UpdateTimer {
+    name: &'static str,
+    started: f64,
+}
+
+impl Drop for UpdateTimer {
+    fn drop(&mut self) {
+        let ended = now();
+        let duration = ended - self.started;
+        PROFILER.with(|profiler| {
+            if let Some(profiler) = profiler.borrow_mut().as_mut() {
+                let stats = profiler.get_component(self.name);
+                stats.updates += 1;
+                stats.total_update_ms += duration;
+                stats.max_update_ms = stats.max_update_ms.max(duration);
+                profiler.recent_messages.push_back(ended);
+            }
+        });
+    }
+}
+
+// To keep alive for the whole update of a component, e.g.
+// `let _timer = profiler::time_update("App");`
+pub fn time_update(name: &'static str) -> Option<UpdateTimer> {
+    is_enabled().then(|| UpdateTimer {name, started: now()})
+}
+
+pub fn get_stats() -> Vec<ComponentStats> {
+    PROFILER.with(|profiler| profiler.borrow().as_ref().map(|profiler| profiler.components.clone()).unwrap_or_default())
+}
+
+pub fn get_messages_per_second() -> usize {
+    let since = now() - 1000.0;
+    PROFILER.with(|profiler| {
+        let mut profiler = profiler.borrow_mut();
+        let Some(profiler) = profiler.as_mut() else {
+            return 0;
+        };
+        while profiler.recent_messages.front().is_some_and(|time| *time < since) {
+            profiler.recent_messages.pop_front();
+        }
+        profiler.recent_messages.len()
+    })
+}
@@ -0,0 +1,191 @@
+// The roster shared by the whole app. Components subscribe to the part of
+// the roster they show and are only told when that part changes, instead of
+// re-rendering on every edit.
+use std::cell::{Cell, Ref, RefCell};
+use std::rc::{Rc, Weak};
+
+use yew::Callback;
+
+use crate::core::commands::{ExecutedCommand, RosterCommand};
+use crate::core::roster::{Roster, RosterEntry};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RosterAspect {
+    // Any change at all
+    Any,
+    // Points, number of entries and whether the roster is valid
+    Totals,
+    // The list of entries, in order
+    Elements,
+    // A single entry, by its id: edited, moved or removed
+    Entry(String),
+    // Name, options and scenario
+    Metadata,
+}
+
+impl RosterAspect {
+    fn has_changed(&self, before: &Roster, after: &Roster) -> bool {
+        match self {
+            RosterAspect::Any => before != after,
+            RosterAspect::Totals => {
+                before.get_total_points() != after.get_total_points() || before.elements.len() != after.elements.len()
+                    || before.get_points_limit() != after.get_points_limit()
+                    || before.check_invariants().is_ok() != after.check_invariants().is_ok()
+            }
+            RosterAspect::Elements => before.elements != after.elements,
+            RosterAspect::Entry(id) => get_entry(before, id) != get_entry(after, id),
+            RosterAspect::Metadata => before.metadata != after.metadata || before.scenario != after.scenario,
+        }
+    }
+}
+
+// Where the entry is and what it holds.
+fn get_entry<'a>(roster: &'a Roster, id: &str) -> Option<(usize, &'a RosterEntry)> {
+    roster.find_entry(id).map(|index| (index, &roster.elements[index]))
+}
+
+struct Subscriber {
+    id: usize,
+    aspect: RosterAspect,
+    callback: Callback<()>,
+}
+
+#[derive(Default)]
+struct Subscribers {
+    next_id: usize,
+    list: Vec<Subscriber>,
+}
+
+// Cheap to clone, all the clones share the same roster.
+pub struct RosterStore {
+    roster: Rc<RefCell<Roster>>,
+    // The roster as of the last notification, to find out what changed
+    notified: Rc<RefCell<Roster>>,
+    subscribers: Rc<RefCell<Subscribers>>,
+    // Commands executed since the history last took them
+    executed: Rc<RefCell<Vec<ExecutedCommand>>>,
+    // Counts the notified changes, and the count when this clone was made
+    revision: Rc<Cell<u64>>,
+    cloned_revision: u64,
+}
+
+// A clone remembers the revision it was made at, see PartialEq.
+impl Clone for RosterStore {
+    fn clone(&self) -> Self {
+        RosterStore {
+            roster: self.roster.clone(),
+            notified: self.notified.clone(),
+            subscribers: self.subscribers.clone(),
+            executed: self.executed.clone(),
+            revision: self.revision.clone(),
+            cloned_revision: self.revision.get(),
+        }
+    }
+}
+
+// The same store at the same revision. Properties holding a clone made
+// before a change differ from the ones made after it, without comparing
+// the rosters themselves.
+impl PartialEq for RosterStore {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.roster, &other.roster) && self.cloned_revision == other.cloned_revision
+    }
+}
+
+// Ends the subscription when dropped, to be kept as long as the component.
+pub struct Subscription {
+    id: usize,
+    subscribers: Weak<RefCell<Subscribers>>,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(subscribers) = self.subscribers.upgrade() {
+            subscribers.borrow_mut().list.retain(|subscriber| subscriber.id != self.id);
+        }
+    }
+}
+
+impl RosterStore {
+    pub fn new(roster: Roster) -> RosterStore {
+        RosterStore {
+            notified: Rc::new(RefCell::new(roster.clone())),
+            roster: Rc::new(RefCell::new(roster)),
+            subscribers: Rc::new(RefCell::new(Subscribers::default())),
+            executed: Rc::new(RefCell::new(Vec::new())),
+            revision: Rc::new(Cell::new(0)),
+            cloned_revision: 0,
+        }
+    }
+
+    pub fn borrow(&self) -> Ref<'_, Roster> {
+        self.roster.borrow()
+    }
+
+    // Edits the roster without telling the subscribers yet. False if the
+    // command doesn't apply to the roster.
+    pub fn apply(&self, command: RosterCommand) -> bool {
+        let executed = command.execute(&mut self.roster.borrow_mut());
+        let is_applied = executed.is_some();
+        self.executed.borrow_mut().extend(executed);
+        is_applied
+    }
+
+    // Edits the roster and tells the subscribers.
+    pub fn execute(&self, command: RosterCommand) -> bool {
+        let is_applied = self.apply(command);
+        if is_applied {
+            self.notify();
+        }
+        is_applied
+    }
+
+    // Applies a command of the history, undoing or redoing an edit: it's
+    // already recorded there, so it isn't taken again.
+    pub fn replay(&self, command: RosterCommand) -> bool {
+        let is_applied = command.apply(&mut self.roster.borrow_mut());
+        if is_applied {
+            self.notify();
+        }
+        is_applied
+    }
+
+    // Several edits made on the roster as one, e.g. an import or a bulk
+    // operation: a single command (see RosterCommand::transform), a single
+    // history entry and a single notification. False if nothing changed.
+    pub fn batch(&self, edit: impl FnOnce(&mut Roster)) -> bool {
+        let mut edited = self.roster.borrow().clone();
+        edit(&mut edited);
+        let command = RosterCommand::transform(&self.roster.borrow(), &edited);
+        command.is_some_and(|command| self.execute(command))
+    }
+
+    // The commands executed since the last call, oldest first.
+    pub fn take_executed(&self) -> Vec<ExecutedCommand> {
+        self.executed.take()
+    }
+
+    pub fn subscribe(&self, aspect: RosterAspect, callback: Callback<()>) -> Subscription {
+        let mut subscribers = self.subscribers.borrow_mut();
+        let id = subscribers.next_id;
+        subscribers.next_id += 1;
+        subscribers.list.push(Subscriber {id, aspect, callback});
+        Subscription {id, subscribers: Rc::downgrade(&self.subscribers)}
+    }
+
+    // Tells the subscribers whose aspect changed since the last notification.
+    pub fn notify(&self) {
+        let roster = self.roster.borrow().clone();
+        let previous = self.notified.replace(roster.clone());
+        if previous != roster {
+            self.revision.set(self.revision.get() + 1);
+        }
+        let callbacks: Vec<Callback<()>> = self.subscribers.borrow().list.iter()
+            .filter(|subscriber| subscriber.aspect.has_changed(&previous, &roster))
+            .map(|subscriber| subscriber.callback.clone())
+            .collect();
+        for callback in callbacks {
+            callback.emit(());
+        }
+    }
+}
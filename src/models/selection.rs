@@ -0,0 +1,99 @@
+// The entries selected in the roster, owned by App and handed down as a
+// context: the canvas, the details, the totals, the catalog and the play mode
+// all read the same selection and change it through the same callback.
+// Entries are addressed by their ids, they stay selected wherever they move.
+use yew::Callback;
+
+use crate::core::roster::{Roster, RosterEntry};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Selection {
+    // In the order they were selected
+    pub selected: Vec<String>,
+    // The one the details and the catalog act on, one of the selected
+    pub focused: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SelectionChange {
+    // Selects the entry alone, or nothing if it was already so
+    Select(String),
+    // Adds the entry to the selection, or takes it out of it (Ctrl+click)
+    Extend(String),
+    // Focuses the entry, selected alone unless it already was
+    Focus(String),
+    Clear,
+}
+
+impl Selection {
+    pub fn is_selected(&self, id: &str) -> bool {
+        self.selected.iter().any(|selected| selected == id)
+    }
+
+    // Index of the focused entry in the roster, if it's still there.
+    pub fn get_focused_index(&self, roster: &Roster) -> Option<usize> {
+        roster.find_entry(self.focused.as_ref()?)
+    }
+
+    pub fn get_selected_entries<'a>(&self, roster: &'a Roster) -> Vec<&'a RosterEntry> {
+        self.selected.iter().filter_map(|id| roster.elements.get(roster.find_entry(id)?)).collect()
+    }
+
+    // False if nothing changed.
+    pub fn apply(&mut self, change: SelectionChange) -> bool {
+        let before = self.clone();
+        match change {
+            SelectionChange::Select(id) if self.selected == [id.as_str()] => self.clear(),
+            SelectionChange::Select(id) => {
+                self.selected = vec![id.clone()];
+                self.focused = Some(id);
+            }
+            SelectionChange::Extend(id) if self.is_selected(&id) => {
+                self.selected.retain(|selected| *selected != id);
+                self.focused = self.selected.last().cloned();
+            }
+            SelectionChange::Extend(id) => {
+                self.selected.push(id.clone());
+                self.focused = Some(id);
+            }
+            SelectionChange::Focus(id) => {
+                if !self.is_selected(&id) {
+                    self.selected = vec![id.clone()];
+                }
+                self.focused = Some(id);
+            }
+            SelectionChange::Clear => self.clear(),
+        }
+        *self != before
+    }
+
+    // Drops the entries no longer in the roster, e.g. after a delete or
+    // an undo. False if none was.
+    pub fn retain_entries(&mut self, roster: &Roster) -> bool {
+        let before = self.selected.len();
+        self.selected.retain(|id| roster.find_entry(id).is_some());
+        if self.focused.as_ref().is_some_and(|id| !self.selected.contains(id)) {
+            self.focused = self.selected.last().cloned();
+        }
+        self.selected.len() != before
+    }
+
+    fn clear(&mut self) {
+        self.selected.clear();
+        self.focused = None;
+    }
+}
+
+// What the components get from the ContextProvider of App. The callback is
+// made once, so the context only changes with the selection.
+#[derive(Clone, PartialEq)]
+pub struct SelectionContext {
+    pub selection: Selection,
+    pub on_change: Callback<SelectionChange>,
+}
+
+impl SelectionContext {
+    pub fn change(&self, change: SelectionChange) {
+        self.on_change.emit(change);
+    }
+}
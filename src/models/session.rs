@@ -0,0 +1,41 @@
+// Whether the last session of the app ended cleanly. The writer tab marks
+// the session as running and clears the mark when the page is closed; a
+// mark still there at the next start means the tab was killed, the browser
+// crashed, or the app panicked (the panic hook marks it as crashed, which
+// closing the page then leaves alone).
+use gloo::events::EventListener;
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Serialize, Deserialize};
+
+const SESSION_KEY: &str = "fsd_builder.session";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum SessionState {
+    Running,
+    Crashed,
+}
+
+pub fn did_last_session_crash() -> bool {
+    LocalStorage::get::<SessionState>(SESSION_KEY).is_ok()
+}
+
+// Marks the session as running until the page is closed, for the writer
+// tab only: the others would see the mark of a tab that is still open.
+pub fn mark_running() -> EventListener {
+    if let Err(e) = LocalStorage::set(SESSION_KEY, SessionState::Running) {
+        log::warn!("Error marking the session as running: {:?}", e);
+    }
+    EventListener::new(&gloo::utils::window(), "pagehide", |_| {
+        if LocalStorage::get::<SessionState>(SESSION_KEY).is_ok_and(|state| state == SessionState::Running) {
+            LocalStorage::delete(SESSION_KEY);
+        }
+    })
+}
+
+// A panic leaves the app dead, the next start offers the snapshots.
+pub fn install_panic_hook() {
+    std::panic::set_hook(Box::new(|info| {
+        log::error!("The app crashed: {}", info);
+        let _ = LocalStorage::set(SESSION_KEY, SessionState::Crashed);
+    }));
+}
@@ -0,0 +1,160 @@
+// User settings, kept by the storage backend and restored at startup. Only
+// the choice of the backend is kept in the local storage, it's needed to
+// reach the others.
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use gloo::storage::{LocalStorage, Storage};
+
+use crate::core::event_pack::{EventPack, RuleOverride};
+use crate::core::print_options::PrintBranding;
+use crate::models::storage_backend::{self, StorageBackend, StorageKind};
+use crate::models::sync::SyncSettings;
+
+const SETTINGS_KEY: &str = "fsd_builder.settings";
+pub const STORED_SETTINGS_KEY: &str = "fsd_builder.preferences";
+
+// Range of the canvas zoom, from a whole list on a tablet to readable cards
+// on a big screen.
+pub const MIN_CANVAS_ZOOM_PERCENT: u32 = 50;
+pub const MAX_CANVAS_ZOOM_PERCENT: u32 = 150;
+
+// Largest logo file for the print header. It's kept in the settings, and the
+// browser storage only holds a few megabytes in all.
+pub const MAX_LOGO_BYTES: u64 = 256 * 1024;
+
+// What double clicking a card of the roster does. Deleting is always in the
+// card context menu and its delete button too.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DoubleClickAction {
+    #[default]
+    Details,
+    Edit,
+    Delete,
+    Nothing,
+    Rename,
+}
+
+impl DoubleClickAction {
+    pub const ALL: [DoubleClickAction; 5] = [DoubleClickAction::Details, DoubleClickAction::Rename, DoubleClickAction::Edit, DoubleClickAction::Delete, DoubleClickAction::Nothing];
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            DoubleClickAction::Details => "Show details",
+            DoubleClickAction::Edit => "Edit campaign record",
+            DoubleClickAction::Delete => "Delete",
+            DoubleClickAction::Nothing => "Nothing",
+            DoubleClickAction::Rename => "Edit name, points and note",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // Overrides the base url of the static assets, see assets::paths
+    pub asset_base: Option<String>,
+    // Limit and warning states shown with icons and patterns, not only colors
+    pub colorblind_safe: bool,
+    // Card borders colored by element type, see the TYPE COLORS styles
+    pub color_code_types: bool,
+    // Size of the roster cards, in percent of the default one
+    pub canvas_zoom_percent: u32,
+    // Roster split in the two deployment waves instead of a single grid
+    pub show_deployment_board: bool,
+    // Largest share of the points a single group may take, in percent, see
+    // core::breakdown. None when the format has no such cap.
+    pub group_cap_percent: Option<u32>,
+    // Points left out of the canvas and of every export
+    pub hide_points: bool,
+    // Sounds for the clock and the points limit, see export::audio
+    pub audio_cues: bool,
+    pub cue_volume_percent: u32,
+    // Developer overlay with render counts and timings, see models::profiler
+    pub show_profiler: bool,
+    // Experimental features turned on or off, by key, see models::features
+    pub feature_flags: BTreeMap<String, bool>,
+    pub double_click_action: DoubleClickAction,
+    // Header of the printed rosters, see export::print
+    pub print_branding: PrintBranding,
+    // Event the builder was set up for, see core::event_pack. Boxed, the
+    // settings travel in messages.
+    pub event_pack: Option<Box<EventPack>>,
+    // Server the rosters are pushed to and pulled from, see models::sync
+    pub sync: SyncSettings,
+    // Where the rosters, the campaign, the scenarios and the rest of the
+    // settings are kept, see models::storage_backend
+    pub storage: StorageKind,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            asset_base: None,
+            colorblind_safe: false,
+            color_code_types: true,
+            canvas_zoom_percent: 100,
+            show_deployment_board: false,
+            group_cap_percent: None,
+            hide_points: false,
+            audio_cues: false,
+            cue_volume_percent: 50,
+            show_profiler: false,
+            feature_flags: BTreeMap::new(),
+            double_click_action: DoubleClickAction::default(),
+            print_branding: PrintBranding::default(),
+            event_pack: None,
+            sync: SyncSettings::default(),
+            storage: StorageKind::default(),
+        }
+    }
+}
+
+impl Settings {
+    // The choice of the backend, with the rest of the settings as they were
+    // before the backend kept them. The backend has the others, see
+    // with_backend_of.
+    pub fn load() -> Settings {
+        LocalStorage::get(SETTINGS_KEY).unwrap_or_default()
+    }
+
+    // The settings loaded from the backend, on the backend chosen in this
+    // browser.
+    pub fn with_backend_of(self, local: &Settings) -> Settings {
+        Settings {storage: local.storage, sync: local.sync.clone(), ..self}
+    }
+
+    // Volume of the cues from 0 to 1, None if they're off.
+    pub fn get_cue_volume(&self) -> Option<f32> {
+        if self.audio_cues { Some(self.cue_volume_percent.min(100) as f32 / 100.0) } else { None }
+    }
+
+    // Applies the format and the branding of the event, the house rules are
+    // read from the pack as long as it's kept.
+    pub fn join_event(&mut self, pack: EventPack) {
+        self.group_cap_percent = pack.group_cap_percent;
+        if !pack.branding.is_empty() {
+            self.print_branding = pack.branding.clone();
+        }
+        self.event_pack = Some(Box::new(pack));
+    }
+
+    pub fn get_house_rules(&self) -> &[RuleOverride] {
+        self.event_pack.as_ref().map(|pack| pack.house_rules.as_slice()).unwrap_or_default()
+    }
+
+    pub fn get_submission_deadline(&self) -> Option<&str> {
+        self.event_pack.as_ref().and_then(|pack| pack.submission_deadline.as_deref())
+    }
+
+    pub fn get_canvas_zoom_percent(&self) -> u32 {
+        self.canvas_zoom_percent.clamp(MIN_CANVAS_ZOOM_PERCENT, MAX_CANVAS_ZOOM_PERCENT)
+    }
+
+    pub fn save(&self, backend: &dyn StorageBackend) {
+        let local = Settings {storage: self.storage, sync: self.sync.clone(), ..Settings::default()};
+        if let Err(e) = LocalStorage::set(SETTINGS_KEY, local) {
+            log::warn!("Error saving settings: {:?}", e);
+        }
+        storage_backend::save_value(backend, STORED_SETTINGS_KEY, self);
+    }
+}
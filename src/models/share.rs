@@ -0,0 +1,53 @@
+// Share links: the whole roster in the url fragment (#roster=..., in binary,
+// compressed), kept up to date while editing so the address of the page is
+// always a link to the list on screen. The fragment never reaches a server.
+use crate::core::compression;
+use crate::core::roster::Roster;
+
+const SHARE_FRAGMENT: &str = "#roster=";
+
+pub fn to_fragment(roster: &Roster) -> Result<String, bincode::Error> {
+    Ok(format!("{}{}", SHARE_FRAGMENT, compression::compress_bytes(&roster.to_binary()?)))
+}
+
+// The roster of a share fragment, None if it isn't one.
+pub fn from_fragment(fragment: &str) -> Option<Result<Roster, String>> {
+    let encoded = fragment.strip_prefix(SHARE_FRAGMENT)?;
+    Some(compression::decompress_bytes(encoded)
+        .map_err(|e| format!("the link is damaged ({})", e))
+        .and_then(|bytes| Roster::from_binary(&bytes).map_err(|e| e.to_string())))
+}
+
+// The shared roster the page was opened with, if any.
+pub fn get_page_roster() -> Option<Result<Roster, String>> {
+    let fragment = gloo::utils::window().location().hash().ok()?;
+    from_fragment(&fragment)
+}
+
+// The fragment is replaced, not added to the browser history: going back
+// leaves the app rather than undoing edits one by one. An empty roster
+// leaves a plain address.
+pub fn update_page_fragment(roster: &Roster) {
+    let location = gloo::utils::window().location();
+    let page = format!("{}{}", location.pathname().unwrap_or_default(), location.search().unwrap_or_default());
+    let url = match roster.elements.is_empty() {
+        true => Ok(page),
+        false => to_fragment(roster).map(|fragment| page + &fragment),
+    };
+    let result = url.map_err(|e| format!("{:?}", e)).and_then(|url| {
+        gloo::utils::window().history()
+            .and_then(|history| history.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&url)))
+            .map_err(|e| format!("{:?}", e))
+    });
+    if let Err(e) = result {
+        log::warn!("Error updating the share link: {}", e);
+    }
+}
+
+// Full address of the page with the roster, for copying.
+pub fn get_share_url(roster: &Roster) -> Option<String> {
+    let location = gloo::utils::window().location();
+    let fragment = to_fragment(roster).ok()?;
+    Some(format!("{}{}{}{}",
+        location.origin().ok()?, location.pathname().ok()?, location.search().unwrap_or_default(), fragment))
+}
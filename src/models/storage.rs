@@ -0,0 +1,93 @@
+// Persistence of the user content in the browser local storage, for the
+// local storage backend and the copies kept by the others, see
+// models::storage_backend. Values are stored compressed, to stay well
+// within the storage quota.
+//
+// Saves are journaled so that a tab closed in the middle of one can't lose
+// the data: the new value is first written to "<key>.tmp", the previous
+// one is kept as "<key>.bak", and loading falls back on them in that order
+// when the value itself is missing or unreadable.
+use gloo::storage::{LocalStorage, Storage};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::core::compression;
+
+pub const CAMPAIGN_KEY: &str = "fsd_builder.campaign";
+pub const SCENARIOS_KEY: &str = "fsd_builder.scenarios";
+pub const ROSTER_KEY: &str = "fsd_builder.roster";
+pub const SLOTS_KEY: &str = "fsd_builder.slots";
+pub const SNAPSHOTS_KEY: &str = "fsd_builder.snapshots";
+
+fn get_journal_keys(key: &str) -> [String; 3] {
+    [key.to_string(), format!("{}.tmp", key), format!("{}.bak", key)]
+}
+
+// Values saved before compression was added are plain JSON, still read.
+fn read_compressed<T: DeserializeOwned>(key: &str) -> Option<T> {
+    if let Ok(value) = LocalStorage::get::<T>(key) {
+        return Some(value);
+    }
+    let compressed = LocalStorage::get::<String>(key).ok()?;
+    serde_json::from_str(&compression::decompress(&compressed).ok()?).ok()
+}
+
+fn load_compressed<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let [key, temporary_key, backup_key] = get_journal_keys(key);
+    if let Some(value) = read_compressed(&key) {
+        return Some(value);
+    }
+    for recovery_key in [temporary_key, backup_key] {
+        if let Some(value) = read_compressed(&recovery_key) {
+            log::warn!("{} was unreadable, recovered from {}", key, recovery_key);
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn save_compressed<T: Serialize + DeserializeOwned>(key: &str, value: &T, label: &str) {
+    let [key, temporary_key, backup_key] = get_journal_keys(key);
+    let result = serde_json::to_string(value)
+        .map_err(|e| e.to_string())
+        .map(|json| compression::compress(&json))
+        .and_then(|compressed| {
+            LocalStorage::set(&temporary_key, &compressed).map_err(|e| e.to_string())?;
+            // The previous value is only replaced by one known to be good.
+            let storage = LocalStorage::raw();
+            if let Ok(Some(previous)) = storage.get_item(&key) {
+                if read_compressed::<T>(&key).is_some() {
+                    storage.set_item(&backup_key, &previous).map_err(|e| format!("{:?}", e))?;
+                }
+            }
+            LocalStorage::set(&key, &compressed).map_err(|e| e.to_string())?;
+            storage.remove_item(&temporary_key).map_err(|e| format!("{:?}", e))
+        });
+    if let Err(e) = result {
+        log::warn!("Error saving {}: {:?}", label, e);
+    }
+}
+
+fn delete_journaled(key: &str) {
+    for key in get_journal_keys(key) {
+        LocalStorage::delete(key);
+    }
+}
+
+// Any value as JSON text, journaled and compressed like the others, for the
+// local storage backend, see models::storage_backend.
+pub fn load_text(key: &str) -> Option<String> {
+    load_compressed::<Value>(key).map(|value| value.to_string())
+}
+
+pub fn save_text(key: &str, json: &str) {
+    match serde_json::from_str::<Value>(json) {
+        Ok(value) => save_compressed(key, &value, key),
+        Err(e) => log::warn!("Error saving {}: {:?}", key, e),
+    }
+}
+
+pub fn delete_text(key: &str) {
+    delete_journaled(key);
+}
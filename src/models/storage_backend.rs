@@ -0,0 +1,438 @@
+// Where the user content is kept: the roster slots, with their revisions,
+// the snapshots, the roster being edited, the campaign, the scenarios and
+// the settings. The local storage is small (a few MB for
+// the whole site) and fills up with many rosters, IndexedDB has room for
+// far more, the sync server follows the player from one device to another
+// and the memory keeps nothing, for trying things out. Every backend holds
+// JSON text by key and loads asynchronously, as IndexedDB and the server do.
+//
+// Only the choice of the backend stays in the local storage whatever it is,
+// see Settings::save.
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use gloo::net::http::Request;
+use serde::de::DeserializeOwned;
+use serde::{Serialize, Deserialize};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{IdbDatabase, IdbOpenDbRequest, IdbRequest, IdbTransactionMode};
+use yew::Callback;
+
+use crate::models::storage::{self, SLOTS_KEY};
+use crate::models::sync::SyncSettings;
+
+const DATABASE_NAME: &str = "fsd_builder";
+const DATABASE_VERSION: u32 = 1;
+const STORE_NAME: &str = "rosters";
+const SLOTS_RECORD: &str = "slots";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StorageKind {
+    // IndexedDB where the browser has it, the local storage otherwise
+    #[default]
+    Automatic,
+    LocalStorage,
+    IndexedDb,
+    // The sync server of the settings, automatic while there's none
+    Server,
+    Memory,
+}
+
+impl StorageKind {
+    pub const ALL: [StorageKind; 5] = [StorageKind::Automatic, StorageKind::LocalStorage, StorageKind::IndexedDb, StorageKind::Server, StorageKind::Memory];
+
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            StorageKind::Automatic => "Automatic",
+            StorageKind::LocalStorage => "Local storage",
+            StorageKind::IndexedDb => "IndexedDB",
+            StorageKind::Server => "Sync server",
+            StorageKind::Memory => "Memory only (lost on reload)",
+        }
+    }
+}
+
+pub trait StorageBackend {
+    fn get_name(&self) -> &'static str;
+    // The JSON saved under the key, None when nothing was saved yet or it
+    // can't be read.
+    fn load(&self, key: &'static str, on_loaded: Callback<Option<String>>);
+    fn save(&self, key: &'static str, json: String);
+}
+
+pub fn get_storage_backend(kind: StorageKind, sync: &SyncSettings) -> Rc<dyn StorageBackend> {
+    match kind {
+        StorageKind::LocalStorage => Rc::new(LocalStorageBackend),
+        StorageKind::IndexedDb => Rc::new(IndexedDbBackend::new()),
+        StorageKind::Server if sync.is_enabled() => Rc::new(ServerBackend::new(sync.clone())),
+        StorageKind::Memory => Rc::new(MemoryBackend::default()),
+        StorageKind::Automatic | StorageKind::Server => match gloo::utils::window().indexed_db() {
+            Ok(Some(_)) => Rc::new(IndexedDbBackend::new()),
+            _ => Rc::new(LocalStorageBackend),
+        },
+    }
+}
+
+// The value saved under the key, its default when there's none.
+pub fn load_value<T: DeserializeOwned + Default + 'static>(backend: &dyn StorageBackend, key: &'static str, on_loaded: Callback<T>) {
+    backend.load(key, Callback::from(move |json: Option<String>| on_loaded.emit(parse_value(key, json))));
+}
+
+pub fn parse_value<T: DeserializeOwned + Default>(key: &str, json: Option<String>) -> T {
+    let value = json.map(|json| serde_json::from_str(&json).unwrap_or_else(|e| {
+        log::warn!("Error reading {}: {:?}", key, e);
+        T::default()
+    }));
+    value.unwrap_or_default()
+}
+
+// The JSON saved under each of the keys, in the same order, once they're
+// all loaded.
+pub fn load_texts(backend: Rc<dyn StorageBackend>, keys: &[&'static str], on_loaded: Callback<Vec<Option<String>>>) {
+    load_next_text(backend, keys.into(), Vec::new(), on_loaded);
+}
+
+fn load_next_text(backend: Rc<dyn StorageBackend>, keys: Rc<[&'static str]>, loaded: Vec<Option<String>>, on_loaded: Callback<Vec<Option<String>>>) {
+    let Some(&key) = keys.get(loaded.len()) else {
+        return on_loaded.emit(loaded);
+    };
+    let next_backend = backend.clone();
+    backend.load(key, Callback::from(move |json: Option<String>| {
+        let mut loaded = loaded.clone();
+        loaded.push(json);
+        load_next_text(next_backend.clone(), keys.clone(), loaded, on_loaded.clone());
+    }));
+}
+
+pub fn save_value<T: Serialize>(backend: &dyn StorageBackend, key: &'static str, value: &T) {
+    match serde_json::to_string(value) {
+        Ok(json) => backend.save(key, json),
+        Err(e) => log::warn!("Error saving {}: {:?}", key, e),
+    }
+}
+
+// What's saved under the key goes along to another backend, for the values
+// the app doesn't hold itself.
+pub fn copy_value(from: &dyn StorageBackend, to: Rc<dyn StorageBackend>, key: &'static str) {
+    from.load(key, Callback::from(move |json: Option<String>| {
+        if let Some(json) = json {
+            to.save(key, json);
+        }
+    }));
+}
+
+// Journaled and compressed, see models::storage.
+pub struct LocalStorageBackend;
+
+impl StorageBackend for LocalStorageBackend {
+    fn get_name(&self) -> &'static str {
+        "Local Storage"
+    }
+
+    fn load(&self, key: &'static str, on_loaded: Callback<Option<String>>) {
+        on_loaded.emit(storage::load_text(key));
+    }
+
+    fn save(&self, key: &'static str, json: String) {
+        storage::save_text(key, &json);
+    }
+}
+
+type PendingOperation = Box<dyn FnOnce(Option<&IdbDatabase>)>;
+
+// Operations wait for the database to be open, then run in the order they
+// were asked for. None once it failed to open.
+enum DatabaseState {
+    Opening(Vec<PendingOperation>),
+    Open(IdbDatabase),
+    Failed,
+}
+
+// Each value as a JSON record. Values saved in the local storage before are
+// read until the first save here, which then frees them. If the database
+// can't be opened (e.g. some private modes) the local storage is used
+// instead.
+pub struct IndexedDbBackend {
+    state: Rc<RefCell<DatabaseState>>,
+}
+
+impl IndexedDbBackend {
+    pub fn new() -> IndexedDbBackend {
+        let state = Rc::new(RefCell::new(DatabaseState::Opening(Vec::new())));
+        if let Err(e) = IndexedDbBackend::open(state.clone()) {
+            log::warn!("Error opening the database: {:?}", e);
+            IndexedDbBackend::finish_opening(&state, None);
+        }
+        IndexedDbBackend {state}
+    }
+
+    fn open(state: Rc<RefCell<DatabaseState>>) -> Result<(), JsValue> {
+        let factory = gloo::utils::window().indexed_db()?.ok_or("No IndexedDB")?;
+        let request = factory.open_with_u32(DATABASE_NAME, DATABASE_VERSION)?;
+
+        let on_upgrade_needed = Closure::<dyn FnMut(web_sys::Event)>::new(|event: web_sys::Event| {
+            let database = event.target()
+                .and_then(|target| target.dyn_into::<IdbOpenDbRequest>().ok())
+                .and_then(|request| request.result().ok())
+                .and_then(|result| result.dyn_into::<IdbDatabase>().ok());
+            if let Some(database) = database {
+                if let Err(e) = database.create_object_store(STORE_NAME) {
+                    log::warn!("Error creating the store: {:?}", e);
+                }
+            }
+        });
+        request.set_onupgradeneeded(Some(on_upgrade_needed.as_ref().unchecked_ref()));
+        on_upgrade_needed.forget();
+
+        let request_clone = request.clone();
+        let state_clone = state.clone();
+        let on_success = Closure::once(move || {
+            let database = request_clone.result().ok().and_then(|result| result.dyn_into::<IdbDatabase>().ok());
+            IndexedDbBackend::finish_opening(&state_clone, database);
+        });
+        request.set_onsuccess(Some(on_success.as_ref().unchecked_ref()));
+        on_success.forget();
+
+        let on_error = Closure::once(move || {
+            log::warn!("Error opening the database, using the local storage");
+            IndexedDbBackend::finish_opening(&state, None);
+        });
+        request.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+        on_error.forget();
+        Ok(())
+    }
+
+    fn finish_opening(state: &Rc<RefCell<DatabaseState>>, database: Option<IdbDatabase>) {
+        let new_state = match &database {
+            Some(database) => DatabaseState::Open(database.clone()),
+            None => DatabaseState::Failed,
+        };
+        let pending = match std::mem::replace(&mut *state.borrow_mut(), new_state) {
+            DatabaseState::Opening(pending) => pending,
+            _ => Vec::new(),
+        };
+        for operation in pending {
+            operation(database.as_ref());
+        }
+    }
+
+    fn run(&self, operation: impl FnOnce(Option<&IdbDatabase>) + 'static) {
+        let mut state = self.state.borrow_mut();
+        match &mut *state {
+            DatabaseState::Opening(pending) => pending.push(Box::new(operation)),
+            DatabaseState::Open(database) => {
+                let database = database.clone();
+                drop(state);
+                operation(Some(&database));
+            }
+            DatabaseState::Failed => {
+                drop(state);
+                operation(None);
+            }
+        }
+    }
+
+    // The slots were the only record at first, under a name of their own.
+    fn get_record_key(key: &str) -> JsValue {
+        JsValue::from_str(if key == SLOTS_KEY { SLOTS_RECORD } else { key })
+    }
+
+    fn get_record(database: &IdbDatabase, key: &str) -> Result<IdbRequest, JsValue> {
+        database.transaction_with_str(STORE_NAME)?
+            .object_store(STORE_NAME)?
+            .get(&IndexedDbBackend::get_record_key(key))
+    }
+
+    fn put_record(database: &IdbDatabase, key: &str, json: &str) -> Result<IdbRequest, JsValue> {
+        database.transaction_with_str_and_mode(STORE_NAME, IdbTransactionMode::Readwrite)?
+            .object_store(STORE_NAME)?
+            .put_with_key(&JsValue::from_str(json), &IndexedDbBackend::get_record_key(key))
+    }
+}
+
+impl Default for IndexedDbBackend {
+    fn default() -> Self {
+        IndexedDbBackend::new()
+    }
+}
+
+impl StorageBackend for IndexedDbBackend {
+    fn get_name(&self) -> &'static str {
+        "IndexedDB"
+    }
+
+    fn load(&self, key: &'static str, on_loaded: Callback<Option<String>>) {
+        self.run(move |database| {
+            let request = match database.map(|database| IndexedDbBackend::get_record(database, key)) {
+                Some(Ok(request)) => request,
+                Some(Err(e)) => {
+                    log::warn!("Error reading the database: {:?}", e);
+                    return on_loaded.emit(storage::load_text(key));
+                }
+                None => return on_loaded.emit(storage::load_text(key)),
+            };
+            let request_clone = request.clone();
+            let on_loaded_clone = on_loaded.clone();
+            let on_success = Closure::once(move || {
+                match request_clone.result().ok().and_then(|result| result.as_string()) {
+                    Some(json) => on_loaded_clone.emit(Some(json)),
+                    // Nothing here yet, the value is still in the local storage.
+                    None => on_loaded_clone.emit(storage::load_text(key)),
+                }
+            });
+            request.set_onsuccess(Some(on_success.as_ref().unchecked_ref()));
+            on_success.forget();
+
+            let on_error = Closure::once(move || on_loaded.emit(storage::load_text(key)));
+            request.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+            on_error.forget();
+        });
+    }
+
+    fn save(&self, key: &'static str, json: String) {
+        self.run(move |database| {
+            let request = match database.map(|database| IndexedDbBackend::put_record(database, key, &json)) {
+                Some(Ok(request)) => request,
+                Some(Err(e)) => {
+                    log::warn!("Error writing the database: {:?}", e);
+                    return storage::save_text(key, &json);
+                }
+                None => return storage::save_text(key, &json),
+            };
+            // Once in the database, the copy in the local storage is stale.
+            let on_success = Closure::once(move || storage::delete_text(key));
+            request.set_onsuccess(Some(on_success.as_ref().unchecked_ref()));
+            on_success.forget();
+
+            let on_error = Closure::once(move || {
+                log::warn!("Error writing the database, saving to the local storage");
+                storage::save_text(key, &json);
+            });
+            request.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+            on_error.forget();
+        });
+    }
+}
+
+// The values on the sync server of the settings, see models::sync, with the
+// same bearer token:
+//
+//   GET {base}/storage/{key}  -> 200 with the JSON, or 404 when there's none
+//   PUT {base}/storage/{key}  <- the JSON
+//
+// A copy is kept in the local storage. It's what is loaded while the server
+// can't be reached, and the first save sends it over.
+//
+// A single save is sent at a time, so that they reach the server in order.
+// The ones waiting meanwhile are replaced by newer saves of the same key.
+pub struct ServerBackend {
+    settings: SyncSettings,
+    pending: Rc<RefCell<HashMap<&'static str, String>>>,
+    is_sending: Rc<Cell<bool>>,
+}
+
+impl ServerBackend {
+    pub fn new(settings: SyncSettings) -> ServerBackend {
+        ServerBackend {settings, pending: Rc::default(), is_sending: Rc::default()}
+    }
+
+    fn get_url(settings: &SyncSettings, key: &str) -> String {
+        format!("{}/storage/{}", settings.base_url.trim().trim_end_matches('/'), key)
+    }
+
+    fn get_authorization(settings: &SyncSettings) -> String {
+        format!("Bearer {}", settings.token.trim())
+    }
+
+    async fn put(settings: &SyncSettings, key: &str, json: String) -> Result<(), String> {
+        let response = Request::put(&ServerBackend::get_url(settings, key))
+            .header("Authorization", &ServerBackend::get_authorization(settings))
+            .header("Content-Type", "application/json")
+            .body(json)
+            .map_err(|e| e.to_string())?
+            .send().await
+            .map_err(|e| e.to_string())?;
+        match response.ok() {
+            true => Ok(()),
+            false => Err(format!("{} {}", response.status(), response.status_text())),
+        }
+    }
+}
+
+impl StorageBackend for ServerBackend {
+    fn get_name(&self) -> &'static str {
+        "Sync Server"
+    }
+
+    fn load(&self, key: &'static str, on_loaded: Callback<Option<String>>) {
+        let request = Request::get(&ServerBackend::get_url(&self.settings, key))
+            .header("Authorization", &ServerBackend::get_authorization(&self.settings));
+        yew::platform::spawn_local(async move {
+            let result = async {
+                let response = request.send().await.map_err(|e| e.to_string())?;
+                match response.status() {
+                    200..=299 => response.text().await.map(Some).map_err(|e| e.to_string()),
+                    404 => Ok(None),
+                    status => Err(format!("{} {}", status, response.status_text())),
+                }
+            };
+            match result.await {
+                Ok(Some(json)) => on_loaded.emit(Some(json)),
+                Ok(None) => on_loaded.emit(storage::load_text(key)),
+                Err(e) => {
+                    log::warn!("Error loading {} from the server, using the local copy: {}", key, e);
+                    on_loaded.emit(storage::load_text(key));
+                }
+            }
+        });
+    }
+
+    fn save(&self, key: &'static str, json: String) {
+        storage::save_text(key, &json);
+        self.pending.borrow_mut().insert(key, json);
+        if self.is_sending.replace(true) {
+            return;
+        }
+        let (settings, pending, is_sending) = (self.settings.clone(), self.pending.clone(), self.is_sending.clone());
+        yew::platform::spawn_local(async move {
+            loop {
+                let next = {
+                    let mut pending = pending.borrow_mut();
+                    let key = pending.keys().next().copied();
+                    key.and_then(|key| pending.remove_entry(key))
+                };
+                let Some((key, json)) = next else {
+                    break;
+                };
+                if let Err(e) = ServerBackend::put(&settings, key, json).await {
+                    log::warn!("Error saving {} to the server: {}", key, e);
+                }
+            }
+            is_sending.set(false);
+        });
+    }
+}
+
+// Nothing is kept past the page, for trying things out without touching
+// what was saved.
+#[derive(Default)]
+pub struct MemoryBackend {
+    values: RefCell<HashMap<&'static str, String>>,
+}
+
+impl StorageBackend for MemoryBackend {
+    fn get_name(&self) -> &'static str {
+        "Memory"
+    }
+
+    fn load(&self, key: &'static str, on_loaded: Callback<Option<String>>) {
+        let json = self.values.borrow().get(key).cloned();
+        on_loaded.emit(json);
+    }
+
+    fn save(&self, key: &'static str, json: String) {
+        self.values.borrow_mut().insert(key, json);
+    }
+}
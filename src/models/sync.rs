@@ -0,0 +1,136 @@
+// Client of the sync server, see core::sync. Any server with these routes
+// will do, the token is sent as a bearer token:
+//
+//   GET {base}/rosters/{id}  -> 200 {"revision": 3, "roster": {...}}
+//   PUT {base}/rosters/{id}  <- {"revision": 3, "roster": {...}}
+//                            -> 200 {"revision": 4}, or 409 when the server
+//                               has another revision than the one sent
+//
+// and the same for the games followed live (see core::live), under
+// {base}/games/{id} with a "game" instead of the "roster". Spectators
+// read the games without a token, unless they have one for that server.
+//
+// The first push of a roster or a game creates it, at the revision 0, under
+// a new id from core::ids.
+use std::rc::Rc;
+
+use gloo::net::http::{Request, RequestBuilder, Response};
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+use yew::Callback;
+
+use crate::core::live::RemoteGame;
+use crate::core::sync::{PushReceipt, RemoteRoster};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncSettings {
+    // Address of the server, without the /rosters, empty when not syncing
+    pub base_url : String,
+    pub token : String,
+}
+
+impl SyncSettings {
+    pub fn is_enabled(&self) -> bool {
+        !self.base_url.trim().is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SyncError {
+    // Pushed from somewhere else since the last sync
+    Conflict,
+    NotFound,
+    Failed(String),
+}
+
+impl SyncError {
+    pub fn get_message(&self) -> String {
+        match self {
+            SyncError::Conflict => "The roster was changed on the server since the last sync, pull it first".to_string(),
+            SyncError::NotFound => "The roster isn't on the server".to_string(),
+            SyncError::Failed(e) => format!("The sync server couldn't be reached: {}", e),
+        }
+    }
+}
+
+pub trait SyncClient {
+    fn push(&self, id: &str, body: RemoteRoster, on_done: Callback<Result<PushReceipt, SyncError>>);
+    fn pull(&self, id: &str, on_done: Callback<Result<RemoteRoster, SyncError>>);
+    fn push_game(&self, id: &str, body: RemoteGame, on_done: Callback<Result<PushReceipt, SyncError>>);
+    fn pull_game(&self, id: &str, on_done: Callback<Result<RemoteGame, SyncError>>);
+}
+
+// None when no server is set.
+pub fn get_sync_client(settings: &SyncSettings) -> Option<Rc<dyn SyncClient>> {
+    settings.is_enabled().then(|| Rc::new(RestSyncClient {settings: settings.clone()}) as Rc<dyn SyncClient>)
+}
+
+pub struct RestSyncClient {
+    settings: SyncSettings,
+}
+
+impl RestSyncClient {
+    fn get_url(&self, route: &str, id: &str) -> String {
+        format!("{}/{}/{}", self.settings.base_url.trim().trim_end_matches('/'), route, id)
+    }
+
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match self.settings.token.trim() {
+            "" => request,
+            token => request.header("Authorization", &format!("Bearer {}", token)),
+        }
+    }
+
+    fn check_status(response: &Response) -> Result<(), SyncError> {
+        match response.status() {
+            200..=299 => Ok(()),
+            404 => Err(SyncError::NotFound),
+            409 => Err(SyncError::Conflict),
+            status => Err(SyncError::Failed(format!("{} {}", status, response.status_text()))),
+        }
+    }
+
+    fn put<T: Serialize>(&self, url: &str, body: &T, on_done: Callback<Result<PushReceipt, SyncError>>) {
+        let request = self.authorize(Request::put(url)).json(body);
+        yew::platform::spawn_local(async move {
+            let result = async {
+                let response = request.map_err(|e| SyncError::Failed(e.to_string()))?
+                    .send().await.map_err(|e| SyncError::Failed(e.to_string()))?;
+                RestSyncClient::check_status(&response)?;
+                response.json::<PushReceipt>().await.map_err(|e| SyncError::Failed(e.to_string()))
+            };
+            on_done.emit(result.await);
+        });
+    }
+
+    fn get<T: DeserializeOwned + 'static>(&self, url: &str, on_done: Callback<Result<T, SyncError>>) {
+        let request = self.authorize(Request::get(url));
+        yew::platform::spawn_local(async move {
+            let result = async {
+                let response = request.send().await.map_err(|e| SyncError::Failed(e.to_string()))?;
+                RestSyncClient::check_status(&response)?;
+                response.json::<T>().await.map_err(|e| SyncError::Failed(e.to_string()))
+            };
+            on_done.emit(result.await);
+        });
+    }
+}
+
+impl SyncClient for RestSyncClient {
+    fn push(&self, id: &str, body: RemoteRoster, on_done: Callback<Result<PushReceipt, SyncError>>) {
+        self.put(&self.get_url("rosters", id), &body, on_done);
+    }
+
+    fn pull(&self, id: &str, on_done: Callback<Result<RemoteRoster, SyncError>>) {
+        self.get(&self.get_url("rosters", id), on_done);
+    }
+
+    fn push_game(&self, id: &str, body: RemoteGame, on_done: Callback<Result<PushReceipt, SyncError>>) {
+        self.put(&self.get_url("games", id), &body, on_done);
+    }
+
+    fn pull_game(&self, id: &str, on_done: Callback<Result<RemoteGame, SyncError>>) {
+        self.get(&self.get_url("games", id), on_done);
+    }
+}
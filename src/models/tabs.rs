@@ -0,0 +1,144 @@
+// Coordination between the tabs of the app, which all share the same local
+// storage. A single tab at a time is the writer: it holds a lock refreshed
+// by a heartbeat, the others only follow what it saves. A writer tab that
+// goes away without releasing the lock is replaced once the lock is stale.
+// Whatever the storage backend, the writer says in the local storage what
+// it saved, the other tabs are only told about changes made there.
+use std::cell::Cell;
+use std::rc::Rc;
+
+use gloo::events::EventListener;
+use gloo::storage::{LocalStorage, Storage};
+use gloo::timers::callback::Interval;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use yew::Callback;
+
+use crate::models::storage::{CAMPAIGN_KEY, ROSTER_KEY, SLOTS_KEY};
+
+const WRITER_KEY: &str = "fsd_builder.writer";
+const SAVED_KEY: &str = "fsd_builder.saved";
+const HEARTBEAT_MS: u32 = 2_000;
+const STALE_AFTER_MS: f64 = 3.0 * HEARTBEAT_MS as f64;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TabEvent {
+    // The campaign or the rosters were saved by another tab
+    CampaignSaved,
+    RosterSaved,
+    // This tab became the writer, or stopped being it
+    WriterChanged(bool),
+}
+
+// The key saved by the writer, at a time for the change to be seen even
+// when it's saved again.
+#[derive(Serialize, Deserialize)]
+struct SavedValue {
+    key: String,
+    time: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WriterLock {
+    tab_id: String,
+    heartbeat: f64,
+}
+
+pub struct TabCoordinator {
+    tab_id: Rc<String>,
+    is_writer: Rc<Cell<bool>>,
+    _heartbeat: Interval,
+    _storage_listener: EventListener,
+    _unload_listener: EventListener,
+}
+
+impl TabCoordinator {
+    pub fn new(on_event: Callback<TabEvent>) -> TabCoordinator {
+        let tab_id = Rc::new(format!("{:x}", (js_sys::Math::random() * u32::MAX as f64) as u32));
+        let is_writer = Rc::new(Cell::new(claim(&tab_id, false)));
+
+        let heartbeat = {
+            let (tab_id, is_writer, on_event) = (tab_id.clone(), is_writer.clone(), on_event.clone());
+            Interval::new(HEARTBEAT_MS, move || {
+                let was_writer = is_writer.replace(claim(&tab_id, false));
+                if is_writer.get() != was_writer {
+                    on_event.emit(TabEvent::WriterChanged(is_writer.get()));
+                }
+            })
+        };
+
+        // Only fired for the changes made by the other tabs.
+        let storage_listener = {
+            let (tab_id, is_writer) = (tab_id.clone(), is_writer.clone());
+            EventListener::new(&gloo::utils::window(), "storage", move |event| {
+                let Some(event) = event.dyn_ref::<web_sys::StorageEvent>() else {
+                    return;
+                };
+                match event.key().as_deref() {
+                    Some(SAVED_KEY) => {
+                        let saved = event.new_value().and_then(|json| serde_json::from_str::<SavedValue>(&json).ok());
+                        match saved.as_ref().map(|saved| saved.key.as_str()) {
+                            Some(CAMPAIGN_KEY) => on_event.emit(TabEvent::CampaignSaved),
+                            Some(ROSTER_KEY | SLOTS_KEY) => on_event.emit(TabEvent::RosterSaved),
+                            _ => {}
+                        }
+                    }
+                    Some(WRITER_KEY) if is_writer.get() && !is_holding(&tab_id) => {
+                        is_writer.set(false);
+                        on_event.emit(TabEvent::WriterChanged(false));
+                    }
+                    _ => {}
+                }
+            })
+        };
+
+        let unload_listener = {
+            let tab_id = tab_id.clone();
+            EventListener::new(&gloo::utils::window(), "pagehide", move |_| {
+                if is_holding(&tab_id) {
+                    LocalStorage::delete(WRITER_KEY);
+                }
+            })
+        };
+
+        TabCoordinator {
+            tab_id,
+            is_writer,
+            _heartbeat: heartbeat,
+            _storage_listener: storage_listener,
+            _unload_listener: unload_listener,
+        }
+    }
+
+    pub fn is_writer(&self) -> bool {
+        self.is_writer.get()
+    }
+
+    // Makes this tab the writer, whatever the other tabs are doing.
+    pub fn take_over(&self) {
+        self.is_writer.set(claim(&self.tab_id, true));
+    }
+}
+
+// Lets the other tabs know the value under the key was saved.
+pub fn announce_saved(key: &str) {
+    let _ = LocalStorage::set(SAVED_KEY, SavedValue {key: key.to_string(), time: js_sys::Date::now()});
+}
+
+fn is_holding(tab_id: &str) -> bool {
+    LocalStorage::get::<WriterLock>(WRITER_KEY).is_ok_and(|lock| lock.tab_id == tab_id)
+}
+
+// Takes or refreshes the lock, unless another tab holds it and is alive.
+fn claim(tab_id: &str, is_forced: bool) -> bool {
+    // Real time, the other tabs may run another clock, see models::clock.
+    let now = js_sys::Date::now();
+    let is_free = match LocalStorage::get::<WriterLock>(WRITER_KEY) {
+        Ok(lock) => lock.tab_id == tab_id || now - lock.heartbeat > STALE_AFTER_MS,
+        Err(_) => true,
+    };
+    if !is_free && !is_forced {
+        return false;
+    }
+    LocalStorage::set(WRITER_KEY, WriterLock {tab_id: tab_id.to_string(), heartbeat: now}).is_ok()
+}
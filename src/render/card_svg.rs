@@ -0,0 +1,342 @@
+// SVG rendering of roster cards and stat cards.
+// The on-screen canvas and every export go through these functions, so
+// a change of layout here is reflected everywhere at once.
+
+use crate::core::roster::{Roster, RosterElement, RosterEntry};
+use crate::core::composed::ComposedElement;
+use crate::core::campaign::CampaignRecord;
+use crate::core::provenance;
+use crate::render::icons::{self, IconName};
+
+use std::cell::RefCell;
+
+pub const ROSTER_CARD_WIDTH: u32 = 200;
+pub const ROSTER_CARD_HEIGHT: u32 = 200;
+pub const STAT_CARD_WIDTH: u32 = 300;
+pub const STAT_CARD_HEIGHT: u32 = 420;
+
+const FONT_FAMILY: &str = "'Gill Sans', 'Gill Sans MT', Calibri, 'Trebuchet MS', sans-serif";
+
+// Points can be hidden everywhere at once, e.g. for photos or streams.
+thread_local! {
+    static POINTS_HIDDEN: RefCell<bool> = const { RefCell::new(false) };
+}
+
+pub fn set_points_hidden(hidden: bool) {
+    POINTS_HIDDEN.with(|points_hidden| *points_hidden.borrow_mut() = hidden);
+}
+
+pub fn are_points_hidden() -> bool {
+    POINTS_HIDDEN.with(|points_hidden| *points_hidden.borrow())
+}
+
+// Colors used by the cards. Kept separate from the CSS so that exports
+// don't depend on the stylesheets being available.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CardTheme {
+    pub background: String,
+    pub foreground: String,
+    pub border: String,
+    pub muted: String,
+}
+
+impl CardTheme {
+    pub fn light() -> CardTheme {
+        CardTheme {
+            background: "#ffffff".to_string(),
+            foreground: "#000000".to_string(),
+            border: "rgba(0, 0, 0, 0.3)".to_string(),
+            muted: "gray".to_string(),
+        }
+    }
+
+    pub fn dark() -> CardTheme {
+        CardTheme {
+            background: "#111111".to_string(),
+            foreground: "#ffffff".to_string(),
+            border: "#ffffff".to_string(),
+            muted: "gray".to_string(),
+        }
+    }
+
+    pub fn for_mode(is_dark_mode: bool) -> CardTheme {
+        if is_dark_mode { CardTheme::dark() } else { CardTheme::light() }
+    }
+}
+
+// The small card used for each entry of the roster.
+pub fn roster_card(entry: &RosterEntry, theme: &CardTheme, image_href: &str) -> String {
+    let elem = &entry.element;
+    let name_lines = wrap_text(&elem.get_name().to_uppercase(), 18);
+    let attached: Vec<String> = elem.get_attached_elements().iter()
+        .map(|attached| attached.get_name().to_uppercase())
+        .collect();
+
+    // Vertically centering the whole block, like the old flex layout did.
+    let name_height = 20 * name_lines.len() as u32;
+    let image_height = 80;
+    let points_height = 28;
+    let attached_height = 16 * attached.len() as u32;
+    let total_height = name_height + 6 + image_height + 10 + points_height + attached_height;
+    let mut y = ROSTER_CARD_HEIGHT.saturating_sub(total_height) / 2;
+
+    let mut body = String::new();
+    for line in &name_lines {
+        y += 20;
+        body.push_str(&text(100, y - 4, 16, "bold", &theme.foreground, line));
+    }
+    y += 6;
+    body.push_str(&image(elem, theme, image_href, 40, y, 120, image_height));
+    y += image_height + 10 + points_height;
+    body.push_str(&text(100, y - 4, 24, "bold", &theme.foreground, &points_label(entry.get_total_points())));
+    for line in &attached {
+        y += 16;
+        body.push_str(&text(100, y - 2, 13, "bold", &theme.muted, line));
+    }
+    if let Some(campaign) = &entry.campaign {
+        body.push_str(&campaign_badge(campaign, theme));
+    }
+    if entry.quantity > 1 {
+        body.push_str(&text(24, 28, 20, "bold", &theme.foreground, &format!("x{}", entry.quantity)));
+    }
+
+    document(ROSTER_CARD_WIDTH, ROSTER_CARD_HEIGHT, theme, &body)
+}
+
+// The roster cards in a grid, as on the canvas, under the name of the
+// roster and above its total: the whole roster as one picture. Returns the
+// document with its width and height.
+pub fn roster_sheet(roster: &Roster, theme: &CardTheme, image_href: impl Fn(&RosterEntry) -> String) -> (String, u32, u32) {
+    const COLUMNS: usize = 4;
+    const MARGIN: u32 = 20;
+    const HEADER_HEIGHT: u32 = 60;
+    const FOOTER_HEIGHT: u32 = 50;
+    let columns = roster.elements.len().clamp(1, COLUMNS) as u32;
+    let rows = roster.elements.len().div_ceil(COLUMNS).max(1) as u32;
+    let width = columns * (ROSTER_CARD_WIDTH + MARGIN) + MARGIN;
+    let height = HEADER_HEIGHT + rows * (ROSTER_CARD_HEIGHT + MARGIN) + FOOTER_HEIGHT;
+
+    let name = if roster.metadata.name.is_empty() { "Roster" } else { &roster.metadata.name };
+    let mut body = text(width / 2, 40, 24, "bold", &theme.foreground, &name.to_uppercase());
+    for (index, entry) in roster.elements.iter().enumerate() {
+        let (column, row) = (index as u32 % columns, index as u32 / columns);
+        body.push_str(&format!(r#"<g transform="translate({}, {})">{}</g>"#,
+            MARGIN + column * (ROSTER_CARD_WIDTH + MARGIN), HEADER_HEIGHT + row * (ROSTER_CARD_HEIGHT + MARGIN),
+            roster_card(entry, theme, &image_href(entry))));
+    }
+    let total = if are_points_hidden() {
+        format!("{} entries", roster.elements.len())
+    } else {
+        format!("Total: {} / {} Points", roster.get_total_points(), roster.get_points_limit())
+    };
+    body.push_str(&text(width / 2, height - 20, 20, "bold", &theme.foreground, &total));
+    (document(width, height, theme, &body), width, height)
+}
+
+// The bigger card with the breakdown of the element. Grouped entries show
+// the whole composition: leaders, the squad and its upgrades, together with
+// the abilities gained from it.
+pub fn stat_card(entry: &RosterEntry, theme: &CardTheme, image_href: &str) -> String {
+    let elem = &entry.element;
+    let composed = ComposedElement::from(elem);
+    let mut body = String::new();
+    let mut y = 18;
+
+    // Where the data comes from, for the organizers
+    let type_label = format!("{} · {}", composed.type_name, provenance::get_entry_provenance(elem).get_label());
+    body.push_str(&text(150, y + 16, 14, "bold", &theme.muted, &type_label.to_uppercase()));
+    y += 24;
+    for line in wrap_text(&composed.name.to_uppercase(), 24) {
+        y += 24;
+        body.push_str(&text(150, y - 4, 20, "bold", &theme.foreground, &line));
+    }
+    y += 10;
+    body.push_str(&image(elem, theme, image_href, 60, y, 180, 120));
+    y += 130;
+
+    for ability in &composed.abilities {
+        y += 18;
+        body.push_str(&text(150, y - 4, 13, "bold", &theme.muted, &ability.to_uppercase()));
+    }
+    y += 10;
+
+    // Breakdown table, one row per piece of the composition.
+    for (role, part) in &composed.parts {
+        y += 22;
+        let role_label = if composed.is_grouped() { Some(role.get_label()) } else { None };
+        body.push_str(&row(y, theme, role_label, &part.get_name(), part.get_name_and_points().1, "normal"));
+    }
+    if !are_points_hidden() {
+        y += 8;
+        body.push_str(&format!(
+            r#"<line x1="24" y1="{y}" x2="276" y2="{y}" stroke="{color}" stroke-width="1"/>"#,
+            y = y, color = theme.border));
+        y += 24;
+        body.push_str(&row(y, theme, None, "Total", composed.total_points, "bold"));
+    }
+
+    // Campaign history, if the entry is being tracked.
+    if let Some(campaign) = &entry.campaign {
+        y += 30;
+        body.push_str(&text(150, y, 14, "bold", &theme.foreground, &campaign.get_badge_label()));
+        for injury in &campaign.injuries {
+            y += 18;
+            body.push_str(&text(150, y, 12, "normal", &theme.muted, &format!("INJURY: {}", injury.to_uppercase())));
+        }
+        for honor in &campaign.honors {
+            y += 18;
+            body.push_str(&text(150, y, 12, "normal", &theme.muted, &format!("HONOR: {}", honor.to_uppercase())));
+        }
+    }
+
+    document(STAT_CARD_WIDTH, STAT_CARD_HEIGHT.max(y + 24), theme, &body)
+}
+
+// The back of the stat card, for double-sided printing: the rules of the
+// entry written out in full, i.e. everything it's made of and the options
+// taken, without the artwork.
+pub fn card_back(entry: &RosterEntry, theme: &CardTheme) -> String {
+    let composed = ComposedElement::from(&entry.element);
+    let mut body = String::new();
+    let mut y = 18;
+
+    for line in wrap_text(&composed.name.to_uppercase(), 24) {
+        y += 24;
+        body.push_str(&text(150, y - 4, 20, "bold", &theme.foreground, &line));
+    }
+    y += 10;
+
+    body.push_str(&text(150, y + 14, 12, "bold", &theme.muted, "COMPOSITION"));
+    y += 18;
+    for (role, part) in &composed.parts {
+        y += 20;
+        body.push_str(&row(y, theme, Some(role.get_label()), &part.get_name(), part.get_name_and_points().1, "normal"));
+    }
+
+    if !composed.abilities.is_empty() {
+        y += 16;
+        body.push_str(&text(150, y + 14, 12, "bold", &theme.muted, "OPTIONS TAKEN"));
+        y += 18;
+        for ability in &composed.abilities {
+            for line in wrap_text(&ability.to_uppercase(), 36) {
+                y += 18;
+                body.push_str(&text(150, y - 4, 12, "normal", &theme.foreground, &line));
+            }
+        }
+    }
+
+    if let Some(campaign) = &entry.campaign {
+        y += 30;
+        body.push_str(&text(150, y, 14, "bold", &theme.foreground, &campaign.get_badge_label()));
+        for line in campaign.injuries.iter().map(|injury| format!("INJURY: {}", injury.to_uppercase()))
+            .chain(campaign.honors.iter().map(|honor| format!("HONOR: {}", honor.to_uppercase()))) {
+            y += 18;
+            body.push_str(&text(150, y, 12, "normal", &theme.muted, &line));
+        }
+    }
+
+    body.push_str(&text(150, STAT_CARD_HEIGHT - 20, 14, "bold", &theme.foreground, &points_label(composed.total_points)));
+
+    // Same size as the front, or the two sides wouldn't line up.
+    document(STAT_CARD_WIDTH, STAT_CARD_HEIGHT, theme, &body)
+}
+
+// Formatting of the points, shared by all the cards. Empty when the
+// points are hidden.
+pub fn points_label(points: u32) -> String {
+    if are_points_hidden() {
+        String::new()
+    } else if points == 1 {
+        "1 Point".to_string()
+    } else {
+        format!("{} Points", points)
+    }
+}
+
+fn document(width: u32, height: u32, theme: &CardTheme, body: &str) -> String {
+    format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" "#,
+            r#"class="card-svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="{font}">"#,
+            r#"<rect class="card-frame" x="2" y="2" width="{iw}" height="{ih}" rx="16" ry="16" "#,
+            r#"fill="{bg}" stroke="{border}" stroke-width="4"/>"#,
+            "{body}</svg>"),
+        w = width, h = height, iw = width - 4, ih = height - 4,
+        font = FONT_FAMILY, bg = theme.background, border = theme.border, body = body)
+}
+
+// Small pill in the top right corner with XP and rank, plus a star per honor.
+fn campaign_badge(campaign: &CampaignRecord, theme: &CardTheme) -> String {
+    let mut label = campaign.get_badge_label();
+    if !campaign.honors.is_empty() {
+        label.push_str(&format!(" {}", "★".repeat(campaign.honors.len().min(3))));
+    }
+    let width = 12 + 6 * label.chars().count() as u32;
+    let x = ROSTER_CARD_WIDTH - 10 - width;
+    format!(
+        concat!(
+            r#"<rect x="{x}" y="10" width="{w}" height="16" rx="8" ry="8" fill="{bg}"/>"#,
+            r#"<text x="{tx}" y="22" font-size="10" font-weight="bold" fill="{fg}" text-anchor="middle">{label}</text>"#),
+        x = x, w = width, tx = x + width / 2, bg = theme.foreground, fg = theme.background, label = escape(&label))
+}
+
+fn text(x: u32, y: u32, size: u32, weight: &str, color: &str, content: &str) -> String {
+    format!(
+        r#"<text x="{}" y="{}" font-size="{}" font-weight="{}" fill="{}" text-anchor="middle">{}</text>"#,
+        x, y, size, weight, color, escape(content))
+}
+
+fn row(y: u32, theme: &CardTheme, role: Option<&str>, name: &str, points: u32, weight: &str) -> String {
+    let role = role
+        .map(|role| format!(r#"<tspan fill="{}" font-size="10">{} </tspan>"#, theme.muted, escape(&role.to_uppercase())))
+        .unwrap_or_default();
+    format!(
+        concat!(
+            r#"<text x="24" y="{y}" font-size="14" font-weight="{weight}" fill="{color}">{role}{name}</text>"#,
+            r#"<text x="276" y="{y}" font-size="14" font-weight="{weight}" fill="{color}" text-anchor="end">{points}</text>"#),
+        y = y, weight = weight, color = theme.foreground, role = role,
+        name = escape(&name.to_uppercase()), points = escape(&points_label(points)))
+}
+
+// An empty href means no image at all, e.g. when printing without images.
+fn image(elem: &RosterElement, theme: &CardTheme, href: &str, x: u32, y: u32, width: u32, height: u32) -> String {
+    if href.is_empty() {
+        return String::new();
+    }
+    // Generic elements get an inline icon, only actual artwork is loaded.
+    if let Some(icon) = IconName::for_element(elem) {
+        return icons::icon_svg(icon, &theme.foreground, x, y, width, height);
+    }
+    format!(
+        r#"<image href="{href}" xlink:href="{href}" x="{x}" y="{y}" width="{w}" height="{h}" preserveAspectRatio="xMidYMid meet"/>"#,
+        href = escape(href), x = x, y = y, w = width, h = height)
+}
+
+// Greedy word wrapping based on a character count, good enough for the
+// fixed width uppercase labels of the cards.
+fn wrap_text(content: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = Vec::<String>::new();
+    let mut current = String::new();
+    for word in content.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+pub fn escape(content: &str) -> String {
+    content
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,56 @@
+// Inline SVG icons, drawn with the theme colors instead of loading (and
+// inverting) PNG files. All paths are drawn on a 24x24 grid.
+
+use crate::core::roster::RosterElement;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IconName {
+    Character,
+    Support,
+    Unit,
+    Other,
+}
+
+impl IconName {
+    // The icon standing in for an element, or None if the element comes
+    // with its own artwork (units, mostly).
+    pub fn for_element(elem: &RosterElement) -> Option<IconName> {
+        match elem {
+            RosterElement::ElemCharacter(_) => Some(IconName::Character),
+            RosterElement::ElemSupport(_) => Some(IconName::Support),
+            RosterElement::ElemUnit(unit) if unit.image.is_empty() => Some(IconName::Unit),
+            RosterElement::ElemUnit(_) => None,
+            RosterElement::ElemOther((_, _, _, image)) => match image.as_str() {
+                "character.png" => Some(IconName::Character),
+                "support.png" => Some(IconName::Support),
+                "" => Some(IconName::Other),
+                _ => None,
+            },
+        }
+    }
+
+    fn path(&self) -> &'static str {
+        match self {
+            IconName::Character => "M12 12a4 4 0 1 0 0-8 4 4 0 0 0 0 8zm0 2c-4 0-8 2-8 5v1h16v-1c0-3-4-5-8-5z",
+            IconName::Support => concat!(
+                "M11 2h2v3.1A7 7 0 0 1 18.9 11H22v2h-3.1A7 7 0 0 1 13 18.9V22h-2v-3.1A7 7 0 0 1 5.1 13H2v-2h3.1",
+                "A7 7 0 0 1 11 5.1V2zm1 5a5 5 0 1 0 0 10 5 5 0 0 0 0-10zm0 3a2 2 0 1 1 0 4 2 2 0 0 1 0-4z"),
+            IconName::Unit => "M12 2l8 3v6c0 5-3.5 9.5-8 11-4.5-1.5-8-6-8-11V5l8-3z",
+            IconName::Other => "M12 2l10 10-10 10L2 12 12 2z",
+        }
+    }
+}
+
+// The icon as a nested <svg>, to be placed inside a bigger document.
+pub fn icon_svg(name: IconName, color: &str, x: u32, y: u32, width: u32, height: u32) -> String {
+    format!(
+        r#"<svg x="{}" y="{}" width="{}" height="{}" viewBox="0 0 24 24" preserveAspectRatio="xMidYMid meet"><path d="{}" fill="{}" fill-rule="evenodd"/></svg>"#,
+        x, y, width, height, name.path(), color)
+}
+
+// The icon as a standalone document, for direct use in the page.
+pub fn standalone_icon_svg(name: IconName, color: &str, size: u32) -> String {
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" class="icon" width="{}" height="{}" viewBox="0 0 24 24"><path d="{}" fill="{}" fill-rule="evenodd"/></svg>"#,
+        size, size, name.path(), color)
+}
@@ -0,0 +1,8 @@
+// Rendering of the roster into self-contained documents (SVG and plain text).
+// Everything here works on plain strings so the same output can be shown
+// on screen, rasterized for exports or embedded in printable pages.
+pub mod card_svg;
+pub mod icons;
+#[cfg(feature = "qr")]
+pub mod qr_svg;
+pub mod text;
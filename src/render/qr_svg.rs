@@ -0,0 +1,22 @@
+// A QR code as SVG, black on white whatever the theme so phones can read
+// it, with the quiet zone around it the readers need.
+use crate::core::qr::QrCode;
+
+const QUIET_ZONE: usize = 4;
+
+pub fn qr_code_svg(qr: &QrCode) -> String {
+    let size = qr.size + 2 * QUIET_ZONE;
+    let mut path = String::new();
+    for y in 0..qr.size {
+        for x in 0..qr.size {
+            if qr.is_dark(x, y) {
+                path.push_str(&format!("M{},{}h1v1h-1z", x + QUIET_ZONE, y + QUIET_ZONE));
+            }
+        }
+    }
+    format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {size} {size}" shape-rendering="crispEdges" class="qr-code">"#,
+            r#"<rect width="{size}" height="{size}" fill="white"/><path d="{path}" fill="black"/></svg>"#),
+        size = size, path = path)
+}
@@ -0,0 +1,179 @@
+// Plain text versions of the roster, for pasting into messages and forums.
+use crate::core::breakdown::Grouping;
+use crate::core::composed::ComposedElement;
+use crate::core::roster::{Roster, RosterElement, RosterEntry};
+use crate::core::rules;
+use crate::render::card_svg;
+
+// Everything the stat card shows about an entry, as a block of text:
+//
+//   HEAVY ROBOTS (Unit) - 7 Points
+//   - Leader: Sentient AI (3)
+//   - Squad: Heavy Robots (4)
+//   Led by Sentient AI
+pub fn entry_summary(entry: &RosterEntry) -> String {
+    let composed = ComposedElement::from(&entry.element);
+    let mut lines = vec![format!("{} ({})", composed.name.to_uppercase(), composed.type_name)];
+    if !card_svg::are_points_hidden() {
+        lines[0].push_str(&format!(" - {}", card_svg::points_label(composed.total_points)));
+    }
+
+    if composed.is_grouped() {
+        for (role, part) in &composed.parts {
+            let mut line = format!("- {}: {}", role.get_label(), part.get_name());
+            if !card_svg::are_points_hidden() {
+                line.push_str(&format!(" ({})", part.get_name_and_points().1));
+            }
+            lines.push(line);
+        }
+    }
+    lines.extend(composed.abilities.iter().cloned());
+    if !entry.note.is_empty() {
+        lines.push(format!("Note: {}", entry.note));
+    }
+
+    if let Some(campaign) = &entry.campaign {
+        lines.push(campaign.get_badge_label());
+        lines.extend(campaign.injuries.iter().map(|injury| format!("Injury: {}", injury)));
+        lines.extend(campaign.honors.iter().map(|honor| format!("Honor: {}", honor)));
+    }
+    lines.join("\n")
+}
+
+// The whole roster in a few lines, to paste into a chat before a game.
+// Identical entries are counted rather than repeated:
+//
+//   Iron Vanguard - 58 pts
+//   2x Battle Robots - 6 pts
+//   Heavy Robots + Sentient AI - 7 pts
+//   Total: 58 / 60 pts
+//
+// followed by the rules set aside, with their reason.
+pub fn roster_paste(roster: &Roster) -> String {
+    let are_points_hidden = card_svg::are_points_hidden();
+    let mut lines = Vec::new();
+    if !roster.metadata.name.is_empty() {
+        lines.push(if are_points_hidden {
+            roster.metadata.name.clone()
+        } else {
+            format!("{} - {} pts", roster.metadata.name, roster.get_total_points())
+        });
+    }
+
+    let mut counted: Vec<(&RosterElement, usize)> = Vec::new();
+    for entry in &roster.elements {
+        match counted.iter_mut().find(|(element, _)| **element == entry.element) {
+            Some((_, count)) => *count += entry.quantity as usize,
+            None => counted.push((&entry.element, entry.quantity as usize)),
+        }
+    }
+    lines.extend(counted.into_iter().map(|(element, count)| {
+        let mut line = if count > 1 { format!("{}x ", count) } else { String::new() };
+        line.push_str(&element.get_name());
+        for attached in element.get_attached_elements() {
+            line.push_str(&format!(" + {}", attached.get_name()));
+        }
+        if !are_points_hidden {
+            line.push_str(&format!(" - {} pts", element.get_total_points() * count as u32));
+        }
+        line
+    }));
+
+    if are_points_hidden {
+        lines.push(format!("{} entries", roster.elements.len()));
+    } else {
+        let points_limit = roster.get_points_limit();
+        lines.push(format!("Total: {} / {} pts", roster.get_total_points(), points_limit));
+        if roster.is_over_points_limit(points_limit) {
+            lines.push(format!("Over the limit by {} pts", roster.get_total_points() - points_limit));
+        }
+    }
+    lines.extend(rules::get_suppression_notes(roster));
+    lines.join("\n")
+}
+
+// The roster as a Markdown document, for forums and wikis: a table per
+// element type, the total at the bottom.
+pub fn roster_markdown(roster: &Roster) -> String {
+    let are_points_hidden = card_svg::are_points_hidden();
+    let show_waves = roster.elements.iter().any(|entry| !entry.wave.is_first());
+    let show_notes = roster.elements.iter().any(|entry| !entry.note.is_empty());
+    let title = if roster.metadata.name.is_empty() { "Roster" } else { &roster.metadata.name };
+    let mut lines = vec![format!("# {}", escape_markdown(title))];
+
+    for type_name in Grouping::Type.get_groups() {
+        let entries: Vec<&RosterEntry> = roster.elements.iter().filter(|entry| entry.element.get_type_name() == type_name).collect();
+        if entries.is_empty() {
+            continue;
+        }
+        lines.push(String::new());
+        lines.push(format!("## {}", get_type_heading(type_name)));
+        lines.push(String::new());
+
+        let mut header = vec!["Element", "Attached"];
+        if show_waves {
+            header.push("Wave");
+        }
+        if !are_points_hidden {
+            header.push("Points");
+        }
+        if show_notes {
+            header.push("Note");
+        }
+        lines.push(format!("| {} |", header.join(" | ")));
+        lines.push(format!("|{}", header.iter().map(|_| "---|").collect::<String>()));
+        for entry in entries {
+            let attached: Vec<String> = entry.element.get_attached_elements().iter().map(|element| element.get_name()).collect();
+            let mut cells = vec![escape_markdown(&entry.get_counted_name()), escape_markdown(&attached.join(", "))];
+            if show_waves {
+                cells.push(entry.wave.get_label().to_string());
+            }
+            if !are_points_hidden {
+                cells.push(entry.get_total_points().to_string());
+            }
+            if show_notes {
+                cells.push(escape_markdown(&entry.note));
+            }
+            lines.push(format!("| {} |", cells.join(" | ")));
+        }
+    }
+
+    lines.push(String::new());
+    if are_points_hidden {
+        lines.push(format!("**{} entries**", roster.elements.len()));
+    } else {
+        let points_limit = roster.get_points_limit();
+        lines.push(format!("**Total: {} / {} pts**", roster.get_total_points(), points_limit));
+        if roster.is_over_points_limit(points_limit) {
+            lines.push(String::new());
+            lines.push(format!("_Over the limit by {} pts_", roster.get_total_points() - points_limit));
+        }
+    }
+    for note in rules::get_suppression_notes(roster) {
+        lines.push(String::new());
+        lines.push(format!("_{}_", escape_markdown(&note)));
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+fn get_type_heading(type_name: &str) -> &str {
+    match type_name {
+        "Character" => "Characters",
+        "Unit" => "Units",
+        "Support" => "Supports",
+        _ => type_name,
+    }
+}
+
+// Names are the player's, they may hold characters Markdown reads as syntax.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        if "\\`*_[]|#<>".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
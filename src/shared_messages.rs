@@ -1,37 +1,197 @@
-use crate::models::armylist::Faction;
-use crate::models::roster::RosterElement;
+use crate::core::armylist::Faction;
+use crate::core::roster::{RosterElement, Wave};
+use crate::models::settings::Settings;
+use crate::core::print_options::PrintOptions;
+use crate::models::presentation::OverlayOptions;
+use crate::models::tabs::TabEvent;
+use crate::core::scenario::Scenario;
+use crate::core::campaign::{BattleResult, CampaignRecord};
+use crate::core::quick_fix::QuickFix;
+use crate::core::sync::{RemoteRoster, SyncRecord};
+use crate::models::sync::SyncError;
+use crate::models::selection::{SelectionChange, SelectionContext};
 
 // pub type GenericElementType = (String, u32, Vec<String>, String);
 
+// The messages of the whole app, handled by App. The components keeping a
+// state of their own have their own Msg, the domains with their own topic
+// have their own types below, see models::bus for the topics.
 pub enum SharedMessage {
     NoOp, // Dummy message for no-operation
-    
+
+    // Loading what was saved, once the app is on screen
+    RestoreSavedState,
+    // The JSON saved under each of the keys, see App
+    FinishRestoringState(Vec<Option<String>>),
+    ReloadSavedState(Vec<Option<String>>),
+
     ClearRoster,
     LoadRoster,
     SaveRoster,
+    CopyShareLink,
+    CopyRoster,
+    RosterCopied(Result<bool /* With the JSON */, String>),
+    HideToast,
+    ExportImage,
+    ImageExported(Result<(), String>),
+    DeadlineTick,
+    ToggleQrDialog,
+    PushRoster,
+    RosterPushed(Result<SyncRecord, SyncError>),
+    PullRoster,
+    RosterPulled(Result<RemoteRoster, SyncError>),
+    ToggleCatalogChanges,
+    PinCatalog,
+    CatalogUpdated(Result<Option<String> /* Edition */, String>),
+    Metadata(MetadataMessage),
 
-    ToggleMenu(Faction),
     ShowUnits(Faction),
     ShowCharacters(Faction),
     ShowSupports(Faction),
 
     AddToRoster(RosterElement),
-    AddToElement(usize /* Index of the element */, RosterElement),
-    RemoveCharacterFromElement(usize),
+    AddToElement(String /* Id of the entry */, RosterElement),
+    RemoveCharacterFromElement(String /* Id of the entry */),
     NotifyRosterUpdated,
-    DeleteElement(usize),
-    ReorderElements,
+    CopyElementInfo(String /* Id of the entry */),
+    OpenCampaignEditor(String /* Id of the entry */),
+    UpdateCampaignRecord(String /* Id of the entry */, Option<CampaignRecord>),
+    CloseCampaignEditor,
+
+    ToggleCampaignDashboard,
+    StartCampaign(String),
+    RecordBattle(String /* Opponent */, BattleResult, i32 /* Resource points */, String /* Notes */),
+    SetCampaignChangeAllowance(u32),
+    SetCampaignPlayer(String),
+    SetCampaignTerritories(u32),
+    ExportCampaign,
+    EndCampaign,
+
+    ToggleScenarioEditor,
+    AttachScenario(Option<Scenario>),
+
+    ToggleSwapDialog,
+    SwapElements(String /* Name of the swapped element */, RosterElement),
 
     FileSelected,
     FileContentReceived(String),
+    BattleScribeFileSelected(web_sys::File),
+    BattleScribeFileLoaded(Result<Vec<u8>, String>),
+    DismissImportNotes,
+    DismissLoadError,
 
-    ShowTooltip(usize),
-    MoveTooltip(i32, i32),
-    HideTooltip,
-    SelectElement(usize),
-    DeselectElements,
+    ChangeSelection(SelectionChange),
 
     ToggleTheme,
+    ToggleHidePoints,
+    ToggleSettings,
+    ToggleProblemReport,
+    CopyProblemReport,
+
+    ToggleRosterManager,
+    NewRosterSlot,
+    SwitchRosterSlot(String /* Id of the slot */),
+    RenameRosterSlot(String /* Id of the slot */, String),
+    DeleteRosterSlot(String /* Id of the slot */),
+    ToggleEventEntrySlot(String /* Id of the slot */),
+
+    TabEvent(TabEvent),
+    RestoreSnapshot(String /* Id of the snapshot */),
+    DismissRecovery,
+    TakeOverWriting,
+
+    ToggleRepairWizard,
+    RepairDropBadElements(usize),
+    RepairResetPoints(usize),
+    UseRepairedRoster(usize),
+    DiscardNeedsAttention(usize),
+    UpdateSettings(Settings),
+    LogoFileSelected(web_sys::File),
+    LogoFileLoaded(Result<String, String> /* Data url */),
+    EventPackFileSelected(web_sys::File),
+    EventPackFileLoaded(Result<String, String>),
+    LeaveEvent,
+    SetCanvasZoom(u32),
+    ToggleDeploymentBoard,
+
+    TogglePrintDialog,
+    UpdatePrintOptions(PrintOptions),
+    #[cfg(feature = "print")]
+    PrintRoster,
+
+    ReadRosterAloud,
+
+    TogglePlayMode,
+
+    TogglePresentationDialog,
+    OpenOverlay(OverlayOptions),
+    CopyOverlayLink(OverlayOptions),
+
+    ToggleHistoryPanel,
+    Undo,
+    Redo,
+    JumpToHistory(usize),
+    CreateCheckpoint(String),
+    SaveRevision(String),
+    RestoreRevision(usize),
+    DeleteRevision(usize),
+
+    PreloadProgress(usize /* Loaded */, usize /* Total */),
 
     // Add more if needed
-}
\ No newline at end of file
+}
+
+// The roster canvas and its cards, handled by MainCanvas alone.
+pub enum CanvasMessage {
+    NotifyRosterUpdated,
+    SelectionChanged(SelectionContext),
+    SelectElement(usize, bool /* Extending the selection */),
+    DeleteElement(usize),
+    FinishDeleteElement(String /* Id of the entry */),
+    DuplicateElement(usize),
+    DuplicateHoveredElement,
+    MoveElement(usize, isize),
+    ChangeQuantity(usize, i32),
+    StartEditingElement(usize),
+    SaveElementEdit(usize),
+    CancelElementEdit,
+    ActivateElement(usize),
+    ShowElementDetails(usize),
+    HideElementDetails,
+    OpenContextMenu(usize, i32, i32),
+    CloseContextMenu,
+    ToggleTypeLegend,
+    ToggleBreakdown,
+    ToggleSuggestions,
+    ApplyQuickFix(QuickFix),
+    StartSuppressing(String /* Violation key */),
+    SuppressViolation(String /* Violation key */, String /* Reason */),
+    UnsuppressViolation(String),
+    DragElement(usize),
+    DragOverElement(usize),
+    DropElement(usize),
+    DropElementOnWave(Wave),
+    EndDrag,
+    ApplyToCopies(usize),
+    DismissCopiesOffer,
+    OpenCampaignEditor(usize),
+    ReorderElements,
+    ShowTooltip(usize),
+    MoveTooltip(i32, i32),
+    HideTooltip,
+    CanvasScrolled,
+    CopyHoveredElement,
+    ExportBattleScribe,
+    ExportMarkdown,
+    CopyRosterForChat,
+}
+
+// Name, points limit and milestones of the roster, published on the bus by
+// whatever edits them and handled by App.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MetadataMessage {
+    Rename(String),
+    RerollName,
+    SetPointsLimit(u32),
+    SetEscalationMilestones(Vec<u32>),
+}
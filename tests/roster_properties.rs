@@ -0,0 +1,121 @@
+// Random sequences of edits on the fixtures (core::fixtures). After every
+// edit the roster keeps its invariants (Roster::check_invariants) and the
+// inverse of the command takes it back; at the end the history undoes and
+// redoes the whole sequence, and RosterCommand::transform finds the way from
+// the fixture to what became of it.
+#![cfg(not(target_arch = "wasm32"))]
+
+use fsd_builder::core::commands::RosterCommand;
+use fsd_builder::core::fixtures::FIXTURES;
+use fsd_builder::core::history::History;
+use fsd_builder::core::roster::{Roster, RosterEntry, Wave};
+use proptest::prelude::*;
+
+// An edit picked at random, made into a command for the roster it's applied
+// to. Indexes are taken modulo the number of entries, see get_command.
+#[derive(Clone, Debug)]
+enum Edit {
+    Insert(usize, usize /* Entry of the pool */),
+    Remove(usize),
+    Move(usize, usize),
+    Replace(usize, usize /* Entry of the pool */),
+    SetQuantity(usize, u32),
+    SetNameAndPoints(usize, u32),
+    SetNote(usize, String),
+    SetWave(usize, usize),
+    SetPointsLimit(u32),
+    Batch(Vec<Edit>),
+}
+
+fn edit() -> impl Strategy<Value = Edit> {
+    let single = prop_oneof![
+        (any::<usize>(), any::<usize>()).prop_map(|(index, pick)| Edit::Insert(index, pick)),
+        any::<usize>().prop_map(Edit::Remove),
+        (any::<usize>(), any::<usize>()).prop_map(|(from, to)| Edit::Move(from, to)),
+        (any::<usize>(), any::<usize>()).prop_map(|(index, pick)| Edit::Replace(index, pick)),
+        (any::<usize>(), 1u32..=5).prop_map(|(index, quantity)| Edit::SetQuantity(index, quantity)),
+        (any::<usize>(), 0u32..=40).prop_map(|(index, points)| Edit::SetNameAndPoints(index, points)),
+        (any::<usize>(), "[a-z ]{0,8}").prop_map(|(index, note)| Edit::SetNote(index, note)),
+        (any::<usize>(), 0..Wave::ALL.len()).prop_map(|(index, wave)| Edit::SetWave(index, wave)),
+        (1u32..=200).prop_map(Edit::SetPointsLimit),
+    ];
+    single.prop_recursive(2, 12, 4, |edit| prop::collection::vec(edit, 1..4).prop_map(Edit::Batch))
+}
+
+// Entries of every fixture, new ones are copies of these.
+fn get_pool() -> Vec<RosterEntry> {
+    FIXTURES.iter().flat_map(|(_, build)| build().elements).collect()
+}
+
+fn get_command(edit: &Edit, roster: &Roster, pool: &[RosterEntry]) -> RosterCommand {
+    let count = roster.elements.len();
+    let index = |index: &usize| index % count.max(1);
+    let picked = |pick: &usize| pool[pick % pool.len()].renewed();
+    match edit {
+        Edit::Insert(at, pick) => RosterCommand::InsertEntry(at % (count + 1), picked(pick)),
+        Edit::Remove(at) => RosterCommand::RemoveEntry(index(at)),
+        Edit::Move(from, to) => RosterCommand::MoveEntry(index(from), index(to)),
+        Edit::Replace(at, pick) => RosterCommand::ReplaceEntry(index(at), picked(pick)),
+        Edit::SetQuantity(at, quantity) => RosterCommand::SetQuantity(index(at), *quantity),
+        Edit::SetNameAndPoints(at, points) => RosterCommand::SetNameAndPoints(index(at), format!("Renamed {}", points), *points),
+        Edit::SetNote(at, note) => RosterCommand::SetNote(index(at), note.clone()),
+        Edit::SetWave(at, wave) => RosterCommand::SetWave(index(at), Wave::ALL[*wave]),
+        Edit::SetPointsLimit(points_limit) => RosterCommand::SetPointsLimit(*points_limit),
+        // Each one on the roster left by the previous ones, as the batch applies them.
+        Edit::Batch(edits) => {
+            let mut edited = roster.clone();
+            RosterCommand::Batch(edits.iter().map(|edit| {
+                let command = get_command(edit, &edited, pool);
+                command.apply(&mut edited);
+                command
+            }).collect())
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn edits_keep_the_invariants(fixture in 0..FIXTURES.len(), edits in prop::collection::vec(edit(), 1..30)) {
+        let (name, build) = FIXTURES[fixture];
+        let start = build();
+        prop_assert_eq!(start.check_invariants(), Ok(()), "fixture {}", name);
+
+        let pool = get_pool();
+        let mut roster = start.clone();
+        let mut history = History::new("Opened roster");
+        for edit in &edits {
+            let before = roster.clone();
+            let Some(executed) = get_command(edit, &roster, &pool).execute(&mut roster) else {
+                prop_assert_eq!(&roster, &before, "a command not applied changed the roster");
+                continue;
+            };
+            prop_assert_eq!(roster.check_invariants(), Ok(()), "after {:?}", executed.command);
+
+            let mut undone = roster.clone();
+            prop_assert!(executed.inverse.apply(&mut undone));
+            prop_assert_eq!(&undone, &before, "undoing {:?}", executed.command);
+            history.record(executed);
+        }
+
+        let end = roster.clone();
+        while let Some(command) = history.undo() {
+            prop_assert!(command.apply(&mut roster));
+            prop_assert_eq!(roster.check_invariants(), Ok(()));
+        }
+        prop_assert_eq!(&roster, &start);
+        while let Some(command) = history.redo() {
+            prop_assert!(command.apply(&mut roster));
+        }
+        prop_assert_eq!(&roster, &end);
+
+        // The same, in a single command and back.
+        let mut transformed = start.clone();
+        if let Some(command) = RosterCommand::transform(&start, &end) {
+            let executed = command.execute(&mut transformed);
+            prop_assert!(executed.is_some());
+            prop_assert_eq!(&transformed, &end);
+            prop_assert!(executed.unwrap().inverse.apply(&mut transformed));
+        }
+        prop_assert_eq!(&transformed, &start);
+    }
+}